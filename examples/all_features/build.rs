@@ -0,0 +1,49 @@
+use convex_typegen::{Configuration, generate};
+
+fn main()
+{
+    // The schema and functions of the `basic` example
+    let convex_dir = std::path::Path::new("../basic/convex");
+    println!("cargo:rerun-if-changed=../basic/convex");
+
+    let mut function_paths: Vec<std::path::PathBuf> = std::fs::read_dir(convex_dir)
+        .expect("../basic/convex/ directory must exist")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            (name.ends_with(".ts") && name != "schema.ts" && !name.starts_with('_')).then_some(path)
+        })
+        .collect();
+    function_paths.sort();
+
+    // `emit_previous_tables` is left out: it needs a `previous_model` to diff against
+    let config = Configuration {
+        schema_path: convex_dir.join("schema.ts"),
+        out_file: std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("convex_types.rs"),
+        function_paths,
+        emit_shared_subscriptions: true,
+        emit_arc_subscriptions: true,
+        emit_snapshot_subscriptions: true,
+        emit_buffered_subscriptions: true,
+        emit_resilient_subscriptions: true,
+        emit_cursor_store: true,
+        emit_frontend_adapters: true,
+        emit_tauri_commands: true,
+        emit_axum_router: true,
+        emit_tenant_router: true,
+        emit_admin_client: true,
+        emit_timeouts: true,
+        emit_consistent_reads: true,
+        emit_offline_queue: true,
+        emit_import_writers: true,
+        emit_fixtures: true,
+        emit_function_refs: true,
+        emit_schedule_requests: true,
+        emit_enum_labels: true,
+        ..Default::default()
+    };
+
+    if let Err(e) = generate(config) {
+        panic!("convex-typegen failed: {}", e);
+    }
+}
@@ -0,0 +1,5 @@
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+
+// Generated types from ../basic/convex/schema.ts with every opt-in output
+include!(concat!(env!("OUT_DIR"), "/convex_types.rs"));
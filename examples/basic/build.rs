@@ -25,9 +25,20 @@ fn main()
         schema_path: std::path::PathBuf::from("convex/schema.ts"),
         out_file: std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("convex_types.rs"),
         function_paths,
-        helper_stubs: std::collections::HashMap::new(),
+        ..Default::default()
     };
 
+    // Two tagged copies for `versioned`, which includes them side by side
+    for tag in ["V6", "V7"] {
+        let config = Configuration {
+            version_tag: Some(tag.to_string()),
+            ..config.clone()
+        };
+        if let Err(e) = generate(config) {
+            panic!("convex-typegen failed for {}: {}", tag, e);
+        }
+    }
+
     match generate(config) {
         Ok(_) => {}
         Err(e) => panic!("convex-typegen failed: {}", e),
@@ -94,6 +94,7 @@ export const testDoc = v.object({
   })),
   optionalArray: v.optional(v.array(v.string())),
   nullable: v.union(v.string(), v.null()),
+  subject: v.optional(v.union(v.id("games"), v.id("players"))),
   complexNested: v.object({
     metadata: v.object({
       tags: v.array(v.string()),
@@ -204,6 +205,9 @@ export default defineSchema({
     // Nullable (union with null)
     nullable: v.union(v.string(), v.null()),
 
+    // Id from one of several tables
+    subject: v.optional(v.union(v.id("games"), v.id("players"))),
+
     // Complex nested
     complexNested: v.object({
       metadata: v.object({
@@ -3,3 +3,10 @@
 
 // Generated types from convex/schema.ts via convex-typegen
 include!(concat!(env!("OUT_DIR"), "/convex_types.rs"));
+
+/// Two tagged schema versions in one module, as a blue/green rollout includes them
+pub mod versioned
+{
+    include!(concat!(env!("OUT_DIR"), "/convex_types_v6.rs"));
+    include!(concat!(env!("OUT_DIR"), "/convex_types_v7.rs"));
+}
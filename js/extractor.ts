@@ -2,18 +2,20 @@
 //
 // 1. Registers mock modules via build.module() to intercept Convex server imports
 //    NOTE: convex/values is NOT mocked — real Convex validators are used so that
-//    .omit(), .extend(), .pick(), .partial() etc. work natively.
-// 2. Dynamically imports the schema file (populates __schema via defineSchema mock)
-// 3. Dynamically imports each function file (exports tagged with __type)
-// 4. Prints the combined result as JSON to stdout
+//    .omit(), .extend(), .pick(), .partial() etc. work natively; chained
+//    .optional() is added by mocks/chaining.ts.
+// 2-4. Runs the steps in run.ts, shared with the embedded backend: imports the
+//    schema and each function file, and prints NDJSON to stdout as it goes —
+//    the schema options, a record per table and per function, the resolution
+//    trace when TYPEGEN_TRACE_RESOLVE=1, and an end record
 
 import { plugin } from "bun";
-import type { FunctionDef } from "./mocks/convex_server.ts";
 import * as convexServer from "./mocks/convex_server.ts";
 import * as convexApi from "./mocks/convex_api.ts";
-import { normalize } from "./mocks/normalize.ts";
-
-type Descriptor = Record<string, unknown>;
+import { CUSTOM_VALIDATOR_PREFIX, customValidators } from "./mocks/normalize.ts";
+import { trace } from "./mocks/trace.ts";
+import { run } from "./run.ts";
+import { v } from "convex/values";
 
 // ---------------------------------------------------------------------------
 // 1. Register mock modules — intercepts `import "convex/server"` etc.
@@ -27,42 +29,112 @@ plugin({
   name: "convex-typegen-mock",
   setup(build) {
     // Mock convex/server (defineSchema, defineTable, query, mutation, etc.)
-    build.module("convex/server", () => ({
-      exports: convexServer,
-      loader: "object",
-    }));
+    build.module("convex/server", () => {
+      trace({ kind: "import", specifier: "convex/server", served: "mocks/convex_server.ts" });
+      return { exports: convexServer, loader: "object" };
+    });
 
     // _generated imports → stubs (need onResolve for pattern matching)
     const MOCK_DIR = import.meta.dir + "/mocks";
 
-    build.onResolve({ filter: /\/_generated\/api/ }, () => ({
-      path: MOCK_DIR + "/convex_api.ts",
-    }));
-
-    build.onResolve({ filter: /\/_generated\/server/ }, () => ({
-      path: MOCK_DIR + "/convex_server.ts",
-    }));
-
-    build.onResolve({ filter: /\/_generated\/dataModel/ }, () => ({
-      path: MOCK_DIR + "/convex_api.ts",
+    // Serve `path` for the import, recording the interception when tracing
+    const serve = (path: string, served: string) => (args: { path: string; importer: string }) => {
+      trace({ kind: "import", specifier: args.path, importer: args.importer, served });
+      return { path };
+    };
+
+    build.onResolve(
+      { filter: /\/_generated\/api/ },
+      serve(MOCK_DIR + "/convex_api.ts", "mocks/convex_api.ts"),
+    );
+
+    build.onResolve(
+      { filter: /\/_generated\/server/ },
+      serve(MOCK_DIR + "/convex_server.ts", "mocks/convex_server.ts"),
+    );
+
+    build.onResolve(
+      { filter: /\/_generated\/dataModel/ },
+      serve(MOCK_DIR + "/convex_api.ts", "mocks/convex_api.ts"),
+    );
+
+    // Custom validator factories → a module exporting the factories of that
+    // module, so the real one never runs. Registered before the helper stubs,
+    // so they win for the same module
+    const customModules = new Map<string, string[]>();
+    for (const [name, { module }] of Object.entries(customValidators)) {
+      customModules.set(module, [...(customModules.get(module) ?? []), name]);
+    }
+    for (const [module, names] of customModules) {
+      const escaped = module
+        .replace(/^\.\//, "")
+        .replace(/\.[jt]s$/, "")
+        .replace(/[.*+?^${}()|[\]\\]/g, "\\$&")
+        .replace(/\//g, "[/\\\\]");
+      const filter = new RegExp(`(^|[/\\\\])${escaped}(\\.[jt]s)?$`);
+      build.onResolve({ filter }, (args) => {
+        const served = `custom validators ${names.join(", ")}`;
+        trace({ kind: "import", specifier: args.path, importer: args.importer, served });
+        return { path: module, namespace: "convex-typegen-custom" };
+      });
+    }
+    build.onLoad({ filter: /.*/, namespace: "convex-typegen-custom" }, (args) => ({
+      exports: Object.fromEntries(
+        (customModules.get(args.path) ?? []).map((name) => [name, () => v.literal(CUSTOM_VALIDATOR_PREFIX + name)]),
+      ),
+      loader: "object",
     }));
 
     // User-supplied helper stubs (passed via TYPEGEN_HELPER_STUBS env var)
-    // Format: JSON object mapping regex patterns to absolute file paths
+    // Format: JSON object mapping regex patterns to absolute file paths.
+    // A `/` in a pattern also matches `\`, so patterns written for POSIX
+    // specifiers keep matching on Windows.
     const raw = process.env.TYPEGEN_HELPER_STUBS;
     if (raw) {
       const helperStubs: Record<string, string> = JSON.parse(raw);
       for (const [pattern, stubPath] of Object.entries(helperStubs)) {
-        build.onResolve({ filter: new RegExp(pattern) }, () => ({
-          path: stubPath,
-        }));
+        const filter = new RegExp(pattern.replace(/\\?\//g, "[/\\\\]"));
+        build.onResolve({ filter }, serve(stubPath, `helper stub ${stubPath}`));
       }
     }
+
+    // Convex Auth → authTables and typed convexAuth() functions, unless a
+    // helper stub above claims the import. Served by path, so the mock's own
+    // convex/server import goes through this plugin
+    build.onResolve(
+      { filter: /^@convex-dev\/auth\/server$/ },
+      serve(MOCK_DIR + "/convex_auth_server.ts", "mocks/convex_auth_server.ts"),
+    );
+
+    build.onResolve(
+      { filter: /^(@convex-dev\/auth|@auth\/core)\/providers\// },
+      serve(MOCK_DIR + "/convex_auth_providers.ts", "mocks/convex_auth_providers.ts"),
+    );
+
+    // convex-helpers validator utilities → the same unions and optionals
+    // their long forms produce
+    build.onResolve(
+      { filter: /^convex-helpers\/validators(\.js)?$/ },
+      serve(MOCK_DIR + "/convex_helpers_validators.ts", "mocks/convex_helpers_validators.ts"),
+    );
+
+    // Common components → client classes that evaluate at import time, and
+    // their convex.config definitions for app.use()
+    build.onResolve(
+      { filter: /^@convex-dev\/(rate-limiter|aggregate|workflow)$/ },
+      serve(MOCK_DIR + "/convex_components.ts", "mocks/convex_components.ts"),
+    );
+
+    build.onResolve(
+      { filter: /^@convex-dev\/[\w-]+\/convex\.config(\.js)?$/ },
+      serve(MOCK_DIR + "/convex_component_config.ts", "mocks/convex_component_config.ts"),
+    );
   },
 });
 
 // ---------------------------------------------------------------------------
-// 2. Import schema — side-effect: populates __schema via defineSchema()
+// 2-4. Import the schema and function files, printing one JSON record per
+//      line; extract.rs converts each as it arrives
 // ---------------------------------------------------------------------------
 
 const [schemaPath, ...functionPaths] = process.argv.slice(2);
@@ -72,96 +144,4 @@ if (!schemaPath) {
   process.exit(1);
 }
 
-await import(schemaPath);
-
-// ---------------------------------------------------------------------------
-// 3. Import each function file and extract registered functions
-// ---------------------------------------------------------------------------
-
-interface FunctionRecord {
-  name: string;
-  type: string;
-  params: Array<{ name: string; data_type: Descriptor }>;
-  return_type: Descriptor | null;
-  file_name: string;
-  module_path: string;
-}
-
-const functions: FunctionRecord[] = [];
-
-for (const fp of functionPaths) {
-  const parts = fp.split(/[/\\]/);
-  const rawName = parts[parts.length - 1] ?? fp;
-  const fileName = rawName.replace(/\.ts$/, "");
-
-  // Compute module path relative to the `convex/` directory for API routing.
-  // e.g. "/abs/path/convex/model/chats.ts" → "model/chats"
-  // Falls back to fileName for top-level files.
-  const convexIdx = parts.findIndex((p) => p === "convex");
-  const modulePath =
-    convexIdx >= 0 && convexIdx < parts.length - 1
-      ? parts
-          .slice(convexIdx + 1)
-          .join("/")
-          .replace(/\.ts$/, "")
-      : fileName;
-
-  const mod = await import(fp);
-
-  for (const [exportName, value] of Object.entries(mod)) {
-    if (
-      value !== null &&
-      typeof value === "object" &&
-      "__type" in (value as object)
-    ) {
-      const def = value as FunctionDef;
-      const config = def.__config ?? {};
-
-      // Extract and normalize params from args.
-      // Since we use real convex/values, args is a real Convex validator
-      // (v.object({ ... })) — normalize() converts it to codegen format.
-      const argsRaw = config.args;
-      let params: Array<{ name: string; data_type: Descriptor }> = [];
-
-      if (argsRaw !== undefined && argsRaw !== null) {
-        const normalized = normalize(argsRaw);
-        // After normalization, should be { type: "object", properties: { ... } }
-        if (
-          normalized.type === "object" &&
-          normalized.properties &&
-          typeof normalized.properties === "object"
-        ) {
-          params = Object.entries(
-            normalized.properties as Record<string, Descriptor>,
-          ).map(([paramName, dt]) => ({
-            name: paramName,
-            data_type: dt,
-          }));
-        }
-      }
-
-      // Normalize return type if present
-      const returnsRaw = config.returns;
-      const returnType =
-        returnsRaw !== undefined && returnsRaw !== null
-          ? normalize(returnsRaw)
-          : null;
-
-      functions.push({
-        name: exportName,
-        type: def.__type,
-        params,
-        return_type: returnType,
-        file_name: fileName,
-        module_path: modulePath,
-      });
-    }
-  }
-}
-
-// ---------------------------------------------------------------------------
-// 4. Print JSON to stdout — Rust extract.rs reads this
-// ---------------------------------------------------------------------------
-
-const output = JSON.stringify({ schema: convexServer.__schema, functions });
-console.log(output);
+await run(schemaPath, functionPaths, (record) => console.log(JSON.stringify(record)));
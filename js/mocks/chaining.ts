@@ -0,0 +1,38 @@
+// Method-chained optionals — lets `v.string().optional()` evaluate under Bun.
+//
+// Real Convex validators only expose `v.optional(...)` (and the internal
+// `asOptional()` it calls), but newer code and helper libraries chain
+// `.optional()` onto a validator. Each validator class gets an `optional()`
+// that forwards to `v.optional(this)`, so both forms normalize to the same
+// `{ type: "optional", inner }` descriptor. Classes that already define it
+// are left alone.
+
+import { v } from "convex/values";
+
+const samples: unknown[] = [
+  v.string(),
+  v.number(),
+  v.boolean(),
+  v.null(),
+  v.any(),
+  v.bytes(),
+  v.int64(),
+  v.id("_"),
+  v.literal("_"),
+  v.object({}),
+  v.array(v.any()),
+  v.record(v.string(), v.any()),
+  v.union(v.null(), v.any()),
+];
+
+for (const sample of samples) {
+  const proto = Object.getPrototypeOf(sample);
+  if (typeof proto.optional === "function") continue;
+  Object.defineProperty(proto, "optional", {
+    value(this: Parameters<typeof v.optional>[0]) {
+      return v.optional(this);
+    },
+    configurable: true,
+    writable: true,
+  });
+}
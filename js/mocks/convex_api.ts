@@ -7,3 +7,6 @@ const handler: ProxyHandler<object> = {
 
 export const api = new Proxy({}, handler);
 export const internal = new Proxy({}, handler);
+
+// `components.<name>` handles passed to component clients (`new RateLimiter(components.rateLimiter)`)
+export const components = new Proxy({}, handler);
@@ -0,0 +1,11 @@
+// Stub for `@convex-dev/auth/providers/*` and `@auth/core/providers/*` —
+// providers are only passed to `convexAuth()`, which ignores them.
+
+const provider = (config: unknown = {}) => ({ id: "provider", config });
+
+export const Password = provider;
+export const Anonymous = provider;
+export const Email = provider;
+export const Phone = provider;
+export const ConvexCredentials = provider;
+export default provider;
@@ -0,0 +1,47 @@
+// Mock implementation of `@convex-dev/auth/server` — `authTables` for the
+// schema, and a `convexAuth()` whose functions take the args of the real ones
+// and declare their documented results, so `signIn` / `signOut` come out typed.
+
+import { action, internalMutation, query } from "convex/server";
+import { v } from "convex/values";
+
+export { authTables } from "./convex_auth_tables.ts";
+
+export function convexAuth(_config: unknown) {
+  return {
+    // The HTTP routes and `ctx` helpers aren't functions a client can call
+    auth: { addHttpRoutes: () => {} },
+    signIn: action({
+      args: {
+        provider: v.optional(v.string()),
+        params: v.optional(v.any()),
+        verifier: v.optional(v.string()),
+        refreshToken: v.optional(v.string()),
+        calledBy: v.optional(v.string()),
+      },
+      returns: v.object({
+        redirect: v.optional(v.string()),
+        verifier: v.optional(v.string()),
+        tokens: v.optional(
+          v.union(v.object({ token: v.string(), refreshToken: v.string() }), v.null()),
+        ),
+        started: v.optional(v.boolean()),
+      }),
+    }),
+    signOut: action({ args: {}, returns: v.null() }),
+    store: internalMutation({ args: { args: v.any() } }),
+    isAuthenticated: query({ args: {}, returns: v.boolean() }),
+  };
+}
+
+// Helpers called inside handlers, which the extractor never runs
+const unused = () => {
+  throw new Error("convex-typegen mock: @convex-dev/auth helpers can't run during extraction");
+};
+export const getAuthUserId = unused;
+export const getAuthSessionId = unused;
+export const createAccount = unused;
+export const retrieveAccount = unused;
+export const modifyAccountCredentials = unused;
+export const invalidateSessions = unused;
+export const signInViaProvider = unused;
@@ -0,0 +1,4 @@
+// Stub for `@convex-dev/*/convex.config` — the component definition that
+// `app.use()` takes in convex.config.ts.
+
+export default { componentDefinitionPath: "convex-typegen-mock" };
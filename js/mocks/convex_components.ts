@@ -0,0 +1,124 @@
+// Mocks of the common `@convex-dev/*` component clients. Their constructors
+// run at import time in user code, so they must exist; their methods only run
+// inside handlers, which the extractor never calls.
+//
+// Helpers that create functions for the app to export (the rate limiter's
+// `hookAPI()`, the workflow manager's `define()`) return registered
+// functions with the validators of the real ones, so those come out typed,
+// marked with the component so they get its namespaced client trait. The
+// component's own functions run inside the component and can't be called
+// from a client, so they aren't extracted.
+
+import { internalMutation, mutation, query } from "convex/server";
+import type { FunctionDef } from "./convex_server.ts";
+import { v } from "convex/values";
+
+function fromComponent(component: string, def: FunctionDef): FunctionDef {
+  return { ...def, __component: component };
+}
+
+// Methods that only run inside handlers
+function unused(pkg: string) {
+  return () => {
+    throw new Error(`convex-typegen mock: ${pkg} can't run during extraction`);
+  };
+}
+
+// ---------------------------------------------------------------------------
+// @convex-dev/rate-limiter
+// ---------------------------------------------------------------------------
+
+export const SECOND = 1000;
+export const MINUTE = 60 * SECOND;
+export const HOUR = 60 * MINUTE;
+
+export class RateLimiter {
+  limit = unused("@convex-dev/rate-limiter");
+  check = unused("@convex-dev/rate-limiter");
+  reset = unused("@convex-dev/rate-limiter");
+  getValue = unused("@convex-dev/rate-limiter");
+  calculateRateLimit = unused("@convex-dev/rate-limiter");
+
+  constructor(_component: unknown, _limits?: unknown) {}
+
+  hookAPI(_name: string, _options?: unknown) {
+    const getRateLimit = query({
+      args: {
+        config: v.optional(v.any()),
+        key: v.optional(v.string()),
+        sampleShards: v.optional(v.number()),
+      },
+      returns: v.object({
+        value: v.number(),
+        ts: v.number(),
+        shard: v.number(),
+        config: v.any(),
+      }),
+    });
+    const getServerTime = mutation({ args: {}, returns: v.number() });
+    return {
+      getRateLimit: fromComponent("rateLimiter", getRateLimit),
+      getServerTime: fromComponent("rateLimiter", getServerTime),
+    };
+  }
+}
+
+export const isRateLimitError = (_error: unknown) => false;
+
+// ---------------------------------------------------------------------------
+// @convex-dev/aggregate
+// ---------------------------------------------------------------------------
+
+class AggregateClient {
+  count = unused("@convex-dev/aggregate");
+  sum = unused("@convex-dev/aggregate");
+  at = unused("@convex-dev/aggregate");
+  indexOf = unused("@convex-dev/aggregate");
+  min = unused("@convex-dev/aggregate");
+  max = unused("@convex-dev/aggregate");
+  random = unused("@convex-dev/aggregate");
+  paginate = unused("@convex-dev/aggregate");
+  iter = unused("@convex-dev/aggregate");
+  insert = unused("@convex-dev/aggregate");
+  delete = unused("@convex-dev/aggregate");
+  replace = unused("@convex-dev/aggregate");
+  insertIfDoesNotExist = unused("@convex-dev/aggregate");
+  deleteIfExists = unused("@convex-dev/aggregate");
+  replaceOrInsert = unused("@convex-dev/aggregate");
+  clear = unused("@convex-dev/aggregate");
+
+  constructor(_component: unknown, _options?: unknown) {}
+
+  // Passed to `triggers.register(...)` at import time
+  trigger() {
+    return unused("@convex-dev/aggregate");
+  }
+  idempotentTrigger() {
+    return unused("@convex-dev/aggregate");
+  }
+}
+
+export class TableAggregate extends AggregateClient {}
+export class DirectAggregate extends AggregateClient {}
+
+// ---------------------------------------------------------------------------
+// @convex-dev/workflow
+// ---------------------------------------------------------------------------
+
+export const vWorkflowId = v.string();
+
+export class WorkflowManager {
+  start = unused("@convex-dev/workflow");
+  status = unused("@convex-dev/workflow");
+  cancel = unused("@convex-dev/workflow");
+  cleanup = unused("@convex-dev/workflow");
+  restart = unused("@convex-dev/workflow");
+
+  constructor(_component: unknown, _options?: unknown) {}
+
+  // The workflow runs as an internal mutation taking the declared args
+  define(config: { args?: unknown; returns?: unknown }) {
+    const workflow = internalMutation({ args: config.args ?? {}, returns: config.returns });
+    return fromComponent("workflow", workflow);
+  }
+}
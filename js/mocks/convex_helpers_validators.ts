@@ -0,0 +1,63 @@
+// Mock implementation of `convex-helpers/validators` — the validator
+// utilities, built from `convex/values` so they normalize like the `v.*`
+// calls they stand for. `nullable(x)` is `v.union(x, v.null())`,
+// `literals("a", "b")` is a union of literals, and `partial(...)` makes every
+// field optional, so they map to the same Rust types as the long forms.
+//
+// Helpers that need a schema or only matter at runtime (`doc`, `typedV`,
+// `validate`, ...) aren't mocked; import them through a helper stub instead.
+
+import { v } from "convex/values";
+
+type Validator = ReturnType<typeof v.any>;
+type Fields = Record<string, Validator>;
+
+const isValidator = (value: unknown): value is Validator & { fields?: Fields } =>
+  typeof value === "object" && value !== null && (value as { isConvexValidator?: boolean }).isConvexValidator === true;
+
+export const literals = (...values: (string | number | boolean | bigint)[]) =>
+  v.union(...values.map((value) => v.literal(value)));
+
+export const nullable = (inner: Validator) => v.union(inner, v.null());
+
+// An object of fields or a `v.object(...)`, with every field optional
+export const partial = (fieldsOrObject: Fields | Validator) => {
+  const fields = isValidator(fieldsOrObject) ? (fieldsOrObject.fields ?? {}) : fieldsOrObject;
+  const optional = Object.fromEntries(
+    Object.entries(fields).map(([name, field]) => [
+      name,
+      (field as { isOptional?: string }).isOptional === "optional" ? field : v.optional(field),
+    ]),
+  );
+  return isValidator(fieldsOrObject) ? v.object(optional) : optional;
+};
+
+// Brands only exist in TypeScript
+export const brandedString = (_brand: string) => v.string();
+
+// `pretend(x)` declares the type of `x` whatever is stored
+export const pretend = (typeToUse: Validator, _validator?: Validator) => typeToUse;
+
+export const deprecated = v.optional(v.any());
+
+export const systemFields = (tableName: string) => ({
+  _id: v.id(tableName),
+  _creationTime: v.number(),
+});
+
+export const withSystemFields = (tableName: string, fields: Fields) => ({
+  ...fields,
+  ...systemFields(tableName),
+});
+
+// Shorthands for the `v.*` builders
+export const { id, object, array, record, literal, optional, union } = v;
+export const any = v.any();
+export const string = v.string();
+export const number = v.number();
+export const float64 = v.float64();
+export const boolean = v.boolean();
+export const bigint = v.int64();
+export const int64 = v.int64();
+export const bytes = v.bytes();
+export const null_ = v.null();
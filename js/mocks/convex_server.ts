@@ -13,40 +13,66 @@ type Descriptor = Record<string, unknown>;
 // Schema collection
 // ---------------------------------------------------------------------------
 
+export interface IndexDef {
+  name: string;
+  fields: string[];
+}
+
 export interface TableDef {
   name: string;
   columns: Array<{ name: string; data_type: Descriptor }>;
+  indexes: IndexDef[];
 }
 
-export const __schema: { tables: TableDef[] } = { tables: [] };
+export interface SchemaOptions {
+  schemaValidation: boolean;
+  strictTableNameTypes: boolean;
+}
+
+export const __schema: { tables: TableDef[]; options: SchemaOptions } = {
+  tables: [],
+  options: { schemaValidation: true, strictTableNameTypes: true },
+};
 
-// Table builder — supports chainable .index() / .searchIndex() (ignored)
+// Table builder — records .index() fields; .searchIndex() / .vectorIndex() are ignored
 interface TableBuilder {
   _validator: unknown;
-  index: (...args: unknown[]) => TableBuilder;
+  _indexes: IndexDef[];
+  index: (name: string, fields: string[]) => TableBuilder;
   searchIndex: (...args: unknown[]) => TableBuilder;
+  vectorIndex: (...args: unknown[]) => TableBuilder;
 }
 
 export function defineTable(validator: unknown): TableBuilder {
   const builder: TableBuilder = {
     _validator: validator,
-    index: () => builder,
+    _indexes: [],
+    index: (name, fields) => {
+      builder._indexes.push({ name, fields: [...fields] });
+      return builder;
+    },
     searchIndex: () => builder,
+    vectorIndex: () => builder,
   };
   return builder;
 }
 
 export function defineSchema(
   tables: Record<string, TableBuilder>,
-  _options?: unknown,
+  options?: Partial<SchemaOptions>,
 ): typeof __schema {
+  // Both options default to true in Convex
+  __schema.options = {
+    schemaValidation: options?.schemaValidation ?? true,
+    strictTableNameTypes: options?.strictTableNameTypes ?? true,
+  };
   for (const [name, table] of Object.entries(tables)) {
     // Normalize the validator into codegen-compatible descriptors.
     // The validator can be:
     //   1. A real Convex v.object() validator (has kind: "object", fields: {...})
     //   2. A raw record of field→validator: { name: v.string(), ... }
     //   3. An already-normalized mock descriptor (has type: "object", properties)
-    const normalized = normalize(table._validator);
+    const normalized = normalize(table._validator, `table ${name}`);
 
     // Extract properties from the normalized object descriptor
     const properties: Record<string, Descriptor> =
@@ -58,7 +84,7 @@ export function defineSchema(
       name: fieldName,
       data_type: dt as Descriptor,
     }));
-    __schema.tables.push({ name, columns });
+    __schema.tables.push({ name, columns, indexes: table._indexes });
   }
   return __schema;
 }
@@ -74,6 +100,8 @@ export interface FunctionDef {
     returns?: unknown;
     handler?: unknown;
   };
+  // The component whose client created the function, e.g. "rateLimiter"
+  __component?: string;
 }
 
 function makeFunctionRegistrar(type: string) {
@@ -107,6 +135,17 @@ export class MutationBuilder {}
 export class ActionBuilder {}
 export class HttpActionBuilder {}
 
+// convex.config.ts — `defineApp().use(component)` registers components, which
+// the extractor doesn't need; the config file only has to evaluate
+interface AppDefinition {
+  use: (component: unknown, options?: { name?: string }) => unknown;
+}
+
+export function defineApp(): AppDefinition {
+  return { use: (_component, _options) => new Proxy({}, proxyHandler) };
+}
+export const defineComponent = (_name: string) => defineApp();
+
 // _generated/api.js imports these from convex/server
 const proxyHandler: ProxyHandler<object> = {
   get: (_target, _prop) => new Proxy({}, proxyHandler),
@@ -1,53 +1,66 @@
-// Mock implementation of `convex/values` — every v.* function returns a JSON
-// descriptor that matches what codegen.rs expects in data_type fields.
+// Mock implementation of `convex/values` for the embedded backend, which
+// can't load the npm package. Validators have the fields real Convex
+// validators serialize (`kind`, `isOptional`, `fields`, `element`, `members`,
+// ...), so normalize(), the other mocks, and custom validators treat them
+// exactly like the real ones. Chained `.optional()` comes from
+// mocks/chaining.ts, as it does for the real package.
 
-type Descriptor = Record<string, unknown>;
+type Fields = Record<string, Validator>;
+
+class Validator {
+  readonly isConvexValidator = true;
+  readonly isOptional: "required" | "optional" = "required";
+
+  constructor(readonly kind: string, props: Record<string, unknown> = {}) {
+    Object.assign(this, props);
+  }
+
+  // What v.optional() calls on real validators
+  asOptional(): Validator {
+    return Object.assign(Object.create(Object.getPrototypeOf(this)), this, { isOptional: "optional" });
+  }
+
+  // Object validators only; `this.fields` is unset for the other kinds
+  extend(fields: Fields): Validator {
+    return v.object({ ...this.fieldsOf(), ...fields });
+  }
+
+  pick(...keys: string[]): Validator {
+    return v.object(Object.fromEntries(Object.entries(this.fieldsOf()).filter(([key]) => keys.includes(key))));
+  }
+
+  omit(...keys: string[]): Validator {
+    return v.object(Object.fromEntries(Object.entries(this.fieldsOf()).filter(([key]) => !keys.includes(key))));
+  }
+
+  partial(): Validator {
+    return v.object(Object.fromEntries(Object.entries(this.fieldsOf()).map(([key, field]) => [key, v.optional(field)])));
+  }
+
+  private fieldsOf(): Fields {
+    return (this as { fields?: Fields }).fields ?? {};
+  }
+}
 
 const v = {
-  // Primitives
-  string: (): Descriptor => ({ type: "string" }),
-  number: (): Descriptor => ({ type: "number" }),
-  boolean: (): Descriptor => ({ type: "boolean" }),
-  int64: (): Descriptor => ({ type: "int64" }),
-  float64: (): Descriptor => ({ type: "float64" }),
-  null: (): Descriptor => ({ type: "null" }),
-  null_: (): Descriptor => ({ type: "null" }),  // alias for compatibility
-  any: (): Descriptor => ({ type: "any" }),
-  bytes: (): Descriptor => ({ type: "bytes" }),
-
-  // Reference to another table
-  id: (tableName: string): Descriptor => ({ type: "id", tableName }),
-
-  // Container types — key names must match what codegen.rs reads:
-  //   optional → "inner"
-  //   array    → "elements"
-  //   object   → "properties" (map of fieldName → type descriptor)
-  //   record   → "keyType" + "valueType"
-  //   union    → "variants" (array of type descriptors)
-  optional: (inner: Descriptor): Descriptor => ({ type: "optional", inner }),
-
-  array: (elements: Descriptor): Descriptor => ({ type: "array", elements }),
-
-  object: (fields: Record<string, Descriptor>): Descriptor => ({
-    type: "object",
-    properties: fields,
-  }),
-
-  record: (keyType: Descriptor, valueType: Descriptor): Descriptor => ({
-    type: "record",
-    keyType,
-    valueType,
-  }),
-
-  union: (...variants: Descriptor[]): Descriptor => ({
-    type: "union",
-    variants,
-  }),
-
-  literal: (value: string | number | boolean): Descriptor => ({
-    type: "literal",
-    value,
-  }),
+  string: () => new Validator("string"),
+  // Real v.number() is a float64 validator
+  number: () => new Validator("float64"),
+  float64: () => new Validator("float64"),
+  int64: () => new Validator("int64"),
+  bigint: () => new Validator("int64"),
+  boolean: () => new Validator("boolean"),
+  null: () => new Validator("null"),
+  any: () => new Validator("any"),
+  bytes: () => new Validator("bytes"),
+  id: (tableName: string) => new Validator("id", { tableName }),
+  literal: (value: string | number | boolean | bigint) => new Validator("literal", { value }),
+  object: (fields: Fields) => new Validator("object", { fields }),
+  array: (element: Validator) => new Validator("array", { element }),
+  record: (key: Validator, value: Validator) => new Validator("record", { key, value }),
+  union: (...members: Validator[]) => new Validator("union", { members }),
+  optional: (inner: Validator) => inner.asOptional(),
+  nullable: (inner: Validator) => v.union(inner, v.null()),
 };
 
 export { v };
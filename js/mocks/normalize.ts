@@ -7,9 +7,23 @@
 // Codegen expects:
 //   { type: "object", properties: { ... } }
 //   { type: "optional", inner: { type: "string" } }
+//
+// `location` names the value in the resolution trace (e.g. `table users.meta`);
+// nested values extend it the way the report's warnings do.
+
+import { trace } from "./trace.ts";
 
 type Descriptor = Record<string, unknown>;
 
+// Custom validator factories (passed via TYPEGEN_CUSTOM_VALIDATORS) are served
+// as `v.literal(CUSTOM_VALIDATOR_PREFIX + name)`, which survives v.optional(),
+// v.array(), and the other wrappers; normalize() swaps in their descriptor.
+export const CUSTOM_VALIDATOR_PREFIX = "\0convex-typegen:custom:";
+
+export const customValidators: Record<string, { module: string; descriptor: Descriptor }> = JSON.parse(
+	process.env.TYPEGEN_CUSTOM_VALIDATORS || "{}",
+);
+
 /** Returns true if `val` is a real Convex validator (has isConvexValidator flag). */
 function isConvexValidator(val: unknown): val is Descriptor {
 	return (
@@ -33,27 +47,28 @@ function isCodegenDescriptor(val: unknown): val is Descriptor {
  * Normalize a value that could be either a real Convex validator or a raw
  * record of field→validator. Returns codegen-compatible descriptor.
  */
-export function normalize(val: unknown): Descriptor {
+export function normalize(val: unknown, location = "value"): Descriptor {
 	// Already a codegen descriptor (e.g. from mock or paginationOptsValidator)
 	if (isCodegenDescriptor(val)) return val as Descriptor;
 
 	// Real Convex validator object
-	if (isConvexValidator(val)) return normalizeValidator(val);
+	if (isConvexValidator(val)) return normalizeValidator(val, location);
 
 	// Raw record of { fieldName: validator } — wrap as object
 	if (val !== null && typeof val === "object" && !Array.isArray(val)) {
 		const properties: Record<string, Descriptor> = {};
 		for (const [key, v] of Object.entries(val as Record<string, unknown>)) {
-			properties[key] = normalize(v);
+			properties[key] = normalize(v, `${location}.${key}`);
 		}
 		return { type: "object", properties };
 	}
 
 	// Fallback — treat as opaque
+	trace({ kind: "any", location, reason: `not a validator (${val === null ? "null" : typeof val})` });
 	return { type: "any" };
 }
 
-function normalizeValidator(v: Descriptor): Descriptor {
+function normalizeValidator(v: Descriptor, location: string): Descriptor {
 	const kind = v.kind as string;
 	const isOpt = v.isOptional === "optional";
 
@@ -66,11 +81,15 @@ function normalizeValidator(v: Descriptor): Descriptor {
 		case "boolean":
 		case "int64":
 		case "null":
-		case "any":
 		case "bytes":
 			inner = { type: kind };
 			break;
 
+		case "any":
+			trace({ kind: "any", location, reason: "declared with v.any()" });
+			inner = { type: "any" };
+			break;
+
 		// Real Convex v.number() → kind:"float64", but codegen.rs expects type:"number"
 		case "float64":
 			inner = { type: "number" };
@@ -80,16 +99,21 @@ function normalizeValidator(v: Descriptor): Descriptor {
 			inner = { type: "id", tableName: v.tableName as string };
 			break;
 
-		case "literal":
-			inner = { type: "literal", value: v.value };
+		case "literal": {
+			const custom =
+				typeof v.value === "string" && v.value.startsWith(CUSTOM_VALIDATOR_PREFIX)
+					? customValidators[v.value.slice(CUSTOM_VALIDATOR_PREFIX.length)]
+					: undefined;
+			inner = custom ? custom.descriptor : { type: "literal", value: v.value };
 			break;
+		}
 
 		case "object": {
 			const fields = v.fields as Record<string, unknown> | undefined;
 			const properties: Record<string, Descriptor> = {};
 			if (fields) {
 				for (const [key, fieldVal] of Object.entries(fields)) {
-					properties[key] = normalize(fieldVal);
+					properties[key] = normalize(fieldVal, `${location}.${key}`);
 				}
 			}
 			inner = { type: "object", properties };
@@ -98,7 +122,7 @@ function normalizeValidator(v: Descriptor): Descriptor {
 
 		case "array": {
 			const element = v.element as unknown;
-			inner = { type: "array", elements: normalize(element) };
+			inner = { type: "array", elements: normalize(element, `${location}[]`) };
 			break;
 		}
 
@@ -106,7 +130,7 @@ function normalizeValidator(v: Descriptor): Descriptor {
 			const members = v.members as unknown[];
 			inner = {
 				type: "union",
-				variants: members.map((m) => normalize(m)),
+				variants: members.map((m, i) => normalize(m, `${location}|${i}`)),
 			};
 			break;
 		}
@@ -114,14 +138,15 @@ function normalizeValidator(v: Descriptor): Descriptor {
 		case "record": {
 			inner = {
 				type: "record",
-				keyType: normalize(v.key),
-				valueType: normalize(v.value),
+				keyType: normalize(v.key, `${location}{key}`),
+				valueType: normalize(v.value, `${location}{value}`),
 			};
 			break;
 		}
 
 		default:
 			// Unknown kind — treat as any
+			trace({ kind: "any", location, reason: `unrecognized validator kind \`${kind}\`` });
 			inner = { type: "any" };
 			break;
 	}
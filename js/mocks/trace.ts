@@ -0,0 +1,18 @@
+// Resolution trace — records what the extractor intercepted and resolved, so
+// a validator that ended up as `any` can be traced back to its cause.
+//
+// Only collected when the Rust side sets TYPEGEN_TRACE_RESOLVE=1; the events
+// are printed with the rest of the output and land in the generation report.
+
+export type TraceEvent =
+  | { kind: "import"; specifier: string; importer?: string; served: string }
+  | { kind: "export"; file: string; name: string; registrar?: string }
+  | { kind: "any"; location: string; reason: string };
+
+export const tracing = process.env.TYPEGEN_TRACE_RESOLVE === "1";
+
+export const __trace: TraceEvent[] = [];
+
+export function trace(event: TraceEvent): void {
+  if (tracing) __trace.push(event);
+}
@@ -0,0 +1,149 @@
+// The extraction steps shared by both engines: Bun (extractor.ts) and the
+// embedded QuickJS backend (src/embedded.rs). The caller registers its module
+// interception first and receives the NDJSON records through `emit`.
+
+import type { FunctionDef } from "./mocks/convex_server.ts";
+import * as convexServer from "./mocks/convex_server.ts";
+import { normalize } from "./mocks/normalize.ts";
+import { __trace, trace, tracing } from "./mocks/trace.ts";
+import "./mocks/chaining.ts";
+
+type Descriptor = Record<string, unknown>;
+
+interface FunctionRecord {
+  name: string;
+  type: string;
+  params: Array<{ name: string; data_type: Descriptor }>;
+  return_type: Descriptor | null;
+  file_name: string;
+  module_path: string;
+  args_validator?: Descriptor;
+  component?: string;
+}
+
+// `SyntaxError: ...` or `error: ...`, in the shape of Bun's own error output
+function describeError(err: unknown): string {
+  if (typeof err === "object" && err !== null && "message" in err) {
+    const { name, message } = err as { name?: string; message: string };
+    return `${name && name !== "Error" ? name : "error"}: ${message}`;
+  }
+  return `error: ${String(err)}`;
+}
+
+const failFast = process.env.TYPEGEN_FAIL_FAST === "1";
+
+export async function run(
+  schemaPath: string,
+  functionPaths: string[],
+  emit: (record: Record<string, unknown>) => void,
+): Promise<void> {
+  // 2. Import schema — side-effect: populates __schema via defineSchema()
+  await import(schemaPath);
+
+  emit({ record: "options", options: convexServer.__schema.options });
+  for (const table of convexServer.__schema.tables) {
+    emit({ record: "table", ...table });
+  }
+
+  // 3. Import each function file and extract registered functions
+  for (const fp of functionPaths) {
+    const parts = fp.split(/[/\\]/);
+    const rawName = parts[parts.length - 1] ?? fp;
+    const fileName = rawName.replace(/\.ts$/, "");
+
+    // Compute module path relative to the `convex/` directory for API routing.
+    // e.g. "/abs/path/convex/model/chats.ts" → "model/chats"
+    // Falls back to fileName for top-level files.
+    const convexIdx = parts.findIndex((p) => p === "convex");
+    const modulePath =
+      convexIdx >= 0 && convexIdx < parts.length - 1
+        ? parts
+            .slice(convexIdx + 1)
+            .join("/")
+            .replace(/\.ts$/, "")
+        : fileName;
+
+    // A file that fails to load is reported and skipped, so one run lists
+    // every broken file, unless the caller only wants the first one
+    let mod: Record<string, unknown>;
+    try {
+      mod = await import(fp);
+    } catch (err) {
+      emit({ record: "error", file: fp, message: describeError(err) });
+      if (failFast) break;
+      continue;
+    }
+
+    for (const [exportName, value] of Object.entries(mod)) {
+      const isFunction =
+        value !== null &&
+        typeof value === "object" &&
+        "__type" in (value as object);
+      trace({
+        kind: "export",
+        file: modulePath,
+        name: exportName,
+        registrar: isFunction ? (value as FunctionDef).__type : undefined,
+      });
+      if (isFunction) {
+        const def = value as FunctionDef;
+        const location = `function ${modulePath}:${exportName}`;
+        const config = def.__config ?? {};
+
+        // Extract and normalize params from args.
+        // args is a Convex validator (v.object({ ... })), real under Bun and
+        // mocked under QuickJS — normalize() converts it to codegen format.
+        const argsRaw = config.args;
+        let params: Array<{ name: string; data_type: Descriptor }> = [];
+        // Args that aren't an object of fields, e.g. v.union(v.object(...), ...)
+        let argsValidator: Descriptor | undefined;
+
+        if (argsRaw !== undefined && argsRaw !== null) {
+          const normalized = normalize(argsRaw, `${location} args`);
+          // After normalization, should be { type: "object", properties: { ... } }
+          if (
+            normalized.type === "object" &&
+            normalized.properties &&
+            typeof normalized.properties === "object"
+          ) {
+            params = Object.entries(
+              normalized.properties as Record<string, Descriptor>,
+            ).map(([paramName, dt]) => ({
+              name: paramName,
+              data_type: dt,
+            }));
+          } else if (normalized.type !== "object") {
+            argsValidator = normalized;
+          }
+        }
+
+        // Normalize return type if present
+        const returnsRaw = config.returns;
+        const returnType =
+          returnsRaw !== undefined && returnsRaw !== null
+            ? normalize(returnsRaw, `${location} returns`)
+            : null;
+
+        const fn: FunctionRecord = {
+          name: exportName,
+          type: def.__type,
+          params,
+          return_type: returnType,
+          file_name: fileName,
+          module_path: modulePath,
+          ...(argsValidator ? { args_validator: argsValidator } : {}),
+          ...(def.__component ? { component: def.__component } : {}),
+        };
+        emit({ record: "function", ...fn });
+      }
+    }
+  }
+
+  // 4. Finish the output — the end record tells extract.rs nothing was cut off
+  if (tracing) {
+    for (const event of __trace) {
+      emit({ record: "trace", event });
+    }
+  }
+  emit({ record: "end" });
+}
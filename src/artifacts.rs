@@ -0,0 +1,354 @@
+//! Extra output files rendered from the same extraction as the Rust code; see
+//! [`crate::Artifact`].
+
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::codegen::{
+    args_struct_name, call_method_name, escape_rust_keyword, table_struct_name, try_match_table_shape, TypeResolver,
+};
+use crate::descriptors::optional_inner;
+use crate::errors::ConvexTypeGeneratorError;
+use crate::types::{ConvexFunction, ConvexFunctions, ConvexSchema, ConvexTable};
+use crate::{migration, Artifact, Configuration};
+
+/// Render every configured artifact. Nothing is written yet, so a failure in
+/// the Rust codegen leaves all outputs untouched.
+pub(crate) fn render(
+    config: &Configuration,
+    schema: &ConvexSchema,
+    functions: &[ConvexFunction],
+    previous: Option<&ConvexSchema>,
+) -> Result<Vec<(Artifact, String)>, ConvexTypeGeneratorError>
+{
+    config
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            let contents = match artifact {
+                Artifact::ModelJson { .. } => model_json(schema, functions)?,
+                Artifact::TypeScriptDeclarations { .. } => typescript_declarations(config, schema, functions),
+                Artifact::SmokeTest { module, .. } => smoke_test(config, functions, module),
+                Artifact::MigrationScaffold { .. } => match previous {
+                    Some(previous) => migration::scaffold(previous, schema, config.typed_ids, config.flatten_system_fields),
+                    None => {
+                        return Err(ConvexTypeGeneratorError::InvalidPreviousModel {
+                            details: "the migration_scaffold artifact needs previous_model to be set".to_string(),
+                        })
+                    }
+                },
+            };
+            Ok((artifact.clone(), contents))
+        })
+        .collect()
+}
+
+/// Write the rendered artifacts, creating their directories and skipping files
+/// that already hold their contents so their mtime stays the same.
+pub(crate) fn write(rendered: &[(Artifact, String)]) -> Result<(), ConvexTypeGeneratorError>
+{
+    for (artifact, contents) in rendered {
+        let path = artifact.path();
+        if std::fs::read(path).is_ok_and(|existing| existing == contents.as_bytes()) {
+            continue;
+        }
+        let io_error = |error| ConvexTypeGeneratorError::IOError {
+            file: path.display().to_string(),
+            error,
+        };
+        // Shared models go to a directory of their own in the target directory
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        std::fs::write(path, contents).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+fn model_json(schema: &ConvexSchema, functions: &[ConvexFunction]) -> Result<String, ConvexTypeGeneratorError>
+{
+    let model = serde_json::json!({ "schema": schema, "functions": functions });
+    let mut json = serde_json::to_string_pretty(&model).map_err(ConvexTypeGeneratorError::SerializationFailed)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Read a model JSON written by [`Artifact::ModelJson`], for
+/// [`Configuration::model_input`].
+pub(crate) fn load_model(path: &Path) -> Result<(ConvexSchema, ConvexFunctions), ConvexTypeGeneratorError>
+{
+    #[derive(Deserialize)]
+    struct Model
+    {
+        schema: ConvexSchema,
+        functions: ConvexFunctions,
+    }
+
+    let json = std::fs::read_to_string(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    })?;
+    let model: Model = serde_json::from_str(&json).map_err(|e| ConvexTypeGeneratorError::InvalidModel {
+        path: path.to_path_buf(),
+        details: e.to_string(),
+    })?;
+    Ok((model.schema, model.functions))
+}
+
+/// Interfaces named after the generated Rust types, with each field's Rust
+/// type in its doc comment, plus a `ConvexApi` interface with the client
+/// methods.
+fn typescript_declarations(config: &Configuration, schema: &ConvexSchema, functions: &[ConvexFunction]) -> String
+{
+    let mut resolver = TypeResolver::new(&schema.tables, config);
+    let mut out = String::from(
+        "// This file is generated by convex-typegen. Do not modify directly.\n// TypeScript view of the generated Rust \
+         types, for documentation only.\n",
+    );
+
+    for table in &schema.tables {
+        let _ = writeln!(out, "\n/** Table `{}`. */", table.name);
+        let _ = writeln!(out, "export interface {} {{", table_struct_name(&table.name));
+        out.push_str("  /** `String` */\n  _id: string;\n");
+        out.push_str("  /** `f64` */\n  _creationTime: number;\n");
+        for column in &table.columns {
+            let rust_type = resolver.column_type(table, column);
+            push_field(&mut out, &column.name, &column.data_type, &rust_type, &schema.tables);
+        }
+        out.push_str("}\n");
+    }
+
+    let public: Vec<&ConvexFunction> = functions.iter().filter(|f| !f.type_.starts_with("internal")).collect();
+    for func in functions {
+        let module = func.module_path.as_deref().unwrap_or(&func.file_name);
+        let _ = writeln!(out, "\n/** Args of {} `{module}:{}`. */", func.type_, func.name);
+        if let Some(args) = func.whole_args() {
+            let rust_type = resolver.whole_args_type(func, args);
+            let _ = writeln!(out, "/** `{rust_type}` */");
+            let _ = writeln!(
+                out,
+                "export type {} = {};",
+                args_struct_name(func),
+                ts_type(args, &schema.tables)
+            );
+            continue;
+        }
+        let _ = writeln!(out, "export interface {} {{", args_struct_name(func));
+        for param in &func.params {
+            let rust_type = resolver.param_type(func, param);
+            push_field(&mut out, &param.name, &param.data_type, &rust_type, &schema.tables);
+        }
+        out.push_str("}\n");
+    }
+
+    if !public.is_empty() {
+        out.push_str("\n/** The `ConvexApi` methods, by their Rust names. */\nexport interface ConvexApi {\n");
+        for func in public {
+            let module = func.module_path.as_deref().unwrap_or(&func.file_name);
+            let returns = match (&func.return_type, resolver.return_type(func)) {
+                (Some(data_type), Some(rust_type)) => {
+                    let _ = writeln!(out, "  /** `{module}:{}`, returns `{rust_type}` */", func.name);
+                    ts_type(data_type, &schema.tables)
+                }
+                _ => {
+                    let _ = writeln!(out, "  /** `{module}:{}` */", func.name);
+                    "unknown".to_string()
+                }
+            };
+            let _ = writeln!(
+                out,
+                "  {}(args: {}): Promise<{returns}>;",
+                call_method_name(func, config.method_naming),
+                args_struct_name(func)
+            );
+        }
+        out.push_str("}\n");
+    }
+
+    out
+}
+
+/// A `main` that calls each query whose args are all optional with them unset,
+/// printing `ok`, `FAIL`, or `skip` per query and exiting with 1 on a failure.
+fn smoke_test(config: &Configuration, functions: &[ConvexFunction], module: &str) -> String
+{
+    let tag = config.version_tag.as_deref().unwrap_or("");
+    let mut calls = String::new();
+    let mut skipped = String::new();
+    let mut count = 0;
+    // Feature gates can leave no calls in some builds
+    let gated = config.feature_gates.is_some();
+
+    for func in functions.iter().filter(|f| f.type_ == "query") {
+        let module_path = func.module_path.as_deref().unwrap_or(&func.file_name);
+        let path = format!("{module_path}:{}", func.name);
+        if func.whole_args().is_some() || !func.params.iter().all(|param| optional_inner(&param.data_type).is_some()) {
+            let _ = writeln!(skipped, "    println!(\"skip  {path} (required args)\");");
+            continue;
+        }
+        let args = if !func.takes_args() {
+            String::new()
+        } else {
+            let fields: Vec<String> = func
+                .params
+                .iter()
+                .map(|param| format!("{}: None", escape_rust_keyword(&param.name)))
+                .collect();
+            format!("{}{tag} {{ {} }}", args_struct_name(func), fields.join(", "))
+        };
+        let call = format!("api.{}({args}).await", call_method_name(func, config.method_naming));
+        if let Some(feature) = config.feature_gates.as_ref().and_then(|gates| gates.for_kind("query")) {
+            let _ = writeln!(calls, "    #[cfg(feature = \"{feature}\")]");
+        }
+        // Deprecated queries still deserve a check while they're deployed
+        if config.deprecated_functions.contains_key(&path) {
+            let _ = writeln!(calls, "    #[allow(deprecated)]");
+        }
+        let outcome = format!("{call}.map(|_| ()).map_err(|e| e.to_string())");
+        let _ = writeln!(calls, "    report(&mut failures, \"{path}\", {outcome});");
+        count += 1;
+    }
+
+    let allow_unused = if gated || count == 0 {
+        "    #[allow(unused_mut)]\n"
+    } else {
+        ""
+    };
+    let allow_dead = if gated || count == 0 { "#[allow(dead_code)]\n" } else { "" };
+    // Component client methods live on traits in their own module, which a
+    // version tag nests inside the versioned one
+    let component_import = if functions.iter().any(|f| f.type_ == "query" && f.component.is_some()) {
+        match &config.version_tag {
+            Some(tag) => format!("#[allow(unused_imports)]\nuse {module}::{}::components::*;\n", tag.to_ascii_lowercase()),
+            None => format!("#[allow(unused_imports)]\nuse {module}::components::*;\n"),
+        }
+    } else {
+        String::new()
+    };
+    format!(
+        "// This file is generated by convex-typegen. Do not modify directly.\n\
+         //\n\
+         // Smoke test: calls every query whose args are all optional and reports the\n\
+         // ones that fail, e.g. because the deployed function no longer matches the\n\
+         // generated types. Usage: convex_smoke <deployment-url>\n\
+         \n\
+         use {module}::*;\n\
+         {component_import}\
+         \n\
+         #[tokio::main]\n\
+         async fn main() {{\n\
+         \x20   let Some(url) = std::env::args().nth(1) else {{\n\
+         \x20       eprintln!(\"usage: convex_smoke <deployment-url>\");\n\
+         \x20       std::process::exit(2);\n\
+         \x20   }};\n\
+         \x20   let api = match convex::ConvexClient::new(&url).await {{\n\
+         \x20       Ok(client) => ConvexApiClient{tag}::new(client),\n\
+         \x20       Err(error) => {{\n\
+         \x20           eprintln!(\"failed to connect to {{url}}: {{error}}\");\n\
+         \x20           std::process::exit(2);\n\
+         \x20       }}\n\
+         \x20   }};\n\
+         \n\
+         {allow_unused}\
+         \x20   let mut failures = 0;\n\
+         {calls}\
+         {skipped}\
+         \x20   println!(\"{{failures}} of {count} queries failed\");\n\
+         \x20   if failures > 0 {{\n\
+         \x20       std::process::exit(1);\n\
+         \x20   }}\n\
+         }}\n\
+         \n\
+         {allow_dead}\
+         fn report(failures: &mut usize, query: &str, outcome: Result<(), String>) {{\n\
+         \x20   match outcome {{\n\
+         \x20       Ok(()) => println!(\"ok    {{query}}\"),\n\
+         \x20       Err(error) => {{\n\
+         \x20           *failures += 1;\n\
+         \x20           println!(\"FAIL  {{query}}: {{error}}\");\n\
+         \x20       }}\n\
+         \x20   }}\n\
+         }}\n"
+    )
+}
+
+fn push_field(out: &mut String, name: &str, data_type: &JsonValue, rust_type: &str, tables: &[ConvexTable])
+{
+    let _ = writeln!(out, "  /** `{rust_type}` */");
+    match data_type["type"].as_str() {
+        Some("optional") => {
+            let _ = writeln!(out, "  {}?: {};", ts_key(name), ts_type(&data_type["inner"], tables));
+        }
+        _ => {
+            let _ = writeln!(out, "  {}: {};", ts_key(name), ts_type(data_type, tables));
+        }
+    }
+}
+
+/// The TypeScript type of a descriptor, as Convex's own `Infer` would give it.
+/// Documents shaped like a table use that table's interface.
+pub(crate) fn ts_type(data_type: &JsonValue, tables: &[ConvexTable]) -> String
+{
+    let document = data_type["properties"].as_object().filter(|props| props.contains_key("_id"));
+    if let Some(table) = document.and_then(|props| try_match_table_shape(props, tables)) {
+        return table;
+    }
+    match data_type["type"].as_str().unwrap_or("any") {
+        "string" | "id" => "string".to_string(),
+        "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "int64" => "bigint".to_string(),
+        "bytes" => "ArrayBuffer".to_string(),
+        "null" => "null".to_string(),
+        "literal" => data_type["value"].to_string(),
+        "optional" => format!("{} | undefined", ts_type(&data_type["inner"], tables)),
+        "array" => match data_type["elements"]["type"].as_str() {
+            Some("union" | "optional") => format!("({})[]", ts_type(&data_type["elements"], tables)),
+            _ => format!("{}[]", ts_type(&data_type["elements"], tables)),
+        },
+        "record" => format!(
+            "Record<{}, {}>",
+            ts_type(&data_type["keyType"], tables),
+            ts_type(&data_type["valueType"], tables)
+        ),
+        "union" => data_type["variants"]
+            .as_array()
+            .map(|variants| {
+                variants
+                    .iter()
+                    .map(|variant| ts_type(variant, tables))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            })
+            .unwrap_or_else(|| "never".to_string()),
+        "object" => match data_type["properties"].as_object() {
+            Some(props) if !props.is_empty() => {
+                let fields: Vec<String> = props
+                    .iter()
+                    .map(|(name, field)| match field["type"].as_str() {
+                        Some("optional") => format!("{}?: {}", ts_key(name), ts_type(&field["inner"], tables)),
+                        _ => format!("{}: {}", ts_key(name), ts_type(field, tables)),
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            _ => "Record<string, never>".to_string(),
+        },
+        _ => "any".to_string(),
+    }
+}
+
+/// A property name, quoted unless it is a plain identifier.
+fn ts_key(name: &str) -> String
+{
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if plain {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
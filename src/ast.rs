@@ -0,0 +1,2048 @@
+//! Static extraction — reads schema and function files without a JS runtime.
+//!
+//! This is the [`ExtractionBackend::Ast`](crate::ExtractionBackend::Ast) path. The
+//! files are tokenized and parsed in Rust, and only the declarative parts are
+//! evaluated:
+//!
+//! - `v.*` validator calls, including `.extend()`, `.pick()`, `.omit()`,
+//!   `.partial()`, and `.fields` on object validators
+//! - `const` bindings, object literals, and object spreads of known values
+//! - calls to local validator factories: arrow functions and `function`
+//!   declarations whose body is a single expression or `return`
+//! - imports of `convex/values`, `convex/server`, `_generated/*`, and relative
+//!   `.ts` modules (resolved recursively), plus `authTables` from
+//!   `@convex-dev/auth/server` and the validator utilities of
+//!   `convex-helpers/validators` (`nullable`, `literals`, `partial`, ...)
+//! - `defineSchema` and its options object, `defineTable` and its `.index()`
+//!   chain, and the `query` / `mutation` / `action` registrars and their
+//!   `internal*` variants
+//!
+//! Anything that needs real evaluation — wrapper factories such as
+//! `authedQuery({ ... })` (even local ones), helpers imported from packages,
+//! computed keys, or conditional validators — is reported as an error naming
+//! the construct. Such files need the Bun backend. `helper_stubs` are not
+//! consulted.
+//!
+//! The tokenizer and parser are hand-written rather than built on oxc, which
+//! the embedded backend uses to strip types. oxc is a large dependency, so it
+//! sits behind the `embedded` feature, while this backend, and the hybrid one
+//! built on it, is in every build. It also only has to follow the
+//! declarative subset above: everything else is reported as unsupported
+//! either way, so a full TypeScript AST would not let it evaluate more.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::errors::{ConvexTypeGeneratorError, Diagnostic};
+use crate::types::{
+    ConvexColumn, ConvexFunction, ConvexFunctionParam, ConvexIndex, ConvexSchema, ConvexTable, SchemaOptions,
+};
+use crate::CustomValidatorHandler;
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Why a file couldn't be extracted statically.
+#[derive(Debug, Clone)]
+pub(crate) struct AstError
+{
+    /// The file being extracted.
+    pub(crate) file: PathBuf,
+    /// The construct that couldn't be evaluated, with its line when known.
+    pub(crate) message: String,
+}
+
+impl From<AstError> for ConvexTypeGeneratorError
+{
+    fn from(error: AstError) -> Self
+    {
+        ConvexTypeGeneratorError::ExtractionFailed {
+            details: format!(
+                "{}: {} (the AST backend only understands static validators; use ExtractionBackend::Bun for this file)",
+                error.file.display(),
+                error.message
+            ),
+            causes: Vec::new(),
+        }
+    }
+}
+
+impl From<AstError> for Diagnostic
+{
+    fn from(error: AstError) -> Self
+    {
+        Diagnostic {
+            file: Some(error.file),
+            line: None,
+            message: error.message,
+            suggestion: Some("use ExtractionBackend::Bun or ExtractionBackend::Hybrid for this file".to_string()),
+        }
+    }
+}
+
+/// Extract the schema and all functions without spawning Bun.
+///
+/// Unless `fail_fast` is set, a file or export that can't be evaluated is
+/// skipped and reported in the returned diagnostics, so one run lists them
+/// all. With it set, the first one is returned as the error.
+pub(crate) fn extract(
+    schema_path: &Path,
+    function_paths: &[PathBuf],
+    custom_validators: &HashMap<String, CustomValidatorHandler>,
+    fail_fast: bool,
+) -> Result<(ConvexSchema, Vec<ConvexFunction>, Vec<Diagnostic>), ConvexTypeGeneratorError>
+{
+    let mut modules = ModuleCache::new(custom_validators);
+    let mut diagnostics = Vec::new();
+    let mut collect = |errors: Vec<AstError>| -> Result<(), ConvexTypeGeneratorError> {
+        let mut errors = errors.into_iter();
+        if fail_fast {
+            return errors.next().map_or(Ok(()), |first| Err(first.into()));
+        }
+        diagnostics.extend(errors.map(Diagnostic::from));
+        Ok(())
+    };
+    let schema = match extract_schema(schema_path, &mut modules) {
+        Ok(schema) => schema,
+        Err(errors) => {
+            collect(errors)?;
+            ConvexSchema {
+                tables: Vec::new(),
+                options: SchemaOptions::default(),
+            }
+        }
+    };
+    let mut functions = Vec::new();
+    for path in function_paths {
+        match extract_functions(path, &mut modules) {
+            Ok(found) => functions.extend(found),
+            Err(errors) => collect(errors)?,
+        }
+    }
+    Ok((schema, functions, diagnostics))
+}
+
+/// Extract the tables from the `defineSchema(...)` default export of a schema file.
+///
+/// A file without a default export yields an empty schema. Fails with one
+/// error per table that can't be evaluated.
+pub(crate) fn extract_schema(schema_path: &Path, modules: &mut ModuleCache) -> Result<ConvexSchema, Vec<AstError>>
+{
+    let path = absolute(schema_path);
+    let fail = |message: String| AstError {
+        file: path.clone(),
+        message,
+    };
+
+    let module = modules.load(&path).map_err(|message| vec![fail(message)])?;
+    let Some(default_export) = &module.default_export else {
+        return Ok(ConvexSchema {
+            tables: Vec::new(),
+            options: SchemaOptions::default(),
+        });
+    };
+
+    match modules.eval(&module, default_export) {
+        Val::Schema(tables, options) => {
+            let mut schema_tables = Vec::new();
+            let mut errors = Vec::new();
+            for (name, table) in tables {
+                match table {
+                    Ok(table) => schema_tables.push(ConvexTable {
+                        name,
+                        columns: ordered_properties(&table.desc, &table.order)
+                            .into_iter()
+                            .map(|(name, data_type)| ConvexColumn { name, data_type })
+                            .collect(),
+                        indexes: table
+                            .indexes
+                            .into_iter()
+                            .map(|(name, fields)| ConvexIndex { name, fields })
+                            .collect(),
+                    }),
+                    Err(reason) => errors.push(fail(reason)),
+                }
+            }
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            Ok(ConvexSchema {
+                tables: schema_tables,
+                options,
+            })
+        }
+        Val::Opaque(reason) | Val::Unsupported(reason) => Err(vec![fail(reason)]),
+        _ => Err(vec![fail("the default export is not a defineSchema(...) call".to_string())]),
+    }
+}
+
+/// Extract every exported query, mutation, and action from a function file.
+///
+/// Functions are returned in export-name order, matching the Bun extractor.
+/// Fails with one error per export that can't be evaluated.
+pub(crate) fn extract_functions(
+    function_path: &Path,
+    modules: &mut ModuleCache,
+) -> Result<Vec<ConvexFunction>, Vec<AstError>>
+{
+    let path = absolute(function_path);
+    let fail = |message: String| AstError {
+        file: path.clone(),
+        message,
+    };
+
+    let module = modules.load(&path).map_err(|message| vec![fail(message)])?;
+    let (file_name, module_path) = module_identity(&path);
+
+    let mut exports: Vec<(String, Val)> = module
+        .exports
+        .iter()
+        .map(|(exported, local)| (exported.clone(), modules.binding(&module, local, 0)))
+        .collect();
+    if let Some(default_export) = &module.default_export {
+        exports.push(("default".to_string(), modules.eval(&module, default_export)));
+    }
+    exports.sort_by(|a, b| a.0.cmp(&b.0));
+    exports.dedup_by(|a, b| a.0 == b.0);
+
+    let mut functions = Vec::new();
+    let mut errors = Vec::new();
+    for (name, value) in exports {
+        match value {
+            Val::Function { kind, args, returns } => {
+                let function = (|| {
+                    let (params, args_validator) = match args {
+                        Some(args) => {
+                            let (desc, order) =
+                                to_validator(&args).map_err(|reason| fail(format!("args of `{name}`: {reason}")))?;
+                            if desc["type"] == "object" {
+                                let params = ordered_properties(&desc, &order)
+                                    .into_iter()
+                                    .map(|(name, data_type)| ConvexFunctionParam { name, data_type })
+                                    .collect();
+                                (params, None)
+                            } else {
+                                (Vec::new(), Some(desc))
+                            }
+                        }
+                        None => (Vec::new(), None),
+                    };
+                    let return_type = match returns {
+                        Some(returns) => Some(
+                            to_validator(&returns)
+                                .map_err(|reason| fail(format!("returns of `{name}`: {reason}")))?
+                                .0,
+                        ),
+                        None => None,
+                    };
+                    Ok(ConvexFunction {
+                        name: name.clone(),
+                        params,
+                        return_type,
+                        type_: kind,
+                        file_name: file_name.clone(),
+                        module_path: Some(module_path.clone()),
+                        args_validator,
+                        component: None,
+                    })
+                })();
+                match function {
+                    Ok(function) => functions.push(function),
+                    Err(error) => errors.push(error),
+                }
+            }
+            Val::Unsupported(reason) => errors.push(fail(format!("export `{name}`: {reason}"))),
+            _ => {}
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(functions)
+}
+
+/// The `(file_name, module_path)` pair the Bun extractor reports for a function file.
+pub(crate) fn module_identity(function_path: &Path) -> (String, String)
+{
+    let path = absolute(function_path);
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .trim_end_matches(".ts")
+        .to_string();
+    let module_path = module_path_of(&path).unwrap_or_else(|| file_name.clone());
+    (file_name, module_path)
+}
+
+/// Module path relative to the `convex/` directory (e.g. `model/chats`), as
+/// computed by the Bun extractor.
+fn module_path_of(path: &Path) -> Option<String>
+{
+    let parts: Vec<&str> = path.iter().filter_map(|part| part.to_str()).collect();
+    let convex_idx = parts.iter().position(|part| *part == "convex")?;
+    if convex_idx + 1 >= parts.len() {
+        return None;
+    }
+    Some(parts[convex_idx + 1..].join("/").trim_end_matches(".ts").to_string())
+}
+
+fn absolute(path: &Path) -> PathBuf
+{
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokKind
+{
+    Ident(String),
+    Str(String),
+    Num(f64),
+    /// A number literal that doesn't parse, e.g. `0b102`, with its text.
+    BadNum(String),
+    Punct(&'static str),
+    /// Template literals and regexes — never evaluated.
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Tok
+{
+    kind: TokKind,
+    line: usize,
+    /// Whether a line break precedes this token (used for ASI).
+    nl_before: bool,
+}
+
+const PUNCTS: &[&str] = &[
+    "...", "=>", "{", "}", "(", ")", "[", "]", ",", ";", ":", ".", "=", "<", ">", "?", "!", "-", "+", "*", "/", "%", "&",
+    "|", "^", "~", "@", "#",
+];
+
+fn tokenize(source: &str) -> Vec<Tok>
+{
+    let chars: Vec<char> = source.chars().collect();
+    let mut toks: Vec<Tok> = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+    let mut nl_before = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            line += 1;
+            nl_before = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                    nl_before = true;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        let start_line = line;
+        let kind = if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                    value.push(match chars[i] {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        '0' => '\0',
+                        other => other,
+                    });
+                } else {
+                    value.push(chars[i]);
+                }
+                i += 1;
+            }
+            i += 1;
+            TokKind::Str(value)
+        } else if c == '`' {
+            i += 1;
+            let mut depth = 0usize;
+            while i < chars.len() && !(chars[i] == '`' && depth == 0) {
+                match chars[i] {
+                    '\\' => i += 1,
+                    '\n' => line += 1,
+                    '$' if chars.get(i + 1) == Some(&'{') => {
+                        depth += 1;
+                        i += 1;
+                    }
+                    '}' if depth > 0 => depth -= 1,
+                    _ => {}
+                }
+                i += 1;
+            }
+            i += 1;
+            TokKind::Other
+        } else if c == '/' && regex_allowed(toks.last()) {
+            i += 1;
+            let mut in_class = false;
+            while i < chars.len() && chars[i] != '\n' && (in_class || chars[i] != '/') {
+                match chars[i] {
+                    '\\' => i += 1,
+                    '[' => in_class = true,
+                    ']' => in_class = false,
+                    _ => {}
+                }
+                i += 1;
+            }
+            i += 1;
+            while i < chars.len() && chars[i].is_alphabetic() {
+                i += 1;
+            }
+            TokKind::Other
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let start = i;
+            let radix = c == '0' && matches!(chars.get(i + 1), Some('x' | 'X' | 'b' | 'B' | 'o' | 'O'));
+            while i < chars.len() {
+                let ch = chars[i];
+                // The sign of a decimal exponent (`1e-5`) belongs to the number
+                let exponent_sign = !radix && matches!(ch, '+' | '-') && matches!(chars[i - 1], 'e' | 'E');
+                if !(ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' || exponent_sign) {
+                    break;
+                }
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            // A trailing `n` marks a BigInt
+            let digits = text.strip_suffix('n').unwrap_or(&text);
+            let value = match digits.get(..2) {
+                Some("0x" | "0X") => u64::from_str_radix(&digits[2..], 16).ok().map(|n| n as f64),
+                Some("0b" | "0B") => u64::from_str_radix(&digits[2..], 2).ok().map(|n| n as f64),
+                Some("0o" | "0O") => u64::from_str_radix(&digits[2..], 8).ok().map(|n| n as f64),
+                _ => digits.parse::<f64>().ok().filter(|n| n.is_finite()),
+            };
+            match value {
+                Some(value) => TokKind::Num(value),
+                None => TokKind::BadNum(text),
+            }
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            TokKind::Ident(chars[start..i].iter().collect())
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            match PUNCTS.iter().find(|punct| rest.starts_with(*punct)) {
+                Some(punct) => {
+                    i += punct.len();
+                    TokKind::Punct(punct)
+                }
+                None => {
+                    i += 1;
+                    TokKind::Other
+                }
+            }
+        };
+
+        toks.push(Tok {
+            kind,
+            line: start_line,
+            nl_before,
+        });
+        nl_before = false;
+    }
+
+    toks
+}
+
+/// Whether a `/` after `prev` starts a regex literal rather than a division.
+fn regex_allowed(prev: Option<&Tok>) -> bool
+{
+    match prev.map(|tok| &tok.kind) {
+        None => true,
+        Some(TokKind::Ident(word)) => matches!(word.as_str(), "return" | "typeof" | "case" | "in" | "of" | "yield"),
+        Some(TokKind::Str(_) | TokKind::Num(_) | TokKind::BadNum(_) | TokKind::Other) => false,
+        Some(TokKind::Punct(punct)) => !matches!(*punct, ")" | "]" | "}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser — just enough of TypeScript to find declarations and validators
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Expr
+{
+    Ident(String, usize),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+    Object(Vec<Prop>),
+    Array(Vec<Expr>),
+    Member(Box<Expr>, String),
+    Call(Box<Expr>, Vec<Expr>, usize),
+    /// `...expr` inside an array literal or call arguments.
+    Spread(Box<Expr>, usize),
+    /// An arrow function or `function` declaration whose body is a single
+    /// expression: `(parameter, default)` pairs and the returned expression.
+    Arrow(Vec<(String, Option<Expr>)>, Rc<Expr>),
+    /// A number literal that doesn't parse, with its text.
+    BadNum(String, usize),
+    /// An expression this parser doesn't model (operators, `new`, ...).
+    Opaque(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Prop
+{
+    KeyValue(String, Expr),
+    Spread(Expr),
+    /// A method, accessor, or computed key.
+    Opaque(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Import
+{
+    source: String,
+    /// Imported name, `default`, or `*` for namespace imports.
+    imported: String,
+}
+
+#[derive(Debug, Default)]
+struct Module
+{
+    path: PathBuf,
+    bindings: HashMap<String, Expr>,
+    imports: HashMap<String, Import>,
+    /// `(exported name, local name)` in source order.
+    exports: Vec<(String, String)>,
+    default_export: Option<Expr>,
+}
+
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "export",
+    "import",
+    "const",
+    "let",
+    "var",
+    "function",
+    "class",
+    "interface",
+    "type",
+    "enum",
+    "declare",
+];
+
+struct Parser
+{
+    toks: Vec<Tok>,
+    pos: usize,
+}
+
+impl Parser
+{
+    fn peek(&self) -> Option<&Tok>
+    {
+        self.toks.get(self.pos)
+    }
+
+    fn peek_kind(&self, offset: usize) -> Option<&TokKind>
+    {
+        self.toks.get(self.pos + offset).map(|tok| &tok.kind)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool
+    {
+        matches!(self.peek_kind(0), Some(TokKind::Punct(p)) if *p == punct)
+    }
+
+    fn is_ident(&self, word: &str) -> bool
+    {
+        matches!(self.peek_kind(0), Some(TokKind::Ident(w)) if w == word)
+    }
+
+    fn line(&self) -> usize
+    {
+        self.peek().or(self.toks.last()).map_or(0, |tok| tok.line)
+    }
+
+    fn bump(&mut self) -> Option<TokKind>
+    {
+        let tok = self.toks.get(self.pos).map(|tok| tok.kind.clone());
+        if tok.is_some() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool
+    {
+        let matched = self.is_punct(punct);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn take_ident(&mut self) -> Option<String>
+    {
+        match self.peek_kind(0) {
+            Some(TokKind::Ident(word)) => {
+                let word = word.clone();
+                self.pos += 1;
+                Some(word)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the current token begins a new top-level statement (used for ASI).
+    fn at_statement_start(&self) -> bool
+    {
+        match self.peek() {
+            Some(Tok {
+                kind: TokKind::Ident(word),
+                nl_before: true,
+                ..
+            }) => {
+                STATEMENT_KEYWORDS.contains(&word.as_str())
+                    && !matches!(
+                        self.peek_kind(1),
+                        Some(TokKind::Punct(":" | "," | "(" | ")" | "." | "}" | "=" | "?"))
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the current token ends an expression.
+    fn at_terminator(&self) -> bool
+    {
+        match self.peek_kind(0) {
+            None => true,
+            Some(TokKind::Punct(punct)) => matches!(*punct, "," | ";" | ")" | "]" | "}"),
+            Some(_) => self.at_statement_start(),
+        }
+    }
+
+    /// Skip tokens until an expression terminator at bracket depth zero.
+    fn skip_to_terminator(&mut self, angle_brackets: bool)
+    {
+        let mut depth = 0usize;
+        while let Some(tok) = self.peek() {
+            if depth == 0 && self.at_terminator() {
+                break;
+            }
+            match &tok.kind {
+                TokKind::Punct("(" | "[" | "{") => depth += 1,
+                TokKind::Punct("<") if angle_brackets => depth += 1,
+                TokKind::Punct(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                TokKind::Punct(">") if angle_brackets => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Skip a balanced `(...)`, `[...]`, or `{...}` group starting at the current token.
+    fn skip_group(&mut self)
+    {
+        let mut depth = 0usize;
+        while let Some(kind) = self.bump() {
+            match kind {
+                TokKind::Punct("(" | "[" | "{") => depth += 1,
+                TokKind::Punct(")" | "]" | "}") => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skip a statement this parser doesn't model (functions, types, expressions).
+    fn skip_statement(&mut self)
+    {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(tok) = self.peek() {
+            if depth == 0 && self.pos > start && self.at_statement_start() {
+                return;
+            }
+            let closed_block = match &tok.kind {
+                TokKind::Punct("(" | "[" | "{") => {
+                    depth += 1;
+                    false
+                }
+                TokKind::Punct(")" | "]" | "}") => {
+                    depth = depth.saturating_sub(1);
+                    depth == 0
+                }
+                TokKind::Punct(";") if depth == 0 => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => false,
+            };
+            self.pos += 1;
+            if closed_block && self.peek().is_none_or(|next| next.nl_before) {
+                return;
+            }
+        }
+    }
+
+    fn parse_module(mut self, path: PathBuf) -> Module
+    {
+        let mut module = Module {
+            path,
+            ..Default::default()
+        };
+
+        while self.peek().is_some() {
+            if self.is_ident("import") {
+                self.parse_import(&mut module);
+            } else if self.is_ident("export") {
+                self.pos += 1;
+                if self.is_ident("default") {
+                    self.pos += 1;
+                    module.default_export = Some(self.parse_expr());
+                    self.eat_punct(";");
+                } else if self.is_ident("const") || self.is_ident("let") || self.is_ident("var") {
+                    self.parse_declaration(&mut module, true);
+                } else if self.is_punct("{") {
+                    self.parse_export_list(&mut module);
+                } else if self.is_ident("function") {
+                    self.parse_function(&mut module, true);
+                } else {
+                    self.skip_statement();
+                }
+            } else if self.is_ident("const") || self.is_ident("let") || self.is_ident("var") {
+                self.parse_declaration(&mut module, false);
+            } else if self.is_ident("function") {
+                self.parse_function(&mut module, false);
+            } else {
+                self.skip_statement();
+            }
+        }
+
+        module
+    }
+
+    fn parse_import(&mut self, module: &mut Module)
+    {
+        self.pos += 1;
+        if let Some(TokKind::Str(_)) = self.peek_kind(0) {
+            self.pos += 1;
+            self.eat_punct(";");
+            return;
+        }
+        let default_named_type = match self.peek_kind(1) {
+            Some(TokKind::Punct(",")) => true,
+            Some(TokKind::Ident(word)) => word == "from",
+            _ => false,
+        };
+        if self.is_ident("type") && !default_named_type {
+            // `import type ...` brings in types only.
+            self.skip_statement();
+            return;
+        }
+
+        let mut bindings: Vec<(String, String)> = Vec::new();
+        loop {
+            if self.eat_punct("*") {
+                if self.is_ident("as") {
+                    self.pos += 1;
+                }
+                if let Some(local) = self.take_ident() {
+                    bindings.push((local, "*".to_string()));
+                }
+            } else if self.eat_punct("{") {
+                while !self.is_punct("}") && self.peek().is_some() {
+                    let is_type = self.is_ident("type") && matches!(self.peek_kind(1), Some(TokKind::Ident(_)));
+                    if is_type {
+                        self.pos += 1;
+                    }
+                    let imported = match self.bump() {
+                        Some(TokKind::Ident(name) | TokKind::Str(name)) => name,
+                        _ => continue,
+                    };
+                    let local = if self.is_ident("as") {
+                        self.pos += 1;
+                        self.take_ident().unwrap_or_else(|| imported.clone())
+                    } else {
+                        imported.clone()
+                    };
+                    if !is_type {
+                        bindings.push((local, imported));
+                    }
+                    self.eat_punct(",");
+                }
+                self.eat_punct("}");
+            } else if self.is_ident("from") {
+                break;
+            } else if let Some(local) = self.take_ident() {
+                bindings.push((local, "default".to_string()));
+            } else {
+                self.skip_statement();
+                return;
+            }
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+
+        if !self.is_ident("from") {
+            self.skip_statement();
+            return;
+        }
+        self.pos += 1;
+        let Some(TokKind::Str(source)) = self.bump() else {
+            self.skip_statement();
+            return;
+        };
+        self.eat_punct(";");
+
+        for (local, imported) in bindings {
+            module.imports.insert(
+                local,
+                Import {
+                    source: source.clone(),
+                    imported,
+                },
+            );
+        }
+    }
+
+    fn parse_export_list(&mut self, module: &mut Module)
+    {
+        self.pos += 1;
+        let mut names: Vec<(String, String)> = Vec::new();
+        while !self.is_punct("}") && self.peek().is_some() {
+            if self.is_ident("type") && matches!(self.peek_kind(1), Some(TokKind::Ident(_))) {
+                self.pos += 2;
+                self.eat_punct(",");
+                continue;
+            }
+            let Some(local) = self.take_ident() else {
+                self.pos += 1;
+                continue;
+            };
+            let exported = if self.is_ident("as") {
+                self.pos += 1;
+                self.take_ident().unwrap_or_else(|| local.clone())
+            } else {
+                local.clone()
+            };
+            names.push((exported, local));
+            self.eat_punct(",");
+        }
+        self.eat_punct("}");
+
+        if self.is_ident("from") {
+            self.pos += 1;
+            if let Some(TokKind::Str(source)) = self.bump() {
+                for (exported, local) in &names {
+                    let synthetic = format!("{exported} (re-export)");
+                    module.imports.insert(
+                        synthetic.clone(),
+                        Import {
+                            source: source.clone(),
+                            imported: local.clone(),
+                        },
+                    );
+                    module.bindings.insert(synthetic.clone(), Expr::Ident(synthetic.clone(), 0));
+                    module.exports.push((exported.clone(), synthetic));
+                }
+            }
+        } else {
+            for (exported, local) in names {
+                if !module.bindings.contains_key(&local) {
+                    module.bindings.insert(local.clone(), Expr::Ident(local.clone(), 0));
+                }
+                module.exports.push((exported, local));
+            }
+        }
+        self.eat_punct(";");
+    }
+
+    fn parse_declaration(&mut self, module: &mut Module, exported: bool)
+    {
+        self.pos += 1;
+        loop {
+            let Some(name) = self.take_ident() else {
+                // Destructuring patterns aren't modelled.
+                self.skip_statement();
+                return;
+            };
+            self.eat_punct("!");
+            if self.eat_punct(":") {
+                self.skip_type();
+            }
+            if self.eat_punct("=") {
+                let expr = self.parse_expr();
+                module.bindings.insert(name.clone(), expr);
+                if exported {
+                    module.exports.push((name.clone(), name));
+                }
+            }
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        if !self.eat_punct(";") && !self.at_terminator() {
+            self.skip_statement();
+        }
+    }
+
+    /// Parse `function name(params) { return expr; }` into a binding. Other
+    /// function declarations are skipped.
+    fn parse_function(&mut self, module: &mut Module, exported: bool)
+    {
+        let start = self.pos;
+        self.pos += 1;
+        if let Some(name) = self.take_ident() {
+            if let Some(params) = self.parse_params() {
+                if self.is_punct("{") {
+                    let body = self.parse_block_body();
+                    module.bindings.insert(name.clone(), Expr::Arrow(params, Rc::new(body)));
+                    if exported {
+                        module.exports.push((name.clone(), name));
+                    }
+                    return;
+                }
+            }
+        }
+        self.pos = start;
+        self.skip_statement();
+    }
+
+    /// Skip a type annotation up to a `=`, `,`, `;`, or closing bracket at depth zero.
+    fn skip_type(&mut self)
+    {
+        let mut depth = 0usize;
+        while let Some(tok) = self.peek() {
+            match &tok.kind {
+                TokKind::Punct("=" | "," | ";" | ")" | "]" | "}" | ">") if depth == 0 => break,
+                TokKind::Punct("(" | "[" | "{" | "<") => depth += 1,
+                TokKind::Punct(")" | "]" | "}" | ">") => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Parse `<T>(a, b?: T, c = x)` and an optional return type. Only plain
+    /// identifier parameters are modelled; `None` for anything else.
+    fn parse_params(&mut self) -> Option<Vec<(String, Option<Expr>)>>
+    {
+        if self.is_punct("<") {
+            let mut depth = 0usize;
+            while let Some(kind) = self.bump() {
+                match kind {
+                    TokKind::Punct("<") => depth += 1,
+                    TokKind::Punct(">") => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !self.eat_punct("(") {
+            return None;
+        }
+        let mut params = Vec::new();
+        while !self.eat_punct(")") {
+            let name = self.take_ident()?;
+            self.eat_punct("?");
+            if self.eat_punct(":") {
+                self.skip_type();
+            }
+            let default = if self.eat_punct("=") { Some(self.parse_expr()) } else { None };
+            params.push((name, default));
+            if !self.eat_punct(",") && !self.is_punct(")") {
+                return None;
+            }
+        }
+        if self.eat_punct(":") {
+            let mut depth = 0usize;
+            while depth > 0 || !(self.is_punct("=>") || self.is_punct("{")) {
+                match self.bump()? {
+                    TokKind::Punct("(" | "[" | "<") => depth += 1,
+                    TokKind::Punct(")" | "]" | ">") => depth = depth.saturating_sub(1),
+                    TokKind::Punct(";") => return None,
+                    _ => {}
+                }
+            }
+        }
+        Some(params)
+    }
+
+    /// Parse an arrow function at the current token, restoring the position
+    /// when the tokens turn out not to be one.
+    fn parse_arrow(&mut self) -> Option<Expr>
+    {
+        let start = self.pos;
+        let params = match self.take_ident() {
+            Some(name) => Some(vec![(name, None)]),
+            None => self.parse_params(),
+        };
+        match params {
+            Some(params) if self.eat_punct("=>") => {
+                let body = if self.is_punct("{") {
+                    self.parse_block_body()
+                } else {
+                    self.parse_expr()
+                };
+                Some(Expr::Arrow(params, Rc::new(body)))
+            }
+            _ => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// Parse a `{ return expr; }` function body. Bodies with other statements
+    /// are skipped and evaluate to an opaque value.
+    fn parse_block_body(&mut self) -> Expr
+    {
+        let start = self.pos;
+        let line = self.line();
+        self.pos += 1;
+        if self.is_ident("return") {
+            self.pos += 1;
+            let body = self.parse_expr();
+            self.eat_punct(";");
+            if self.eat_punct("}") {
+                return body;
+            }
+        }
+        self.pos = start;
+        self.skip_group();
+        Expr::Opaque(line)
+    }
+
+    fn parse_expr(&mut self) -> Expr
+    {
+        let line = self.line();
+        let mut expr = self.parse_primary();
+        expr = self.parse_postfix(expr);
+
+        loop {
+            if self.is_ident("as") || self.is_ident("satisfies") {
+                self.pos += 1;
+                self.skip_to_terminator(true);
+            } else if self.is_punct("!") && !matches!(self.peek_kind(1), Some(TokKind::Punct("="))) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        if self.at_terminator() {
+            expr
+        } else {
+            self.skip_to_terminator(false);
+            Expr::Opaque(line)
+        }
+    }
+
+    fn parse_primary(&mut self) -> Expr
+    {
+        let line = self.line();
+        match self.peek_kind(0).cloned() {
+            Some(TokKind::Str(value)) => {
+                self.pos += 1;
+                Expr::Str(value)
+            }
+            Some(TokKind::Num(value)) => {
+                self.pos += 1;
+                Expr::Num(value)
+            }
+            Some(TokKind::BadNum(text)) => {
+                self.pos += 1;
+                Expr::BadNum(text, line)
+            }
+            Some(TokKind::Punct("-")) if matches!(self.peek_kind(1), Some(TokKind::Num(_) | TokKind::BadNum(_))) => {
+                self.pos += 1;
+                match self.bump() {
+                    Some(TokKind::Num(value)) => Expr::Num(-value),
+                    Some(TokKind::BadNum(text)) => Expr::BadNum(format!("-{text}"), line),
+                    _ => Expr::Opaque(line),
+                }
+            }
+            Some(TokKind::Punct("{")) => self.parse_object(),
+            Some(TokKind::Punct("[")) => self.parse_array(),
+            Some(TokKind::Punct("<")) => self.parse_arrow().unwrap_or(Expr::Opaque(line)),
+            Some(TokKind::Punct("(")) => {
+                if let Some(arrow) = self.parse_arrow() {
+                    return arrow;
+                }
+                let start = self.pos;
+                if self.eat_punct("(") {
+                    let inner = self.parse_expr();
+                    if self.eat_punct(")") {
+                        return inner;
+                    }
+                }
+                self.pos = start;
+                self.skip_group();
+                Expr::Opaque(line)
+            }
+            Some(TokKind::Ident(_)) if matches!(self.peek_kind(1), Some(TokKind::Punct("=>"))) => {
+                self.parse_arrow().unwrap_or(Expr::Opaque(line))
+            }
+            Some(TokKind::Ident(word)) => {
+                self.pos += 1;
+                match word.as_str() {
+                    "true" => Expr::Bool(true),
+                    "false" => Expr::Bool(false),
+                    "null" | "undefined" => Expr::Null,
+                    "function" | "class" | "new" | "await" | "typeof" | "void" | "delete" => {
+                        self.skip_to_terminator(false);
+                        Expr::Opaque(line)
+                    }
+                    _ => Expr::Ident(word, line),
+                }
+            }
+            _ => Expr::Opaque(line),
+        }
+    }
+
+    fn parse_postfix(&mut self, mut expr: Expr) -> Expr
+    {
+        loop {
+            if self.is_punct(".") || (self.is_punct("?") && matches!(self.peek_kind(1), Some(TokKind::Punct(".")))) {
+                self.eat_punct("?");
+                self.pos += 1;
+                let Some(name) = self.take_ident() else {
+                    return Expr::Opaque(self.line());
+                };
+                expr = Expr::Member(Box::new(expr), name);
+            } else if self.is_punct("(") {
+                let line = self.line();
+                let args = self.parse_call_args();
+                expr = Expr::Call(Box::new(expr), args, line);
+            } else if self.is_punct("[") {
+                let line = self.line();
+                self.skip_group();
+                expr = Expr::Opaque(line);
+            } else {
+                return expr;
+            }
+        }
+    }
+
+    fn parse_call_args(&mut self) -> Vec<Expr>
+    {
+        self.pos += 1;
+        let mut args = Vec::new();
+        while !self.is_punct(")") && self.peek().is_some() {
+            let start = self.pos;
+            let line = self.line();
+            if self.eat_punct("...") {
+                args.push(Expr::Spread(Box::new(self.parse_expr()), line));
+            } else {
+                args.push(self.parse_expr());
+            }
+            if !self.eat_punct(",") && !self.is_punct(")") && self.pos == start {
+                self.pos += 1;
+            }
+        }
+        self.eat_punct(")");
+        args
+    }
+
+    fn parse_array(&mut self) -> Expr
+    {
+        self.pos += 1;
+        let mut elements = Vec::new();
+        while !self.is_punct("]") && self.peek().is_some() {
+            let start = self.pos;
+            let line = self.line();
+            if self.eat_punct("...") {
+                elements.push(Expr::Spread(Box::new(self.parse_expr()), line));
+            } else {
+                elements.push(self.parse_expr());
+            }
+            if !self.eat_punct(",") && !self.is_punct("]") && self.pos == start {
+                self.pos += 1;
+            }
+        }
+        self.eat_punct("]");
+        Expr::Array(elements)
+    }
+
+    fn parse_object(&mut self) -> Expr
+    {
+        self.pos += 1;
+        let mut props = Vec::new();
+        while !self.is_punct("}") && self.peek().is_some() {
+            let start = self.pos;
+            let line = self.line();
+
+            if self.eat_punct("...") {
+                props.push(Prop::Spread(self.parse_expr()));
+            } else {
+                let is_modifier = matches!(self.peek_kind(0), Some(TokKind::Ident(w)) if matches!(w.as_str(), "async" | "get" | "set"))
+                    && matches!(self.peek_kind(1), Some(TokKind::Ident(_) | TokKind::Str(_)));
+                if is_modifier {
+                    self.pos += 1;
+                }
+                let key = match self.peek_kind(0).cloned() {
+                    Some(TokKind::Ident(name) | TokKind::Str(name)) => {
+                        self.pos += 1;
+                        Some(name)
+                    }
+                    Some(TokKind::Num(value)) => {
+                        self.pos += 1;
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                };
+
+                match key {
+                    Some(key) if !is_modifier && self.eat_punct(":") => props.push(Prop::KeyValue(key, self.parse_expr())),
+                    Some(key) if !is_modifier && (self.is_punct(",") || self.is_punct("}")) => {
+                        props.push(Prop::KeyValue(key.clone(), Expr::Ident(key, line)))
+                    }
+                    _ => {
+                        self.skip_to_terminator(false);
+                        props.push(Prop::Opaque(line));
+                    }
+                }
+            }
+
+            if !self.eat_punct(",") && !self.is_punct("}") {
+                self.skip_to_terminator(false);
+                if !self.eat_punct(",") && !self.is_punct("}") && self.pos == start {
+                    self.pos += 1;
+                }
+            }
+        }
+        self.eat_punct("}");
+        Expr::Object(props)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/// A statically evaluated value.
+#[derive(Debug, Clone)]
+enum Val
+{
+    /// A validator descriptor, plus field order for object validators.
+    Validator(JsonValue, Vec<String>),
+    /// A plain object literal.
+    Object(Vec<(String, Val)>),
+    /// A string, number, boolean, or null literal.
+    Literal(JsonValue),
+    /// The `v` namespace from `convex/values`.
+    V,
+    /// `v.<method>` before it is called.
+    VMethod(String),
+    /// A validator utility from `convex-helpers/validators` before it is called.
+    Helper(String),
+    /// A known export of `convex/server` (`defineTable`, `query`, ...).
+    Builtin(String),
+    /// A factory from [`Configuration::custom_validators`](crate::Configuration::custom_validators)
+    /// and the descriptor its calls produce.
+    Custom(JsonValue),
+    /// `<receiver>.<method>` before it is called.
+    Bound(Box<Val>, String),
+    /// `import * as ns from "./module"`.
+    Namespace(PathBuf),
+    /// An array literal.
+    Array(Vec<Val>),
+    Table(TableVal),
+    /// `(table name, table)` pairs and the options from `defineSchema(...)`.
+    /// Each table that can't be evaluated holds why, so every bad table is
+    /// reported at once.
+    Schema(Vec<(String, Result<TableVal, String>)>, SchemaOptions),
+    Function
+    {
+        kind: String,
+        args: Option<Box<Val>>,
+        returns: Option<Box<Val>>,
+    },
+    /// A locally defined arrow function or `function` declaration.
+    Closure(Rc<Closure>),
+    /// A value that is fine to ignore but can't be used as a validator.
+    Opaque(String),
+    /// A value that could only be produced by running code (wrapper factories,
+    /// package helpers). Exports with this value need the Bun backend.
+    Unsupported(String),
+}
+
+/// The result of `defineTable(...)` and its index chain.
+#[derive(Debug, Clone)]
+struct TableVal
+{
+    desc: JsonValue,
+    order: Vec<String>,
+    /// `(index name, fields)` from `.index(...)` calls.
+    indexes: Vec<(String, Vec<String>)>,
+}
+
+/// A function value: its parameters and body, the file it was defined in, and
+/// the parameters of enclosing functions it can see.
+#[derive(Debug)]
+struct Closure
+{
+    path: PathBuf,
+    params: Vec<(String, Option<Expr>)>,
+    body: Rc<Expr>,
+    captured: Vec<(String, Val)>,
+}
+
+/// How deeply calls to local functions may nest before evaluation gives up.
+const MAX_CALL_DEPTH: usize = 32;
+
+const REGISTRARS: &[&str] = &[
+    "query",
+    "mutation",
+    "action",
+    "internalQuery",
+    "internalMutation",
+    "internalAction",
+    "httpAction",
+];
+
+/// Parsed modules and memoized top-level values, shared across files.
+#[derive(Default)]
+pub(crate) struct ModuleCache
+{
+    modules: HashMap<PathBuf, Rc<Module>>,
+    values: HashMap<(PathBuf, String), Val>,
+    in_progress: HashSet<(PathBuf, String)>,
+    /// Parameters bound by the function calls being evaluated, innermost last.
+    scope: Vec<(String, Val)>,
+    call_depth: usize,
+    custom_validators: HashMap<String, CustomValidatorHandler>,
+}
+
+impl ModuleCache
+{
+    pub(crate) fn new(custom_validators: &HashMap<String, CustomValidatorHandler>) -> Self
+    {
+        Self {
+            custom_validators: custom_validators.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> Result<Rc<Module>, String>
+    {
+        if let Some(module) = self.modules.get(path) {
+            return Ok(module.clone());
+        }
+        let source = std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let parser = Parser {
+            toks: tokenize(&source),
+            pos: 0,
+        };
+        let module = Rc::new(parser.parse_module(path.to_path_buf()));
+        self.modules.insert(path.to_path_buf(), module.clone());
+        Ok(module)
+    }
+
+    /// Value of a top-level name in `module`, following imports.
+    fn binding(&mut self, module: &Rc<Module>, name: &str, line: usize) -> Val
+    {
+        let key = (module.path.clone(), name.to_string());
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+        if !self.in_progress.insert(key.clone()) {
+            return Val::Unsupported(format!("`{name}` on line {line} refers to itself"));
+        }
+
+        // Top-level bindings never see the parameters of the call that reached them
+        let scope = std::mem::take(&mut self.scope);
+        let value = if let Some(import) = module.imports.get(name) {
+            self.import(module, import, line)
+        } else if let Some(expr) = module
+            .bindings
+            .get(name)
+            .filter(|expr| !matches!(expr, Expr::Ident(n, 0) if n == name))
+        {
+            self.eval(module, expr)
+        } else {
+            Val::Unsupported(format!("`{name}` on line {line} is not defined in this file"))
+        };
+
+        self.scope = scope;
+        self.in_progress.remove(&key);
+        self.values.insert(key, value.clone());
+        value
+    }
+
+    fn import(&mut self, module: &Rc<Module>, import: &Import, line: usize) -> Val
+    {
+        let source = import.source.as_str();
+        let name = import.imported.as_str();
+
+        if let Some(handler) = self.custom_validators.get(name).filter(|handler| handler.matches(source)) {
+            return Val::Custom(handler.descriptor_for(name));
+        }
+
+        if source == "convex/values" {
+            return match name {
+                "v" | "default" => Val::V,
+                _ => Val::Opaque(format!("`{name}` from convex/values")),
+            };
+        }
+        if source == "convex/server" || source.ends_with("_generated/server") || source.ends_with("_generated/server.js") {
+            return match name {
+                "defineSchema" | "defineTable" => Val::Builtin(name.to_string()),
+                "paginationOptsValidator" => pagination_opts_validator(),
+                _ => match name.strip_suffix("Generic").unwrap_or(name) {
+                    registrar if REGISTRARS.contains(&registrar) => Val::Builtin(registrar.to_string()),
+                    _ => Val::Opaque(format!("`{name}` from {source}")),
+                },
+            };
+        }
+        if source.contains("_generated/") {
+            return Val::Opaque(format!("`{name}` from {source}"));
+        }
+        // The same declarative file the Bun mock re-exports
+        if source == "@convex-dev/auth/server" && name == "authTables" {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("js/mocks/convex_auth_tables.ts");
+            return match self.load(&path) {
+                Ok(tables) => self.export(&tables, name, line),
+                Err(e) => Val::Unsupported(e),
+            };
+        }
+        if source == "convex-helpers/validators" || source == "convex-helpers/validators.js" {
+            return convex_helpers_import(name, line);
+        }
+        if !source.starts_with('.') {
+            return Val::Unsupported(format!(
+                "`{name}` on line {line} is imported from package `{source}`, which can't be evaluated statically"
+            ));
+        }
+
+        let Some(path) = resolve_relative(&module.path, source) else {
+            return Val::Unsupported(format!("can't find module `{source}` imported on line {line}"));
+        };
+        let imported = match self.load(&path) {
+            Ok(imported) => imported,
+            Err(e) => return Val::Unsupported(e),
+        };
+
+        match name {
+            "*" => Val::Namespace(path),
+            "default" => match imported.default_export.clone() {
+                Some(expr) => self.eval(&imported, &expr),
+                None => Val::Unsupported(format!("`{source}` has no default export")),
+            },
+            _ => self.export(&imported, name, line),
+        }
+    }
+
+    fn export(&mut self, module: &Rc<Module>, name: &str, line: usize) -> Val
+    {
+        match module.exports.iter().find(|(exported, _)| exported == name) {
+            Some((_, local)) => {
+                let local = local.clone();
+                self.binding(module, &local, line)
+            }
+            None => Val::Unsupported(format!("`{name}` is not exported from {}", module.path.display())),
+        }
+    }
+
+    fn eval(&mut self, module: &Rc<Module>, expr: &Expr) -> Val
+    {
+        match expr {
+            Expr::Str(value) => Val::Literal(JsonValue::String(value.clone())),
+            Expr::Num(value) => Val::Literal(number_json(*value)),
+            Expr::Bool(value) => Val::Literal(JsonValue::Bool(*value)),
+            Expr::Null => Val::Literal(JsonValue::Null),
+            Expr::Ident(name, line) => match self.scope.iter().rev().find(|(param, _)| param == name) {
+                Some((_, value)) => value.clone(),
+                None => self.binding(module, name, *line),
+            },
+            Expr::Arrow(params, body) => Val::Closure(Rc::new(Closure {
+                path: module.path.clone(),
+                params: params.clone(),
+                body: body.clone(),
+                captured: self.scope.clone(),
+            })),
+            Expr::BadNum(text, line) => Val::Unsupported(format!("invalid number `{text}` on line {line}")),
+            Expr::Opaque(line) => Val::Opaque(format!("unsupported expression on line {line}")),
+            Expr::Array(elements) => Val::Array(self.eval_list(module, elements)),
+            Expr::Spread(_, line) => Val::Opaque(format!("unsupported spread on line {line}")),
+            Expr::Object(props) => {
+                let mut fields: Vec<(String, Val)> = Vec::new();
+                for prop in props {
+                    match prop {
+                        Prop::KeyValue(key, value) => {
+                            let value = self.eval(module, value);
+                            set_field(&mut fields, key.clone(), value);
+                        }
+                        Prop::Spread(inner) => match self.eval(module, inner) {
+                            Val::Object(spread) => {
+                                for (key, value) in spread {
+                                    set_field(&mut fields, key, value);
+                                }
+                            }
+                            validator @ Val::Validator(..) => match object_fields(&validator) {
+                                Ok(spread) => {
+                                    for (key, data_type) in spread {
+                                        set_field(&mut fields, key, Val::Validator(data_type, Vec::new()));
+                                    }
+                                }
+                                Err(reason) => return Val::Unsupported(reason),
+                            },
+                            Val::Opaque(reason) | Val::Unsupported(reason) => {
+                                return Val::Unsupported(format!("object spread: {reason}"))
+                            }
+                            _ => return Val::Unsupported("object spread of a non-object value".to_string()),
+                        },
+                        Prop::Opaque(line) => {
+                            fields.push((String::new(), Val::Opaque(format!("unsupported property on line {line}"))))
+                        }
+                    }
+                }
+                Val::Object(fields)
+            }
+            Expr::Member(object, name) => match self.eval(module, object) {
+                Val::V => Val::VMethod(name.clone()),
+                Val::Namespace(path) => match self.load(&path) {
+                    Ok(target) => self.export(&target, name, 0),
+                    Err(e) => Val::Unsupported(e),
+                },
+                Val::Object(fields) => fields
+                    .into_iter()
+                    .find(|(key, _)| key == name)
+                    .map_or_else(|| Val::Opaque(format!("no field `{name}`")), |(_, value)| value),
+                Val::Validator(desc, order) if name == "fields" && desc["type"] == "object" => {
+                    let validator = Val::Validator(desc, order);
+                    match object_fields(&validator) {
+                        Ok(fields) => Val::Object(
+                            fields
+                                .into_iter()
+                                .map(|(key, data_type)| (key, Val::Validator(data_type, Vec::new())))
+                                .collect(),
+                        ),
+                        Err(reason) => Val::Unsupported(reason),
+                    }
+                }
+                unsupported @ Val::Unsupported(_) => unsupported,
+                receiver => Val::Bound(Box::new(receiver), name.clone()),
+            },
+            Expr::Call(callee, args, line) => {
+                let callee_val = self.eval(module, callee);
+                let args = self.eval_list(module, args);
+                self.call(callee_val, args, callee, *line)
+            }
+        }
+    }
+
+    /// Evaluate array elements or call arguments, flattening `...array` spreads.
+    fn eval_list(&mut self, module: &Rc<Module>, exprs: &[Expr]) -> Vec<Val>
+    {
+        let mut values = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            let Expr::Spread(inner, line) = expr else {
+                values.push(self.eval(module, expr));
+                continue;
+            };
+            match self.eval(module, inner) {
+                Val::Array(items) => values.extend(items),
+                Val::Opaque(reason) | Val::Unsupported(reason) => values.push(Val::Unsupported(format!("spread: {reason}"))),
+                _ => values.push(Val::Unsupported(format!("spread of a non-array value on line {line}"))),
+            }
+        }
+        values
+    }
+
+    fn call(&mut self, callee: Val, args: Vec<Val>, callee_expr: &Expr, line: usize) -> Val
+    {
+        let arg = |idx: usize| args.get(idx).cloned().unwrap_or(Val::Literal(JsonValue::Null));
+        let validator = |val: Val| -> Result<JsonValue, String> { to_validator(&val).map(|(desc, _)| desc) };
+
+        let result: Result<Val, String> = match callee {
+            Val::VMethod(method) => (|| {
+                let desc = match method.as_str() {
+                    "string" | "boolean" | "null" | "any" | "bytes" | "int64" => json!({ "type": method }),
+                    "number" | "float64" => json!({ "type": "number" }),
+                    "bigint" => json!({ "type": "int64" }),
+                    "id" => match arg(0) {
+                        Val::Literal(JsonValue::String(table)) => json!({ "type": "id", "tableName": table }),
+                        _ => return Err(format!("v.id() on line {line} needs a string literal table name")),
+                    },
+                    "literal" => match arg(0) {
+                        Val::Literal(value) => json!({ "type": "literal", "value": value }),
+                        _ => return Err(format!("v.literal() on line {line} needs a literal value")),
+                    },
+                    "optional" => json!({ "type": "optional", "inner": validator(arg(0))? }),
+                    "nullable" => json!({ "type": "union", "variants": [validator(arg(0))?, { "type": "null" }] }),
+                    "array" => json!({ "type": "array", "elements": validator(arg(0))? }),
+                    "record" => json!({ "type": "record", "keyType": validator(arg(0))?, "valueType": validator(arg(1))? }),
+                    "union" => {
+                        let variants = args.iter().cloned().map(validator).collect::<Result<Vec<_>, _>>()?;
+                        json!({ "type": "union", "variants": variants })
+                    }
+                    "object" => return to_validator(&arg(0)).map(|(desc, order)| Val::Validator(desc, order)),
+                    other => return Err(format!("`v.{other}()` on line {line} is not supported")),
+                };
+                Ok(Val::Validator(desc, Vec::new()))
+            })(),
+            Val::Helper(name) => helper_call(&name, &args, line),
+            Val::Builtin(name) if name == "defineTable" => to_validator(&arg(0)).map(|(desc, order)| {
+                Val::Table(TableVal {
+                    desc,
+                    order,
+                    indexes: Vec::new(),
+                })
+            }),
+            Val::Builtin(name) if name == "defineSchema" => match (arg(0), schema_options(arg(1), line)) {
+                (_, Err(reason)) => Err(reason),
+                (Val::Object(fields), Ok(options)) => {
+                    let tables = fields
+                        .into_iter()
+                        .map(|(name, table)| {
+                            let table = match table {
+                                Val::Table(table) => Ok(table),
+                                Val::Opaque(reason) | Val::Unsupported(reason) => Err(format!("table `{name}`: {reason}")),
+                                _ => Err(format!("table `{name}` is not a defineTable(...) call")),
+                            };
+                            (name, table)
+                        })
+                        .collect();
+                    Ok(Val::Schema(tables, options))
+                }
+                (Val::Opaque(reason) | Val::Unsupported(reason), _) => Err(reason),
+                _ => Err(format!("defineSchema() on line {line} needs an object literal")),
+            },
+            Val::Builtin(kind) => {
+                let (args, returns) = match arg(0) {
+                    Val::Object(fields) => {
+                        let field = |name: &str| {
+                            fields
+                                .iter()
+                                .find(|(key, _)| key == name)
+                                .map(|(_, value)| Box::new(value.clone()))
+                                .filter(|value| !matches!(**value, Val::Literal(JsonValue::Null)))
+                        };
+                        (field("args"), field("returns"))
+                    }
+                    _ => (None, None),
+                };
+                Ok(Val::Function { kind, args, returns })
+            }
+            Val::Bound(receiver, method) => match *receiver {
+                Val::Table(mut table) => {
+                    if method == "index" {
+                        if let (Val::Literal(JsonValue::String(name)), Val::Array(fields)) = (arg(0), arg(1)) {
+                            let fields = fields
+                                .into_iter()
+                                .filter_map(|field| match field {
+                                    Val::Literal(JsonValue::String(field)) => Some(field),
+                                    _ => None,
+                                })
+                                .collect();
+                            table.indexes.push((name, fields));
+                        }
+                    }
+                    Ok(Val::Table(table))
+                }
+                receiver @ Val::Validator(..) => self.validator_method(receiver, &method, &args, line),
+                // `STATUSES.map(v.literal)` builds union members from a shared constant
+                Val::Array(items) if method == "map" => items
+                    .into_iter()
+                    .enumerate()
+                    .map(
+                        |(idx, item)| match self.call(arg(0), vec![item, Val::Literal(json!(idx))], callee_expr, line) {
+                            Val::Unsupported(reason) => Err(reason),
+                            value => Ok(value),
+                        },
+                    )
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Val::Array),
+                _ => Err(format!("`.{method}()` on line {line} can't be evaluated statically")),
+            },
+            Val::Custom(desc) => Ok(Val::Validator(desc, Vec::new())),
+            Val::Closure(closure) => match self.apply(&closure, args, line) {
+                // Wrapper factories stay a Bun concern, even when they're simple enough to evaluate
+                Val::Function { .. } => Err(format!(
+                    "`{}(...)` on line {line} wraps a function registrar, which needs the Bun backend",
+                    callee_name(callee_expr)
+                )),
+                result => Ok(result),
+            },
+            Val::Opaque(reason) | Val::Unsupported(reason) => Err(reason),
+            _ => Err(format!(
+                "call to `{}` on line {line} can't be evaluated statically",
+                callee_name(callee_expr)
+            )),
+        };
+
+        result.unwrap_or_else(Val::Unsupported)
+    }
+
+    /// Evaluate a call to a local function by binding the arguments to its
+    /// parameters. Missing or `undefined` arguments take the parameter's default.
+    fn apply(&mut self, closure: &Closure, args: Vec<Val>, line: usize) -> Val
+    {
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Val::Unsupported(format!("the call on line {line} nests too deeply"));
+        }
+        let module = match self.load(&closure.path) {
+            Ok(module) => module,
+            Err(e) => return Val::Unsupported(e),
+        };
+
+        let outer = std::mem::replace(&mut self.scope, closure.captured.clone());
+        self.call_depth += 1;
+        let mut args = args.into_iter();
+        for (name, default) in &closure.params {
+            let value = match (args.next(), default) {
+                (None | Some(Val::Literal(JsonValue::Null)), Some(default)) => self.eval(&module, default),
+                (arg, _) => arg.unwrap_or(Val::Literal(JsonValue::Null)),
+            };
+            self.scope.push((name.clone(), value));
+        }
+        let result = self.eval(&module, &closure.body);
+        self.call_depth -= 1;
+        self.scope = outer;
+        result
+    }
+
+    fn validator_method(&mut self, receiver: Val, method: &str, args: &[Val], line: usize) -> Result<Val, String>
+    {
+        // `v.string().optional()` is the chained form of `v.optional(v.string())`
+        if method == "optional" {
+            return match receiver {
+                Val::Validator(desc, _) if desc["type"] != "optional" => {
+                    Ok(Val::Validator(json!({ "type": "optional", "inner": desc }), Vec::new()))
+                }
+                receiver => Ok(receiver),
+            };
+        }
+
+        let mut fields = object_fields(&receiver)?;
+        let keys: Vec<String> = args
+            .iter()
+            .filter_map(|arg| match arg {
+                Val::Literal(JsonValue::String(key)) => Some(key.clone()),
+                _ => None,
+            })
+            .collect();
+
+        match method {
+            "extend" => {
+                let extra = object_fields(args.first().unwrap_or(&Val::Object(Vec::new())))?;
+                for (key, data_type) in extra {
+                    match fields.iter_mut().find(|(existing, _)| *existing == key) {
+                        Some(slot) => slot.1 = data_type,
+                        None => fields.push((key, data_type)),
+                    }
+                }
+            }
+            "pick" => fields.retain(|(key, _)| keys.contains(key)),
+            "omit" => fields.retain(|(key, _)| !keys.contains(key)),
+            "partial" => {
+                for (_, data_type) in &mut fields {
+                    if data_type["type"] != "optional" {
+                        *data_type = json!({ "type": "optional", "inner": data_type.clone() });
+                    }
+                }
+            }
+            other => return Err(format!("`.{other}()` on line {line} is not supported on validators")),
+        }
+
+        Ok(object_validator(fields))
+    }
+}
+
+/// An import from `convex-helpers/validators`; mirrors the Bun mock.
+fn convex_helpers_import(name: &str, line: usize) -> Val
+{
+    let validator = |desc: JsonValue| Val::Validator(desc, Vec::new());
+    match name {
+        "literals" | "nullable" | "partial" | "brandedString" | "pretend" | "systemFields" | "withSystemFields" => {
+            Val::Helper(name.to_string())
+        }
+        "id" | "object" | "array" | "record" | "literal" | "optional" | "union" => Val::VMethod(name.to_string()),
+        "any" | "string" | "boolean" | "bytes" | "int64" => validator(json!({ "type": name })),
+        "number" | "float64" => validator(json!({ "type": "number" })),
+        "bigint" => validator(json!({ "type": "int64" })),
+        "null_" => validator(json!({ "type": "null" })),
+        "deprecated" => validator(json!({ "type": "optional", "inner": { "type": "any" } })),
+        _ => Val::Unsupported(format!(
+            "`{name}` on line {line} from convex-helpers/validators can't be evaluated statically"
+        )),
+    }
+}
+
+/// A call to a [`Val::Helper`] from `convex-helpers/validators`.
+fn helper_call(name: &str, args: &[Val], line: usize) -> Result<Val, String>
+{
+    let arg = |idx: usize| args.get(idx).cloned().unwrap_or(Val::Literal(JsonValue::Null));
+    let validator = |val: &Val| to_validator(val).map(|(desc, _)| desc);
+    let optional = |desc: JsonValue| {
+        if desc["type"] == "optional" {
+            desc
+        } else {
+            json!({ "type": "optional", "inner": desc })
+        }
+    };
+    let system_fields = |table: Val| match table {
+        Val::Literal(JsonValue::String(table)) => Ok(vec![
+            (
+                "_id".to_string(),
+                Val::Validator(json!({ "type": "id", "tableName": table }), Vec::new()),
+            ),
+            (
+                "_creationTime".to_string(),
+                Val::Validator(json!({ "type": "number" }), Vec::new()),
+            ),
+        ]),
+        _ => Err(format!("`{name}()` on line {line} needs a string literal table name")),
+    };
+
+    let desc = match name {
+        "literals" => {
+            let variants = args
+                .iter()
+                .map(|value| match value {
+                    Val::Literal(value) => Ok(json!({ "type": "literal", "value": value })),
+                    _ => Err(format!("literals() on line {line} needs literal values")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            json!({ "type": "union", "variants": variants })
+        }
+        "nullable" => json!({ "type": "union", "variants": [validator(&arg(0))?, { "type": "null" }] }),
+        "brandedString" => json!({ "type": "string" }),
+        "pretend" => return to_validator(&arg(0)).map(|(desc, order)| Val::Validator(desc, order)),
+        // Plain fields stay plain fields, for spreading into `defineTable({ ... })`
+        "partial" => {
+            return match arg(0) {
+                Val::Object(fields) => fields
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, Val::Validator(optional(validator(&value)?), Vec::new()))))
+                    .collect::<Result<Vec<_>, String>>()
+                    .map(Val::Object),
+                object => object_fields(&object)
+                    .map(|fields| object_validator(fields.into_iter().map(|(key, desc)| (key, optional(desc))).collect())),
+            };
+        }
+        "systemFields" => return system_fields(arg(0)).map(Val::Object),
+        "withSystemFields" => {
+            let mut fields = match arg(1) {
+                Val::Object(fields) => fields,
+                _ => return Err(format!("withSystemFields() on line {line} needs an object of fields")),
+            };
+            for (key, value) in system_fields(arg(0))? {
+                set_field(&mut fields, key, value);
+            }
+            return Ok(Val::Object(fields));
+        }
+        other => {
+            return Err(format!(
+                "`{other}()` on line {line} from convex-helpers/validators is not supported"
+            ))
+        }
+    };
+    Ok(Val::Validator(desc, Vec::new()))
+}
+
+/// The validator `paginationOptsValidator` expands to (mirrors the Bun mock).
+fn pagination_opts_validator() -> Val
+{
+    let nullable_string = json!({ "type": "union", "variants": [{ "type": "string" }, { "type": "null" }] });
+    let optional_number = json!({ "type": "optional", "inner": { "type": "number" } });
+    object_validator(vec![
+        ("numItems".to_string(), json!({ "type": "number" })),
+        ("cursor".to_string(), nullable_string.clone()),
+        (
+            "endCursor".to_string(),
+            json!({ "type": "optional", "inner": nullable_string }),
+        ),
+        ("id".to_string(), optional_number.clone()),
+        ("maximumRowsRead".to_string(), optional_number.clone()),
+        ("maximumBytesRead".to_string(), optional_number),
+    ])
+}
+
+fn object_validator(fields: Vec<(String, JsonValue)>) -> Val
+{
+    let (desc, order) = object_descriptor(fields);
+    Val::Validator(desc, order)
+}
+
+fn object_descriptor(fields: Vec<(String, JsonValue)>) -> (JsonValue, Vec<String>)
+{
+    let order = fields.iter().map(|(key, _)| key.clone()).collect();
+    let properties: Map<String, JsonValue> = fields.into_iter().collect();
+    (json!({ "type": "object", "properties": properties }), order)
+}
+
+/// Convert a value into a descriptor, treating plain objects as `v.object(...)`.
+fn to_validator(val: &Val) -> Result<(JsonValue, Vec<String>), String>
+{
+    match val {
+        Val::Validator(desc, order) => Ok((desc.clone(), order.clone())),
+        Val::Object(fields) => {
+            let mut converted = Vec::new();
+            for (key, value) in fields {
+                if key.is_empty() {
+                    if let Val::Opaque(reason) = value {
+                        return Err(reason.clone());
+                    }
+                }
+                let (desc, _) = to_validator(value).map_err(|reason| format!("field `{key}`: {reason}"))?;
+                converted.push((key.clone(), desc));
+            }
+            Ok(object_descriptor(converted))
+        }
+        Val::Opaque(reason) | Val::Unsupported(reason) => Err(reason.clone()),
+        Val::Literal(value) => Err(format!("expected a validator, found the literal {value}")),
+        _ => Err("expected a validator".to_string()),
+    }
+}
+
+/// The options object passed as `defineSchema`'s second argument. Options the
+/// generator doesn't use are ignored.
+fn schema_options(val: Val, line: usize) -> Result<SchemaOptions, String>
+{
+    let mut options = SchemaOptions::default();
+    let fields = match val {
+        Val::Literal(JsonValue::Null) => return Ok(options),
+        Val::Object(fields) => fields,
+        Val::Opaque(reason) | Val::Unsupported(reason) => return Err(reason),
+        _ => return Err(format!("defineSchema() options on line {line} need an object literal")),
+    };
+    for (key, value) in fields {
+        let flag = match key.as_str() {
+            "schemaValidation" => &mut options.schema_validation,
+            "strictTableNameTypes" => &mut options.strict_table_name_types,
+            "" => match value {
+                Val::Opaque(reason) | Val::Unsupported(reason) => return Err(reason),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        match value {
+            Val::Literal(JsonValue::Bool(enabled)) => *flag = enabled,
+            _ => return Err(format!("defineSchema() option `{key}` on line {line} must be true or false")),
+        }
+    }
+    Ok(options)
+}
+
+/// Fields of an object validator (or plain object) in declaration order.
+fn object_fields(val: &Val) -> Result<Vec<(String, JsonValue)>, String>
+{
+    let (desc, order) = to_validator(val)?;
+    if desc["type"] != "object" {
+        return Ok(Vec::new());
+    }
+    Ok(ordered_properties(&desc, &order))
+}
+
+fn ordered_properties(desc: &JsonValue, order: &[String]) -> Vec<(String, JsonValue)>
+{
+    let Some(properties) = desc["properties"].as_object() else {
+        return Vec::new();
+    };
+    let mut fields: Vec<(String, JsonValue)> = order
+        .iter()
+        .filter_map(|key| properties.get(key).map(|value| (key.clone(), value.clone())))
+        .collect();
+    for (key, value) in properties {
+        if !order.contains(key) {
+            fields.push((key.clone(), value.clone()));
+        }
+    }
+    fields
+}
+
+fn set_field(fields: &mut Vec<(String, Val)>, key: String, value: Val)
+{
+    match fields.iter_mut().find(|(existing, _)| *existing == key) {
+        Some(slot) => slot.1 = value,
+        None => fields.push((key, value)),
+    }
+}
+
+fn number_json(value: f64) -> JsonValue
+{
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        JsonValue::from(value as i64)
+    } else {
+        JsonValue::from(value)
+    }
+}
+
+fn callee_name(expr: &Expr) -> String
+{
+    match expr {
+        Expr::Ident(name, _) => name.clone(),
+        Expr::Member(object, name) => format!("{}.{name}", callee_name(object)),
+        Expr::Call(callee, ..) => format!("{}(...)", callee_name(callee)),
+        _ => "<expression>".to_string(),
+    }
+}
+
+/// Resolve a relative import specifier to a `.ts` file.
+fn resolve_relative(from: &Path, source: &str) -> Option<PathBuf>
+{
+    let base = from.parent()?.join(source);
+    let base: PathBuf = base.components().fold(PathBuf::new(), |mut path, component| {
+        match component {
+            Component::ParentDir => {
+                path.pop();
+            }
+            Component::CurDir => {}
+            other => path.push(other),
+        }
+        path
+    });
+    let stem = base.to_string_lossy().trim_end_matches(".js").to_string();
+    [PathBuf::from(format!("{stem}.ts")), base.clone(), base.join("index.ts")]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
@@ -12,36 +12,50 @@
 //! - Gets cleaned with `cargo clean`
 //! - Respects `CARGO_TARGET_DIR` environment variable
 //! - Can be added to `.gitignore` if desired
+//! - Can be cleared with `convex_typegen::clean_cache`
+//!
+//! ## Platforms
+//!
+//! Linux, macOS, and Windows on x64 and aarch64 are downloaded. Linux picks
+//! the `musl` build on musl systems such as Alpine, and x64 picks the
+//! `baseline` build on CPUs without AVX2, which crash on the default one.
+//! Other platforms need `Configuration::bun_path` or the Ast backend.
 
 use std::path::{Path, PathBuf};
 use std::time::Duration;
-use std::{fs, io, thread};
+use std::{fs, thread};
+
+use log::Level;
 
+use crate::cache::{self, CacheLock};
 use crate::errors::ConvexTypeGeneratorError;
+use crate::logging;
 
 const BUN_VERSION: &str = "1.2.6";
 
-/// RAII guard that removes the lock file when dropped.
-struct FileLockGuard
-{
-    path: PathBuf,
-    _file: fs::File,
-}
-
-impl Drop for FileLockGuard
-{
-    fn drop(&mut self)
-    {
-        let _ = fs::remove_file(&self.path);
-    }
-}
-
 /// Get the path to the cached bun binary, downloading it if necessary.
 ///
-/// Uses a file lock to prevent concurrent downloads when multiple processes
+/// Uses a [`CacheLock`] to prevent concurrent downloads when multiple processes
 /// or test threads try to get bun at the same time (avoids "Text file busy" errors).
-pub(crate) fn get_bun_path() -> Result<PathBuf, ConvexTypeGeneratorError>
+/// With `offline`, a missing binary is an error instead of a download.
+/// `configured` (`Configuration::bun_path`) is used as is. `quiet` drops the
+/// download notices.
+pub(crate) fn get_bun_path(
+    configured: Option<&Path>,
+    offline: bool,
+    quiet: bool,
+) -> Result<PathBuf, ConvexTypeGeneratorError>
 {
+    if let Some(path) = configured {
+        if verify_bun_binary(path)? {
+            return Ok(path.to_path_buf());
+        }
+        return Err(ConvexTypeGeneratorError::ExtractionFailed {
+            details: format!("bun_path {} doesn't run `bun --version`", path.display()),
+            causes: Vec::new(),
+        });
+    }
+
     // First, check if bun is available in PATH
     if let Ok(output) = std::process::Command::new("bun").arg("--version").output() {
         if output.status.success() {
@@ -54,82 +68,116 @@ pub(crate) fn get_bun_path() -> Result<PathBuf, ConvexTypeGeneratorError>
     let cache_dir = get_cache_dir()?;
     let bun_path = cache_dir.join(get_bun_executable_name());
 
-    // Use a lock file to synchronize concurrent access.
-    // The lock is held until _lock is dropped (end of this function).
-    let lock_path = cache_dir.join(".lock");
-    let _lock = acquire_file_lock(&lock_path)?;
+    // Lock the cache so concurrent builds wait for one download instead of
+    // racing. The lock is held until _lock is dropped (end of this function).
+    let _lock = CacheLock::acquire(&cache_dir.join(".lock"), || {
+        logging::emit(
+            Level::Info,
+            quiet,
+            format_args!(
+                "Waiting for another build to finish with the Bun cache in {}",
+                cache_dir.display()
+            ),
+        );
+    })?;
 
     if bun_path.exists() && verify_bun_binary(&bun_path)? {
         return Ok(bun_path);
     }
 
-    // Download and install bun (writes to temp file, then atomically renames)
-    download_and_install_bun(&cache_dir, &bun_path)?;
+    if offline {
+        return Err(ConvexTypeGeneratorError::ExtractionFailed {
+            details: format!(
+                "Bun is not on PATH or cached in {} and offline is set; install Bun or use ExtractionBackend::Ast",
+                cache_dir.display()
+            ),
+            causes: Vec::new(),
+        });
+    }
 
-    Ok(bun_path)
+    download_bun(bun_path, &cache_dir, quiet)
 }
 
-/// Acquire an exclusive file lock, retrying with backoff.
-/// Returns a guard that removes the lock file when dropped.
-fn acquire_file_lock(lock_path: &Path) -> Result<FileLockGuard, ConvexTypeGeneratorError>
+/// Download Bun to `bun_path` and check that it runs.
+#[cfg(not(feature = "no-download"))]
+fn download_bun(bun_path: PathBuf, _cache_dir: &Path, quiet: bool) -> Result<PathBuf, ConvexTypeGeneratorError>
 {
-    use std::io::Write;
-
-    let mut attempts = 0;
-    let max_attempts = 60; // Up to ~60 seconds total wait
-
-    loop {
-        match fs::OpenOptions::new().write(true).create_new(true).open(lock_path) {
-            Ok(mut file) => {
-                let _ = write!(file, "{}", std::process::id());
-                return Ok(FileLockGuard {
-                    path: lock_path.to_path_buf(),
-                    _file: file,
-                });
-            }
-            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
-                attempts += 1;
-                if attempts >= max_attempts {
-                    // Stale lock — remove and retry once
-                    let _ = fs::remove_file(lock_path);
-                    let file = fs::OpenOptions::new()
-                        .write(true)
-                        .create_new(true)
-                        .open(lock_path)
-                        .map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
-                            details: format!("Failed to acquire lock after timeout: {e}"),
-                        })?;
-                    return Ok(FileLockGuard {
-                        path: lock_path.to_path_buf(),
-                        _file: file,
-                    });
-                }
-                thread::sleep(Duration::from_secs(1));
-            }
-            Err(e) => {
-                return Err(ConvexTypeGeneratorError::ExtractionFailed {
-                    details: format!("Failed to create lock file: {e}"),
-                });
-            }
+    // Download and install bun (writes to temp file, then atomically renames)
+    let platform = get_platform_info()?;
+    let install = |platform: &Platform| -> Result<bool, ConvexTypeGeneratorError> {
+        logging::emit(
+            Level::Info,
+            quiet,
+            format_args!("Downloading bun {BUN_VERSION} ({})...", platform.artifact()),
+        );
+        download_and_install_bun(platform, &bun_path)?;
+        let runs = verify_bun_binary(&bun_path)?;
+        if runs {
+            logging::emit(
+                Level::Info,
+                quiet,
+                format_args!("Bun downloaded successfully to {}", bun_path.display()),
+            );
         }
+        Ok(runs)
+    };
+    if install(&platform)? {
+        return Ok(bun_path);
     }
+
+    // AVX2 detection can miss (e.g. under emulation); the baseline build runs
+    // on any x64 CPU
+    if platform.arch == "x64" && !platform.baseline {
+        logging::emit(
+            Level::Warn,
+            quiet,
+            format_args!(
+                "Bun {} doesn't run on this CPU, trying the baseline build",
+                platform.artifact()
+            ),
+        );
+        let baseline = Platform {
+            baseline: true,
+            ..platform
+        };
+        if install(&baseline)? {
+            return Ok(bun_path);
+        }
+    }
+
+    Err(ConvexTypeGeneratorError::ExtractionFailed {
+        details: format!(
+            "The downloaded Bun ({}) doesn't run here; {}",
+            bun_path.display(),
+            UNSUPPORTED_GUIDANCE
+        ),
+        causes: Vec::new(),
+    })
+}
+
+/// With the `no-download` feature there is no downloader to fall back to.
+#[cfg(feature = "no-download")]
+fn download_bun(_bun_path: PathBuf, cache_dir: &Path, _quiet: bool) -> Result<PathBuf, ConvexTypeGeneratorError>
+{
+    Err(ConvexTypeGeneratorError::ExtractionFailed {
+        details: format!(
+            "Bun is not on PATH or cached in {} and convex-typegen was built with the `no-download` feature; {}",
+            cache_dir.display(),
+            UNSUPPORTED_GUIDANCE
+        ),
+        causes: Vec::new(),
+    })
 }
 
 /// Get the cache directory for bun binaries.
 /// Uses project-local target directory: target/.convex-typegen-cache/bun/{version}/
 fn get_cache_dir() -> Result<PathBuf, ConvexTypeGeneratorError>
 {
-    // Use CARGO_TARGET_DIR if set (for workspaces), otherwise default to ./target
-    let target_dir = std::env::var("CARGO_TARGET_DIR")
-        .ok()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("target"));
-
-    // Store bun in target/.convex-typegen-cache/bun/{version}/
-    let cache_dir = target_dir.join(".convex-typegen-cache").join("bun").join(BUN_VERSION);
+    let cache_dir = cache::bun_dir().join(BUN_VERSION);
 
     fs::create_dir_all(&cache_dir).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
         details: format!("Failed to create cache directory {}: {e}", cache_dir.display()),
+        causes: Vec::new(),
     })?;
 
     Ok(cache_dir)
@@ -165,6 +213,7 @@ fn verify_bun_binary(path: &Path) -> Result<bool, ConvexTypeGeneratorError>
                 }
                 return Err(ConvexTypeGeneratorError::ExtractionFailed {
                     details: format!("Failed to verify bun binary: {e}"),
+                    causes: Vec::new(),
                 });
             }
         }
@@ -174,11 +223,15 @@ fn verify_bun_binary(path: &Path) -> Result<bool, ConvexTypeGeneratorError>
 }
 
 /// Download and install bun to the cache directory.
-fn download_and_install_bun(_cache_dir: &Path, target_path: &Path) -> Result<(), ConvexTypeGeneratorError>
+#[cfg(not(feature = "no-download"))]
+fn download_and_install_bun(platform: &Platform, target_path: &Path) -> Result<(), ConvexTypeGeneratorError>
 {
-    let download_url = get_download_url()?;
-
-    eprintln!("Downloading bun {BUN_VERSION}...");
+    // Bun release URLs follow this pattern:
+    // https://github.com/oven-sh/bun/releases/download/bun-v{version}/bun-{os}-{arch}[-musl][-baseline].zip
+    let download_url = format!(
+        "https://github.com/oven-sh/bun/releases/download/bun-v{BUN_VERSION}/{}.zip",
+        platform.artifact()
+    );
 
     // Create a client with timeout to prevent hanging
     let client = reqwest::blocking::Client::builder()
@@ -187,6 +240,7 @@ fn download_and_install_bun(_cache_dir: &Path, target_path: &Path) -> Result<(),
         .build()
         .map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
             details: format!("Failed to create HTTP client: {e}"),
+            causes: Vec::new(),
         })?;
 
     let response = client
@@ -194,16 +248,19 @@ fn download_and_install_bun(_cache_dir: &Path, target_path: &Path) -> Result<(),
         .send()
         .map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
             details: format!("Failed to download bun from {download_url}: {e}"),
+            causes: Vec::new(),
         })?;
 
     if !response.status().is_success() {
         return Err(ConvexTypeGeneratorError::ExtractionFailed {
             details: format!("Failed to download bun: HTTP {} from {download_url}", response.status()),
+            causes: Vec::new(),
         });
     }
 
     let bytes = response.bytes().map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
         details: format!("Failed to read download response: {e}"),
+        causes: Vec::new(),
     })?;
 
     // Extract the archive and find the bun binary
@@ -212,21 +269,49 @@ fn download_and_install_bun(_cache_dir: &Path, target_path: &Path) -> Result<(),
     Ok(())
 }
 
-/// Get the download URL for the current platform.
-fn get_download_url() -> Result<String, ConvexTypeGeneratorError>
-{
-    let (os, arch) = get_platform_info()?;
+/// What to do where no downloaded Bun runs.
+const UNSUPPORTED_GUIDANCE: &str =
+    "install Bun on PATH, set bun_path (CONVEX_TYPEGEN_BUN_PATH) to a Bun binary, or use ExtractionBackend::Ast";
 
-    // Bun release URLs follow this pattern:
-    // https://github.com/oven-sh/bun/releases/download/bun-v{version}/bun-{os}-{arch}.zip
-    let url = format!("https://github.com/oven-sh/bun/releases/download/bun-v{BUN_VERSION}/bun-{os}-{arch}.zip");
+/// The Bun release build for this machine.
+#[cfg(not(feature = "no-download"))]
+#[derive(Debug, Clone, Copy)]
+struct Platform
+{
+    os: &'static str,
+    arch: &'static str,
+    /// Linked against musl instead of glibc (Linux only).
+    musl: bool,
+    /// Built for x64 CPUs without AVX2.
+    baseline: bool,
+}
 
-    Ok(url)
+#[cfg(not(feature = "no-download"))]
+impl Platform
+{
+    /// The release artifact name, e.g. `bun-linux-x64-musl-baseline`.
+    fn artifact(&self) -> String
+    {
+        let mut name = format!("bun-{}-{}", self.os, self.arch);
+        if self.musl {
+            name.push_str("-musl");
+        }
+        if self.baseline {
+            name.push_str("-baseline");
+        }
+        name
+    }
 }
 
-/// Get the OS and architecture for downloading the correct binary.
-fn get_platform_info() -> Result<(&'static str, &'static str), ConvexTypeGeneratorError>
+/// Get the OS, architecture, and variant for downloading the correct binary.
+#[cfg(not(feature = "no-download"))]
+fn get_platform_info() -> Result<Platform, ConvexTypeGeneratorError>
 {
+    let unsupported = |what: &str| ConvexTypeGeneratorError::ExtractionFailed {
+        details: format!("Bun publishes no build for {what}; {UNSUPPORTED_GUIDANCE}"),
+        causes: Vec::new(),
+    };
+
     let os = if cfg!(target_os = "linux") {
         "linux"
     } else if cfg!(target_os = "macos") {
@@ -234,9 +319,7 @@ fn get_platform_info() -> Result<(&'static str, &'static str), ConvexTypeGenerat
     } else if cfg!(target_os = "windows") {
         "windows"
     } else {
-        return Err(ConvexTypeGeneratorError::ExtractionFailed {
-            details: format!("Unsupported OS: {}", std::env::consts::OS),
-        });
+        return Err(unsupported(&format!("the {} OS", std::env::consts::OS)));
     };
 
     let arch = if cfg!(target_arch = "x86_64") {
@@ -244,23 +327,66 @@ fn get_platform_info() -> Result<(&'static str, &'static str), ConvexTypeGenerat
     } else if cfg!(target_arch = "aarch64") {
         "aarch64"
     } else {
-        return Err(ConvexTypeGeneratorError::ExtractionFailed {
-            details: format!("Unsupported architecture: {}", std::env::consts::ARCH),
-        });
+        // e.g. `arm` for ARMv7 boards
+        return Err(unsupported(&format!("the {} architecture", std::env::consts::ARCH)));
     };
+    if os == "windows" && arch == "aarch64" {
+        return Err(unsupported("Windows on aarch64"));
+    }
 
-    Ok((os, arch))
+    Ok(Platform {
+        os,
+        arch,
+        musl: os == "linux" && is_musl(),
+        baseline: arch == "x64" && !has_avx2(),
+    })
+}
+
+/// Whether this Linux system uses musl libc: this crate was built for a musl
+/// target, or the system has the musl loader and no glibc one (Alpine).
+#[cfg(not(feature = "no-download"))]
+fn is_musl() -> bool
+{
+    if cfg!(target_env = "musl") {
+        return true;
+    }
+    let has_loader = |prefix: &str| {
+        ["/lib", "/lib64"].iter().any(|dir| {
+            fs::read_dir(dir).is_ok_and(|entries| {
+                entries
+                    .flatten()
+                    .any(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+            })
+        })
+    };
+    has_loader("ld-musl-") && !has_loader("ld-linux")
+}
+
+/// Whether the CPU has AVX2, which the default x64 Bun build requires.
+#[cfg(not(feature = "no-download"))]
+fn has_avx2() -> bool
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
 }
 
 /// Extract the bun binary from the downloaded archive.
 /// Writes to a temporary file first, then atomically renames to the target path.
 /// This prevents "Text file busy" (ETXTBSY) errors when another process tries to
 /// execute the binary while it's still being written.
+#[cfg(not(feature = "no-download"))]
 fn extract_bun_from_archive(bytes: &[u8], target_path: &Path) -> Result<(), ConvexTypeGeneratorError>
 {
-    let cursor = io::Cursor::new(bytes);
+    let cursor = std::io::Cursor::new(bytes);
     let mut archive = zip::ZipArchive::new(cursor).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
         details: format!("Failed to read zip archive: {e}"),
+        causes: Vec::new(),
     })?;
 
     let exe_name = get_bun_executable_name();
@@ -270,6 +396,7 @@ fn extract_bun_from_archive(bytes: &[u8], target_path: &Path) -> Result<(), Conv
     for i in 0..archive.len() {
         let mut file = archive.by_index(i).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
             details: format!("Failed to read zip entry: {e}"),
+            causes: Vec::new(),
         })?;
 
         let name = file.name();
@@ -279,12 +406,14 @@ fn extract_bun_from_archive(bytes: &[u8], target_path: &Path) -> Result<(), Conv
             // Write to a temp file first to avoid ETXTBSY
             let mut outfile = fs::File::create(&temp_path).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
                 details: format!("Failed to create temp file {}: {e}", temp_path.display()),
+                causes: Vec::new(),
             })?;
 
-            io::copy(&mut file, &mut outfile).map_err(|e| {
+            std::io::copy(&mut file, &mut outfile).map_err(|e| {
                 let _ = fs::remove_file(&temp_path);
                 ConvexTypeGeneratorError::ExtractionFailed {
                     details: format!("Failed to extract bun binary: {e}"),
+                    causes: Vec::new(),
                 }
             })?;
 
@@ -298,11 +427,13 @@ fn extract_bun_from_archive(bytes: &[u8], target_path: &Path) -> Result<(), Conv
                 let mut perms = fs::metadata(&temp_path)
                     .map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
                         details: format!("Failed to read file metadata: {e}"),
+                        causes: Vec::new(),
                     })?
                     .permissions();
                 perms.set_mode(0o755);
                 fs::set_permissions(&temp_path, perms).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
                     details: format!("Failed to set executable permissions: {e}"),
+                    causes: Vec::new(),
                 })?;
             }
 
@@ -312,15 +443,15 @@ fn extract_bun_from_archive(bytes: &[u8], target_path: &Path) -> Result<(), Conv
                 let _ = fs::remove_file(&temp_path);
                 ConvexTypeGeneratorError::ExtractionFailed {
                     details: format!("Failed to rename temp file to {}: {e}", target_path.display()),
+                    causes: Vec::new(),
                 }
             })?;
-
-            eprintln!("Bun downloaded successfully to {}", target_path.display());
             return Ok(());
         }
     }
 
     Err(ConvexTypeGeneratorError::ExtractionFailed {
         details: format!("Bun binary '{}' not found in archive", exe_name),
+        causes: Vec::new(),
     })
 }
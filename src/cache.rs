@@ -0,0 +1,362 @@
+//! The on-disk caches and [`clean_cache`] to clear them.
+//!
+//! Everything lives under `target/.convex-typegen-cache/`, or under
+//! `$CARGO_TARGET_DIR` when it is set:
+//!
+//! - `bun/{version}/` holds a downloaded Bun binary and the `.lock` file that
+//!   keeps two builds from downloading it at once.
+//! - `extract/` is Bun's transpiler cache for extraction runs. Bun writes its
+//!   entries atomically, so concurrent runs share it without a lock.
+//!
+//! Build scripts of several workspace members run at the same time, so every
+//! change to a Bun directory happens under its [`CacheLock`].
+
+use std::fs::{self, File, TryLockError};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::errors::ConvexTypeGeneratorError;
+
+/// Which caches [`clean_cache`] clears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind
+{
+    /// Everything below.
+    All,
+    /// Downloaded Bun binaries, every version.
+    Bun,
+    /// Bun's transpiler cache for extraction runs.
+    Extraction,
+    /// Only lock files no running build holds.
+    Locks,
+}
+
+/// What [`clean_cache`] removed.
+#[derive(Debug, Clone, Default)]
+pub struct CleanReport
+{
+    /// Removed files and directories.
+    pub removed: Vec<PathBuf>,
+    /// Bytes the removed files took up.
+    pub freed_bytes: u64,
+    /// Locks held by a running build. Their Bun directories were left alone.
+    pub held_locks: Vec<PathBuf>,
+}
+
+/// Clears the caches of `kind`, so a corrupted download or cache can be
+/// recovered from without knowing where it lives. Every kind removes lock
+/// files left behind by crashed builds. A Bun directory whose lock is held by
+/// a running build is skipped and listed in [`CleanReport::held_locks`].
+///
+/// The cache is found the way generation finds it, from `CARGO_TARGET_DIR` or
+/// `target` in the current directory. Missing caches are not an error.
+///
+/// # Errors
+/// Fails when a cache file can't be read or removed.
+pub fn clean_cache(kind: CacheKind) -> Result<CleanReport, ConvexTypeGeneratorError>
+{
+    let mut report = CleanReport::default();
+
+    for version_dir in subdirectories(&bun_dir())? {
+        let lock = version_dir.join(".lock");
+        if !lock.exists() && !matches!(kind, CacheKind::All | CacheKind::Bun) {
+            continue;
+        }
+        // Holding the lock while removing keeps a build from using the
+        // directory halfway through
+        let Some(_guard) = CacheLock::try_acquire(&lock)? else {
+            report.held_locks.push(lock);
+            continue;
+        };
+        if matches!(kind, CacheKind::All | CacheKind::Bun) {
+            remove(&version_dir, &mut report)?;
+        } else {
+            remove(&lock, &mut report)?;
+        }
+    }
+
+    if matches!(kind, CacheKind::All | CacheKind::Extraction) {
+        remove(&extraction_dir(), &mut report)?;
+    }
+
+    Ok(report)
+}
+
+/// `target/.convex-typegen-cache/bun`, holding one directory per Bun version.
+pub(crate) fn bun_dir() -> PathBuf
+{
+    root().join("bun")
+}
+
+/// `target/.convex-typegen-cache/extract`, passed to Bun as its transpiler
+/// cache.
+pub(crate) fn extraction_dir() -> PathBuf
+{
+    root().join("extract")
+}
+
+fn root() -> PathBuf
+{
+    // Use CARGO_TARGET_DIR if set (for workspaces), otherwise default to ./target
+    std::env::var("CARGO_TARGET_DIR")
+        .ok()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target"))
+        .join(".convex-typegen-cache")
+}
+
+fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>, ConvexTypeGeneratorError>
+{
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(dir, e)),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| io_error(dir, e))?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Remove the file or directory at `path`, if there is one, and record it.
+fn remove(path: &Path, report: &mut CleanReport) -> Result<(), ConvexTypeGeneratorError>
+{
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(io_error(path, e)),
+    };
+    let size = size_of(path, &metadata);
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+    .map_err(|e| io_error(path, e))?;
+
+    report.freed_bytes += size;
+    report.removed.push(path.to_path_buf());
+    Ok(())
+}
+
+/// Bytes taken up by the files at and below `path`. Unreadable entries count
+/// as empty.
+fn size_of(path: &Path, metadata: &fs::Metadata) -> u64
+{
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            fs::symlink_metadata(&path).ok().map(|metadata| size_of(&path, &metadata))
+        })
+        .sum()
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> ConvexTypeGeneratorError
+{
+    ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    }
+}
+
+/// An exclusive lock on a cache directory, held until dropped.
+///
+/// It is an OS advisory lock on a `.lock` file, so the OS releases it when the
+/// holder exits, even by crashing, and a waiting build never has to guess
+/// whether it is stale. The file records the holder's pid and stays behind.
+/// On file systems without advisory locks, such as some network mounts, the
+/// file's existence is the lock instead. Such a lock is broken once its pid is
+/// no longer running.
+pub(crate) struct CacheLock
+{
+    path: PathBuf,
+    _file: File,
+    /// Whether the lock is the file's existence, so dropping removes it.
+    pid_file: bool,
+}
+
+impl CacheLock
+{
+    /// Wait for the lock at `path`. `on_wait` runs once if another process
+    /// holds it.
+    pub(crate) fn acquire(path: &Path, on_wait: impl FnOnce()) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let mut on_wait = Some(on_wait);
+        loop {
+            let result = match Self::try_lock(path)? {
+                Attempt::Locked(lock) => return Ok(lock),
+                Attempt::Held(file) => {
+                    if let Some(on_wait) = on_wait.take() {
+                        on_wait();
+                    }
+                    match file {
+                        Some(file) => file.lock().map(|()| Some(file)),
+                        None => {
+                            thread::sleep(Duration::from_millis(500));
+                            Ok(None)
+                        }
+                    }
+                }
+            };
+            match result {
+                Ok(Some(file)) if still_at(&file, path) => return Self::locked(path, file, false),
+                Ok(_) => {}
+                Err(e) => return Err(io_error(path, e)),
+            }
+        }
+    }
+
+    /// The lock at `path`, or `None` when another process holds it.
+    pub(crate) fn try_acquire(path: &Path) -> Result<Option<Self>, ConvexTypeGeneratorError>
+    {
+        match Self::try_lock(path)? {
+            Attempt::Locked(lock) => Ok(Some(lock)),
+            Attempt::Held(_) => Ok(None),
+        }
+    }
+
+    /// One attempt at the lock, without waiting.
+    fn try_lock(path: &Path) -> Result<Attempt, ConvexTypeGeneratorError>
+    {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        loop {
+            let file = fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)
+                .map_err(|e| io_error(path, e))?;
+            match file.try_lock() {
+                // Another process may have removed the file between open and
+                // lock; then lock the new one
+                Ok(()) if still_at(&file, path) => return Self::locked(path, file, false).map(Attempt::Locked),
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => return Ok(Attempt::Held(Some(file))),
+                Err(TryLockError::Error(e)) if e.kind() == io::ErrorKind::Unsupported => {
+                    drop(file);
+                    return Self::try_pid_file(path);
+                }
+                Err(TryLockError::Error(e)) => return Err(io_error(path, e)),
+            }
+        }
+    }
+
+    /// The fallback lock: create `path`, breaking it when its pid is gone.
+    fn try_pid_file(path: &Path) -> Result<Attempt, ConvexTypeGeneratorError>
+    {
+        match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(file) => Self::locked(path, file, true).map(Attempt::Locked),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if holder(path).is_some_and(is_running) {
+                    return Ok(Attempt::Held(None));
+                }
+                // Left behind by a crashed process. If another waiter broke it
+                // first, create_new fails again and this one waits.
+                let _ = fs::remove_file(path);
+                match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                    Ok(file) => Self::locked(path, file, true).map(Attempt::Locked),
+                    Err(_) => Ok(Attempt::Held(None)),
+                }
+            }
+            Err(e) => Err(io_error(path, e)),
+        }
+    }
+
+    /// Record this process as the holder.
+    fn locked(path: &Path, mut file: File, pid_file: bool) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        file.set_len(0)
+            .and_then(|()| write!(file, "{}", std::process::id()))
+            .map_err(|e| io_error(path, e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            _file: file,
+            pid_file,
+        })
+    }
+}
+
+impl Drop for CacheLock
+{
+    fn drop(&mut self)
+    {
+        // An advisory lock is released when the file closes. Removing its file
+        // would let a waiter lock a file nobody else can see.
+        if self.pid_file {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+enum Attempt
+{
+    Locked(CacheLock),
+    /// Held by another process, with the open file to wait on unless the lock
+    /// is a pid file.
+    Held(Option<File>),
+}
+
+/// Whether `path` still names the open `file`.
+fn still_at(file: &File, path: &Path) -> bool
+{
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (file.metadata(), fs::metadata(path)) {
+            (Ok(open), Ok(named)) => open.dev() == named.dev() && open.ino() == named.ino(),
+            _ => false,
+        }
+    }
+    // Windows can't remove a file while it is open
+    #[cfg(not(unix))]
+    {
+        let _ = file;
+        path.exists()
+    }
+}
+
+/// The pid recorded in the lock file at `path`.
+fn holder(path: &Path) -> Option<u32>
+{
+    let mut contents = String::new();
+    File::open(path).ok()?.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+/// Whether a process with `pid` is running on this machine.
+fn is_running(pid: u32) -> bool
+{
+    if pid == std::process::id() {
+        return true;
+    }
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else if cfg!(unix) {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    } else {
+        std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+    }
+}
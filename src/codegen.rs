@@ -1,10 +1,19 @@
-use std::collections::HashSet;
-use std::io::{Seek, SeekFrom, Write};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use serde_json::Value as JsonValue;
 
+use crate::descriptors::{
+    accepts, is_displayable, is_required_string, object_array_properties, optional_inner, string_literal_values,
+};
+use crate::directives::{parse_duration, parse_priority};
 use crate::errors::ConvexTypeGeneratorError;
-use crate::types::{ConvexFunction, ConvexFunctions, ConvexSchema, ConvexTable};
+use crate::report::{arg_default_key, function_path};
+use crate::types::{
+    ConvexColumn, ConvexFunction, ConvexFunctionParam, ConvexFunctions, ConvexSchema, ConvexTable, SchemaOptions,
+};
+use crate::{manifest, migration, verify, Configuration, DuplicateKeys, FieldOverride, MethodNaming};
 
 // =============================================================================
 // CodegenContext — accumulates inline struct/enum definitions during generation
@@ -13,18 +22,33 @@ use crate::types::{ConvexFunction, ConvexFunctions, ConvexSchema, ConvexTable};
 struct CodegenContext<'a>
 {
     tables: &'a [ConvexTable],
-    extra_structs: Vec<String>,
+    config: &'a Configuration,
+    extra_structs: Vec<Chunk>,
     generated_names: HashSet<String>,
+    /// Structural key (canonical descriptor JSON) → name of the type generated
+    /// for that shape. The first occurrence names the type.
+    interned: HashMap<String, String>,
+    /// Whether new occurrences reuse interned types. Off while generating
+    /// tables, so each table field keeps its own type; on for function args
+    /// and returns, which then share schema types and each other's.
+    reuse_interned: bool,
+    /// The table or function new nested types are attributed to.
+    owner: Option<Owner>,
 }
 
 impl<'a> CodegenContext<'a>
 {
-    fn new(tables: &'a [ConvexTable]) -> Self
+    fn new(tables: &'a [ConvexTable], config: &'a Configuration) -> Self
     {
         CodegenContext {
             tables,
+            config,
             extra_structs: Vec::new(),
-            generated_names: HashSet::new(),
+            // Nested types must not take a table struct's name
+            generated_names: tables.iter().map(|table| table_struct_name(&table.name)).collect(),
+            interned: HashMap::new(),
+            reuse_interned: false,
+            owner: None,
         }
     }
 
@@ -34,15 +58,232 @@ impl<'a> CodegenContext<'a>
     {
         if !self.generated_names.contains(name) {
             self.generated_names.insert(name.to_string());
-            self.extra_structs.push(code.to_string());
+            self.extra_structs.push(Chunk {
+                name: Some(name.to_string()),
+                code: code.to_string(),
+                owner: self.owner.clone(),
+            });
         }
         name.to_string()
     }
 
-    /// Drain accumulated struct definitions into a single string.
-    fn drain_extra_structs(&mut self) -> String
+    /// `base`, or `base2`, `base3`, ... when another type already has it.
+    ///
+    /// Nested names concatenate field names, so different paths can meet
+    /// (`a.bC` and `aB.c` both give `...ABC`); without this the second shape
+    /// would silently reuse the first one's type. Nested types are named after
+    /// the type containing them, so a name claimed for a type still being
+    /// built can't be claimed again before it is registered.
+    fn claim_name(&self, base: &str) -> String
     {
-        self.extra_structs.drain(..).collect()
+        if !self.generated_names.contains(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{base}{n}"))
+            .find(|name| !self.generated_names.contains(name))
+            .expect("some numbered name is free")
+    }
+
+    /// The type already generated for an identical descriptor, if reuse is on.
+    ///
+    /// `naming_ctx` still gets a `pub type` alias to the shared type, and so do
+    /// the types nested in it, so the per-function names keep working:
+    /// `PlayersCreateProfileSettings` for `PlayersProfileSettings`.
+    fn interned_type(&mut self, data_type: &JsonValue, naming_ctx: &str) -> Option<String>
+    {
+        if !self.reuse_interned {
+            return None;
+        }
+        let shared = self.interned.get(&data_type.to_string())?.clone();
+        if shared != naming_ctx && self.alias(naming_ctx, &shared) {
+            self.alias_nested(data_type, naming_ctx);
+        }
+        Some(shared)
+    }
+
+    /// Add `pub type {name} = {shared};` unless another type has `name`.
+    /// Nothing in the output refers to the alias; it is there for callers.
+    fn alias(&mut self, name: &str, shared: &str) -> bool
+    {
+        if !self.generated_names.insert(name.to_string()) {
+            return false;
+        }
+        let alias = format!("pub type {} = {};\n\n", name, shared);
+        self.extra_structs.push(Chunk::owned(alias, self.owner.clone()));
+        true
+    }
+
+    /// Alias the types nested in the shared object type for `data_type` under
+    /// the names they would have had in a struct of its own called
+    /// `naming_ctx`, following the naming of [`convex_type_to_rust_type`].
+    fn alias_nested(&mut self, data_type: &JsonValue, naming_ctx: &str)
+    {
+        let Some(props) = data_type["properties"].as_object() else {
+            return;
+        };
+        for (field_name, field_type) in props {
+            self.alias_value(field_type, &format!("{}{}", naming_ctx, capitalize_first_letter(field_name)));
+        }
+    }
+
+    /// Alias the type generated for a value of `data_type` as `naming_ctx`,
+    /// then the types nested in it.
+    fn alias_value(&mut self, data_type: &JsonValue, naming_ctx: &str)
+    {
+        match data_type["type"].as_str().unwrap_or("unknown") {
+            "array" => self.alias_value(&data_type["elements"], naming_ctx),
+            "optional" => self.alias_value(&data_type["inner"], naming_ctx),
+            "record" => {
+                self.alias_value(&data_type["keyType"], &format!("{naming_ctx}Key"));
+                self.alias_value(&data_type["valueType"], naming_ctx);
+            }
+            "union" => {
+                let variants = data_type["variants"].as_array().map(Vec::as_slice).unwrap_or_default();
+                let (nulls, non_null): (Vec<&JsonValue>, Vec<&JsonValue>) =
+                    variants.iter().partition(|v| v["type"].as_str() == Some("null"));
+                if let ([_], [inner]) = (&nulls[..], &non_null[..]) {
+                    return self.alias_value(inner, naming_ctx);
+                }
+                if let Some((ok_type, err_type)) = try_match_result_pattern(variants) {
+                    self.alias_value(&ok_type, &format!("{naming_ctx}Value"));
+                    self.alias_value(&err_type, &format!("{naming_ctx}Error"));
+                    return;
+                }
+                self.alias_shared(data_type, naming_ctx);
+            }
+            "object" => self.alias_shared(data_type, naming_ctx),
+            _ => {}
+        }
+    }
+
+    /// Alias the interned type for `data_type`, if there is one, as
+    /// `naming_ctx`, then the types nested in it.
+    fn alias_shared(&mut self, data_type: &JsonValue, naming_ctx: &str)
+    {
+        let Some(shared) = self.interned.get(&data_type.to_string()).cloned() else {
+            return;
+        };
+        if shared != naming_ctx && self.alias(naming_ctx, &shared) {
+            self.alias_nested(data_type, naming_ctx);
+        }
+    }
+
+    /// Remember `name` as the type for this descriptor's shape (first one wins).
+    fn intern(&mut self, data_type: &JsonValue, name: &str) -> String
+    {
+        self.interned.entry(data_type.to_string()).or_insert_with(|| name.to_string());
+        name.to_string()
+    }
+
+    /// The type of an id in `table` within an id union: `ConvexId<{Table}Table>`
+    /// with typed ids, otherwise a `{Table}Id` newtype over the id string,
+    /// generated the first time it's needed.
+    fn table_id_type(&mut self, table: &str) -> String
+    {
+        if self.config.typed_ids && self.tables.iter().any(|t| t.name == table) {
+            return format!("ConvexId<{}>", table_struct_name(table));
+        }
+        let descriptor = serde_json::json!({ "type": "id", "tableName": table });
+        if let Some(name) = self.interned.get(&descriptor.to_string()) {
+            return name.clone();
+        }
+        let name = self.claim_name(&format!("{}Id", to_pascal_case(table)));
+        self.register_struct(&name, &generate_table_id_newtype(&name, table));
+        self.intern(&descriptor, &name)
+    }
+
+    /// Drain accumulated struct definitions, in registration order.
+    fn drain_extra_structs(&mut self) -> impl Iterator<Item = Chunk> + '_
+    {
+        self.extra_structs.drain(..)
+    }
+}
+
+/// A piece of the generated file. Named chunks are the nested types from
+/// [`CodegenContext::register_struct`], which `allow_dead_code` annotates;
+/// unnamed chunks are written as they are.
+struct Chunk
+{
+    name: Option<String>,
+    code: String,
+    owner: Option<Owner>,
+}
+
+impl Chunk
+{
+    fn kept(code: String) -> Self
+    {
+        Chunk::owned(code, None)
+    }
+
+    fn owned(code: String, owner: Option<Owner>) -> Self
+    {
+        Chunk { name: None, code, owner }
+    }
+}
+
+/// The table or function a piece of generated code was emitted for.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Owner
+{
+    /// A table, by schema name.
+    Table(String),
+    /// A function, by Convex path (e.g. `"users:byName"`).
+    Function(String),
+}
+
+impl fmt::Display for Owner
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Owner::Table(name) => write!(f, "table {name}"),
+            Owner::Function(path) => write!(f, "function {path}"),
+        }
+    }
+}
+
+/// How much of the generated file a table or function accounts for: its own
+/// struct plus the nested types it introduced. The API trait and helpers are
+/// shared and not attributed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ItemSize
+{
+    pub(crate) lines: usize,
+    pub(crate) types: usize,
+}
+
+/// Line and type counts for a generated file.
+#[derive(Debug, Default)]
+pub(crate) struct OutputSize
+{
+    pub(crate) lines: usize,
+    pub(crate) items: HashMap<Owner, ItemSize>,
+}
+
+/// Line prefixes that start a generated type definition.
+const TYPE_PREFIXES: [&str; 3] = ["pub struct ", "pub enum ", "pub type "];
+
+impl OutputSize
+{
+    fn measure(chunks: &[Chunk]) -> Self
+    {
+        let mut size = OutputSize::default();
+        for chunk in chunks {
+            let lines = chunk.code.lines().count();
+            size.lines += lines;
+            if let Some(owner) = &chunk.owner {
+                let item = size.items.entry(owner.clone()).or_default();
+                item.lines += lines;
+                item.types += chunk
+                    .code
+                    .lines()
+                    .filter(|line| TYPE_PREFIXES.iter().any(|prefix| line.starts_with(prefix)))
+                    .count();
+            }
+        }
+        size
     }
 }
 
@@ -50,55 +291,506 @@ impl<'a> CodegenContext<'a>
 // Main entry point
 // =============================================================================
 
-pub(crate) fn generate_code(
-    path: &std::path::Path,
-    data: (ConvexSchema, ConvexFunctions),
-) -> Result<(), ConvexTypeGeneratorError>
+/// The rendered code of a generation.
+pub(crate) struct GeneratedCode
 {
-    let mut file = std::fs::File::create(path)?;
-
-    file.set_len(0)?;
-    file.seek(SeekFrom::Start(0))?;
+    /// What goes in `out_file`.
+    pub(crate) code: String,
+    /// With `split_output_by_table`, each table's code by table name, in
+    /// schema order. `code` includes them.
+    pub(crate) table_files: Vec<(String, String)>,
+}
 
-    let file_header = r#"// This file is generated by convex-typegen. Do not modify directly.
-// You can find more information about convex-typegen at https://github.com/JamalLyons/convex-typegen
+/// Where `split_output_by_table` puts the table files: `src/convex_types/`
+/// for `src/convex_types.rs`.
+pub(crate) fn table_files_dir(out_file: &Path) -> PathBuf
+{
+    out_file.with_extension("")
+}
 
-#[allow(unused_imports)]
+/// Generate the code for `config.out_file`, with its size. Writing it is up
+/// to the caller.
+pub(crate) fn render_code(
+    config: &Configuration,
+    data: (ConvexSchema, ConvexFunctions),
+    previous: Option<&ConvexSchema>,
+) -> Result<(GeneratedCode, OutputSize), ConvexTypeGeneratorError>
+{
+    // The comment header comes from `manifest::Manifest::stamp`
+    let file_header = r#"#[allow(unused_imports)]
 use serde::{Serialize, Deserialize};
 
 "#;
 
-    file.write_all(file_header.as_bytes())?;
-
-    let mut code = String::new();
+    let mut chunks = vec![Chunk::kept(file_header.to_string())];
     let (schema, functions) = data;
-    let mut ctx = CodegenContext::new(&schema.tables);
+    let mut ctx = CodegenContext::new(&schema.tables, config);
+    ctx.generated_names.extend(functions.iter().map(args_struct_name));
+    ctx.generated_names
+        .extend(functions.iter().filter(|f| is_paginated_query(f)).map(cursor_struct_name));
+    ctx.generated_names
+        .extend(functions.iter().filter(|f| f.return_type.is_some()).map(output_struct_name));
+    if config.emit_import_writers {
+        ctx.generated_names
+            .extend(schema.tables.iter().map(|table| insert_struct_name(&table.name)));
+    }
+    if config.emit_fixtures {
+        ctx.generated_names
+            .extend(schema.tables.iter().map(|table| fixture_struct_name(&table.name)));
+    }
+    ctx.generated_names.insert("ConvexDocument".to_string());
+    if config.typed_ids {
+        ctx.generated_names.extend(["ConvexId", "InvalidConvexId"].map(String::from));
+    }
+    if config.emit_offline_queue {
+        ctx.generated_names
+            .extend(["QueuedMutation", "QueuedCall", "Conflict", "OfflineQueue"].map(String::from));
+    }
+    if config.emit_function_refs || config.emit_schedule_requests {
+        ctx.generated_names.extend(["FunctionRef", "FunctionKind"].map(String::from));
+    }
+    if config.emit_schedule_requests {
+        ctx.generated_names
+            .extend(["Schedulable", "ScheduleAt", "ScheduleRequest"].map(String::from));
+    }
+    if config.flatten_system_fields {
+        ctx.generated_names.insert("SystemFields".to_string());
+    }
+
+    if schema.options != SchemaOptions::default() {
+        chunks.push(Chunk::kept(format!(
+            "// defineSchema options: schemaValidation: {}, strictTableNameTypes: {}\n\n",
+            schema.options.schema_validation, schema.options.strict_table_name_types
+        )));
+    }
+
+    chunks.push(Chunk::kept(generate_schema_fingerprint(&schema)));
+    chunks.push(Chunk::kept(generate_convex_document_trait()));
+    if !schema.tables.is_empty() {
+        chunks.push(Chunk::kept(generate_content_digest_helper()));
+    }
+
+    if config.typed_ids {
+        chunks.push(Chunk::kept(generate_convex_id_code()));
+    }
+    if config.flatten_system_fields {
+        chunks.push(Chunk::kept(generate_system_fields_code(config.typed_ids)));
+    }
 
     // Generate table structs (enums/inline types are accumulated in ctx)
     for table in &schema.tables {
-        code.push_str(&generate_table_code(table, &mut ctx));
+        ctx.owner = Some(Owner::Table(table.name.clone()));
+        let code = generate_table_code(table, schema.options.schema_validation, true, &mut ctx);
+        chunks.push(Chunk::owned(code, ctx.owner.clone()));
     }
 
     // Emit inline types from table processing
-    code.push_str(&ctx.drain_extra_structs());
+    chunks.extend(ctx.drain_extra_structs());
+
+    if let Some(previous) = previous.filter(|_| config.emit_previous_tables) {
+        chunks.extend(generate_previous_tables(previous, &schema, config));
+    }
+
+    // Function args and returns share types with the schema and each other
+    ctx.reuse_interned = true;
 
     // Generate function argument types
+    let mut internal_chunks = Vec::new();
     for function in &functions {
-        code.push_str(&generate_function_code(function, &mut ctx));
+        ctx.owner = Some(Owner::Function(function_path(function)));
+        let code = generate_function_code(function, &mut ctx);
+        if config.internal_module && function.type_.starts_with("internal") {
+            internal_chunks.push(Chunk::owned(indent_items(&code), ctx.owner.clone()));
+        } else {
+            chunks.push(Chunk::owned(code, ctx.owner.clone()));
+        }
+    }
+    if !internal_chunks.is_empty() {
+        chunks.push(Chunk::kept(
+            "/// Args of functions registered with `internalQuery`, `internalMutation`, or\n/// `internalAction`. Only \
+             other Convex functions and the dashboard can call\n/// them, so `ConvexApi` has no methods for them.\npub mod \
+             internal {\n    #[allow(unused_imports)]\n    use super::*;\n\n"
+                .to_string(),
+        ));
+        chunks.extend(internal_chunks);
+        chunks.push(Chunk::kept("}\n\n".to_string()));
     }
 
     // Emit inline types from function arg processing
-    code.push_str(&ctx.drain_extra_structs());
+    chunks.extend(ctx.drain_extra_structs());
+
+    let finite_args = functions
+        .iter()
+        .flat_map(|function| &function.params)
+        .any(|param| finite_arg_type(&param.data_type).is_some());
+    if config.finite_number_args && finite_args {
+        chunks.push(Chunk::kept(generate_finite_type()));
+    }
+
+    // Schedule requests name their function by its ref
+    if config.emit_function_refs || config.emit_schedule_requests {
+        chunks.push(Chunk::kept(generate_function_refs(&functions, config)));
+    }
+    if config.emit_schedule_requests {
+        chunks.push(Chunk::kept(generate_schedule_requests(&functions, &ctx)));
+    }
+
+    // Resolve return types in the order the API code does, so the nested types
+    // they introduce are attributed to their function
+    for function in functions.iter().filter(|f| !f.type_.starts_with("internal")) {
+        ctx.owner = Some(Owner::Function(function_path(function)));
+        get_return_type_str(function, &mut ctx);
+    }
+    ctx.owner = None;
 
     // Generate typed API trait + impl for ConvexClient
-    code.push_str(&generate_api_code(&functions, &mut ctx));
+    chunks.push(Chunk::kept(generate_api_code(&functions, &mut ctx)));
 
     // Emit inline types from return type processing
-    code.push_str(&ctx.drain_extra_structs());
+    chunks.extend(ctx.drain_extra_structs());
+
+    let chunks = if config.allow_dead_code {
+        allow_dead_code_on_nested(chunks)
+    } else {
+        chunks
+    };
+    let size = OutputSize::measure(&chunks);
+    let (code, table_files) = if config.split_output_by_table {
+        split_by_table(chunks, &config.out_file)?
+    } else {
+        (chunks.into_iter().map(|chunk| chunk.code).collect(), Vec::new())
+    };
+    let code = match &config.version_tag {
+        // The table files are included inside the module, so their types are re-exported too
+        Some(tag) => {
+            let all: String = table_files.iter().fold(code.clone(), |all, (_, file)| all + file);
+            wrap_versioned(&code, &all, tag)
+        }
+        None => code,
+    };
+    if config.verify_syntax {
+        verify::check_syntax(&code)?;
+        for (_, file) in &table_files {
+            verify::check_syntax(file)?;
+        }
+    }
+    if let Some(max_lines) = config.max_output_lines {
+        check_output_budget(&size, max_lines, config)?;
+    }
+
+    Ok((GeneratedCode { code, table_files }, size))
+}
+
+/// Move each table's code into its own file, leaving an `include!` of the file
+/// where the table's code began. The include path is relative to `out_file`,
+/// which `include!` resolves against the including file.
+///
+/// Fails when two tables differ only in case, like `Users` and `users`: their
+/// files would be the same file on case-insensitive filesystems.
+fn split_by_table(chunks: Vec<Chunk>, out_file: &Path) -> Result<(String, Vec<(String, String)>), ConvexTypeGeneratorError>
+{
+    let dir = table_files_dir(out_file);
+    let dir = dir.file_name().unwrap_or_default().to_string_lossy();
+    let mut code = String::new();
+    let mut table_files: Vec<(String, String)> = Vec::new();
+    for chunk in chunks {
+        let Some(Owner::Table(table)) = &chunk.owner else {
+            code.push_str(&chunk.code);
+            continue;
+        };
+        match table_files.iter_mut().find(|(name, _)| name == table) {
+            Some((_, file)) => file.push_str(&chunk.code),
+            None => {
+                if let Some((other, _)) = table_files.iter().find(|(name, _)| name.eq_ignore_ascii_case(table)) {
+                    return Err(ConvexTypeGeneratorError::InvalidSchema {
+                        context: format!("tables `{other}` and `{table}`"),
+                        details: format!(
+                            "with split_output_by_table their files {dir}/{other}.rs and {dir}/{table}.rs differ only in \
+                             case, which is the same file on case-insensitive filesystems; rename one of the tables"
+                        ),
+                    });
+                }
+                code.push_str(&format!("include!(\"{dir}/{table}.rs\");\n\n"));
+                let header = format!(
+                    "// The `{table}` table, generated by convex-typegen and included by {dir}.rs. Do not\n// modify \
+                     directly.\n\n"
+                );
+                table_files.push((table.clone(), header + &chunk.code));
+            }
+        }
+    }
+    Ok((code, table_files))
+}
+
+/// The previous version of each changed table, for migrations: `GamesTable`
+/// becomes `GamesTableV1` and its nested types get the same `V1` suffix.
+///
+/// References to unchanged tables keep the current name, which has the same
+/// shape.
+fn generate_previous_tables(previous: &ConvexSchema, schema: &ConvexSchema, config: &Configuration) -> Vec<Chunk>
+{
+    let changed: Vec<&ConvexTable> = migration::changed_tables(previous, schema)
+        .into_iter()
+        .map(|(old, _)| old)
+        .collect();
+    let mut ctx = CodegenContext::new(&previous.tables, config);
+    let mut chunks = Vec::new();
+    for table in &changed {
+        let code = generate_table_code(table, previous.options.schema_validation, false, &mut ctx);
+        chunks.push(Chunk::kept(code));
+    }
+    chunks.extend(ctx.drain_extra_structs());
+
+    let unchanged: HashSet<String> = previous
+        .tables
+        .iter()
+        .filter(|table| !changed.iter().any(|old| old.name == table.name))
+        .map(|table| table_struct_name(&table.name))
+        .collect();
+    let renames: HashMap<String, String> = ctx
+        .generated_names
+        .iter()
+        .filter(|name| !unchanged.contains(*name))
+        .map(|name| (name.clone(), format!("{name}V1")))
+        .collect();
+    for chunk in &mut chunks {
+        chunk.code = rename_identifiers(&chunk.code, &renames);
+        chunk.name = chunk.name.take().map(|name| renames.get(&name).cloned().unwrap_or(name));
+    }
+    chunks
+}
+
+/// `code` in a module named after `tag`, e.g. `pub mod v7`, followed by a
+/// re-export of each public type with `tag` appended, e.g. `GamesTableV7`, for
+/// [`Configuration::version_tag`]. `all` is `code` plus its table files.
+///
+/// Two versions can then share a module: everything else, such as
+/// `v7::convex_router`, stays behind its version's module. A `#[cfg]` on a type
+/// is copied to its re-export.
+fn wrap_versioned(code: &str, all: &str, tag: &str) -> String
+{
+    let module = tag.to_ascii_lowercase();
+    let mut wrapped = format!(
+        "/// The code generated with version tag `{tag}`. Its public types are also\n/// re-exported below with the \
+         tag appended.\npub mod {module} {{\n    #[allow(unused_imports)]\n    use super::*;\n\n"
+    );
+    wrapped.push_str(&indent_items(code));
+    wrapped.push_str("}\n\n");
+
+    let mut cfgs: Vec<&str> = Vec::new();
+    for line in all.lines() {
+        if line.starts_with("#[cfg(") {
+            cfgs.push(line);
+            continue;
+        }
+        // Cfgs belong to the next item, past its other attributes and docs
+        if line.starts_with(|c: char| c == '#' || c == '/' || c.is_whitespace()) {
+            continue;
+        }
+        let name = ["pub struct ", "pub enum ", "pub trait ", "pub type "]
+            .iter()
+            .find_map(|keyword| line.strip_prefix(keyword))
+            .map(|rest| rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '_').collect::<String>());
+        if let Some(name) = name {
+            for cfg in &cfgs {
+                wrapped.push_str(cfg);
+                wrapped.push('\n');
+            }
+            wrapped.push_str(&format!("pub use self::{module}::{name} as {name}{tag};\n"));
+        }
+        cfgs.clear();
+    }
+    wrapped
+}
+
+/// `code` with every identifier in `renames` replaced. Path segments after
+/// `::` are left alone, since they name foreign items such as
+/// `convex::FunctionResult::ConvexError`.
+fn rename_identifiers(code: &str, renames: &HashMap<String, String>) -> String
+{
+    let mut out = String::with_capacity(code.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        let qualified = out.ends_with("::");
+        match renames.get(word.as_str()) {
+            Some(renamed) if !qualified => out.push_str(renamed),
+            _ => out.push_str(word),
+        }
+        word.clear();
+    };
+    for c in code.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// Put `#[allow(dead_code)]` on the nested types, for crates that use only
+/// part of the API.
+fn allow_dead_code_on_nested(chunks: Vec<Chunk>) -> Vec<Chunk>
+{
+    chunks
+        .into_iter()
+        .map(|mut chunk| {
+            if chunk.name.is_some() {
+                chunk.code = allow_dead_code_on_items(&chunk.code);
+            }
+            chunk
+        })
+        .collect()
+}
+
+/// Fail when the generated file is longer than `max_lines`, naming the largest
+/// tables and functions and the settings that would shrink it.
+fn check_output_budget(size: &OutputSize, max_lines: usize, config: &Configuration) -> Result<(), ConvexTypeGeneratorError>
+{
+    if size.lines <= max_lines {
+        return Ok(());
+    }
+
+    let mut items: Vec<(&Owner, &ItemSize)> = size.items.iter().collect();
+    items.sort_by(|(a_owner, a), (b_owner, b)| b.lines.cmp(&a.lines).then_with(|| a_owner.cmp(b_owner)));
+    let largest = items
+        .iter()
+        .take(5)
+        .map(|(owner, item)| format!("{owner} ({} lines)", item.lines))
+        .collect();
+
+    let mut suggestions = Vec::new();
+    if config.feature_gates.is_none() {
+        suggestions.push("set feature_gates so each crate compiles only the function kinds it calls".to_string());
+    }
+    let extras: Vec<&str> = [
+        ("emit_tauri_commands", config.emit_tauri_commands),
+        ("emit_axum_router", config.emit_axum_router),
+        ("emit_tenant_router", config.emit_tenant_router),
+        ("emit_admin_client", config.emit_admin_client),
+        ("emit_timeouts", config.emit_timeouts),
+        ("emit_consistent_reads", config.emit_consistent_reads),
+        ("emit_offline_queue", config.emit_offline_queue),
+        ("emit_import_writers", config.emit_import_writers),
+        ("emit_fixtures", config.emit_fixtures),
+        ("emit_enum_labels", config.emit_enum_labels),
+        ("emit_frontend_adapters", config.emit_frontend_adapters),
+        ("emit_shared_subscriptions", config.emit_shared_subscriptions),
+        ("emit_arc_subscriptions", config.emit_arc_subscriptions),
+        ("emit_snapshot_subscriptions", config.emit_snapshot_subscriptions),
+        ("emit_buffered_subscriptions", config.emit_buffered_subscriptions),
+        ("emit_resilient_subscriptions", config.emit_resilient_subscriptions),
+        ("emit_cursor_store", config.emit_cursor_store),
+        ("emit_function_refs", config.emit_function_refs),
+        ("emit_schedule_requests", config.emit_schedule_requests),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    if !extras.is_empty() {
+        suggestions.push(format!("turn off unused extras: {}", extras.join(", ")));
+    }
+    suggestions.extend([
+        "declare shared validators once and reuse them; identical shapes then share one generated type".to_string(),
+        "split function_paths across several generate() calls with separate out_files, one module each".to_string(),
+    ]);
+
+    Err(ConvexTypeGeneratorError::OutputTooLarge {
+        lines: size.lines,
+        max_lines,
+        largest,
+        suggestions,
+    })
+}
+
+/// Indent `code` one level, for items inside a generated `mod`.
+fn indent_items(code: &str) -> String
+{
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("    {line}\n")
+            }
+        })
+        .collect()
+}
+
+/// Put `#[allow(dead_code)]` on every top-level item in `code`. Items start
+/// unindented after a blank line.
+fn allow_dead_code_on_items(code: &str) -> String
+{
+    let mut annotated = String::new();
+    let mut item_start = true;
+    for line in code.split_inclusive('\n') {
+        if item_start && !line.starts_with(|c: char| c.is_whitespace() || c == '}') {
+            annotated.push_str("#[allow(dead_code)]\n");
+        }
+        item_start = line.trim().is_empty();
+        annotated.push_str(line);
+    }
+    annotated
+}
+
+// =============================================================================
+// Type resolution without emitting code (used by `explain`)
+// =============================================================================
+
+/// Resolves the Rust type each column, parameter, and return type maps to,
+/// using the same naming rules as [`render_code`].
+pub(crate) struct TypeResolver<'a>
+{
+    ctx: CodegenContext<'a>,
+}
+
+impl<'a> TypeResolver<'a>
+{
+    pub(crate) fn new(tables: &'a [ConvexTable], config: &'a Configuration) -> Self
+    {
+        TypeResolver {
+            ctx: CodegenContext::new(tables, config),
+        }
+    }
+
+    pub(crate) fn column_type(&mut self, table: &ConvexTable, column: &ConvexColumn) -> String
+    {
+        let naming_ctx = format!(
+            "{}{}",
+            capitalize_first_letter(&table.name),
+            capitalize_first_letter(&column.name)
+        );
+        self.ctx.reuse_interned = false;
+        let rust_type = convex_type_to_rust_type(&column.data_type, &naming_ctx, &mut self.ctx);
+        override_type(rust_type, table, column, &mut self.ctx)
+    }
+
+    pub(crate) fn param_type(&mut self, func: &ConvexFunction, param: &ConvexFunctionParam) -> String
+    {
+        let naming_ctx = format!(
+            "{}{}{}",
+            capitalize_first_letter(&func.file_name),
+            capitalize_first_letter(&func.name),
+            capitalize_first_letter(&param.name)
+        );
+        self.ctx.reuse_interned = true;
+        arg_field_type(param, &naming_ctx, &mut self.ctx)
+    }
 
-    file.write_all(code.as_bytes())?;
+    /// The type an args struct wraps when its validator is taken whole.
+    pub(crate) fn whole_args_type(&mut self, func: &ConvexFunction, args: &JsonValue) -> String
+    {
+        self.ctx.reuse_interned = true;
+        whole_args_type(func, args, &mut self.ctx)
+    }
 
-    Ok(())
+    pub(crate) fn return_type(&mut self, func: &ConvexFunction) -> Option<String>
+    {
+        self.ctx.reuse_interned = true;
+        get_return_type_str(func, &mut self.ctx)
+    }
 }
 
 // =============================================================================
@@ -109,22 +801,7 @@ use serde::{Serialize, Deserialize};
 /// This is true for `v.optional(...)` and `v.union(..., v.null())` with exactly one non-null variant.
 fn is_optional_param(param: &crate::types::ConvexFunctionParam) -> bool
 {
-    match param.data_type["type"].as_str() {
-        Some("optional") => true,
-        Some("union") => {
-            if let Some(variants) = param.data_type["variants"].as_array() {
-                let null_count = variants
-                    .iter()
-                    .filter(|variant| variant["type"].as_str() == Some("null"))
-                    .count();
-                let non_null_count = variants.len() - null_count;
-                null_count == 1 && non_null_count == 1
-            } else {
-                false
-            }
-        }
-        _ => false,
-    }
+    optional_inner(&param.data_type).is_some()
 }
 
 // =============================================================================
@@ -139,14 +816,23 @@ fn is_optional_param(param: &crate::types::ConvexFunctionParam) -> bool
 ///
 /// Union detection chain (checked in order):
 /// 1. **Nullable**: `union(T, null)` → `Option<T>`
-/// 2. **Result**: `union(object{Ok: T}, object{Err: E})` → `Result<T, E>`
+/// 2. **Id union**: `union(id("a"), id("b"), ...)` (optionally with `null`) → id enum
+/// 3. **Result**: `union(object{Ok: T}, object{Err: E})` → `Result<T, E>`
 ///    Matches the `result()` helper from Convex, which produces `{Ok: T} | {Err: string}`.
 ///    This maps directly to serde's externally-tagged `Result<T, E>` serialization.
-/// 3. **Tagged union**: all-object variants with a `type` literal field → `#[serde(tag = "type")]`
-/// 4. **Literal union**: all-literal variants → `enum` with string/number arms
-/// 5. **Mixed/untagged**: fallback → `#[serde(untagged)]` enum
+/// 4. **Tagged union**: all-object variants with a `type` literal field → `#[serde(tag = "type")]`
+/// 5. **Literal union**: all-literal variants → `enum` with string/number arms
+/// 6. **Mixed/untagged**: fallback → `#[serde(untagged)]` enum
 fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut CodegenContext) -> String
 {
+    if let Some(rust_type) = data_type["customValidator"]
+        .as_str()
+        .and_then(|name| ctx.config.custom_validators.get(name))
+        .and_then(|handler| handler.rust_type.clone())
+    {
+        return rust_type;
+    }
+
     let type_str = data_type["type"].as_str().unwrap_or("unknown");
 
     match type_str {
@@ -172,10 +858,14 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
                 if let Some(table_struct) = try_match_table_shape(props, ctx.tables) {
                     return table_struct;
                 }
+                if let Some(interned) = ctx.interned_type(data_type, naming_ctx) {
+                    return interned;
+                }
                 // Generate a dedicated struct
-                let struct_name = naming_ctx.to_string();
+                let struct_name = ctx.claim_name(naming_ctx);
                 let mut struct_code = String::new();
-                struct_code += "#[derive(Debug, Clone, Serialize, Deserialize)]\n";
+                let mut any_accessors = Vec::new();
+                struct_code += "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n";
                 struct_code += &format!("pub struct {} {{\n", struct_name);
                 for (field_name, field_type) in props {
                     let nested_ctx = format!("{}{}", struct_name, capitalize_first_letter(field_name));
@@ -187,18 +877,25 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
                     if rust_type.starts_with("Option<") {
                         struct_code += "    #[serde(skip_serializing_if = \"Option::is_none\")]\n";
                     }
+                    if defaults_when_missing(&rust_type, ctx.config) {
+                        struct_code += "    #[serde(default)]\n";
+                    }
                     let safe_name = escape_rust_keyword(&rust_name);
                     struct_code += &format!("    pub {}: {},\n", safe_name, rust_type);
+                    any_accessors.extend(any_accessor(field_type, &rust_name, &safe_name, &rust_type));
                 }
                 struct_code += "}\n\n";
-                ctx.register_struct(&struct_name, &struct_code)
+                struct_code += &generate_any_accessors(&struct_name, &any_accessors);
+                ctx.register_struct(&struct_name, &struct_code);
+                ctx.intern(data_type, &struct_name)
             } else {
                 "serde_json::Value".to_string()
             }
         }
 
         "record" => {
-            let key_type = convex_type_to_rust_type(&data_type["keyType"], naming_ctx, ctx);
+            // The value takes the field's name, like an array element does
+            let key_type = convex_type_to_rust_type(&data_type["keyType"], &format!("{naming_ctx}Key"), ctx);
             let value_type = convex_type_to_rust_type(&data_type["valueType"], naming_ctx, ctx);
             format!("std::collections::HashMap<{}, {}>", key_type, value_type)
         }
@@ -218,6 +915,41 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
                     return format!("Option<{}>", inner);
                 }
 
+                // Id union: union(id("a"), id("b")) → enum with one variant per table
+                if let Some(tables) = try_match_id_union(&non_null) {
+                    let enum_name = match ctx.interned_type(data_type, naming_ctx) {
+                        Some(interned) => interned,
+                        None => {
+                            let enum_name = ctx.claim_name(naming_ctx);
+                            let ids: Vec<(String, String)> =
+                                tables.into_iter().map(|table| (ctx.table_id_type(&table), table)).collect();
+                            ctx.register_struct(&enum_name, &generate_id_union_enum(&enum_name, &ids));
+                            ctx.intern(data_type, &enum_name)
+                        }
+                    };
+                    return match null_count {
+                        0 => enum_name,
+                        _ => format!("Option<{}>", enum_name),
+                    };
+                }
+
+                // Off-or-config: union(literal("off"), object{...}) → unit variants plus `Config`
+                if let Some((literals, object)) = try_match_literals_and_object(&non_null) {
+                    let enum_name = match ctx.interned_type(data_type, naming_ctx) {
+                        Some(interned) => interned,
+                        None => {
+                            let enum_name = ctx.claim_name(naming_ctx);
+                            let enum_code = generate_literals_and_object_enum(&enum_name, &literals, object, ctx);
+                            ctx.register_struct(&enum_name, &enum_code);
+                            ctx.intern(data_type, &enum_name)
+                        }
+                    };
+                    return match null_count {
+                        0 => enum_name,
+                        _ => format!("Option<{}>", enum_name),
+                    };
+                }
+
                 // Result pattern: union(object{Ok: T}, object{Err: E}) → Result<T, E>
                 if let Some((ok_type, err_type)) = try_match_result_pattern(variants) {
                     let value_rust = convex_type_to_rust_type(&ok_type, &format!("{naming_ctx}Value"), ctx);
@@ -225,26 +957,25 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
                     return format!("Result<{value_rust}, {error_rust}>");
                 }
 
-                // Tagged union: all variants are objects with a `type` literal field
-                if is_tagged_union(variants) {
-                    let enum_code = generate_tagged_enum(naming_ctx, variants, ctx);
-                    ctx.register_struct(naming_ctx, &enum_code);
-                    return naming_ctx.to_string();
+                if let Some(interned) = ctx.interned_type(data_type, naming_ctx) {
+                    return interned;
                 }
 
-                // Literal union: all variants are literals -> Copy enum
-                let all_literals = variants.iter().all(|v| v["type"].as_str() == Some("literal"));
-                if all_literals {
-                    let enum_code = generate_simple_enum(naming_ctx, variants, ctx);
-                    ctx.register_struct(naming_ctx, &enum_code);
-                    return naming_ctx.to_string();
+                // Tagged union: all variants are objects with a `type` literal field
+                if is_tagged_union(variants) {
+                    let enum_name = ctx.claim_name(naming_ctx);
+                    let enum_code = generate_tagged_enum(&enum_name, variants, ctx);
+                    ctx.register_struct(&enum_name, &enum_code);
+                    return ctx.intern(data_type, &enum_name);
                 }
 
-                // Mixed union (e.g. string | number) -> untagged enum
+                // Literal union (all variants are literals) -> Copy enum;
+                // mixed union (e.g. string | number) -> untagged enum
                 if !variants.is_empty() {
-                    let enum_code = generate_simple_enum(naming_ctx, variants, ctx);
-                    ctx.register_struct(naming_ctx, &enum_code);
-                    return naming_ctx.to_string();
+                    let enum_name = ctx.claim_name(naming_ctx);
+                    let enum_code = generate_simple_enum(&enum_name, variants, ctx);
+                    ctx.register_struct(&enum_name, &enum_code);
+                    return ctx.intern(data_type, &enum_name);
                 }
             }
             "serde_json::Value".to_string()
@@ -259,7 +990,12 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
                 "String".to_string()
             }
         }
-        "id" => "String".to_string(),
+        "id" => match data_type["tableName"].as_str() {
+            Some(table) if ctx.config.typed_ids && ctx.tables.iter().any(|t| t.name == table) => {
+                format!("ConvexId<{}>", table_struct_name(table))
+            }
+            _ => "String".to_string(),
+        },
 
         _ => "serde_json::Value".to_string(),
     }
@@ -268,7 +1004,7 @@ fn convex_type_to_rust_type(data_type: &JsonValue, naming_ctx: &str, ctx: &mut C
 /// Check if an object type's properties match a known table's columns.
 /// When a return type is `v.object({_id: v.id("clients"), _creationTime: v.number(), ...})`,
 /// we detect it matches `ClientsTable` and reuse that struct instead of generating a new one.
-fn try_match_table_shape(props: &serde_json::Map<String, JsonValue>, tables: &[ConvexTable]) -> Option<String>
+pub(crate) fn try_match_table_shape(props: &serde_json::Map<String, JsonValue>, tables: &[ConvexTable]) -> Option<String>
 {
     // User-defined columns (exclude system fields)
     let user_props: std::collections::BTreeMap<&str, &JsonValue> = props
@@ -288,7 +1024,7 @@ fn try_match_table_shape(props: &serde_json::Map<String, JsonValue>, tables: &[C
                 .unwrap_or(false)
         });
         if all_match {
-            return Some(format!("{}Table", capitalize_first_letter(&table.name)));
+            return Some(table_struct_name(&table.name));
         }
     }
     None
@@ -298,19 +1034,274 @@ fn try_match_table_shape(props: &serde_json::Map<String, JsonValue>, tables: &[C
 // Union helpers
 // =============================================================================
 
-/// Detect the Result pattern: union of exactly 2 single-field objects,
-/// one with key "Ok" and one with key "Err".
-/// Matches `v.union(v.object({ Ok: T }), v.object({ Err: E }))`.
-fn try_match_result_pattern(variants: &[JsonValue]) -> Option<(JsonValue, JsonValue)>
+/// Detect a union of ids from at least two different tables. Returns the
+/// distinct table names in declaration order.
+fn try_match_id_union(variants: &[&JsonValue]) -> Option<Vec<String>>
 {
-    if variants.len() != 2 {
-        return None;
-    }
-    if !variants.iter().all(|v| v["type"].as_str() == Some("object")) {
-        return None;
-    }
-
-    let (mut ok_type, mut err_type) = (None, None);
+    let mut tables: Vec<String> = Vec::new();
+    for variant in variants {
+        if variant["type"].as_str() != Some("id") {
+            return None;
+        }
+        let table = variant["tableName"].as_str()?;
+        if !tables.iter().any(|t| t == table) {
+            tables.push(table.to_string());
+        }
+    }
+    (tables.len() >= 2).then_some(tables)
+}
+
+/// Detect a union of string literals and exactly one object, the
+/// "`\"off\"` or a config object" idiom. Returns the literals in declaration
+/// order and the object.
+fn try_match_literals_and_object<'a>(variants: &[&'a JsonValue]) -> Option<(Vec<&'a str>, &'a JsonValue)>
+{
+    let mut literals = Vec::new();
+    let mut object = None;
+    for variant in variants {
+        match variant["type"].as_str() {
+            Some("literal") => literals.push(variant["value"].as_str()?),
+            Some("object") if object.is_none() && variant["properties"].as_object().is_some_and(|p| !p.is_empty()) => {
+                object = Some(*variant)
+            }
+            _ => return None,
+        }
+    }
+    (!literals.is_empty()).then_some((literals, object?))
+}
+
+/// Generate an enum for a union of string literals and one object: a unit
+/// variant per literal and a `Config` variant holding the object's struct.
+///
+/// `#[serde(untagged)]` would write unit variants as `null`, so the literals
+/// get hand-written impls that read and write them as strings.
+fn generate_literals_and_object_enum(
+    enum_name: &str,
+    literals: &[&str],
+    object: &JsonValue,
+    ctx: &mut CodegenContext,
+) -> String
+{
+    let variants: Vec<(String, &str)> = literals.iter().map(|value| (to_pascal_case(value), *value)).collect();
+    let config_variant = if variants.iter().any(|(name, _)| name == "Config") {
+        "ConfigObject"
+    } else {
+        "Config"
+    };
+    let config_type = convex_type_to_rust_type(object, &format!("{enum_name}Config"), ctx);
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// One of {}, or a `{config_type}`.\n",
+        literals
+            .iter()
+            .map(|value| format!("`{:?}`", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    code.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+    code.push_str(&format!("pub enum {} {{\n", enum_name));
+    for (variant_name, _) in &variants {
+        code.push_str(&format!("    {},\n", variant_name));
+    }
+    code.push_str(&format!("    {}({}),\n", config_variant, config_type));
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl Serialize for {} {{\n", enum_name));
+    code.push_str("    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n");
+    code.push_str("        match self {\n");
+    for (variant_name, value) in &variants {
+        code.push_str(&format!(
+            "            Self::{} => serializer.serialize_str({:?}),\n",
+            variant_name, value
+        ));
+    }
+    code.push_str(&format!(
+        "            Self::{}(config) => config.serialize(serializer),\n",
+        config_variant
+    ));
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    let expected: Vec<String> = literals.iter().map(|value| format!("{:?}", value)).collect();
+    code.push_str(&format!("impl<'de> Deserialize<'de> for {} {{\n", enum_name));
+    code.push_str("    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {\n");
+    code.push_str("        #[derive(Deserialize)]\n");
+    code.push_str("        #[serde(untagged)]\n");
+    code.push_str("        enum Repr {\n");
+    code.push_str("            Literal(String),\n");
+    code.push_str(&format!("            Config({}),\n", config_type));
+    code.push_str("        }\n");
+    code.push_str("        match Repr::deserialize(deserializer)? {\n");
+    code.push_str("            Repr::Literal(value) => match value.as_str() {\n");
+    for (variant_name, value) in &variants {
+        code.push_str(&format!("                {:?} => Ok(Self::{}),\n", value, variant_name));
+    }
+    code.push_str(&format!(
+        "                other => Err(serde::de::Error::unknown_variant(other, &[{}])),\n",
+        expected.join(", ")
+    ));
+    code.push_str("            },\n");
+    code.push_str(&format!(
+        "            Repr::Config(config) => Ok(Self::{}(config)),\n",
+        config_variant
+    ));
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// Generate `{Table}Id`, the id of a document in `table` for id unions
+/// without typed ids. Serde passes the raw id string through.
+fn generate_table_id_newtype(name: &str, table: &str) -> String
+{
+    let mut code = String::new();
+    code.push_str(&format!("/// An id in the `{}` table.\n", table));
+    code.push_str("#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
+    code.push_str("#[serde(transparent)]\n");
+    code.push_str(&format!("pub struct {}(pub String);\n\n", name));
+
+    code.push_str(&format!("impl {} {{\n", name));
+    code.push_str("    /// The raw Convex id string.\n");
+    code.push_str("    pub fn as_str(&self) -> &str {\n");
+    code.push_str("        &self.0\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl From<String> for {} {{\n", name));
+    code.push_str("    fn from(id: String) -> Self {\n");
+    code.push_str("        Self(id)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl std::fmt::Display for {} {{\n", name));
+    code.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    code.push_str("        f.write_str(&self.0)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// Generate an enum for a union of ids from several tables, with a variant
+/// per `(id type, table)` in `ids`.
+///
+/// Convex ids encode their table's number rather than its name, so the client
+/// can't tell which table an id read from the wire points into; those land in
+/// `Unknown` until `resolve` is given the table, e.g. from a sibling field.
+/// `table_hint` then names it. Serde passes the raw id string through either
+/// way.
+fn generate_id_union_enum(enum_name: &str, ids: &[(String, String)]) -> String
+{
+    let variants: Vec<(String, &str, &str)> = ids
+        .iter()
+        .map(|(id_type, table)| (to_pascal_case(table), id_type.as_str(), table.as_str()))
+        .collect();
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// An id from one of: {}.\n",
+        ids.iter().map(|(_, t)| format!("`{}`", t)).collect::<Vec<_>>().join(", ")
+    ));
+    code.push_str("#[derive(Debug, Clone)]\n");
+    code.push_str(&format!("pub enum {} {{\n", enum_name));
+    for (variant_name, id_type, table) in &variants {
+        code.push_str(&format!("    /// An id in the `{}` table.\n", table));
+        code.push_str(&format!("    {}({}),\n", variant_name, id_type));
+    }
+    code.push_str("    /// An id whose table isn't known, e.g. one deserialized from Convex.\n");
+    code.push_str("    Unknown(String),\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl {} {{\n", enum_name));
+    code.push_str("    /// Tables this id can point into, in declaration order.\n");
+    code.push_str(&format!(
+        "    pub const TABLES: &'static [&'static str] = &[{}];\n\n",
+        ids.iter().map(|(_, t)| format!("\"{}\"", t)).collect::<Vec<_>>().join(", ")
+    ));
+    code.push_str("    /// The raw Convex id string.\n");
+    code.push_str("    pub fn as_str(&self) -> &str {\n");
+    code.push_str("        match self {\n");
+    for (variant_name, _, _) in &variants {
+        code.push_str(&format!("            Self::{}(id) => id.as_str(),\n", variant_name));
+    }
+    code.push_str("            Self::Unknown(id) => id,\n");
+    code.push_str("        }\n");
+    code.push_str("    }\n\n");
+    code.push_str("    /// The table this id points into, when known.\n");
+    code.push_str("    pub fn table_hint(&self) -> Option<&'static str> {\n");
+    code.push_str("        match self {\n");
+    for (variant_name, _, table) in &variants {
+        code.push_str(&format!("            Self::{}(_) => Some(\"{}\"),\n", variant_name, table));
+    }
+    code.push_str("            Self::Unknown(_) => None,\n");
+    code.push_str("        }\n");
+    code.push_str("    }\n\n");
+    code.push_str("    /// Move an `Unknown` id into the variant of `table`, which the caller knows\n");
+    code.push_str("    /// from elsewhere, e.g. a field stored next to the id. Ids that already\n");
+    code.push_str("    /// have a table, and tables not in `TABLES`, are returned unchanged.\n");
+    code.push_str("    pub fn resolve(self, table: &str) -> Self {\n");
+    code.push_str("        match (self, table) {\n");
+    for (variant_name, _, table) in &variants {
+        code.push_str(&format!(
+            "            (Self::Unknown(id), \"{}\") => Self::{}(id.into()),\n",
+            table, variant_name
+        ));
+    }
+    code.push_str("            (id, _) => id,\n");
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    // Ids are unique across tables, so equality ignores the variant: an id read
+    // back from Convex equals the one it was written from
+    code.push_str(&format!("impl PartialEq for {} {{\n", enum_name));
+    code.push_str("    fn eq(&self, other: &Self) -> bool {\n");
+    code.push_str("        self.as_str() == other.as_str()\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code.push_str(&format!("impl Eq for {} {{}}\n\n", enum_name));
+    code.push_str(&format!("impl std::hash::Hash for {} {{\n", enum_name));
+    code.push_str("    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {\n");
+    code.push_str("        std::hash::Hash::hash(self.as_str(), state)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl std::fmt::Display for {} {{\n", enum_name));
+    code.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    code.push_str("        f.write_str(self.as_str())\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl Serialize for {} {{\n", enum_name));
+    code.push_str("    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n");
+    code.push_str("        serializer.serialize_str(self.as_str())\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl<'de> Deserialize<'de> for {} {{\n", enum_name));
+    code.push_str("    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {\n");
+    code.push_str("        String::deserialize(deserializer).map(Self::Unknown)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    code
+}
+
+/// Detect the Result pattern: union of exactly 2 single-field objects,
+/// one with key "Ok" and one with key "Err".
+/// Matches `v.union(v.object({ Ok: T }), v.object({ Err: E }))`.
+fn try_match_result_pattern(variants: &[JsonValue]) -> Option<(JsonValue, JsonValue)>
+{
+    if variants.len() != 2 {
+        return None;
+    }
+    if !variants.iter().all(|v| v["type"].as_str() == Some("object")) {
+        return None;
+    }
+
+    let (mut ok_type, mut err_type) = (None, None);
     for variant in variants {
         let props = variant["properties"].as_object()?;
         if props.len() != 1 {
@@ -352,10 +1343,13 @@ fn is_tagged_union(variants: &[JsonValue]) -> bool
 fn generate_tagged_enum(enum_name: &str, variants: &[JsonValue], ctx: &mut CodegenContext) -> String
 {
     let mut code = String::new();
-    code.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+    code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
     code.push_str("#[serde(tag = \"type\")]\n");
     code.push_str(&format!("pub enum {} {{\n", enum_name));
 
+    // (variant name, tag, has fields) for the `kind()` accessor
+    let mut kinds: Vec<(String, &str, bool)> = Vec::new();
+
     for variant in variants {
         if let Some(props) = variant["properties"].as_object() {
             let tag = props.get("type").and_then(|t| t["value"].as_str()).unwrap_or("Unknown");
@@ -369,6 +1363,7 @@ fn generate_tagged_enum(enum_name: &str, variants: &[JsonValue], ctx: &mut Codeg
 
             // Collect non-`type` fields
             let fields: Vec<(&String, &JsonValue)> = props.iter().filter(|(k, _)| k.as_str() != "type").collect();
+            kinds.push((variant_name.clone(), tag, !fields.is_empty()));
 
             if fields.is_empty() {
                 code.push_str(&format!("    {},\n", variant_name));
@@ -388,6 +1383,37 @@ fn generate_tagged_enum(enum_name: &str, variants: &[JsonValue], ctx: &mut Codeg
         }
     }
 
+    code.push_str("}\n\n");
+    code.push_str(&generate_tagged_enum_kind_impl(enum_name, &kinds));
+    code
+}
+
+/// Generate `KINDS` and `kind()` for a tagged union so callers can dispatch or
+/// log by tag without an exhaustive match.
+fn generate_tagged_enum_kind_impl(enum_name: &str, kinds: &[(String, &str, bool)]) -> String
+{
+    let tags: Vec<String> = kinds.iter().map(|(_, tag, _)| format!("\"{}\"", tag)).collect();
+
+    let mut code = String::new();
+    code.push_str(&format!("impl {} {{\n", enum_name));
+    code.push_str("    /// Every `type` tag, in declaration order.\n");
+    code.push_str(&format!(
+        "    pub const KINDS: &'static [&'static str] = &[{}];\n\n",
+        tags.join(", ")
+    ));
+    code.push_str("    /// The `type` tag of this variant.\n");
+    code.push_str("    pub fn kind(&self) -> &'static str {\n");
+    code.push_str("        match self {\n");
+    for (variant_name, tag, has_fields) in kinds {
+        let pattern = if *has_fields {
+            format!("Self::{} {{ .. }}", variant_name)
+        } else {
+            format!("Self::{}", variant_name)
+        };
+        code.push_str(&format!("            {} => \"{}\",\n", pattern, tag));
+    }
+    code.push_str("        }\n");
+    code.push_str("    }\n");
     code.push_str("}\n\n");
     code
 }
@@ -399,9 +1425,10 @@ fn generate_simple_enum(enum_name: &str, variants: &[JsonValue], ctx: &mut Codeg
 
     let mut code = String::new();
     if all_literals {
-        code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n");
+        // `Hash` so the enum can key a `v.record(...)` map
+        code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
     } else {
-        code.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+        code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
         code.push_str("#[serde(untagged)]\n");
     }
     code.push_str(&format!("pub enum {} {{\n", enum_name));
@@ -477,159 +1504,1892 @@ fn generate_simple_enum(enum_name: &str, variants: &[JsonValue], ctx: &mut Codeg
 // =============================================================================
 
 /// Generate the struct for a table.
-fn generate_table_code(table: &ConvexTable, ctx: &mut CodegenContext) -> String
+///
+/// `validated` is false when the schema turns off `schemaValidation`, which
+/// makes the struct's shape advisory; `current` is false for previous versions
+/// of a table, which get no `{Table}Insert` struct or fixture builder.
+fn generate_table_code(table: &ConvexTable, validated: bool, current: bool, ctx: &mut CodegenContext) -> String
 {
     let mut code = String::new();
 
     let table_cap = capitalize_first_letter(&table.name);
-    let table_struct_name = format!("{}Table", table_cap);
-
-    code.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
-    code.push_str(&format!("pub struct {} {{\n", table_struct_name));
+    let table_struct_name = table_struct_name(&table.name);
 
-    // Convex system fields
-    code.push_str("    #[serde(rename = \"_id\")]\n");
-    code.push_str("    pub id: String,\n");
-    code.push_str("    #[serde(rename = \"_creationTime\")]\n");
-    code.push_str("    pub creation_time: f64,\n");
+    let mut fields = String::new();
+    let mut uses_serde_as = false;
 
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    let mut literal_impls = String::new();
+    let mut collections = Vec::new();
+    let mut any_accessors = Vec::new();
     for column in &table.columns {
         let naming_ctx = format!("{}{}", table_cap, capitalize_first_letter(&column.name));
         let rust_type = convex_type_to_rust_type(&column.data_type, &naming_ctx, ctx);
+        let rust_type = override_type(rust_type, table, column, ctx);
         let rust_name = to_snake_case(&column.name);
         if rust_name != column.name {
-            code.push_str(&format!("    #[serde(rename = \"{}\")]\n", column.name));
+            fields.push_str(&format!("    #[serde(rename = \"{}\")]\n", column.name));
         }
         if rust_type.starts_with("Option<") {
-            code.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+            fields.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+        }
+        let config = ctx.config;
+        let mut default = defaults_when_missing(&rust_type, config);
+        let field_override = config
+            .field_overrides
+            .get(&format!("{}.{}", table.name, column.name))
+            .filter(|field_override| !field_override.raw_json);
+        if let Some(field_override) = field_override {
+            if let Some(serde_as) = serde_as_type(&rust_type, field_override, ctx) {
+                fields.push_str(&format!("    #[serde_as(as = \"{}\")]\n", serde_as));
+                uses_serde_as = true;
+            }
+            default |= field_override.default_on_error;
+        }
+        if default {
+            fields.push_str("    #[serde(default)]\n");
+        }
+        let safe_name = table_field_name(&column.name);
+        fields.push_str(&format!("    pub {}: {},\n", safe_name, rust_type));
+        any_accessors.extend(any_accessor(&column.data_type, &rust_name, &safe_name, &rust_type));
+        field_types.push(rust_type.clone());
+        field_names.push(safe_name);
+
+        if let Some(values) = column_literal_values(column, &rust_type, &naming_ctx) {
+            if table.indexes.iter().any(|index| index.fields.contains(&column.name)) {
+                literal_impls.push_str(&generate_convex_literal_impl(&naming_ctx, &values));
+            }
+            if ctx.config.emit_enum_labels {
+                let key = format!("{}.{}", table.name, column.name);
+                literal_impls.push_str(&generate_label_impl(&naming_ctx, &key, &values, ctx.config));
+            }
+        }
+        if object_array_properties(&column.data_type).is_some() {
+            collections.push((column, rust_type));
         }
-        let safe_name = escape_rust_keyword(&rust_name);
-        code.push_str(&format!("    pub {}: {},\n", safe_name, rust_type));
     }
 
+    if !validated {
+        code.push_str("/// Advisory: the schema sets `schemaValidation: false`, so Convex doesn't check\n");
+        code.push_str("/// stored documents against this shape.\n");
+    }
+    // `serde_as` must run before the derives see the fields
+    if uses_serde_as {
+        code.push_str("#[serde_with::serde_as]\n");
+    }
+    code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    code.push_str(&format!("pub struct {} {{\n", table_struct_name));
+    // Convex system fields
+    let system_fields = if ctx.config.typed_ids {
+        format!("SystemFields<{}>", table_struct_name)
+    } else {
+        "SystemFields".to_string()
+    };
+    if ctx.config.flatten_system_fields {
+        code.push_str("    #[serde(flatten)]\n");
+        code.push_str(&format!("    pub system: {},\n", system_fields));
+    } else {
+        code.push_str("    #[serde(rename = \"_id\")]\n");
+        if ctx.config.typed_ids {
+            code.push_str(&format!("    pub id: ConvexId<{}>,\n", table_struct_name));
+        } else {
+            code.push_str("    pub id: String,\n");
+        }
+        code.push_str("    #[serde(rename = \"_creationTime\")]\n");
+        code.push_str("    pub creation_time: f64,\n");
+    }
+    code.push_str(&fields);
     code.push_str("}\n\n");
-    code
-}
 
-// =============================================================================
-// Function code generation
-// =============================================================================
+    if ctx.config.flatten_system_fields {
+        code.push_str(&format!("impl AsRef<{}> for {} {{\n", system_fields, table_struct_name));
+        code.push_str(&format!("    fn as_ref(&self) -> &{} {{\n", system_fields));
+        code.push_str("        &self.system\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+    }
 
-/// Generate the args struct for a function.
-fn generate_function_code(function: &ConvexFunction, ctx: &mut CodegenContext) -> String
-{
-    let mut code = String::new();
+    code.push_str(&generate_content_eq_impl(&table_struct_name, &field_names, &field_types));
+    if let Some(field) = ctx.config.display_fields.get(&table.name) {
+        if let Some(column) = table.columns.iter().find(|column| column.name == *field) {
+            code.push_str(&generate_display_impl(&table_struct_name, column, ctx.config));
+        }
+    }
+    code.push_str(&literal_impls);
+    code.push_str(&generate_any_accessors(&table_struct_name, &any_accessors));
+    code.push_str(&generate_collection_helpers(
+        table,
+        &table_struct_name,
+        &collections,
+        ctx.config,
+    ));
 
-    let file_cap = capitalize_first_letter(&function.file_name);
-    let fn_cap = capitalize_first_letter(&function.name);
-    let struct_name = format!("{}{}Args", file_cap, fn_cap);
+    // Generic access to the system fields
+    let system = if ctx.config.flatten_system_fields {
+        "self.system"
+    } else {
+        "self"
+    };
+    let id = if ctx.config.typed_ids {
+        format!("{system}.id.as_str()")
+    } else {
+        format!("&{system}.id")
+    };
+    code.push_str(&format!("impl ConvexDocument for {} {{\n", table_struct_name));
+    code.push_str(&format!("    const TABLE: &'static str = \"{}\";\n\n", table.name));
+    code.push_str("    fn id(&self) -> &str {\n");
+    code.push_str(&format!("        {id}\n"));
+    code.push_str("    }\n\n");
+    code.push_str("    fn creation_time(&self) -> f64 {\n");
+    code.push_str(&format!("        {system}.creation_time\n"));
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    // Previous versions of a table are only read, never written or built
+    if current && ctx.config.emit_import_writers {
+        code.push_str(&generate_insert_struct(
+            table,
+            &fields,
+            uses_serde_as,
+            &field_names,
+            ctx.config,
+        ));
+    }
+    if current && ctx.config.emit_fixtures {
+        let id_type = if ctx.config.typed_ids {
+            format!("ConvexId<{}>", table_struct_name)
+        } else {
+            "String".to_string()
+        };
+        let typed_fields: Vec<(&str, &str)> = [("id", id_type.as_str()), ("creation_time", "f64")]
+            .into_iter()
+            .chain(
+                field_names
+                    .iter()
+                    .map(String::as_str)
+                    .zip(field_types.iter().map(String::as_str)),
+            )
+            .collect();
+        code.push_str(&generate_fixture_builder(table, &typed_fields, ctx.config));
+    }
+    code
+}
 
-    code.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
-    if !function.params.is_empty() {
-        code.push_str("#[allow(non_snake_case)]\n");
+/// A `{field}_as::<T>()` method for a `v.any()` field, or `v.optional(v.any())`,
+/// that deserializes the payload into a type the caller knows it has.
+fn any_accessor(data_type: &JsonValue, rust_name: &str, field: &str, rust_type: &str) -> Option<String>
+{
+    let inner = optional_inner(data_type).unwrap_or(data_type);
+    if inner["type"].as_str() != Some("any") || inner.get("customValidator").is_some() {
+        return None;
     }
-    code.push_str(&format!("pub struct {} {{\n", struct_name));
+    let (returns, body) = match rust_type {
+        "serde_json::Value" => ("T", format!("T::deserialize(&self.{field})")),
+        "Option<serde_json::Value>" => ("Option<T>", format!("self.{field}.as_ref().map(T::deserialize).transpose()")),
+        _ => return None,
+    };
+    Some(format!(
+        "    /// `{rust_name}` deserialized into `T`; the schema only says `v.any()`.\n    pub fn {rust_name}_as<T: \
+         serde::de::DeserializeOwned>(&self) -> Result<{returns}, serde_json::Error> {{\n        {body}\n    }}\n"
+    ))
+}
 
-    for param in &function.params {
-        let naming_ctx = format!("{}{}{}", file_cap, fn_cap, capitalize_first_letter(&param.name));
-        let rust_type = convex_type_to_rust_type(&param.data_type, &naming_ctx, ctx);
-        if rust_type.starts_with("Option<") {
-            code.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
-        }
-        let safe_param = escape_rust_keyword(&param.name);
-        code.push_str(&format!("    pub {}: {},\n", safe_param, rust_type));
+/// An `impl` block with the [`any_accessor`] methods of `struct_name`.
+fn generate_any_accessors(struct_name: &str, accessors: &[String]) -> String
+{
+    if accessors.is_empty() {
+        return String::new();
     }
+    format!("impl {} {{\n{}}}\n\n", struct_name, accessors.join("\n"))
+}
 
-    code.push_str("}\n\n");
+/// Generate `{Table}TableFixture`: a builder with a setter per field whose
+/// `build()` fills the unset ones from `convex_typegen::fixtures`.
+fn generate_fixture_builder(table: &ConvexTable, fields: &[(&str, &str)], config: &Configuration) -> String
+{
+    let fixture_name = fixture_struct_name(&table.name);
+    let table_struct_name = table_struct_name(&table.name);
+    // A field called `seed` or `build` keeps its setter apart from the builder's own methods
+    let setter_name = |field: &str| match field {
+        "builder" | "seed" | "build" => format!("{field}_field"),
+        _ => field.to_string(),
+    };
 
-    // FUNCTION_PATH constant
-    code.push_str(&format!("impl {} {{\n", struct_name));
-    code.push_str("    pub const FUNCTION_PATH: &'static str = ");
-    let module = function.module_path.as_deref().unwrap_or(&function.file_name);
-    code.push_str(&format!("\"{}:{}\";\n", module, function.name));
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// Builds `{}` documents for tests. Fields left unset get deterministic values\n",
+        table_struct_name
+    ));
+    code.push_str("/// derived from the seed (0 unless set) and the field's name.\n");
+    code.push_str("#[derive(Debug, Clone, Default)]\n");
+    code.push_str(&format!("pub struct {} {{\n", fixture_name));
+    code.push_str("    seed: u64,\n");
+    // Setters of optional fields take the value itself, so `None` means unset
+    let fields: Vec<(&str, &str, bool)> = fields
+        .iter()
+        .map(
+            |(field, rust_type)| match rust_type.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+                Some(inner) => (*field, inner, true),
+                None => (*field, *rust_type, false),
+            },
+        )
+        .collect();
+    for (field, value_type, _) in &fields {
+        code.push_str(&format!("    {}: Option<{}>,\n", field, value_type));
+    }
     code.push_str("}\n\n");
 
-    // From impl for BTreeMap
+    code.push_str(&format!("impl {} {{\n", fixture_name));
     code.push_str(&format!(
-        "impl From<{}> for std::collections::BTreeMap<String, serde_json::Value> {{\n",
-        struct_name
+        "    const VALIDATOR: &'static str = {:?};\n\n",
+        table_validator(table, config).to_string()
     ));
-    code.push_str(&format!("    fn from(_args: {}) -> Self {{\n", struct_name));
-
-    if function.params.is_empty() {
-        code.push_str("        std::collections::BTreeMap::new()\n");
-    } else {
-        code.push_str("        let mut map = std::collections::BTreeMap::new();\n");
-        for param in &function.params {
-            let safe_param = escape_rust_keyword(&param.name);
-            if is_optional_param(param) {
-                code.push_str(&format!(
-                    "        if let Some(val) = _args.{} {{\n            map.insert(\"{}\".to_string(), \
-                     serde_json::to_value(val).unwrap());\n        }}\n",
-                    safe_param, param.name
-                ));
-            } else {
-                code.push_str(&format!(
-                    "        map.insert(\"{}\".to_string(), serde_json::to_value(_args.{}).unwrap());\n",
-                    param.name, safe_param
-                ));
-            }
-        }
-        code.push_str("        map\n");
+    code.push_str("    /// A builder with seed 0 and no fields set.\n");
+    code.push_str("    pub fn builder() -> Self {\n");
+    code.push_str("        Self::default()\n");
+    code.push_str("    }\n\n");
+    code.push_str("    /// Derive the unset fields from `seed` instead of 0.\n");
+    code.push_str("    pub fn seed(mut self, seed: u64) -> Self {\n");
+    code.push_str("        self.seed = seed;\n");
+    code.push_str("        self\n");
+    code.push_str("    }\n\n");
+    for (field, value_type, _) in &fields {
+        code.push_str(&format!(
+            "    pub fn {}(mut self, value: {}) -> Self {{\n",
+            setter_name(field),
+            value_type
+        ));
+        code.push_str(&format!("        self.{} = Some(value);\n", field));
+        code.push_str("        self\n");
+        code.push_str("    }\n\n");
     }
-
+    code.push_str("    /// The document, with the unset fields filled in from the seed.\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// # Panics\n");
+    code.push_str("    /// Panics when a field override's type can't read the sampled JSON of an\n");
+    code.push_str("    /// unset field; set that field explicitly.\n");
+    code.push_str(&format!("    pub fn build(self) -> {} {{\n", table_struct_name));
+    code.push_str(&format!(
+        "        let sample = convex_typegen::fixtures::sample_document(\"{}\", Self::VALIDATOR, self.seed);\n",
+        table.name
+    ));
+    code.push_str(&format!(
+        "        let mut document: {} = serde_json::from_value(sample).expect(\"sampled documents match the table\");\n",
+        table_struct_name
+    ));
+    for (i, (field, _, optional)) in fields.iter().enumerate() {
+        let value = if *optional { "Some(value)" } else { "value" };
+        // The first two fields are `id` and `creation_time`
+        let target = if i < 2 && config.flatten_system_fields {
+            format!("system.{field}")
+        } else {
+            field.to_string()
+        };
+        code.push_str(&format!(
+            "        if let Some(value) = self.{field} {{\n            document.{target} = {value};\n        }}\n"
+        ));
+    }
+    code.push_str("        document\n");
     code.push_str("    }\n");
     code.push_str("}\n\n");
-
     code
 }
 
-// =============================================================================
-// API function generation
-// =============================================================================
-
-/// Generate the ConvexApi trait + ConvexApiClient wrapper struct.
-///
-/// The trait has `&self` methods returning `Result<T, ConvexError>`.
-/// The wrapper holds a `convex::ConvexClient` and clones it internally
-/// on each call, satisfying the SDK's `&mut self` requirement.
-fn generate_api_code(functions: &[ConvexFunction], ctx: &mut CodegenContext) -> String
+/// The JSON descriptor of a table's documents without the system fields.
+fn table_validator(table: &ConvexTable, config: &Configuration) -> JsonValue
 {
-    let public_functions: Vec<&ConvexFunction> = functions.iter().filter(|f| !f.type_.starts_with("internal")).collect();
+    let properties: serde_json::Map<String, JsonValue> = table
+        .columns
+        .iter()
+        .map(|column| {
+            let mut data_type = column.data_type.clone();
+            let key = format!("{}.{}", table.name, column.name);
+            if let Some(format) = config
+                .field_overrides
+                .get(&key)
+                .and_then(|field_override| field_override.format)
+            {
+                mark_string_format(&mut data_type, format.name());
+            }
+            (column.name.clone(), data_type)
+        })
+        .collect();
+    serde_json::json!({ "type": "object", "properties": properties })
+}
 
-    if public_functions.is_empty() {
-        return String::new();
+/// Add `"format"` to the string validator in `data_type`, looking through
+/// `v.optional` and `v.array`, so fixtures sample dates for it.
+fn mark_string_format(data_type: &mut JsonValue, format: &str)
+{
+    match data_type["type"].as_str() {
+        Some("string") => data_type["format"] = JsonValue::from(format),
+        Some("optional") => mark_string_format(&mut data_type["inner"], format),
+        Some("array") => mark_string_format(&mut data_type["elements"], format),
+        _ => {}
     }
+}
 
-    let mut code = String::new();
+/// Whether a document field of `rust_type` gets `#[serde(default)]` under
+/// [`Configuration::default_missing_fields`]: options and collections, whose
+/// empty value is what a document written before the field existed means.
+fn defaults_when_missing(rust_type: &str, config: &Configuration) -> bool
+{
+    config.default_missing_fields
+        && ["Option<", "Vec<", "std::collections::HashMap<"]
+            .iter()
+            .any(|prefix| rust_type.starts_with(prefix))
+}
 
-    // ConvexError type (always generated)
-    code.push_str(&generate_convex_error_type());
+/// `Display` for a table struct that prints `column`, or `_id` when the column
+/// is optional and unset. Columns whose type has no `Display` get no impl;
+/// the report warns about them.
+fn generate_display_impl(struct_name: &str, column: &ConvexColumn, config: &Configuration) -> String
+{
+    let field = table_field_name(&column.name);
+    let id = if config.flatten_system_fields { "system.id" } else { "id" };
+    let body = match optional_inner(&column.data_type) {
+        Some(inner) if is_displayable(inner) => format!(
+            "        match &self.{field} {{\n\
+             \x20           Some(value) => std::fmt::Display::fmt(value, f),\n\
+             \x20           None => std::fmt::Display::fmt(&self.{id}, f),\n\
+             \x20       }}\n"
+        ),
+        None if is_displayable(&column.data_type) => format!("        std::fmt::Display::fmt(&self.{field}, f)\n"),
+        _ => return String::new(),
+    };
+    format!(
+        "impl std::fmt::Display for {struct_name} {{\n\x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> \
+         std::fmt::Result {{\n{body}\x20   }}\n}}\n\n"
+    )
+}
 
-    // json_to_convex_value helper (always needed for args conversion)
-    code.push_str(&generate_json_to_convex_value_helper());
+/// `iter_{field}()` for each array-of-object column of a table struct, plus
+/// `find_{entry}_by_{key}()` when the objects have a key field (see
+/// [`Configuration::collection_keys`]). Columns whose type was overridden,
+/// e.g. to `RawJson`, are skipped.
+fn generate_collection_helpers(
+    table: &ConvexTable,
+    struct_name: &str,
+    collections: &[(&ConvexColumn, String)],
+    config: &Configuration,
+) -> String
+{
+    let mut methods = String::new();
+    for (column, rust_type) in collections {
+        let (element, optional) = match rust_type.strip_prefix("Option<Vec<").and_then(|t| t.strip_suffix(">>")) {
+            Some(element) => (element, true),
+            None => match rust_type.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+                Some(element) => (element, false),
+                None => continue,
+            },
+        };
+        let field = table_field_name(&column.name);
+        let name = to_snake_case(&column.name);
+        let iter = if optional { "iter().flatten()" } else { "iter()" };
+        methods.push_str(&format!(
+            "    /// The entries of `{}`{}.\n\
+             \x20   pub fn iter_{name}(&self) -> impl Iterator<Item = &{element}> {{\n\
+             \x20       self.{field}.{iter}\n\
+             \x20   }}\n\n",
+            column.name,
+            if optional { ", none when it is unset" } else { "" },
+        ));
 
-    // convex_value_to_json helper if any function has a typed return
-    let has_typed_returns = public_functions.iter().any(|f| f.return_type.is_some());
-    if has_typed_returns {
-        code.push_str(&generate_convex_value_to_json_helper());
+        let Some(key) = collection_key(table, column, config) else {
+            continue;
+        };
+        let key_name = to_snake_case(key);
+        let key_field = escape_rust_keyword(&key_name);
+        methods.push_str(&format!(
+            "    /// The first entry of `{}` with the given `{key}`.\n\x20   pub fn find_{}_by_{key_name}(&self, \
+             {key_field}: &str) -> Option<&{element}> {{\n\x20       self.{field}.{iter}.find(|entry| entry.{key_field} == \
+             {key_field})\n\x20   }}\n\n",
+            column.name,
+            singular(&name),
+        ));
     }
-
-    // TypedSubscription wrapper if any query has a typed return
-    let has_typed_queries = public_functions.iter().any(|f| f.type_ == "query" && f.return_type.is_some());
-    if has_typed_queries {
-        code.push_str(&generate_typed_subscription_code());
+    if methods.is_empty() {
+        return String::new();
     }
+    methods.truncate(methods.len() - 1);
+    format!("impl {struct_name} {{\n{methods}}}\n\n")
+}
 
-    // ConvexApiClient wrapper struct
-    code.push_str(&generate_wrapper_struct());
+/// The key field of an array-of-object column: the configured one, else the
+/// first of `id`, `key`, `name`, `slug` that is a required string.
+fn collection_key<'a>(table: &ConvexTable, column: &ConvexColumn, config: &'a Configuration) -> Option<&'a str>
+{
+    let props = object_array_properties(&column.data_type)?;
+    let configured = config.collection_keys.get(&format!("{}.{}", table.name, column.name));
+    let key = match configured {
+        Some(key) if key.is_empty() => return None,
+        Some(key) => key.as_str(),
+        None => ["id", "key", "name", "slug"]
+            .into_iter()
+            .find(|key| props.get(*key).is_some_and(is_required_string))?,
+    };
+    props.get(key).filter(|data_type| is_required_string(data_type))?;
+    Some(key)
+}
+
+/// A field name's singular, for the entries it holds: `entries` → `entry`,
+/// `achievements` → `achievement`.
+fn singular(name: &str) -> String
+{
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if name.ends_with("ss") {
+        name.to_string()
+    } else {
+        name.strip_suffix('s').unwrap_or(name).to_string()
+    }
+}
+
+/// Generate `{Table}Insert`: the table's fields without the system fields, as
+/// written to `npx convex import` files by `convex_typegen::import::ImportWriter`.
+fn generate_insert_struct(
+    table: &ConvexTable,
+    fields: &str,
+    uses_serde_as: bool,
+    field_names: &[String],
+    config: &Configuration,
+) -> String
+{
+    let insert_name = insert_struct_name(&table.name);
+    let table_struct_name = table_struct_name(&table.name);
+    let validator = table_validator(table, config);
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// A new `{}` document, for `convex_typegen::import::ImportWriter`.\n",
+        table.name
+    ));
+    if uses_serde_as {
+        code.push_str("#[serde_with::serde_as]\n");
+    }
+    code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    code.push_str(&format!("pub struct {} {{\n", insert_name));
+    code.push_str(fields);
+    code.push_str("}\n\n");
+
+    code.push_str(&format!(
+        "impl convex_typegen::import::ImportDocument for {} {{\n",
+        insert_name
+    ));
+    code.push_str(&format!("    const TABLE: &'static str = \"{}\";\n", table.name));
+    code.push_str(&format!("    const VALIDATOR: &'static str = {:?};\n", validator.to_string()));
+    code.push_str("}\n\n");
+
+    // Re-importing an exported document drops its id and creation time
+    code.push_str(&format!("impl From<{}> for {} {{\n", table_struct_name, insert_name));
+    code.push_str(&format!("    fn from(document: {}) -> Self {{\n", table_struct_name));
+    if field_names.is_empty() {
+        code.push_str("        let _ = document;\n");
+        code.push_str("        Self {}\n");
+    } else {
+        code.push_str("        Self {\n");
+        for name in field_names {
+            code.push_str(&format!("            {name}: document.{name},\n"));
+        }
+        code.push_str("        }\n");
+    }
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// The values of a string-literal enum column, when `rust_type` is the enum
+/// generated for it (optionally wrapped in `Option`).
+fn column_literal_values<'a>(column: &'a ConvexColumn, rust_type: &str, enum_name: &str) -> Option<Vec<&'a str>>
+{
+    let inner = rust_type
+        .strip_prefix("Option<")
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(rust_type);
+    if inner != enum_name {
+        return None;
+    }
+    string_literal_values(optional_inner(&column.data_type).unwrap_or(&column.data_type))
+}
+
+/// Generate `label()` and `LABELS` for the literal enum of the column at
+/// `key` (`table.field`), for [`Configuration::emit_enum_labels`].
+fn generate_label_impl(enum_name: &str, key: &str, values: &[&str], config: &Configuration) -> String
+{
+    let labels: Vec<(String, String)> = values
+        .iter()
+        .map(|value| {
+            let label = match config.enum_labels.get(&format!("{key}.{value}")) {
+                Some(label) => label.clone(),
+                None => humanize_literal(value),
+            };
+            (to_pascal_case(value), label)
+        })
+        .collect();
+
+    let mut code = String::new();
+    code.push_str(&format!("impl {} {{\n", enum_name));
+    code.push_str("    /// Every value with its label, in schema order.\n");
+    code.push_str("    pub const LABELS: &'static [(Self, &'static str)] = &[\n");
+    for (variant_name, label) in &labels {
+        code.push_str(&format!("        (Self::{}, {:?}),\n", variant_name, label));
+    }
+    code.push_str("    ];\n\n");
+    code.push_str("    /// The label to show for this value in a UI.\n");
+    code.push_str("    pub fn label(&self) -> &'static str {\n");
+    code.push_str("        match self {\n");
+    for (variant_name, label) in &labels {
+        code.push_str(&format!("            Self::{} => {:?},\n", variant_name, label));
+    }
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// A label for a literal value without one in `enum_labels`: its words in
+/// sentence case, so `in_progress`, `in-progress`, and `inProgress` all become
+/// "In progress". All-caps words such as `HTTP` keep their case.
+fn humanize_literal(value: &str) -> String
+{
+    let mut words: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for c in value.chars() {
+        if matches!(c, '_' | '-' | ' ' | '.') {
+            previous = None;
+            continue;
+        }
+        let starts_word = match previous {
+            None => true,
+            Some(previous) => c.is_uppercase() && (previous.is_lowercase() || previous.is_ascii_digit()),
+        };
+        if starts_word {
+            words.push(String::new());
+        }
+        if let Some(word) = words.last_mut() {
+            word.push(c);
+        }
+        previous = Some(c);
+    }
+    let words: Vec<String> = words
+        .into_iter()
+        .map(|word| {
+            if word.chars().all(|c| !c.is_lowercase()) {
+                word
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect();
+    capitalize_first_letter(&words.join(" "))
+}
+
+/// Generate `convex_literal()` for a literal enum on an indexed column, so index
+/// range bounds can be built from the enum instead of a free-form string.
+fn generate_convex_literal_impl(enum_name: &str, values: &[&str]) -> String
+{
+    let mut code = String::new();
+    code.push_str(&format!("impl {} {{\n", enum_name));
+    code.push_str("    /// The string Convex stores for this value, e.g. for an index range bound.\n");
+    code.push_str("    pub fn convex_literal(&self) -> &'static str {\n");
+    code.push_str("        match self {\n");
+    for value in values {
+        code.push_str(&format!("            Self::{} => \"{}\",\n", to_pascal_case(value), value));
+    }
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// `rust_type` as changed by the column's override: wrapped in `RawJson` when
+/// it sets `raw_json`, keeping an `Option` outermost so unset fields stay
+/// `None`, or with `String` replaced by the date type its `format` names.
+fn override_type(rust_type: String, table: &ConvexTable, column: &ConvexColumn, ctx: &mut CodegenContext) -> String
+{
+    let Some(field_override) = ctx.config.field_overrides.get(&format!("{}.{}", table.name, column.name)) else {
+        return rust_type;
+    };
+    if !field_override.raw_json {
+        return match field_override.format {
+            Some(format) if is_string_shaped(&rust_type) => rust_type.replace("String", format.rust_type()),
+            _ => rust_type,
+        };
+    }
+    let raw_json = ctx.register_struct("RawJson", &generate_raw_json_code());
+    match rust_type.strip_prefix("Option<").and_then(|inner| inner.strip_suffix('>')) {
+        Some(inner) => format!("Option<{raw_json}<{inner}>>"),
+        None => format!("{raw_json}<{rust_type}>"),
+    }
+}
+
+/// Whether `rust_type` is a `String`, possibly inside `Option`s and `Vec`s.
+fn is_string_shaped(rust_type: &str) -> bool
+{
+    let mut inner = rust_type;
+    while let Some(wrapped) = inner
+        .strip_prefix("Option<")
+        .or_else(|| inner.strip_prefix("Vec<"))
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        inner = wrapped;
+    }
+    inner == "String"
+}
+
+/// The `serde_as` type for a field with a [`FieldOverride`], or `None` when the
+/// override doesn't change how the field's type is (de)serialized.
+fn serde_as_type(rust_type: &str, field_override: &FieldOverride, ctx: &mut CodegenContext) -> Option<String>
+{
+    let (optional, inner) = match rust_type.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        Some(inner) => (true, inner),
+        None => (false, rust_type),
+    };
+
+    let mut adapter = if field_override.string_or_number && inner == "String" {
+        ctx.register_struct("StringOrNumber", &generate_string_or_number_adapter())
+    } else if field_override.string_or_number && matches!(inner, "f64" | "i64") {
+        "serde_with::PickFirst<(_, serde_with::DisplayFromStr)>".to_string()
+    } else if inner.starts_with("std::collections::HashMap<") {
+        match field_override.duplicate_keys {
+            DuplicateKeys::LastWins => "_".to_string(),
+            DuplicateKeys::FirstWins => "serde_with::MapFirstKeyWins<_, _>".to_string(),
+            DuplicateKeys::Reject => "serde_with::MapPreventDuplicates<_, _>".to_string(),
+        }
+    } else {
+        "_".to_string()
+    };
+
+    if optional && adapter != "_" {
+        adapter = format!("Option<{}>", adapter);
+    }
+    if field_override.default_on_error {
+        adapter = format!("serde_with::DefaultOnError<{}>", adapter);
+    }
+
+    (adapter != "_").then_some(adapter)
+}
+
+/// Generate the `ConvexDocument` trait, which every table struct implements,
+/// for code that is generic over documents.
+fn generate_convex_document_trait() -> String
+{
+    r#"/// A table struct. Generic caches, sorters, and pagination helpers can take
+/// any table's documents through it.
+pub trait ConvexDocument {
+    /// The table's name in the schema.
+    const TABLE: &'static str;
+
+    /// The document's `_id`.
+    fn id(&self) -> &str;
+
+    /// The document's `_creationTime`, in milliseconds since the Unix epoch.
+    fn creation_time(&self) -> f64;
+}
+
+"#
+    .to_string()
+}
+
+/// Generate `ConvexId<T>`, for [`Configuration::typed_ids`]. It names its
+/// table through `ConvexDocument`.
+fn generate_convex_id_code() -> String
+{
+    r#"/// The id of a document in the table of `T`, e.g. `ConvexId<GamesTable>`.
+///
+/// Serialized as the raw id string. The table is part of the type, so it costs
+/// nothing at runtime. Deserializing, `parse`, and `TryFrom<String>` check the
+/// Convex id format; `From<String>` doesn't. An id encodes its table's number
+/// rather than its name, so none of them can check which table it points into.
+pub struct ConvexId<T> {
+    raw: String,
+    _table: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: ConvexDocument> ConvexId<T> {
+    /// The raw Convex id string.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// The raw Convex id string, taking ownership.
+    pub fn into_string(self) -> String {
+        self.raw
+    }
+
+    /// The table this id points into.
+    pub fn table(&self) -> &'static str {
+        T::TABLE
+    }
+
+    /// Whether `raw` has the Convex id format: 31 to 37 characters of
+    /// lowercase Crockford base32.
+    pub fn is_valid(raw: &str) -> bool {
+        (31..=37).contains(&raw.len())
+            && raw.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'h' | b'j' | b'k' | b'm' | b'n' | b'p'..=b't' | b'v'..=b'z'))
+    }
+}
+
+/// Unchecked, e.g. for ids Convex just returned. Use `parse` or `TryFrom` for
+/// strings from elsewhere.
+impl<T: ConvexDocument> From<String> for ConvexId<T> {
+    fn from(raw: String) -> Self {
+        Self { raw, _table: std::marker::PhantomData }
+    }
+}
+
+impl<T: ConvexDocument> TryFrom<String> for ConvexId<T> {
+    type Error = InvalidConvexId;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        if !Self::is_valid(&raw) {
+            return Err(InvalidConvexId { raw, table: T::TABLE });
+        }
+        Ok(Self::from(raw))
+    }
+}
+
+impl<T: ConvexDocument> std::str::FromStr for ConvexId<T> {
+    type Err = InvalidConvexId;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Self::try_from(raw.to_string())
+    }
+}
+
+impl<T> From<ConvexId<T>> for String {
+    fn from(id: ConvexId<T>) -> Self {
+        id.raw
+    }
+}
+
+impl<T> Clone for ConvexId<T> {
+    fn clone(&self) -> Self {
+        Self { raw: self.raw.clone(), _table: std::marker::PhantomData }
+    }
+}
+
+impl<T> PartialEq for ConvexId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T> Eq for ConvexId<T> {}
+
+impl<T> std::hash::Hash for ConvexId<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<T: ConvexDocument> std::fmt::Debug for ConvexId<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConvexId<{}>({:?})", T::TABLE, self.raw)
+    }
+}
+
+impl<T> std::fmt::Display for ConvexId<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl<T> Serialize for ConvexId<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de, T: ConvexDocument> Deserialize<'de> for ConvexId<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::try_from(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A string without the Convex id format, from `ConvexId`'s `TryFrom`,
+/// `parse`, or deserializing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidConvexId {
+    /// The string that was rejected.
+    pub raw: String,
+    /// The table of the id it was converted into.
+    pub table: &'static str,
+}
+
+impl std::fmt::Display for InvalidConvexId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not a Convex id (for table `{}`)", self.raw, self.table)
+    }
+}
+
+impl std::error::Error for InvalidConvexId {}
+
+"#
+    .to_string()
+}
+
+/// Generate `SystemFields`, the `_id` and `_creationTime` of a document, for
+/// [`Configuration::flatten_system_fields`]. With typed ids it is generic over
+/// the table struct.
+fn generate_system_fields_code(typed_ids: bool) -> String
+{
+    let mut code = String::new();
+    code.push_str("/// The system fields every document has, flattened into each table struct.\n");
+    code.push_str("/// Table structs implement `AsRef<SystemFields>`, so code that handles any\n");
+    code.push_str("/// table's documents can read their ids and creation times.\n");
+    code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    if typed_ids {
+        code.push_str("#[serde(bound = \"\")]\n");
+        code.push_str("pub struct SystemFields<T: ConvexDocument> {\n");
+        code.push_str("    #[serde(rename = \"_id\")]\n");
+        code.push_str("    pub id: ConvexId<T>,\n");
+    } else {
+        code.push_str("pub struct SystemFields {\n");
+        code.push_str("    #[serde(rename = \"_id\")]\n");
+        code.push_str("    pub id: String,\n");
+    }
+    code.push_str("    #[serde(rename = \"_creationTime\")]\n");
+    code.push_str("    pub creation_time: f64,\n");
+    code.push_str("}\n\n");
+    code
+}
+
+/// `serde_with` adapter that reads a JSON string or number into a `String`.
+fn generate_string_or_number_adapter() -> String
+{
+    r#"/// `serde_with` adapter: accepts a JSON string or number, keeps it as a `String`.
+pub struct StringOrNumber;
+
+impl<'de> serde_with::DeserializeAs<'de, String> for StringOrNumber {
+    fn deserialize_as<D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(s) => Ok(s),
+            serde_json::Value::Number(n) => Ok(n.to_string()),
+            other => Err(serde::de::Error::custom(format!("expected a string or number, got {}", other))),
+        }
+    }
+}
+
+impl serde_with::SerializeAs<String> for StringOrNumber {
+    fn serialize_as<S: serde::Serializer>(source: &String, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(source)
+    }
+}
+
+"#
+    .to_string()
+}
+
+/// Generate `RawJson<T>`, the type of `raw_json` fields: the JSON text as
+/// received, parsed into `T` on demand.
+fn generate_raw_json_code() -> String
+{
+    r#"/// A field kept as its raw JSON text and parsed into `T` only on `parse()`.
+pub struct RawJson<T> {
+    raw: Box<serde_json::value::RawValue>,
+    _marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> RawJson<T> {
+    pub fn from_raw(raw: Box<serde_json::value::RawValue>) -> Self {
+        Self { raw, _marker: std::marker::PhantomData }
+    }
+
+    /// The JSON text.
+    pub fn get(&self) -> &str {
+        self.raw.get()
+    }
+
+    pub fn into_raw(self) -> Box<serde_json::value::RawValue> {
+        self.raw
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> RawJson<T> {
+    /// Parse the JSON into its typed form.
+    pub fn parse(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(self.raw.get())
+    }
+}
+
+impl<T: Serialize> RawJson<T> {
+    /// Serialize `value` to its raw JSON.
+    pub fn new(value: &T) -> Result<Self, serde_json::Error> {
+        serde_json::value::to_raw_value(value).map(Self::from_raw)
+    }
+}
+
+impl<T> Clone for RawJson<T> {
+    fn clone(&self) -> Self {
+        Self::from_raw(self.raw.clone())
+    }
+}
+
+impl<T> std::fmt::Debug for RawJson<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RawJson({})", self.raw.get())
+    }
+}
+
+/// Compares the JSON text, so the same value with different whitespace or key
+/// order is not equal.
+impl<T> PartialEq for RawJson<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw.get() == other.raw.get()
+    }
+}
+
+impl<T> Serialize for RawJson<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RawJson<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Box::<serde_json::value::RawValue>::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+"#
+    .to_string()
+}
+
+/// FNV-1a of the schema's canonical JSON (tables, fields, validators, indexes,
+/// and options), as 16 hex digits.
+pub(crate) fn schema_fingerprint(schema: &ConvexSchema) -> String
+{
+    let canonical = serde_json::to_value(schema).unwrap_or_default().to_string();
+    manifest::digest(canonical.as_bytes())
+}
+
+/// `CONVEX_SCHEMA_FINGERPRINT`, from [`schema_fingerprint`].
+fn generate_schema_fingerprint(schema: &ConvexSchema) -> String
+{
+    let mut code = String::new();
+    code.push_str("/// Fingerprint of the schema this file was generated from. It changes with any\n");
+    code.push_str("/// table, field, validator, or index, so services can log or report it to tell\n");
+    code.push_str("/// which schema they were built against.\n");
+    code.push_str(&format!(
+        "pub const CONVEX_SCHEMA_FINGERPRINT: &str = \"{}\";\n\n",
+        schema_fingerprint(schema)
+    ));
+    code
+}
+
+/// Generate `FIELD_COUNT` and `content_eq` / `content_hash` for a table struct.
+///
+/// All three ignore the `_id` and `_creationTime` system fields, so a locally
+/// constructed document compares equal to the fetched copy of the same data.
+/// `field_types` are the Rust types of `field_names`, in order.
+fn generate_content_eq_impl(struct_name: &str, field_names: &[String], field_types: &[String]) -> String
+{
+    let mut code = String::new();
+    code.push_str(&format!("impl {} {{\n", struct_name));
+
+    code.push_str("    /// Number of user-defined fields in the schema, not counting `_id` and `_creationTime`.\n");
+    code.push_str(&format!("    pub const FIELD_COUNT: usize = {};\n\n", field_names.len()));
+
+    // `v.null()` fields are `()`, always equal, and comparing them trips `clippy::unit_cmp`
+    let comparisons: Vec<String> = field_names
+        .iter()
+        .zip(field_types)
+        .filter(|(_, rust_type)| *rust_type != "()")
+        .map(|(name, _)| format!("self.{name} == other.{name}"))
+        .collect();
+    code.push_str("    /// Compare user-defined fields only, ignoring `_id` and `_creationTime`.\n");
+    if comparisons.is_empty() {
+        code.push_str("    pub fn content_eq(&self, _other: &Self) -> bool {\n");
+        code.push_str("        true\n");
+    } else {
+        code.push_str("    pub fn content_eq(&self, other: &Self) -> bool {\n");
+        code.push_str(&format!("        {}\n", comparisons.join("\n            && ")));
+    }
+    code.push_str("    }\n\n");
+
+    // Hash the JSON form so f64 fields are covered (they don't implement `Hash`)
+    code.push_str("    /// Hash of the user-defined fields, ignoring `_id` and `_creationTime`.\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// Documents that are `content_eq` produce the same hash. It is the FNV-1a\n");
+    code.push_str("    /// hash of the fields' JSON with object keys sorted, so it doesn't change\n");
+    code.push_str("    /// between builds, Rust releases, or `serde_json` features.\n");
+    code.push_str("    pub fn content_hash(&self) -> u64 {\n");
+    code.push_str("        let mut value = serde_json::to_value(self).expect(\"table documents serialize to JSON\");\n");
+    code.push_str("        if let Some(map) = value.as_object_mut() {\n");
+    code.push_str("            map.remove(\"_id\");\n");
+    code.push_str("            map.remove(\"_creationTime\");\n");
+    code.push_str("        }\n");
+    code.push_str("        content_digest(&value)\n");
+    code.push_str("    }\n");
+
+    code.push_str("}\n\n");
+    code
+}
+
+/// Generate `content_digest`, the hash behind every table's `content_hash`.
+///
+/// Keys are sorted by hand: with `serde_json`'s `preserve_order` feature, maps
+/// keep insertion order, so `HashMap` fields would serialize in iteration
+/// order. FNV-1a is fixed by its spec, unlike `DefaultHasher`.
+fn generate_content_digest_helper() -> String
+{
+    r#"/// 64-bit FNV-1a of `value`'s JSON with object keys sorted, for `content_hash`.
+fn content_digest(value: &serde_json::Value) -> u64 {
+    fn write(value: &serde_json::Value, out: &mut String) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                out.push('{');
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&serde_json::Value::from(key.as_str()).to_string());
+                    out.push(':');
+                    write(value, out);
+                }
+                out.push('}');
+            }
+            serde_json::Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write(item, out);
+                }
+                out.push(']');
+            }
+            // -0.0 == 0.0, so `content_eq` documents must hash alike
+            serde_json::Value::Number(n) if n.as_f64() == Some(0.0) => out.push('0'),
+            other => out.push_str(&other.to_string()),
+        }
+    }
+    let mut json = String::new();
+    write(value, &mut json);
+    json.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+"#
+    .to_string()
+}
+
+// =============================================================================
+// Function code generation
+// =============================================================================
+
+/// Generate the args struct for a function.
+fn generate_function_code(function: &ConvexFunction, ctx: &mut CodegenContext) -> String
+{
+    let mut code = String::new();
+
+    let file_cap = capitalize_first_letter(&function.file_name);
+    let fn_cap = capitalize_first_letter(&function.name);
+    let struct_name = args_struct_name(function);
+    let cfg = function_attrs(function, ctx, "");
+
+    // Args wrapped whole have no fields to redact
+    let redacted: Vec<&str> = function
+        .params
+        .iter()
+        .filter(|_| function.whole_args().is_none())
+        .filter(|param| ctx.config.redacted_args.contains(&arg_default_key(function, param)))
+        .map(|param| param.name.as_str())
+        .collect();
+
+    code.push_str(&cfg);
+    code.push_str(&deprecated_attr(function, ctx, ""));
+    if redacted.is_empty() {
+        code.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+    } else {
+        code.push_str("#[derive(Clone, Serialize, Deserialize)]\n");
+    }
+    let mut fields = Vec::new();
+    if let Some(args) = function.whole_args() {
+        // A union of objects or a record: the struct is the args value itself
+        let rust_type = whole_args_type(function, args, ctx);
+        code.push_str("#[serde(transparent)]\n");
+        code.push_str(&format!("pub struct {}(pub {});\n\n", struct_name, rust_type));
+    } else {
+        if !function.params.is_empty() {
+            code.push_str("#[allow(non_snake_case)]\n");
+        }
+        code.push_str(&format!("pub struct {} {{\n", struct_name));
+        for param in &function.params {
+            let naming_ctx = format!("{}{}{}", file_cap, fn_cap, capitalize_first_letter(&param.name));
+            let rust_type = arg_field_type(param, &naming_ctx, ctx);
+            if rust_type.starts_with("Option<") {
+                code.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            let safe_param = escape_rust_keyword(&param.name);
+            code.push_str(&format!("    pub {}: {},\n", safe_param, rust_type));
+            fields.push((safe_param, rust_type));
+        }
+        code.push_str("}\n\n");
+    }
+
+    // FUNCTION_PATH constant
+    code.push_str(&cfg);
+    code.push_str(&format!("impl {} {{\n", struct_name));
+    code.push_str("    pub const FUNCTION_PATH: &'static str = ");
+    let module = function.module_path.as_deref().unwrap_or(&function.file_name);
+    code.push_str(&format!("\"{}:{}\";\n", module, function.name));
+    let constructor = generate_args_constructor(function, &fields, ctx.config);
+    if let Some((new_fn, _)) = &constructor {
+        code.push('\n');
+        code.push_str(new_fn);
+    }
+    code.push_str("}\n\n");
+
+    if !redacted.is_empty() {
+        code.push_str(&cfg);
+        code.push_str(&generate_redacted_debug(&struct_name, function, &redacted));
+    }
+
+    // Default, when new() takes no args
+    if let Some((_, true)) = constructor {
+        code.push_str(&cfg);
+        code.push_str(&format!("impl Default for {} {{\n", struct_name));
+        code.push_str("    fn default() -> Self {\n");
+        code.push_str("        Self::new()\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+    }
+
+    // From impls for BTreeMap. They go through the derived Serialize, so the
+    // args sent by `ConvexApi` and the serialized struct have one wire shape.
+    // The borrowed one backs the `_ref` methods, which leave the struct to reuse
+    for args_type in [format!("&{struct_name}"), struct_name.clone()] {
+        code.push_str(&cfg);
+        code.push_str(&format!(
+            "impl From<{}> for std::collections::BTreeMap<String, serde_json::Value> {{\n",
+            args_type
+        ));
+        code.push_str(&format!("    fn from(args: {}) -> Self {{\n", args_type));
+        code.push_str("        match serde_json::to_value(args) {\n");
+        code.push_str("            Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),\n");
+        code.push_str("            other => panic!(\"args structs serialize to a JSON object, got {other:?}\"),\n");
+        code.push_str("        }\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+    }
+
+    if is_paginated_query(function) {
+        code.push_str(&generate_pagination_cursor(function, &cfg));
+    }
+
+    code
+}
+
+/// `Debug` for an args struct that prints the `redacted` args as `***`, from
+/// [`Configuration::redacted_args`].
+fn generate_redacted_debug(struct_name: &str, function: &ConvexFunction, redacted: &[&str]) -> String
+{
+    let mut code = format!(
+        "impl std::fmt::Debug for {struct_name} {{\n\x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> \
+         std::fmt::Result {{\n\x20       f.debug_struct({struct_name:?})\n"
+    );
+    for param in &function.params {
+        // Redacted even when `None`, so the log doesn't tell whether it was set
+        let value = if redacted.contains(&param.name.as_str()) {
+            "format_args!(\"***\")".to_string()
+        } else {
+            format!("self.{}", escape_rust_keyword(&param.name))
+        };
+        code.push_str(&format!("            .field({:?}, &{value})\n", param.name));
+    }
+    code.push_str("            .finish()\n    }\n}\n\n");
+    code
+}
+
+/// The type of an args struct field.
+fn arg_field_type(param: &ConvexFunctionParam, naming_ctx: &str, ctx: &mut CodegenContext) -> String
+{
+    match finite_arg_type(&param.data_type) {
+        Some(finite) if ctx.config.finite_number_args => finite,
+        _ => convex_type_to_rust_type(&param.data_type, naming_ctx, ctx),
+    }
+}
+
+/// The type of a number arg with [`Configuration::finite_number_args`]:
+/// `Finite` for `v.number()`, wrapped in `Option` or `Vec` for an optional
+/// number or an array of numbers. `None` for other args.
+fn finite_arg_type(data_type: &JsonValue) -> Option<String>
+{
+    match data_type["type"].as_str()? {
+        "number" => Some("Finite".to_string()),
+        "optional" => finite_arg_type(&data_type["inner"]).map(|inner| format!("Option<{inner}>")),
+        "array" => finite_arg_type(&data_type["elements"]).map(|element| format!("Vec<{element}>")),
+        _ => None,
+    }
+}
+
+/// Generate `FunctionKind` and `FunctionRef`, with an associated const for
+/// every function, public or internal, e.g. `FunctionRef::GAMES_GET_GAME`.
+fn generate_function_refs(functions: &[ConvexFunction], config: &Configuration) -> String
+{
+    let mut code = String::new();
+    code.push_str(
+        "/// Whether a Convex function is a query, a mutation, or an action.\n#[derive(Debug, Clone, Copy, PartialEq, Eq, \
+         Hash)]\npub enum FunctionKind {\n\x20   Query,\n\x20   Mutation,\n\x20   Action,\n}\n\n/// A Convex function, for \
+         code that names one: scheduling, logging, or\n/// allowlists. It serializes as its path and deserializes only \
+         from the\n/// path of a known function.\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub struct \
+         FunctionRef {\n\x20   /// The path Convex calls it by, e.g. `\"games:getGame\"`.\n\x20   pub path: &'static \
+         str,\n\x20   /// The TypeScript reference, e.g. `api.games.getGame` or `internal.games.cleanup`.\n\x20   pub \
+         reference: &'static str,\n\x20   /// Query, mutation, or action.\n\x20   pub kind: FunctionKind,\n\x20   /// \
+         Registered with `internalQuery`, `internalMutation`, or `internalAction`, so\n\x20   /// only other Convex \
+         functions can call it, e.g. through `ctx.scheduler`.\n\x20   pub internal: bool,\n}\n\nimpl FunctionRef {\n",
+    );
+
+    let mut names = Vec::new();
+    for function in functions {
+        let kind = match function.type_.trim_start_matches("internal").to_ascii_lowercase().as_str() {
+            "query" => "Query",
+            "mutation" => "Mutation",
+            "action" => "Action",
+            _ => continue,
+        };
+        let internal = function.type_.starts_with("internal");
+        let path = function_path(function);
+        let module = function.module_path.as_deref().unwrap_or(&function.file_name);
+        let reference = format!(
+            "{}.{}.{}",
+            if internal { "internal" } else { "api" },
+            module.replace('/', "."),
+            function.name
+        );
+        let name = function_ref_name(function);
+        let cfg = config
+            .feature_gates
+            .as_ref()
+            .and_then(|gates| gates.for_kind(&function.type_));
+        if let Some(feature) = cfg {
+            code.push_str(&format!("    #[cfg(feature = \"{feature}\")]\n"));
+        }
+        code.push_str(&format!(
+            "    pub const {name}: FunctionRef = FunctionRef {{\n        path: \"{path}\",\n        reference: \
+             \"{reference}\",\n        kind: FunctionKind::{kind},\n        internal: {internal},\n    }};\n\n"
+        ));
+        names.push((name, cfg));
+    }
+
+    code.push_str("    /// Every function, in the order they were generated.\n");
+    code.push_str("    pub const ALL: &'static [FunctionRef] = &[\n");
+    for (name, cfg) in &names {
+        if let Some(feature) = cfg {
+            code.push_str(&format!("        #[cfg(feature = \"{feature}\")]\n"));
+        }
+        code.push_str(&format!("        Self::{name},\n"));
+    }
+    code.push_str("    ];\n\n");
+    code.push_str(
+        "\x20   /// The function at `path`, e.g. `\"games:getGame\"`.\n\x20   pub fn from_path(path: &str) -> \
+         Option<FunctionRef> {\n\x20       Self::ALL.iter().copied().find(|function| function.path == path)\n\x20   \
+         }\n}\n\nimpl std::fmt::Display for FunctionRef {\n\x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> \
+         std::fmt::Result {\n\x20       f.write_str(self.path)\n\x20   }\n}\n\nimpl Serialize for FunctionRef {\n\x20   fn \
+         serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n\x20       \
+         serializer.serialize_str(self.path)\n\x20   }\n}\n\nimpl<'de> Deserialize<'de> for FunctionRef {\n\x20   fn \
+         deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {\n\x20       let path = \
+         String::deserialize(deserializer)?;\n\x20       Self::from_path(&path)\n\x20           .ok_or_else(|| \
+         serde::de::Error::custom(format!(\"unknown Convex function `{path}`\")))\n\x20   }\n}\n\n",
+    );
+    code
+}
+
+/// Name of a function's `FunctionRef` const (e.g. `GAMES_GET_GAME`).
+fn function_ref_name(function: &ConvexFunction) -> String
+{
+    format!("{}_{}", to_snake_case(&function.file_name), to_snake_case(&function.name)).to_uppercase()
+}
+
+/// `Schedulable`, for the args of every mutation and action, internal ones
+/// included, and `ScheduleRequest`, a call to one of them with its delay that
+/// serializes as the args of a scheduling mutation.
+fn generate_schedule_requests(functions: &[ConvexFunction], ctx: &CodegenContext) -> String
+{
+    let mut code = r#"/// The args of a mutation or action, which Convex can schedule.
+pub trait Schedulable: Serialize {
+    /// The function the args are for.
+    const FUNCTION: FunctionRef;
+}
+
+/// When a scheduled function runs, as `ctx.scheduler.runAfter` or `runAt` takes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAt {
+    /// This long after the scheduling mutation commits.
+    After(std::time::Duration),
+    /// At this time, or right away if it has passed.
+    At(std::time::SystemTime),
+}
+
+/// A call to a mutation or action for a scheduling mutation to pass to
+/// `ctx.scheduler`. It serializes as that mutation's args:
+/// `{ "fn": "games:cleanup", "args": { ... }, "delayMs": 5000 }`, with
+/// `"runAt"`, in milliseconds since the epoch, instead of `"delayMs"` for
+/// `ScheduleAt::At`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleRequest<A: Schedulable> {
+    /// The args, which also pick the function.
+    pub args: A,
+    /// When the function runs.
+    pub at: ScheduleAt,
+}
+
+impl<A: Schedulable> ScheduleRequest<A> {
+    /// Run the function as soon as the scheduling mutation commits.
+    pub fn now(args: A) -> Self {
+        Self::after(args, std::time::Duration::ZERO)
+    }
+
+    /// Run the function `delay` after the scheduling mutation commits.
+    pub fn after(args: A, delay: std::time::Duration) -> Self {
+        Self { args, at: ScheduleAt::After(delay) }
+    }
+
+    /// Run the function at `time`.
+    pub fn at(args: A, time: std::time::SystemTime) -> Self {
+        Self { args, at: ScheduleAt::At(time) }
+    }
+
+    /// The function to run.
+    pub fn function(&self) -> FunctionRef {
+        A::FUNCTION
+    }
+
+    /// The scheduling mutation's args struct, e.g. `JobsEnqueueArgs`, filled
+    /// in from this request.
+    pub fn to_args<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(serde_json::to_value(self)?)
+    }
+}
+
+impl<A: Schedulable> Serialize for ScheduleRequest<A> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("fn", A::FUNCTION.path)?;
+        map.serialize_entry("args", &self.args)?;
+        match self.at {
+            ScheduleAt::After(delay) => map.serialize_entry("delayMs", &(delay.as_secs_f64() * 1000.0))?,
+            ScheduleAt::At(time) => {
+                let since_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+                map.serialize_entry("runAt", &(since_epoch.as_secs_f64() * 1000.0))?
+            }
+        }
+        map.end()
+    }
+}
+
+impl<A: Schedulable> From<ScheduleRequest<A>> for std::collections::BTreeMap<String, serde_json::Value> {
+    fn from(request: ScheduleRequest<A>) -> Self {
+        match serde_json::to_value(&request) {
+            Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),
+            other => panic!("schedule requests serialize to a JSON object, got {other:?}"),
+        }
+    }
+}
+
+"#
+    .to_string();
+
+    // Queries can't be scheduled
+    for function in functions {
+        if !matches!(
+            function.type_.trim_start_matches("internal").to_ascii_lowercase().as_str(),
+            "mutation" | "action"
+        ) {
+            continue;
+        }
+        code.push_str(&function_attrs(function, ctx, ""));
+        code.push_str(&format!(
+            "impl Schedulable for {} {{\n\x20   const FUNCTION: FunctionRef = FunctionRef::{};\n}}\n\n",
+            args_struct_path(function, ctx.config),
+            function_ref_name(function)
+        ));
+    }
+    code
+}
+
+/// Generate `Finite`, the `f64` of number args that can't be NaN or infinite,
+/// and its error.
+fn generate_finite_type() -> String
+{
+    "/// A number arg: an `f64` that is neither NaN nor infinite, which JSON\n/// would turn into `null`. It serializes as \
+     the plain number.\n#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize)]\n#[serde(transparent)]\npub struct \
+     Finite(f64);\n\nimpl Finite {\n\x20   /// `value`, unless it is NaN or infinite.\n\x20   pub fn new(value: f64) -> \
+     Result<Self, NonFiniteError> {\n\x20       if value.is_finite() {\n\x20           Ok(Self(value))\n\x20       } else \
+     {\n\x20           Err(NonFiniteError(value))\n\x20       }\n\x20   }\n\n\x20   pub fn get(self) -> f64 {\n\x20       \
+     self.0\n\x20   }\n}\n\nimpl TryFrom<f64> for Finite {\n\x20   type Error = NonFiniteError;\n\x20   fn try_from(value: \
+     f64) -> Result<Self, Self::Error> {\n\x20       Self::new(value)\n\x20   }\n}\n\nimpl From<Finite> for f64 {\n\x20   \
+     fn from(value: Finite) -> Self {\n\x20       value.0\n\x20   }\n}\n\nimpl std::fmt::Display for Finite {\n\x20   fn \
+     fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n\x20       self.0.fmt(f)\n\x20   }\n}\n\nimpl<'de> \
+     Deserialize<'de> for Finite {\n\x20   fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, \
+     D::Error> {\n\x20       Self::new(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)\n\x20   \
+     }\n}\n\n/// A NaN or infinite `f64` given for a number arg.\n#[derive(Debug, Clone, Copy, PartialEq)]\npub struct \
+     NonFiniteError(pub f64);\n\nimpl std::fmt::Display for NonFiniteError {\n\x20   fn fmt(&self, f: &mut \
+     std::fmt::Formatter<'_>) -> std::fmt::Result {\n\x20       write!(f, \"{} is not a finite number, which JSON \
+     requires\", self.0)\n\x20   }\n}\n\nimpl std::error::Error for NonFiniteError {}\n\n"
+        .to_string()
+}
+
+/// Whether `function` is a public query taking `paginationOpts` from
+/// `paginationOptsValidator`: an object with `numItems` and a nullable `cursor`.
+fn is_paginated_query(function: &ConvexFunction) -> bool
+{
+    let is_nullable_string = |data_type: &JsonValue| {
+        data_type["type"] == "union"
+            && data_type["variants"].as_array().is_some_and(|variants| {
+                variants.len() == 2
+                    && variants.iter().any(|variant| variant["type"] == "string")
+                    && variants.iter().any(|variant| variant["type"] == "null")
+            })
+    };
+    function.type_ == "query"
+        && function.params.iter().any(|param| {
+            let props = &param.data_type["properties"];
+            param.name == "paginationOpts"
+                && param.data_type["type"] == "object"
+                && props["numItems"]["type"] == "number"
+                && is_nullable_string(&props["cursor"])
+        })
+}
+
+/// The `{File}{Fn}Cursor` newtype of a paginated query, its
+/// `PaginationCursor` impl, and `with_cursor()` on the args struct.
+fn generate_pagination_cursor(function: &ConvexFunction, cfg: &str) -> String
+{
+    let cursor = cursor_struct_name(function);
+    let args = args_struct_name(function);
+    let module = function.module_path.as_deref().unwrap_or(&function.file_name);
+    let path = format!("{}:{}", module, function.name);
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// Continuation cursor of the paginated query `{path}`.\n///\n/// `to_string()` and `from_string()` round-trip \
+         it through storage, so a\n/// long-running sync can resume from the last page it saw.\n"
+    ));
+    code.push_str(cfg);
+    code.push_str("#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
+    code.push_str("#[serde(transparent)]\n");
+    code.push_str(&format!("pub struct {cursor}(String);\n\n"));
+
+    code.push_str(cfg);
+    code.push_str(&format!(
+        "impl {cursor} {{\n\x20   /// A cursor read back from storage or a page's `continueCursor`.\n\x20   pub fn \
+         from_string(cursor: impl Into<String>) -> Self {{\n\x20       Self(cursor.into())\n\x20   }}\n\n\x20   pub fn \
+         as_str(&self) -> &str {{\n\x20       &self.0\n\x20   }}\n}}\n\n"
+    ));
+
+    code.push_str(cfg);
+    code.push_str(&format!(
+        "impl std::fmt::Display for {cursor} {{\n\x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result \
+         {{\n\x20       f.write_str(&self.0)\n\x20   }}\n}}\n\n"
+    ));
+
+    code.push_str(cfg);
+    code.push_str(&format!(
+        "impl PaginationCursor for {cursor} {{\n\x20   const QUERY: &'static str = \"{path}\";\n\n\x20   fn \
+         from_string(cursor: String) -> Self {{\n\x20       Self(cursor)\n\x20   }}\n\n\x20   fn as_str(&self) -> &str \
+         {{\n\x20       &self.0\n\x20   }}\n}}\n\n"
+    ));
+
+    code.push_str(cfg);
+    code.push_str(&format!(
+        "impl {args} {{\n\x20   /// Start after `cursor`, or from the first page when it is `None`.\n\x20   pub fn \
+         with_cursor(mut self, cursor: Option<&{cursor}>) -> Self {{\n\x20       self.paginationOpts.cursor = \
+         cursor.map(|cursor| cursor.0.clone());\n\x20       self\n\x20   }}\n}}\n\n"
+    ));
+
+    code
+}
+
+/// `new()` taking the required args and filling the optional ones from
+/// [`Configuration::arg_defaults`] (`None` where unset), and whether it takes
+/// no args so `Default` can delegate to it. `None` unless some arg has a default.
+fn generate_args_constructor(
+    function: &ConvexFunction,
+    fields: &[(String, String)],
+    config: &Configuration,
+) -> Option<(String, bool)>
+{
+    let defaults: Vec<Option<&JsonValue>> = function
+        .params
+        .iter()
+        .map(|param| arg_default(function, param, config))
+        .collect();
+    if defaults.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    let mut body = String::new();
+    for ((param, (safe_param, rust_type)), default) in function.params.iter().zip(fields).zip(&defaults) {
+        if !is_optional_param(param) {
+            params.push(format!("{}: {}", safe_param, rust_type));
+            body.push_str(&format!("            {},\n", safe_param));
+        } else if let Some(value) = default {
+            let inner = &rust_type["Option<".len()..rust_type.len() - 1];
+            body.push_str(&format!(
+                "            {}: Some({}),\n",
+                safe_param,
+                default_expr(inner, value, &param.name)
+            ));
+        } else {
+            body.push_str(&format!("            {}: None,\n", safe_param));
+        }
+    }
+
+    let mut code = String::new();
+    code.push_str("    /// The required args as given, the optional ones at their defaults.\n");
+    if !params.is_empty() {
+        code.push_str("    #[allow(non_snake_case)]\n");
+    }
+    if params.len() > 7 {
+        code.push_str("    #[allow(clippy::too_many_arguments)]\n");
+    }
+    code.push_str(&format!("    pub fn new({}) -> Self {{\n", params.join(", ")));
+    code.push_str("        Self {\n");
+    code.push_str(&body);
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+
+    Some((code, params.is_empty()))
+}
+
+/// The configured default of an optional arg, if the arg's validator accepts it.
+fn arg_default<'a>(
+    function: &ConvexFunction,
+    param: &crate::types::ConvexFunctionParam,
+    config: &'a Configuration,
+) -> Option<&'a JsonValue>
+{
+    let inner = optional_inner(&param.data_type)?;
+    let value = config.arg_defaults.get(&arg_default_key(function, param))?;
+    accepts(inner, value).then_some(value)
+}
+
+/// A Rust expression of type `rust_type` for the JSON default `value`.
+///
+/// Scalars become literals; anything else is deserialized from its JSON text,
+/// which [`arg_default`] has already checked against the validator.
+fn default_expr(rust_type: &str, value: &JsonValue, arg: &str) -> String
+{
+    match (rust_type, value) {
+        ("String", JsonValue::String(text)) => format!("{:?}.to_string()", text),
+        ("f64", JsonValue::Number(number)) => format!("{:?}", number.as_f64().unwrap_or_default()),
+        ("i64", JsonValue::Number(number)) => number.to_string(),
+        ("bool", JsonValue::Bool(flag)) => flag.to_string(),
+        _ => format!(
+            "serde_json::from_str({:?}).expect(\"default for `{}` matches its validator\")",
+            value.to_string(),
+            arg
+        ),
+    }
+}
+
+// =============================================================================
+// API function generation
+// =============================================================================
+
+/// Generate the ConvexApi trait + ConvexApiClient wrapper struct.
+///
+/// The trait has `&self` methods returning `Result<T, ConvexError>`.
+/// The wrapper holds a `convex::ConvexClient` and clones it internally
+/// on each call, satisfying the SDK's `&mut self` requirement.
+fn generate_api_code(functions: &[ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let public_functions: Vec<&ConvexFunction> = functions.iter().filter(|f| !f.type_.starts_with("internal")).collect();
+
+    if public_functions.is_empty() {
+        return String::new();
+    }
+
+    let mut code = String::new();
+
+    // ConvexError type (always generated)
+    code.push_str(&generate_convex_error_type());
+
+    // With feature gates the helpers may have no callers in some builds
+    let gated = ctx.config.feature_gates.is_some();
+
+    // json_to_convex_value helper (always needed for args conversion)
+    if gated {
+        code.push_str("#[allow(dead_code)]\n");
+    }
+    code.push_str(&generate_json_to_convex_value_helper());
+    if public_functions.iter().any(|f| f.takes_args()) {
+        code.push_str(&generate_value_shape_helpers(gated));
+    }
+
+    // Mutations with args fields can be queued offline; the queue converts
+    // queued args field by field
+    let queueable: Vec<&ConvexFunction> = if ctx.config.emit_offline_queue {
+        public_functions
+            .iter()
+            .copied()
+            .filter(|f| f.type_ == "mutation" && !f.params.is_empty())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Every call decodes its result, into `serde_json::Value` when the function
+    // has no `returns` (the offline queue and admin client also use
+    // convex_value_to_json to report server errors)
+    let has_calls = public_functions
+        .iter()
+        .any(|f| matches!(f.type_.as_str(), "query" | "mutation" | "action"));
+    // Consistent sessions read queries of any kind, typed or not
+    let consistent_reads = ctx.config.emit_consistent_reads && public_functions.iter().any(|f| f.type_ == "query");
+    if has_calls || ctx.config.emit_admin_client || !queueable.is_empty() {
+        if gated {
+            code.push_str("#[allow(dead_code)]\n");
+        }
+        code.push_str(&generate_convex_value_to_json_helper());
+    }
+    if has_calls {
+        if gated {
+            code.push_str("#[allow(dead_code)]\n");
+        }
+        code.push_str(&generate_decode_function_result_helper());
+    }
+
+    // TypedSubscription wrapper if any query has a typed return
+    let has_typed_queries = public_functions.iter().any(|f| f.type_ == "query" && f.return_type.is_some());
+    if has_typed_queries {
+        code.push_str(&generate_typed_subscription_code());
+        if ctx.config.emit_shared_subscriptions {
+            code.push_str(&generate_shared_subscription_code());
+        }
+        if ctx.config.emit_arc_subscriptions {
+            code.push_str(&generate_arc_subscription_code());
+        }
+        if ctx.config.emit_snapshot_subscriptions {
+            code.push_str(&generate_snapshot_subscription_code());
+        }
+        if ctx.config.emit_buffered_subscriptions {
+            code.push_str(&generate_buffered_subscription_code());
+        }
+        if ctx.config.emit_resilient_subscriptions {
+            code.push_str(&generate_resilient_subscription_code());
+        }
+        if ctx.config.emit_frontend_adapters {
+            code.push_str(&generate_frontend_adapters_code());
+        }
+    }
+
+    if public_functions.iter().any(|f| is_paginated_query(f)) {
+        code.push_str(&generate_pagination_cursor_trait());
+        if ctx.config.emit_cursor_store {
+            code.push_str(&generate_cursor_store_code());
+        }
+    }
+
+    // ConvexApiClient wrapper struct
+    code.push_str(&generate_wrapper_struct());
+
+    // Functions a component's client created get that component's trait
+    // instead of `ConvexApi`
+    let (component_functions, app_functions): (Vec<&ConvexFunction>, Vec<&ConvexFunction>) =
+        public_functions.iter().copied().partition(|f| f.component.is_some());
 
     // ConvexApi trait + impl
-    code.push_str(&generate_trait_and_impl(&public_functions, ctx));
+    code.push_str(&generate_trait_and_impl(&app_functions, ctx));
+
+    if !component_functions.is_empty() {
+        code.push_str(&generate_component_traits(&component_functions, ctx));
+    }
+
+    code.push_str(&generate_function_result_conversions(&public_functions, ctx));
+
+    code.push_str(&generate_index_query_helpers(&app_functions, ctx));
+
+    code.push_str(&generate_default_timeout_methods(&app_functions, ctx));
+
+    code.push_str(&generate_call_priorities(&app_functions, ctx));
+
+    if ctx.config.emit_timeouts {
+        code.push_str(&generate_timeout_methods(&app_functions, ctx));
+    }
+
+    if consistent_reads {
+        code.push_str(&generate_consistent_session(&app_functions, ctx));
+    }
+
+    if !queueable.is_empty() {
+        code.push_str(&generate_offline_queue(&queueable, ctx));
+    }
+
+    if ctx.config.emit_tauri_commands {
+        code.push_str(&generate_tauri_commands(&app_functions, ctx));
+    }
+
+    if ctx.config.emit_axum_router {
+        code.push_str(&generate_axum_router(&app_functions, ctx));
+    }
+
+    if ctx.config.emit_tenant_router {
+        code.push_str(&generate_tenant_router(&app_functions, ctx));
+    }
+
+    if ctx.config.emit_admin_client {
+        code.push_str(&generate_admin_client(ctx.tables));
+    }
 
     code
 }
 
+/// Attribute lines (with `indent`) for every item generated from `func`: a
+/// `#[cfg(feature = "...")]` when its kind is gated in
+/// [`Configuration::feature_gates`], and `#[allow(deprecated)]` when it is in
+/// [`Configuration::deprecated_functions`], so only the caller's uses warn.
+fn function_attrs(func: &ConvexFunction, ctx: &CodegenContext, indent: &str) -> String
+{
+    let mut attrs = String::new();
+    let feature = ctx
+        .config
+        .feature_gates
+        .as_ref()
+        .and_then(|gates| gates.for_kind(&func.type_));
+    if let Some(feature) = feature {
+        attrs.push_str(&format!("{indent}#[cfg(feature = \"{feature}\")]\n"));
+    }
+    if deprecation_note(func, ctx.config).is_some() {
+        attrs.push_str(&format!("{indent}#[allow(deprecated)]\n"));
+    }
+    attrs
+}
+
+/// The [`Configuration::deprecated_functions`] note of `func`, if it is deprecated.
+fn deprecation_note<'a>(func: &ConvexFunction, config: &'a Configuration) -> Option<&'a str>
+{
+    config.deprecated_functions.get(&function_path(func)).map(String::as_str)
+}
+
+/// `#[deprecated]` line (with `indent`) for the public items of a deprecated
+/// function, or an empty string.
+fn deprecated_attr(func: &ConvexFunction, ctx: &CodegenContext, indent: &str) -> String
+{
+    match deprecation_note(func, ctx.config) {
+        Some("") => format!("{indent}#[deprecated]\n"),
+        Some(note) => format!("{indent}#[deprecated(note = {note:?})]\n"),
+        None => String::new(),
+    }
+}
+
+/// Name of the generated struct for a table (e.g. `GamesTable`).
+pub(crate) fn table_struct_name(table_name: &str) -> String
+{
+    format!("{}Table", capitalize_first_letter(table_name))
+}
+
+/// Name of the generated insert struct for a table (e.g. `GamesInsert`).
+fn insert_struct_name(table_name: &str) -> String
+{
+    format!("{}Insert", capitalize_first_letter(table_name))
+}
+
+/// Name of the generated fixture builder for a table (e.g. `GamesTableFixture`).
+fn fixture_struct_name(table_name: &str) -> String
+{
+    format!("{}Fixture", table_struct_name(table_name))
+}
+
+/// Name of a table struct's field for a column (e.g. `win_count`).
+pub(crate) fn table_field_name(column_name: &str) -> String
+{
+    escape_rust_keyword(&to_snake_case(column_name))
+}
+
+/// Path of a function's args struct from the generated module, with the
+/// `internal::` prefix under [`Configuration::internal_module`].
+pub(crate) fn args_struct_path(func: &ConvexFunction, config: &Configuration) -> String
+{
+    if config.internal_module && func.type_.starts_with("internal") {
+        format!("internal::{}", args_struct_name(func))
+    } else {
+        args_struct_name(func)
+    }
+}
+
+/// Name of the generated args struct for a function (e.g. `GamesWinGameArgs`).
+pub(crate) fn args_struct_name(func: &ConvexFunction) -> String
+{
+    format!(
+        "{}{}Args",
+        capitalize_first_letter(&func.file_name),
+        capitalize_first_letter(&func.name)
+    )
+}
+
+/// Name of the cursor newtype of a paginated query (e.g. `MessagesListCursor`).
+fn cursor_struct_name(func: &ConvexFunction) -> String
+{
+    format!(
+        "{}{}Cursor",
+        capitalize_first_letter(&func.file_name),
+        capitalize_first_letter(&func.name)
+    )
+}
+
+/// Name of the newtype that carries a function's `TryFrom<convex::FunctionResult>`
+/// (e.g. `GamesListOutput`).
+fn output_struct_name(func: &ConvexFunction) -> String
+{
+    format!(
+        "{}{}Output",
+        capitalize_first_letter(&func.file_name),
+        capitalize_first_letter(&func.name)
+    )
+}
+
+/// Name of the one-shot `ConvexApi` method for a function: `query_{file}_{fn}`
+/// for queries and, depending on `naming`, `{file}_{fn}` or
+/// `mutation_{file}_{fn}`/`action_{file}_{fn}` for mutations and actions.
+pub(crate) fn call_method_name(func: &ConvexFunction, naming: MethodNaming) -> String
+{
+    let file_snake = to_snake_case(&func.file_name);
+    let fn_snake = to_snake_case(&func.name);
+    match func.type_.as_str() {
+        "query" => format!("query_{file_snake}_{fn_snake}"),
+        "mutation" | "action" if naming != MethodNaming::Bare => format!("{}_{file_snake}_{fn_snake}", func.type_),
+        _ => format!("{file_snake}_{fn_snake}"),
+    }
+}
+
+/// The bare name [`MethodNaming::Prefixed`] keeps as a deprecated alias, for
+/// mutations and actions whose name it changed.
+fn legacy_method_name(func: &ConvexFunction, naming: MethodNaming) -> Option<String>
+{
+    let legacy = call_method_name(func, MethodNaming::Bare);
+    (naming == MethodNaming::Prefixed && legacy != call_method_name(func, naming)).then_some(legacy)
+}
+
+/// The type an args struct wraps when its validator is taken whole, named like
+/// a param called `input` (e.g. `GamesCreateInput`).
+fn whole_args_type(func: &ConvexFunction, args: &JsonValue, ctx: &mut CodegenContext) -> String
+{
+    // Convex args are always an object, so args that accept any value accept
+    // any object, and the args struct can't hold a value that isn't one
+    let is_any = |data_type: &JsonValue| data_type["type"] == "any";
+    if is_any(args)
+        || args["variants"]
+            .as_array()
+            .is_some_and(|variants| variants.iter().any(is_any))
+    {
+        return "serde_json::Map<String, serde_json::Value>".to_string();
+    }
+    let naming_ctx = format!(
+        "{}{}Input",
+        capitalize_first_letter(&func.file_name),
+        capitalize_first_letter(&func.name)
+    );
+    convex_type_to_rust_type(args, &naming_ctx, ctx)
+}
+
 /// Get the Rust return type string for a function's return type.
 /// Returns None if the function has no typed return (uses FunctionResult).
 fn get_return_type_str(func: &ConvexFunction, ctx: &mut CodegenContext) -> Option<String>
@@ -676,28 +3436,268 @@ fn generate_wrapper_struct() -> String
 /// Generate the ConvexApi trait definition and its impl for ConvexApiClient.
 fn generate_trait_and_impl(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
 {
-    let mut trait_methods = String::new();
-    let mut impl_methods = String::new();
-
+    // (trait name, trait methods, impl methods), one entry per file when split
+    let mut traits: Vec<(String, String, String)> = Vec::new();
     for func in functions {
+        let trait_name = if ctx.config.split_api_by_file {
+            file_trait_name(func)
+        } else {
+            "ConvexApi".to_string()
+        };
         let (trait_method, impl_method) = generate_trait_method(func, ctx);
-        trait_methods.push_str(&trait_method);
-        impl_methods.push_str(&impl_method);
+        match traits.iter_mut().find(|(name, ..)| *name == trait_name) {
+            Some((_, trait_methods, impl_methods)) => {
+                trait_methods.push_str(&trait_method);
+                impl_methods.push_str(&impl_method);
+            }
+            None => traits.push((trait_name, trait_method, impl_method)),
+        }
     }
 
     let mut code = String::new();
 
     // Trait definition
-    code.push_str("#[allow(unused)]\n");
-    code.push_str("pub trait ConvexApi {\n");
-    code.push_str(&trait_methods);
-    code.push_str("}\n\n");
+    match &ctx.config.doc_examples_module {
+        Some(module) => code.push_str(&generate_api_doc_examples(module, functions, ctx.config)),
+        None => code.push_str(&doc_comment(&api_doc_intro(functions, ctx.config))),
+    }
+    if !ctx.config.split_api_by_file || traits.is_empty() {
+        let (trait_methods, impl_methods) = traits.pop().map(|(_, t, i)| (t, i)).unwrap_or_default();
+        code.push_str("#[allow(unused)]\n");
+        code.push_str("pub trait ConvexApi {\n");
+        code.push_str(&trait_methods);
+        code.push_str("}\n\n");
+
+        // Impl for ConvexApiClient
+        code.push_str("impl ConvexApi for ConvexApiClient {\n");
+        code.push_str(&impl_methods);
+        code.push_str("}\n\n");
+        return code;
+    }
+
+    let bounds = traits.iter().map(|(name, ..)| name.as_str()).collect::<Vec<_>>().join(" + ");
+    code.push_str(&format!("pub trait ConvexApi: {bounds} {{}}\n\n"));
+    code.push_str(&format!("impl<T: {bounds} + ?Sized> ConvexApi for T {{}}\n\n"));
+    for (trait_name, trait_methods, impl_methods) in &traits {
+        let file = functions
+            .iter()
+            .find(|func| file_trait_name(func) == *trait_name)
+            .map(|func| func.module_path.as_deref().unwrap_or(&func.file_name))
+            .unwrap_or_default();
+        code.push_str(&format!("/// The `{file}` functions of [`ConvexApi`].\n"));
+        code.push_str("#[allow(unused)]\n");
+        code.push_str(&format!("pub trait {trait_name} {{\n"));
+        code.push_str(trait_methods);
+        code.push_str("}\n\n");
+        code.push_str(&format!("impl {trait_name} for ConvexApiClient {{\n"));
+        code.push_str(impl_methods);
+        code.push_str("}\n\n");
+    }
+
+    code
+}
+
+/// Generate `components`, a module with a trait per component for the functions
+/// its client created in the app, e.g. `components::RateLimiterApi` for the
+/// rate limiter's `hookAPI()`. `ConvexApi` leaves these functions out.
+fn generate_component_traits(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    // (component, trait methods, impl methods)
+    let mut traits: Vec<(String, String, String)> = Vec::new();
+    for func in functions {
+        let component = func.component.clone().unwrap_or_default();
+        let (trait_method, impl_method) = generate_trait_method(func, ctx);
+        match traits.iter_mut().find(|(name, ..)| *name == component) {
+            Some((_, trait_methods, impl_methods)) => {
+                trait_methods.push_str(&trait_method);
+                impl_methods.push_str(&impl_method);
+            }
+            None => traits.push((component, trait_method, impl_method)),
+        }
+    }
 
-    // Impl for ConvexApiClient
-    code.push_str("impl ConvexApi for ConvexApiClient {\n");
-    code.push_str(&impl_methods);
+    let mut items = String::new();
+    for (component, trait_methods, impl_methods) in &traits {
+        let trait_name = format!("{}Api", capitalize_first_letter(component));
+        items.push_str(&format!("/// The functions the `{component}` component's client created in the app.\n"));
+        items.push_str("#[allow(unused)]\n");
+        items.push_str(&format!("pub trait {trait_name} {{\n"));
+        items.push_str(trait_methods);
+        items.push_str("}\n\n");
+        items.push_str(&format!("impl {trait_name} for ConvexApiClient {{\n"));
+        items.push_str(impl_methods);
+        items.push_str("}\n\n");
+    }
+
+    let mut code = String::from(
+        "/// Traits for the functions that component clients, such as the rate limiter's\n/// `hookAPI()`, created in \
+         the app. The components' own functions run inside\n/// the deployment and can't be called from a client.\npub \
+         mod components {\n    #[allow(unused_imports)]\n    use super::*;\n\n",
+    );
+    code.push_str(&indent_items(&items));
     code.push_str("}\n\n");
+    code
+}
+
+/// Name of the per-file API trait of a function's file (e.g. `GamesApi`), with
+/// `split_api_by_file`.
+fn file_trait_name(func: &ConvexFunction) -> String
+{
+    format!("{}Api", capitalize_first_letter(&func.file_name))
+}
+
+/// Generate the `ConvexApi` doc comment with `no_run` examples that use this
+/// schema's generated names.
+///
+/// Examples go on the trait rather than in `//!` docs because the generated file
+/// is usually pulled in with `include!`, which rejects inner doc comments.
+fn generate_api_doc_examples(module: &str, functions: &[&ConvexFunction], config: &Configuration) -> String
+{
+    let mut docs = api_doc_intro(functions, config);
+
+    // Signature of the hidden wrapper fn each example runs in
+    let wrapper = |func: &ConvexFunction| {
+        if !func.takes_args() {
+            (
+                "# async fn example(api: ConvexApiClient) -> Result<(), ConvexError> {".to_string(),
+                "",
+            )
+        } else {
+            let header = format!(
+                "# async fn example(api: ConvexApiClient, args: {}) -> Result<(), ConvexError> {{",
+                args_struct_name(func)
+            );
+            (header, "args")
+        }
+    };
+    let mut example = |title: &str, body: Vec<String>| {
+        docs.push(String::new());
+        docs.push(format!("{title}:"));
+        docs.push(String::new());
+        docs.push("```no_run".to_string());
+        docs.push(format!("# use {module}::*;"));
+        docs.extend(body);
+        docs.push("# Ok(())".to_string());
+        docs.push("# }".to_string());
+        docs.push("```".to_string());
+    };
+
+    let query = functions
+        .iter()
+        .find(|f| f.type_ == "query" && f.return_type.is_some())
+        .or_else(|| functions.iter().find(|f| f.type_ == "query"));
+    if let Some(query) = query {
+        let (header, args) = wrapper(query);
+        let file_snake = to_snake_case(&query.file_name);
+        let fn_snake = to_snake_case(&query.name);
+        example(
+            "Run a query once",
+            vec![
+                header.clone(),
+                format!(
+                    "let result = api.{}({args}).await?;",
+                    call_method_name(query, config.method_naming)
+                ),
+            ],
+        );
+        if query.return_type.is_some() {
+            example(
+                "Subscribe to a query",
+                vec![
+                    "use futures::StreamExt;".to_string(),
+                    String::new(),
+                    header,
+                    format!("let mut subscription = api.subscribe_{file_snake}_{fn_snake}({args}).await?;"),
+                    "while let Some(update) = subscription.next().await {".to_string(),
+                    "    let result = update?;".to_string(),
+                    "}".to_string(),
+                ],
+            );
+        }
+    }
+
+    let mutation = functions
+        .iter()
+        .find(|f| f.type_ == "mutation" && f.params.iter().any(is_optional_param))
+        .or_else(|| functions.iter().find(|f| f.type_ == "mutation"));
+    if let Some(mutation) = mutation {
+        let (header, args) = wrapper(mutation);
+        let optional: Vec<String> = mutation
+            .params
+            .iter()
+            .filter(|param| is_optional_param(param))
+            .map(|param| format!("{}: None", escape_rust_keyword(&param.name)))
+            .collect();
+        let call = format!("api.{}({args}).await?;", call_method_name(mutation, config.method_naming));
+        if optional.is_empty() {
+            example("Run a mutation", vec![header, call]);
+        } else {
+            let update = format!(
+                "let args = {} {{ {}, ..args }};",
+                args_struct_name(mutation),
+                optional.join(", ")
+            );
+            example(
+                "Run a mutation with optional args",
+                vec![
+                    header,
+                    "// Optional args are `Option`s; `None` leaves them out".to_string(),
+                    update,
+                    call,
+                ],
+            );
+        }
+    }
+
+    doc_comment(&docs)
+}
 
+/// The opening of the `ConvexApi` docs: what it is and how its futures behave
+/// when dropped.
+fn api_doc_intro(functions: &[&ConvexFunction], config: &Configuration) -> Vec<String>
+{
+    let mut docs = vec!["Typed calls to the Convex deployment, implemented by [`ConvexApiClient`].".to_string()];
+    if let Some(func) = functions.first().filter(|_| config.split_api_by_file) {
+        docs.extend([
+            String::new(),
+            format!(
+                "Its methods come from one trait per function file, e.g. [`{}`], so",
+                file_trait_name(func)
+            ),
+            "code that calls one file's functions can depend on, and mock, only that".to_string(),
+            "trait. Anything that implements them all implements `ConvexApi`.".to_string(),
+        ]);
+    }
+    docs.extend([
+        String::new(),
+        "# Cancellation".to_string(),
+        String::new(),
+        "Every method is cancel-safe: dropping its future, e.g. in `select!` or on".to_string(),
+        "a timeout, leaves the client usable because each call runs on its own clone".to_string(),
+        "of the connection. A mutation or action that was already sent may still run".to_string(),
+        "on the server, so only retry one that is idempotent.".to_string(),
+    ]);
+    if config.emit_timeouts {
+        docs.extend([
+            String::new(),
+            "The `*_with_timeout` methods of [`ConvexApiClient`] give up after a deadline".to_string(),
+            "with `ConvexError::Timeout` instead of waiting on a dead connection.".to_string(),
+        ]);
+    }
+    docs
+}
+
+/// `///` lines for `lines`, with empty lines as bare `///`.
+fn doc_comment(lines: &[String]) -> String
+{
+    let mut code = String::new();
+    for line in lines {
+        if line.is_empty() {
+            code.push_str("///\n");
+        } else {
+            code.push_str(&format!("/// {}\n", line));
+        }
+    }
     code
 }
 
@@ -710,63 +3710,43 @@ fn generate_trait_method(func: &ConvexFunction, ctx: &mut CodegenContext) -> (St
     let module = func.module_path.as_deref().unwrap_or(&func.file_name);
     let function_path = format!("{}:{}", module, func.name);
 
-    let has_args = !func.params.is_empty();
-    let args_param = if has_args {
-        let struct_name = format!(
-            "{}{}Args",
-            capitalize_first_letter(&func.file_name),
-            capitalize_first_letter(&func.name)
-        );
-        format!(", args: {}", struct_name)
+    let args_param = if func.takes_args() {
+        format!(", args: {}", args_struct_name(func))
     } else {
         String::new()
-    };
-
-    let args_body = if has_args {
-        "        let json_args: std::collections::BTreeMap<String, serde_json::Value> = args.into();\n\x20       let args = \
-         json_args.into_iter().map(|(k, v)| (k, json_to_convex_value(v))).collect();\n"
-            .to_string()
-    } else {
-        "        let args = std::collections::BTreeMap::new();\n".to_string()
-    };
-
-    let return_type_str = get_return_type_str(func, ctx);
-
-    // Helper to generate the body that unwraps FunctionResult
-    let typed_return_body = |sdk_call: &str| -> String {
-        match &return_type_str {
-            Some(rt) if rt == "()" => {
-                format!(
-                    "        let result = self.inner.clone().{sdk_call}(\"{function_path}\", args).await\n\
-                     \x20           .map_err(ConvexError::Transport)?;\n\
-                     \x20       match result {{\n\
-                     \x20           convex::FunctionResult::Value(_) => Ok(()),\n\
-                     \x20           convex::FunctionResult::ErrorMessage(msg) => Err(ConvexError::Function(msg)),\n\
-                     \x20           convex::FunctionResult::ConvexError(err) => Err(ConvexError::Server {{ message: err.message, data: convex_value_to_json(&err.data) }}),\n\
-                     \x20       }}\n"
-                )
-            }
-            Some(_) => {
-                format!(
-                    "        let result = self.inner.clone().{sdk_call}(\"{function_path}\", args).await\n\x20           \
-                     .map_err(ConvexError::Transport)?;\n\x20       match result {{\n\x20           \
-                     convex::FunctionResult::Value(value) => {{\n\x20               let json = \
-                     convex_value_to_json(&value);\n\x20               \
-                     serde_json::from_value(json).map_err(ConvexError::Deserialization)\n\x20           }}\n\x20           \
-                     convex::FunctionResult::ErrorMessage(msg) => Err(ConvexError::Function(msg)),\n\x20           \
-                     convex::FunctionResult::ConvexError(err) => Err(ConvexError::Server {{ message: err.message, data: \
-                     convex_value_to_json(&err.data) }}),\n\x20       }}\n"
-                )
-            }
-            None => {
-                format!(
-                    "        self.inner.clone().{sdk_call}(\"{function_path}\", args).await\n\x20           \
-                     .map_err(ConvexError::Transport)\n"
-                )
-            }
+    };
+
+    let args_body = args_conversion(func);
+
+    let return_type_str = get_return_type_str(func, ctx);
+
+    let typed_return_body = |sdk_call: &str| call_body(sdk_call, &function_path);
+    let timeout = default_timeout(func, ctx.config);
+    // With a default timeout the call runs in `{method}_with_timeout`
+    let call_method_body = |method_name: &str, sdk_call: &str| match timeout {
+        Some(_) => {
+            let args = if func.takes_args() { "args, " } else { "" };
+            let constant = timeout_const_name(method_name);
+            format!("        self.{method_name}_with_timeout({args}Self::{constant}).await\n")
         }
+        None => format!("{args_body}{}", typed_return_body(sdk_call)),
+    };
+    let timeout_doc = |method_name: &str| match timeout {
+        Some((_, text)) => format!(
+            "    /// Fails with `ConvexError::Timeout` after {text}; [`ConvexApiClient::{method_name}_with_timeout`] sets \
+             another deadline.\n"
+        ),
+        None => String::new(),
+    };
+    // The forwarding body calls the deprecated `_with_timeout` method
+    let allow_deprecated = if timeout.is_some() && deprecation_note(func, ctx.config).is_some() {
+        "    #[allow(deprecated)]\n"
+    } else {
+        ""
     };
 
+    let cfg = function_attrs(func, ctx, "    ");
+    let deprecated = deprecated_attr(func, ctx, "    ");
     let mut trait_code = String::new();
     let mut impl_code = String::new();
 
@@ -778,74 +3758,1234 @@ fn generate_trait_method(func: &ConvexFunction, ctx: &mut CodegenContext) -> (St
                 None => "Result<convex::QuerySubscription, ConvexError>".to_string(),
             };
             let sub_name = format!("subscribe_{file_snake}_{fn_snake}");
+            trait_code.push_str(&cfg);
+            trait_code.push_str(&deprecated);
+            // A query has no side effects, so a dropped result is a wasted call
+            trait_code.push_str("    #[must_use]\n");
             trait_code.push_str(&format!(
                 "    fn {sub_name}(&self{args_param}) -> impl std::future::Future<Output = {sub_return}> + Send;\n"
             ));
-            impl_code.push_str(&format!("    async fn {sub_name}(&self{args_param}) -> {sub_return} {{\n"));
-            impl_code.push_str(&args_body);
-            if return_type_str.is_some() {
-                impl_code.push_str(&format!(
-                    "        let sub = self.inner.clone().subscribe(\"{function_path}\", args).await\n\x20           \
-                     .map_err(ConvexError::Transport)?;\n\x20       Ok(TypedSubscription::new(sub))\n"
-                ));
+            let sub_body = if return_type_str.is_some() {
+                format!(
+                    "{args_body}        let token = QueryToken::new(\"{function_path}\", &args);\n\x20       let sub = \
+                     self.inner.clone().subscribe(\"{function_path}\", args).await\n\x20           \
+                     .map_err(ConvexError::Transport)?;\n\x20       \
+                     Ok(TypedSubscription::new(sub).with_query_token(token).with_function(\"{function_path}\"))\n"
+                )
             } else {
-                impl_code.push_str(&format!(
-                    "        self.inner.clone().subscribe(\"{function_path}\", args).await\n\x20           \
+                format!(
+                    "{args_body}        self.inner.clone().subscribe(\"{function_path}\", args).await\n\x20           \
                      .map_err(ConvexError::Transport)\n"
-                ));
-            }
+                )
+            };
+            impl_code.push_str(&cfg);
+            impl_code.push_str(&format!("    async fn {sub_name}(&self{args_param}) -> {sub_return} {{\n"));
+            impl_code.push_str(&sub_body);
             impl_code.push_str("    }\n\n");
 
             // Query method
             let return_type = match &return_type_str {
                 Some(rt) => format!("Result<{}, ConvexError>", rt),
-                None => "Result<convex::FunctionResult, ConvexError>".to_string(),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
             };
-            let query_name = format!("query_{file_snake}_{fn_snake}");
+            let query_name = call_method_name(func, ctx.config.method_naming);
+            trait_code.push_str(&timeout_doc(&query_name));
+            trait_code.push_str(&cfg);
+            trait_code.push_str(&deprecated);
+            trait_code.push_str("    #[must_use]\n");
             trait_code.push_str(&format!(
                 "    fn {query_name}(&self{args_param}) -> impl std::future::Future<Output = {return_type}> + Send;\n"
             ));
+            impl_code.push_str(&cfg);
+            impl_code.push_str(allow_deprecated);
             impl_code.push_str(&format!("    async fn {query_name}(&self{args_param}) -> {return_type} {{\n"));
-            impl_code.push_str(&args_body);
-            impl_code.push_str(&typed_return_body("query"));
+            impl_code.push_str(&call_method_body(&query_name, "query"));
             impl_code.push_str("    }\n\n");
+
+            // Borrowed-args variants, so one args struct serves a query and a
+            // subscription without a clone
+            if func.takes_args() {
+                let ref_param = format!(", args: &{}", args_struct_name(func));
+                let query_body = format!("{args_body}{}", typed_return_body("query"));
+                let query_body = match timeout {
+                    Some(_) => deadline_body(&query_body, &format!("Self::{}", timeout_const_name(&query_name))),
+                    None => query_body,
+                };
+                let variants = [(&sub_name, &sub_return, &sub_body), (&query_name, &return_type, &query_body)];
+                for (name, return_type, body) in variants {
+                    trait_code.push_str(&format!(
+                        "    /// `{name}` with borrowed args, which are left for the caller to reuse.\n"
+                    ));
+                    trait_code.push_str(&cfg);
+                    trait_code.push_str(&deprecated);
+                    trait_code.push_str("    #[must_use]\n");
+                    trait_code.push_str(&format!(
+                        "    fn {name}_ref(&self{ref_param}) -> impl std::future::Future<Output = {return_type}> + Send;\n"
+                    ));
+                    impl_code.push_str(&cfg);
+                    impl_code.push_str(&format!("    async fn {name}_ref(&self{ref_param}) -> {return_type} {{\n"));
+                    impl_code.push_str(body);
+                    impl_code.push_str("    }\n\n");
+                }
+            }
         }
         "mutation" => {
             let return_type = match &return_type_str {
                 Some(rt) => format!("Result<{}, ConvexError>", rt),
-                None => "Result<convex::FunctionResult, ConvexError>".to_string(),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
+            };
+            let method_name = call_method_name(func, ctx.config.method_naming);
+            trait_code.push_str(&timeout_doc(&method_name));
+            trait_code.push_str(&cfg);
+            trait_code.push_str(&deprecated);
+            trait_code.push_str(&format!(
+                "    fn {method_name}(&self{args_param}) -> impl std::future::Future<Output = {return_type}> + Send;\n"
+            ));
+            trait_code.push_str(&legacy_method_alias(func, &method_name, &args_param, &return_type, ctx));
+            impl_code.push_str(&cfg);
+            impl_code.push_str(allow_deprecated);
+            impl_code.push_str(&format!(
+                "    async fn {method_name}(&self{args_param}) -> {return_type} {{\n"
+            ));
+            impl_code.push_str(&call_method_body(&method_name, "mutation"));
+            impl_code.push_str("    }\n\n");
+        }
+        "action" => {
+            let return_type = match &return_type_str {
+                Some(rt) => format!("Result<{}, ConvexError>", rt),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
             };
-            let method_name = format!("{file_snake}_{fn_snake}");
+            let method_name = call_method_name(func, ctx.config.method_naming);
+            trait_code.push_str(&timeout_doc(&method_name));
+            trait_code.push_str(&cfg);
+            trait_code.push_str(&deprecated);
             trait_code.push_str(&format!(
                 "    fn {method_name}(&self{args_param}) -> impl std::future::Future<Output = {return_type}> + Send;\n"
             ));
+            trait_code.push_str(&legacy_method_alias(func, &method_name, &args_param, &return_type, ctx));
+            impl_code.push_str(&cfg);
+            impl_code.push_str(allow_deprecated);
             impl_code.push_str(&format!(
                 "    async fn {method_name}(&self{args_param}) -> {return_type} {{\n"
             ));
-            impl_code.push_str(&args_body);
-            impl_code.push_str(&typed_return_body("mutation"));
+            impl_code.push_str(&call_method_body(&method_name, "action"));
             impl_code.push_str("    }\n\n");
         }
-        "action" => {
+        _ => {}
+    }
+
+    (trait_code, impl_code)
+}
+
+/// Method body lines that make the SDK call to `function_path` with `args` and
+/// decode the result into the method's return type, `serde_json::Value` when
+/// the function has no `returns`. Error results become the same `ConvexError`
+/// variants either way.
+fn call_body(sdk_call: &str, function_path: &str) -> String
+{
+    format!(
+        "        let result = self.inner.clone().{sdk_call}(\"{function_path}\", args).await\n\x20           \
+         .map_err(ConvexError::Transport)?;\n\x20       decode_function_result(result, Some(\"{function_path}\"))\n"
+    )
+}
+
+/// Method body lines that turn the `args` struct into the `convex::Value` map
+/// the SDK sends, bound to `args`.
+fn args_conversion(func: &ConvexFunction) -> String
+{
+    // Each arg is encoded by its validator, e.g. `v.number()` as Float64 even when whole
+    if let Some(args) = func.whole_args() {
+        let shape = value_shape_expr(args);
+        format!(
+            "        let json_args: std::collections::BTreeMap<String, serde_json::Value> = args.into();\n\x20       let \
+             args = match json_to_convex_value_as(serde_json::Value::Object(json_args.into_iter().collect()), &{shape}) \
+             {{\n\x20           convex::Value::Object(args) => args,\n\x20           _ => unreachable!(\"args structs \
+             serialize to a JSON object\"),\n\x20       }};\n"
+        )
+    } else if !func.params.is_empty() {
+        let shapes = field_shapes_expr(func.params.iter().map(|param| (&param.name, &param.data_type)));
+        format!(
+            "        let json_args: std::collections::BTreeMap<String, serde_json::Value> = args.into();\n\x20       let \
+             args = json_to_convex_args(json_args, &[{shapes}]);\n"
+        )
+    } else {
+        "        let args = std::collections::BTreeMap::new();\n".to_string()
+    }
+}
+
+/// A provided `ConvexApi` method under the function's bare name that forwards
+/// to `method_name`, while [`MethodNaming::Prefixed`] keeps the old names.
+fn legacy_method_alias(
+    func: &ConvexFunction,
+    method_name: &str,
+    args_param: &str,
+    return_type: &str,
+    ctx: &CodegenContext,
+) -> String
+{
+    let Some(legacy) = legacy_method_name(func, ctx.config.method_naming) else {
+        return String::new();
+    };
+    let args_call = if args_param.is_empty() { "" } else { "args" };
+    format!(
+        "{}    #[deprecated(note = \"renamed to `{method_name}`\")]\n\x20   fn {legacy}(&self{args_param}) -> impl \
+         std::future::Future<Output = {return_type}> + Send {{\n\x20       self.{method_name}({args_call})\n\x20   }}\n",
+        function_attrs(func, ctx, "    ")
+    )
+}
+
+/// `TryFrom<convex::FunctionResult>` for every typed return, so a result from
+/// the raw `ConvexClient` decodes exactly like the `ConvexApi` methods' results.
+///
+/// Table documents get the impl directly. Other return types (`Vec<_>`,
+/// `Option<_>`, `String`, ...) aren't local to the generated code, so each
+/// function gets a `{File}{Fn}Output` newtype to carry it.
+fn generate_function_result_conversions(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let table_structs: HashSet<String> = ctx.tables.iter().map(|table| table_struct_name(&table.name)).collect();
+    let mut converted = HashSet::new();
+    let mut code = String::new();
+
+    for func in functions {
+        let Some(return_type) = get_return_type_str(func, ctx) else {
+            continue;
+        };
+        let cfg = function_attrs(func, ctx, "");
+        // Several functions can return one table's documents
+        let (target, function) = if table_structs.contains(&return_type) {
+            if !converted.insert(return_type.clone()) {
+                continue;
+            }
+            (return_type, "None".to_string())
+        } else {
+            let module = func.module_path.as_deref().unwrap_or(&func.file_name);
+            let output = output_struct_name(func);
+            code.push_str(&format!(
+                "/// The decoded result of `{module}:{}`, for converting what the raw\n/// `ConvexClient` returns: \
+                 `{output}::try_from(result)?.0`.\n",
+                func.name
+            ));
+            code.push_str(&cfg);
+            code.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+            code.push_str(&format!("pub struct {output}(pub {return_type});\n\n"));
+            (output, format!("Some({:?})", function_path(func)))
+        };
+        code.push_str(&cfg);
+        code.push_str(&format!(
+            "impl TryFrom<convex::FunctionResult> for {target} {{\n\x20   type Error = ConvexError;\n\n\x20   fn \
+             try_from(result: convex::FunctionResult) -> Result<Self, Self::Error> {{\n\x20       \
+             decode_function_result(result, {function}){}\n\x20   }}\n}}\n\n",
+            if table_structs.contains(&target) { "" } else { ".map(Self)" }
+        ));
+    }
+
+    code
+}
+
+/// Generate `query_{table}_by_{column}` on `ConvexApiClient` for each index whose
+/// first field is a string-literal enum column.
+///
+/// The helper calls the query in the table's file (e.g. `games.ts`) that takes
+/// that column as its only required arg, so the enum can't be swapped for an
+/// arbitrary string. Indexes without such a query get no helper.
+fn generate_index_query_helpers(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let naming = ctx.config.method_naming;
+    let taken: HashSet<String> = functions
+        .iter()
+        .flat_map(|func| std::iter::once(call_method_name(func, naming)).chain(legacy_method_name(func, naming)))
+        .collect();
+    let mut helpers = String::new();
+    let mut emitted = HashSet::new();
+
+    for table in ctx.tables {
+        for index in &table.indexes {
+            let Some(column) = index
+                .fields
+                .first()
+                .and_then(|field| table.columns.iter().find(|c| &c.name == field))
+            else {
+                continue;
+            };
+            let data_type = optional_inner(&column.data_type).unwrap_or(&column.data_type);
+            if string_literal_values(data_type).is_none() {
+                continue;
+            }
+            let enum_name = format!(
+                "{}{}",
+                capitalize_first_letter(&table.name),
+                capitalize_first_letter(&column.name)
+            );
+            // Args share the table's enum only when it was the first type of that shape
+            if ctx.interned.get(&data_type.to_string()) != Some(&enum_name) {
+                continue;
+            }
+            let method_name = format!("query_{}_by_{}", to_snake_case(&table.name), to_snake_case(&column.name));
+            if taken.contains(&method_name) || emitted.contains(&method_name) {
+                continue;
+            }
+            let query = functions.iter().find(|func| {
+                func.type_ == "query"
+                    && func.file_name == table.name
+                    && func
+                        .params
+                        .iter()
+                        .any(|param| param.name == column.name && param.data_type == *data_type)
+                    && func
+                        .params
+                        .iter()
+                        .all(|param| param.name == column.name || is_optional_param(param))
+            });
+            let Some(query) = query else {
+                continue;
+            };
+
+            let return_type = match get_return_type_str(query, ctx) {
+                Some(rt) => format!("Result<{rt}, ConvexError>"),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
+            };
+            let arg = escape_rust_keyword(&to_snake_case(&column.name));
+            let fields: Vec<String> = query
+                .params
+                .iter()
+                .map(|param| {
+                    let field = escape_rust_keyword(&param.name);
+                    if param.name != column.name {
+                        format!("{field}: None")
+                    } else if field == arg {
+                        field
+                    } else {
+                        format!("{field}: {arg}")
+                    }
+                })
+                .collect();
+            let module = query.module_path.as_deref().unwrap_or(&query.file_name);
+
+            helpers.push_str(&format!(
+                "    /// Run `{module}:{}` for one `{}` value, typed as `{enum_name}`.\n",
+                query.name, column.name
+            ));
+            helpers.push_str(&function_attrs(query, ctx, "    "));
+            helpers.push_str(&deprecated_attr(query, ctx, "    "));
+            helpers.push_str(&format!(
+                "    pub async fn {method_name}(&self, {arg}: {enum_name}) -> {return_type} {{\n"
+            ));
+            helpers.push_str(&format!(
+                "        self.{}({} {{ {} }}).await\n",
+                call_method_name(query, ctx.config.method_naming),
+                args_struct_name(query),
+                fields.join(", ")
+            ));
+            helpers.push_str("    }\n\n");
+            emitted.insert(method_name);
+        }
+    }
+
+    if helpers.is_empty() {
+        return String::new();
+    }
+    format!("impl ConvexApiClient {{\n{helpers}}}\n\n")
+}
+
+/// The default timeout of a function's call method from
+/// [`Configuration::function_timeouts`], with the text it was written as.
+/// Invalid values are reported as warnings and ignored here.
+fn default_timeout<'a>(func: &ConvexFunction, config: &'a Configuration) -> Option<(std::time::Duration, &'a str)>
+{
+    let text = config.function_timeouts.get(&function_path(func))?;
+    parse_duration(text).map(|timeout| (timeout, text.as_str()))
+}
+
+/// Name of the `ConvexApiClient` const holding a method's default timeout
+/// (e.g. `QUERY_GAMES_GET_GAME_TIMEOUT`).
+fn timeout_const_name(method_name: &str) -> String
+{
+    format!("{}_TIMEOUT", method_name.to_uppercase())
+}
+
+/// Generate, for every function with a default timeout, a const holding it
+/// and the `{method}_with_timeout` method that makes the call, which the
+/// `ConvexApi` method runs with the default.
+///
+/// The generated code uses `tokio::time::timeout`, so the consuming crate needs
+/// `tokio` with the `time` feature.
+fn generate_default_timeout_methods(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut methods = String::new();
+    for func in functions {
+        let Some((timeout, text)) = default_timeout(func, ctx.config) else {
+            continue;
+        };
+        let sdk_call = match func.type_.as_str() {
+            "query" | "mutation" | "action" => func.type_.as_str(),
+            _ => continue,
+        };
+        let args_param = if func.takes_args() {
+            format!("args: {}, ", args_struct_name(func))
+        } else {
+            String::new()
+        };
+        let return_type_str = get_return_type_str(func, ctx);
+        let return_type = match &return_type_str {
+            Some(rt) => format!("Result<{}, ConvexError>", rt),
+            None => "Result<serde_json::Value, ConvexError>".to_string(),
+        };
+        let method_name = call_method_name(func, ctx.config.method_naming);
+        let constant = timeout_const_name(&method_name);
+        let call = format!(
+            "{}{}",
+            args_conversion(func),
+            call_body(sdk_call, &function_path(func))
+        );
+
+        if !methods.is_empty() {
+            methods.push('\n');
+        }
+        let cfg = function_attrs(func, ctx, "    ");
+        methods.push_str(&format!("    /// The default timeout of `{method_name}`: {text}.\n"));
+        methods.push_str(&cfg);
+        methods.push_str(&format!(
+            "    pub const {constant}: std::time::Duration = std::time::Duration::from_millis({});\n\n",
+            timeout.as_millis()
+        ));
+        methods.push_str(&format!(
+            "    /// `{method_name}` with `timeout` in place of its default of {text}.\n"
+        ));
+        methods.push_str(&cfg);
+        methods.push_str(&deprecated_attr(func, ctx, "    "));
+        methods.push_str(&format!(
+            "    pub async fn {method_name}_with_timeout(&self, {args_param}timeout: std::time::Duration) -> {return_type} \
+             {{\n{}\x20   }}\n",
+            deadline_body(&call, "timeout")
+        ));
+    }
+
+    if methods.is_empty() {
+        return String::new();
+    }
+    format!("impl ConvexApiClient {{\n{methods}}}\n\n")
+}
+
+/// Method body lines that run `call` (method body lines) in an `async move`
+/// block that fails with `ConvexError::Timeout` after `timeout`, an expression.
+fn deadline_body(call: &str, timeout: &str) -> String
+{
+    let call: String = call.lines().map(|line| format!("    {line}\n")).collect();
+    format!(
+        "        let call = async move {{\n{call}\x20       }};\n\x20       match tokio::time::timeout({timeout}, \
+         call).await {{\n\x20           Ok(result) => result,\n\x20           Err(_) => \
+         Err(ConvexError::Timeout({timeout})),\n\x20       }}\n"
+    )
+}
+
+/// Generate the `CallPriority` enum, a `{METHOD}_PRIORITY` const on
+/// `ConvexApiClient` for every function with a priority in
+/// [`Configuration::function_priorities`], and `priority_of` to look one up by
+/// path. Nothing is emitted when no function has a priority.
+fn generate_call_priorities(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut consts = String::new();
+    let mut arms = String::new();
+    for func in functions {
+        if !matches!(func.type_.as_str(), "query" | "mutation" | "action") {
+            continue;
+        }
+        let Some(text) = ctx.config.function_priorities.get(&function_path(func)) else {
+            continue;
+        };
+        let Some(variant) = parse_priority(text) else {
+            continue;
+        };
+        let method_name = call_method_name(func, ctx.config.method_naming);
+        let cfg = function_attrs(func, ctx, "    ");
+        consts.push_str(&format!("    /// The priority of `{method_name}`: {text}.\n"));
+        consts.push_str(&cfg);
+        consts.push_str(&format!(
+            "    pub const {}_PRIORITY: CallPriority = CallPriority::{variant};\n\n",
+            method_name.to_uppercase()
+        ));
+        arms.push_str(&function_attrs(func, ctx, "            "));
+        arms.push_str(&format!(
+            "            {:?} => CallPriority::{variant},\n",
+            function_path(func)
+        ));
+    }
+
+    if arms.is_empty() {
+        return String::new();
+    }
+    format!(
+        "/// How urgent a call is, from `typegen:priority` comments and\n/// `function_priorities`. The client sends \
+         every call the same way; this is for\n/// the application's own scheduling, e.g. which calls to hold back \
+         under load.\n#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]\npub enum \
+         CallPriority {{\n\x20   Low,\n\x20   #[default]\n\x20   Normal,\n\x20   High,\n}}\n\nimpl ConvexApiClient \
+         {{\n{consts}\x20   /// The priority of a function by its path, e.g. `\"search:rebuildIndex\"`, or\n\x20   \
+         /// `Normal` for functions without one.\n\x20   pub fn priority_of(path: &str) -> CallPriority {{\n\x20       \
+         match path {{\n{arms}\x20           _ => CallPriority::Normal,\n\x20       }}\n\x20   }}\n}}\n\n"
+    )
+}
+
+/// Generate a `{method}_with_timeout` variant on `ConvexApiClient` for every
+/// `ConvexApi` method, which fails with `ConvexError::Timeout` once the
+/// deadline passes.
+///
+/// The generated code uses `tokio::time::timeout`, so the consuming crate needs
+/// `tokio` with the `time` feature.
+fn generate_timeout_methods(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut methods = String::new();
+    for func in functions {
+        let (args_param, args_call) = if !func.takes_args() {
+            (String::new(), "")
+        } else {
+            (format!("args: {}, ", args_struct_name(func)), "args")
+        };
+        let return_type_str = get_return_type_str(func, ctx);
+        let mut variants = Vec::new();
+        if func.type_ == "query" {
+            let sub_return = match &return_type_str {
+                Some(rt) => format!("Result<TypedSubscription<{}>, ConvexError>", rt),
+                None => "Result<convex::QuerySubscription, ConvexError>".to_string(),
+            };
+            let sub_name = format!("subscribe_{}_{}", to_snake_case(&func.file_name), to_snake_case(&func.name));
+            variants.push((sub_name, sub_return));
+        }
+        // Functions with a default timeout already have it
+        if matches!(func.type_.as_str(), "query" | "mutation" | "action") && default_timeout(func, ctx.config).is_none() {
+            let return_type = match &return_type_str {
+                Some(rt) => format!("Result<{}, ConvexError>", rt),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
+            };
+            variants.push((call_method_name(func, ctx.config.method_naming), return_type));
+        }
+        for (method_name, return_type) in variants {
+            if !methods.is_empty() {
+                methods.push('\n');
+            }
+            methods.push_str(&function_attrs(func, ctx, "    "));
+            methods.push_str(&deprecated_attr(func, ctx, "    "));
+            methods.push_str(&format!(
+                "    pub async fn {method_name}_with_timeout(&self, {args_param}timeout: std::time::Duration) -> \
+                 {return_type} {{\n\x20       match tokio::time::timeout(timeout, self.{method_name}({args_call})).await \
+                 {{\n\x20           Ok(result) => result,\n\x20           Err(_) => \
+                 Err(ConvexError::Timeout(timeout)),\n\x20       }}\n\x20   }}\n"
+            ));
+        }
+    }
+
+    if methods.is_empty() {
+        return String::new();
+    }
+    format!("impl ConvexApiClient {{\n{methods}}}\n\n")
+}
+
+/// Generate `ConsistentSession`, which reads several queries from one server
+/// state, with a `query_*` method per query in `functions`.
+///
+/// The Convex client can't run a query at a chosen timestamp, but every update
+/// of its query set (`watch_all`) holds the results of all subscribed queries
+/// as of one server transition. A session subscribes its queries and reads
+/// them from the newest update that has them all. A query the client already
+/// subscribed has its result at hand and may see no new update, so its cached
+/// result counts as long as no update arrived since it was added.
+fn generate_consistent_session(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut methods = String::new();
+    for func in functions.iter().filter(|func| func.type_ == "query") {
+        let method_name = call_method_name(func, ctx.config.method_naming);
+        let args_param = if func.takes_args() {
+            format!(", args: {}", args_struct_name(func))
+        } else {
+            String::new()
+        };
+        let result_type = get_return_type_str(func, ctx).unwrap_or_else(|| "convex::FunctionResult".to_string());
+        methods.push('\n');
+        methods.push_str(&function_attrs(func, ctx, "    "));
+        methods.push_str(&deprecated_attr(func, ctx, "    "));
+        methods.push_str(&format!(
+            "    pub async fn {method_name}(&mut self{args_param}) -> Result<ConsistentQuery<{result_type}>, ConvexError> \
+             {{\n"
+        ));
+        methods.push_str(&args_conversion(func));
+        methods.push_str(&format!("        self.add({:?}, args).await\n    }}\n", function_path(func)));
+    }
+
+    let allow = if ctx.config.feature_gates.is_some() {
+        "#[allow(dead_code)]\n"
+    } else {
+        ""
+    };
+    let mut code = format!(
+        r#"/// Reads several queries from one consistent state of the deployment.
+///
+/// Start one with `ConvexApiClient::consistent_session`, add queries with its
+/// `query_*` methods, then call `snapshot` for all their results as of the
+/// same server transition, so they can't disagree about the data. Queries
+/// stay subscribed until the session is dropped, and a later `snapshot` sees
+/// later data.
+pub struct ConsistentSession {{
+    client: convex::ConvexClient,
+    watch: convex::QuerySetSubscription,
+    /// The newest query set update.
+    latest: Option<convex::QueryResults>,
+    /// How many updates have arrived.
+    updates: u64,
+    queries: Vec<SessionQuery>,
+}}
+
+struct SessionQuery {{
+    subscription: convex::QuerySubscription,
+    /// The result the client already had when the query was added.
+    cached: Option<convex::FunctionResult>,
+    /// `ConsistentSession::updates` when the query was added. `cached` is
+    /// current until another update arrives.
+    added_at: u64,
+}}
+
+/// A query added to a `ConsistentSession`, whose result a
+/// `ConsistentSnapshot` holds.
+#[derive(Debug)]
+pub struct ConsistentQuery<T> {{
+    index: usize,
+    path: &'static str,
+    _phantom: std::marker::PhantomData<fn() -> T>,
+}}
+
+/// The results of a session's queries, all from one server transition.
+#[derive(Debug, Clone)]
+pub struct ConsistentSnapshot {{
+    results: Vec<convex::FunctionResult>,
+}}
+
+impl ConsistentSnapshot {{
+    /// The decoded result of `query`.
+    pub fn get<T: serde::de::DeserializeOwned>(&self, query: &ConsistentQuery<T>) -> Result<T, ConvexError> {{
+        decode_function_result(self.results[query.index].clone(), Some(query.path))
+    }}
+
+    /// The undecoded result of `query`, e.g. for a query without `returns`.
+    pub fn raw<T>(&self, query: &ConsistentQuery<T>) -> &convex::FunctionResult {{
+        &self.results[query.index]
+    }}
+}}
+
+impl ConvexApiClient {{
+    /// Start a `ConsistentSession` on this client's connection.
+    pub fn consistent_session(&self) -> ConsistentSession {{
+        ConsistentSession {{
+            client: self.inner.clone(),
+            watch: self.inner.watch_all(),
+            latest: None,
+            updates: 0,
+            queries: Vec::new(),
+        }}
+    }}
+}}
+
+impl ConsistentSession {{
+    /// Wait until every query has a result, then return them all as of one
+    /// server transition.
+    pub async fn snapshot(&mut self) -> Result<ConsistentSnapshot, ConvexError> {{
+        self.catch_up().await?;
+        loop {{
+            let results: Option<Vec<convex::FunctionResult>> = self
+                .queries
+                .iter()
+                .map(|query| {{
+                    match self.latest.as_ref().and_then(|latest| latest.get(query.subscription.id())) {{
+                        Some(result) => Some(result.clone()),
+                        None if query.added_at == self.updates => query.cached.clone(),
+                        None => None,
+                    }}
+                }})
+                .collect();
+            if let Some(results) = results {{
+                return Ok(ConsistentSnapshot {{ results }});
+            }}
+            let update = std::future::poll_fn(|cx| futures_core::Stream::poll_next(std::pin::Pin::new(&mut self.watch), cx)).await;
+            self.record(update)?;
+        }}
+    }}
+
+    {allow}async fn add<T>(
+        &mut self,
+        path: &'static str,
+        args: std::collections::BTreeMap<String, convex::Value>,
+    ) -> Result<ConsistentQuery<T>, ConvexError> {{
+        // Updates from before the query was added can't hold it
+        self.catch_up().await?;
+        let mut subscription = self.client.subscribe(path, args).await.map_err(ConvexError::Transport)?;
+        let cached = match poll_now(&mut subscription).await {{
+            std::task::Poll::Ready(result) => result,
+            std::task::Poll::Pending => None,
+        }};
+        self.queries.push(SessionQuery {{ subscription, cached, added_at: self.updates }});
+        Ok(ConsistentQuery {{ index: self.queries.len() - 1, path, _phantom: std::marker::PhantomData }})
+    }}
+
+    /// Take every update that has already arrived.
+    async fn catch_up(&mut self) -> Result<(), ConvexError> {{
+        while let std::task::Poll::Ready(update) = poll_now(&mut self.watch).await {{
+            self.record(update)?;
+        }}
+        Ok(())
+    }}
+
+    fn record(&mut self, update: Option<convex::QueryResults>) -> Result<(), ConvexError> {{
+        let results = update.ok_or_else(|| ConvexError::Transport(anyhow::anyhow!("the Convex client shut down")))?;
+        self.latest = Some(results);
+        self.updates += 1;
+        Ok(())
+    }}
+"#
+    );
+    code.push_str(&methods);
+    code.push_str(
+        "}\n\n/// The next item of `stream` if it is ready, without waiting.\nasync fn poll_now<S: futures_core::Stream + \
+         Unpin>(stream: &mut S) -> std::task::Poll<Option<S::Item>> {\n\x20   std::future::poll_fn(|cx| \
+         std::task::Poll::Ready(futures_core::Stream::poll_next(std::pin::Pin::new(&mut *stream), cx))).await\n}\n\n",
+    );
+    code
+}
+
+/// Generate the `OfflineQueue`, and `QueuedMutation` for the args struct of
+/// every mutation in `functions`.
+///
+/// The queue file is JSONL with one `QueuedCall` per line. Args are kept as
+/// the struct serializes them and converted by the mutation's arg shapes when
+/// they are replayed, exactly like a direct call.
+fn generate_offline_queue(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut impls = String::new();
+    let mut shapes = String::new();
+    for func in functions {
+        let cfg = function_attrs(func, ctx, "");
+        let path = function_path(func);
+        impls.push_str(&cfg);
+        impls.push_str(&format!(
+            "impl QueuedMutation for {} {{\n\x20   const PATH: &'static str = Self::FUNCTION_PATH;\n}}\n\n",
+            args_struct_name(func)
+        ));
+        let fields = field_shapes_expr(func.params.iter().map(|param| (&param.name, &param.data_type)));
+        shapes.push_str(&function_attrs(func, ctx, "        "));
+        shapes.push_str(&format!("        {path:?} => &[{fields}],\n"));
+    }
+
+    let mut code = r#"/// The args of a public mutation, which an `OfflineQueue` can hold until the
+/// client is back online.
+pub trait QueuedMutation: serde::Serialize {
+    /// The mutation's path, e.g. `"messages:send"`.
+    const PATH: &'static str;
+}
+
+/// One mutation call waiting in an `OfflineQueue`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct QueuedCall {
+    /// The mutation's path, e.g. `"messages:send"`.
+    pub path: String,
+    /// The args, as the args struct serializes them.
+    pub args: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What `OfflineQueue::replay` does with a call the server rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    /// Drop the call and go on with the next one.
+    Skip,
+    /// Send the call again with these args.
+    Retry(serde_json::Map<String, serde_json::Value>),
+    /// Keep the call and every call after it queued, and stop replaying.
+    Stop,
+}
+
+/// Mutation calls made while the client is offline. They are kept in a JSONL
+/// file so they survive a restart, and `replay` sends them in order once the
+/// connection is back.
+#[derive(Debug)]
+pub struct OfflineQueue {
+    path: std::path::PathBuf,
+    calls: std::collections::VecDeque<QueuedCall>,
+}
+
+impl OfflineQueue {
+    /// Open the queue file at `path`, with the calls a previous run left in it.
+    /// The file is created on the first `push`.
+    pub fn open(path: impl Into<std::path::PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let calls = match std::fs::read_to_string(&path) {
+            Ok(text) => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| serde_json::from_str(line).map_err(std::io::Error::from))
+                .collect::<std::io::Result<_>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => std::collections::VecDeque::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, calls })
+    }
+
+    /// Queue a call. It is written to the file before this returns.
+    ///
+    /// Fails with `InvalidData` if the args don't serialize to a JSON object.
+    pub fn push<M: QueuedMutation>(&mut self, args: &M) -> std::io::Result<()> {
+        let args = match serde_json::to_value(args)? {
+            serde_json::Value::Object(map) => map,
+            other => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} args must serialize to a JSON object, got {other}", M::PATH),
+                ))
+            }
+        };
+        let call = QueuedCall { path: M::PATH.to_string(), args };
+        let mut line = serde_json::to_string(&call)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        std::io::Write::write_all(&mut file, line.as_bytes())?;
+        file.sync_data()?;
+        self.calls.push_back(call);
+        Ok(())
+    }
+
+    /// The queued calls, oldest first.
+    pub fn calls(&self) -> impl Iterator<Item = &QueuedCall> {
+        self.calls.iter()
+    }
+
+    /// How many calls are queued.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether no calls are queued.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Send the queued calls in order, removing each one once it is done, and
+    /// return how many the server accepted.
+    ///
+    /// A call the server rejects goes to `on_conflict`. A transport error, or
+    /// failing to rewrite the queue file, stops the replay with the current
+    /// call still queued, so the next replay starts with it.
+    ///
+    /// The file is rewritten after every call, so a crash mid-replay never sends
+    /// a finished call again. Replaying `n` calls therefore writes `O(n²)` bytes,
+    /// which is fine for what piles up while offline but slow for a backlog of
+    /// many thousands.
+    pub async fn replay(
+        &mut self,
+        api: &ConvexApiClient,
+        mut on_conflict: impl FnMut(&QueuedCall, &ConvexError) -> Conflict,
+    ) -> Result<usize, ConvexError> {
+        let mut accepted = 0;
+        while let Some(call) = self.calls.front_mut() {
+            let args = json_to_convex_args(call.args.clone(), queued_mutation_shapes(&call.path));
+            let rejected = match api.inner.clone().mutation(&call.path, args).await.map_err(ConvexError::Transport)? {
+                convex::FunctionResult::Value(_) => None,
+                convex::FunctionResult::ErrorMessage(msg) => Some(ConvexError::from_message(msg)),
+                convex::FunctionResult::ConvexError(err) => {
+                    Some(ConvexError::Server { message: err.message, data: convex_value_to_json(&err.data) })
+                }
+            };
+            match rejected.map(|error| on_conflict(call, &error)) {
+                None => {
+                    accepted += 1;
+                    self.calls.pop_front();
+                }
+                Some(Conflict::Skip) => {
+                    self.calls.pop_front();
+                }
+                Some(Conflict::Retry(args)) => call.args = args,
+                Some(Conflict::Stop) => break,
+            }
+            self.save().map_err(|e| ConvexError::Transport(e.into()))?;
+        }
+        Ok(accepted)
+    }
+
+    /// Rewrite the file with the calls still queued.
+    fn save(&self) -> std::io::Result<()> {
+        let mut text = String::new();
+        for call in &self.calls {
+            text.push_str(&serde_json::to_string(call)?);
+            text.push('\n');
+        }
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+"#
+    .to_string();
+    code.push_str(&impls);
+    code.push_str(&format!(
+        "/// The arg shapes of each queueable mutation, by path.\nfn queued_mutation_shapes(path: &str) -> &'static \
+         [(&'static str, ValueShape)] {{\n\x20   match path {{\n{shapes}\x20       _ => &[],\n\x20   }}\n}}\n\n"
+    ));
+    code
+}
+
+/// Generate `#[tauri::command]` wrappers for every public function.
+///
+/// Each command takes the managed `ConvexApiClient` state plus the typed args
+/// and flattens `ConvexError` into a string, which is what the webview sees.
+/// Untyped returns are converted to `serde_json::Value` so they serialize.
+fn generate_tauri_commands(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut code = String::new();
+    code.push_str("/// `#[tauri::command]` wrappers around `ConvexApi`.\n");
+    code.push_str("///\n");
+    code.push_str("/// Manage a `ConvexApiClient` on the app builder and register the commands with\n");
+    code.push_str("/// `tauri::generate_handler![tauri_commands::query_games_get_game, ...]`. Commands\n");
+    code.push_str("/// with arguments expect them under an `args` key: `invoke(\"...\", { args })`.\n");
+    code.push_str("pub mod tauri_commands {\n");
+    code.push_str("    #[allow(unused_imports)]\n    use super::*;\n\n");
+
+    for func in functions {
+        if !matches!(func.type_.as_str(), "query" | "mutation" | "action") {
+            continue;
+        }
+        let method_name = call_method_name(func, ctx.config.method_naming);
+        let (args_param, args_call) = if !func.takes_args() {
+            (String::new(), "")
+        } else {
+            (format!(", args: {}", args_struct_name(func)), "args")
+        };
+
+        code.push_str(&function_attrs(func, ctx, "    "));
+        code.push_str("    #[tauri::command]\n");
+        // Functions without `returns` give the result as JSON
+        let rt = get_return_type_str(func, ctx).unwrap_or_else(|| "serde_json::Value".to_string());
+        code.push_str(&format!(
+            "    pub async fn {method_name}(api: tauri::State<'_, ConvexApiClient>{args_param}) -> Result<{rt}, String> {{\n"
+        ));
+        code.push_str(&format!("        api.{method_name}({args_call}).await.map_err(|e| e.to_string())\n"));
+        code.push_str("    }\n\n");
+    }
+
+    code.push_str("}\n\n");
+    code
+}
+
+/// Generate `ConvexAdminClient`, a `ConvexApiClient` authenticated with an
+/// admin key, with typed calls to the `_system` queries admin tooling uses.
+fn generate_admin_client(tables: &[ConvexTable]) -> String
+{
+    let table_names = tables
+        .iter()
+        .map(|table| format!("{:?}", table.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut code = String::from(
+        r#"/// A [`ConvexApiClient`] authenticated with a deployment admin key, for tests
+/// and admin tooling.
+///
+/// It derefs to `ConvexApiClient`, so every `ConvexApi` method runs as an
+/// admin. Admin keys come from `npx convex deploy-key` or the dashboard's
+/// deployment settings; never hand one to end users.
+#[derive(Clone)]
+pub struct ConvexAdminClient {
+    api: ConvexApiClient,
+}
+
+impl ConvexAdminClient {
+    /// Connect to `deployment_url` and authenticate with `admin_key`.
+    pub async fn with_admin_key(deployment_url: &str, admin_key: impl Into<String>) -> Result<Self, ConvexError> {
+        let mut client = convex::ConvexClient::new(deployment_url).await.map_err(ConvexError::Transport)?;
+        client.set_admin_auth(admin_key.into(), None).await;
+        Ok(Self { api: ConvexApiClient::new(client) })
+    }
+
+    /// The authenticated API client.
+    pub fn api(&self) -> &ConvexApiClient {
+        &self.api
+    }
+
+    /// Number of documents in `T`'s table.
+    pub async fn table_size<T: ConvexDocument>(&self) -> Result<u64, ConvexError> {
+        self.system_table_size(T::TABLE).await
+    }
+
+    /// Number of documents in each table of the schema, keyed by table name.
+    ///
+    /// Fails when the deployment is unreachable or rejects the key, so it also
+    /// serves as a health check of the deployment and its schema.
+    pub async fn document_counts(&self) -> Result<std::collections::BTreeMap<&'static str, u64>, ConvexError> {
+        let mut counts = std::collections::BTreeMap::new();
+"#,
+    );
+    code.push_str(&format!("        for table in [{table_names}] {{\n"));
+    code.push_str(
+        r#"            counts.insert(table, self.system_table_size(table).await?);
+        }
+        Ok(counts)
+    }
+
+    async fn system_table_size(&self, table: &str) -> Result<u64, ConvexError> {
+        const PATH: &str = "_system/cli/tableSize:default";
+        let mut args = std::collections::BTreeMap::new();
+        args.insert("tableName".to_string(), convex::Value::String(table.to_string()));
+        match self.api.inner.clone().query(PATH, args).await.map_err(ConvexError::Transport)? {
+            convex::FunctionResult::Value(convex::Value::Float64(size)) => Ok(size as u64),
+            convex::FunctionResult::Value(convex::Value::Int64(size)) => Ok(size as u64),
+            convex::FunctionResult::Value(other) => Err(ConvexError::Deserialization {
+                function: Some(PATH.to_string()),
+                path: ".".to_string(),
+                source: serde::de::Error::custom(format!("expected a document count, got {other:?}")),
+            }),
+            convex::FunctionResult::ErrorMessage(msg) => Err(ConvexError::from_message(msg)),
+            convex::FunctionResult::ConvexError(err) => {
+                Err(ConvexError::Server { message: err.message, data: convex_value_to_json(&err.data) })
+            }
+        }
+    }
+}
+
+impl std::ops::Deref for ConvexAdminClient {
+    type Target = ConvexApiClient;
+
+    fn deref(&self) -> &ConvexApiClient {
+        &self.api
+    }
+}
+
+"#,
+    );
+    code
+}
+
+/// Generate `ConvexApiRouter<K>`, a cache of one `ConvexApiClient` per tenant
+/// with a forwarding method for every `ConvexApi` method.
+///
+/// The cache evicts the least recently used client once `capacity` tenants are
+/// connected. Connecting happens outside the lock, so a slow deployment doesn't
+/// hold up calls to other tenants.
+fn generate_tenant_router(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let mut methods = String::new();
+    for func in functions {
+        let (args_param, args_call) = if !func.takes_args() {
+            (String::new(), "")
+        } else {
+            (format!(", args: {}", args_struct_name(func)), "args")
+        };
+        let return_type_str = get_return_type_str(func, ctx);
+        let mut forwarded = Vec::new();
+        if func.type_ == "query" {
+            let sub_return = match &return_type_str {
+                Some(rt) => format!("Result<TypedSubscription<{}>, ConvexError>", rt),
+                None => "Result<convex::QuerySubscription, ConvexError>".to_string(),
+            };
+            let sub_name = format!("subscribe_{}_{}", to_snake_case(&func.file_name), to_snake_case(&func.name));
+            forwarded.push((sub_name, sub_return));
+        }
+        if matches!(func.type_.as_str(), "query" | "mutation" | "action") {
             let return_type = match &return_type_str {
                 Some(rt) => format!("Result<{}, ConvexError>", rt),
-                None => "Result<convex::FunctionResult, ConvexError>".to_string(),
+                None => "Result<serde_json::Value, ConvexError>".to_string(),
             };
-            let method_name = format!("{file_snake}_{fn_snake}");
-            trait_code.push_str(&format!(
-                "    fn {method_name}(&self{args_param}) -> impl std::future::Future<Output = {return_type}> + Send;\n"
-            ));
-            impl_code.push_str(&format!(
-                "    async fn {method_name}(&self{args_param}) -> {return_type} {{\n"
+            forwarded.push((call_method_name(func, ctx.config.method_naming), return_type));
+        }
+        for (method_name, return_type) in forwarded {
+            methods.push('\n');
+            methods.push_str(&function_attrs(func, ctx, "    "));
+            methods.push_str(&deprecated_attr(func, ctx, "    "));
+            methods.push_str(&format!(
+                "    pub async fn {method_name}(&self, tenant: &K{args_param}) -> {return_type} {{\n\x20       \
+                 self.client(tenant).await?.{method_name}({args_call}).await\n\x20   }}\n"
             ));
-            impl_code.push_str(&args_body);
-            impl_code.push_str(&typed_return_body("action"));
-            impl_code.push_str("    }\n\n");
         }
-        _ => {}
     }
 
-    (trait_code, impl_code)
+    let mut code = String::new();
+    code.push_str(
+        "/// Routes `ConvexApi` calls to one Convex deployment per tenant.\n\
+         ///\n\
+         /// Each method is the `ConvexApi` method with a leading `tenant` key. A\n\
+         /// tenant's client connects on first use; once `capacity` tenants are\n\
+         /// connected, the least recently used client is dropped to make room.\n\
+         /// Subscriptions already open on a dropped client keep running.\n\
+         pub struct ConvexApiRouter<K> {\n\
+         \x20   deployment_url: Box<dyn Fn(&K) -> String + Send + Sync>,\n\
+         \x20   capacity: usize,\n\
+         \x20   clients: tokio::sync::Mutex<TenantClients<K>>,\n\
+         }\n\
+         \n\
+         struct TenantClients<K> {\n\
+         \x20   /// Each connected client with the tick of its last use.\n\
+         \x20   clients: std::collections::HashMap<K, (ConvexApiClient, u64)>,\n\
+         \x20   tick: u64,\n\
+         }\n\
+         \n\
+         impl<K: std::hash::Hash + Eq> TenantClients<K> {\n\
+         \x20   fn touch(&mut self, tenant: &K) -> Option<ConvexApiClient> {\n\
+         \x20       self.tick += 1;\n\
+         \x20       let tick = self.tick;\n\
+         \x20       let (client, used) = self.clients.get_mut(tenant)?;\n\
+         \x20       *used = tick;\n\
+         \x20       Some(client.clone())\n\
+         \x20   }\n\
+         }\n\
+         \n\
+         impl<K: std::hash::Hash + Eq + Clone> ConvexApiRouter<K> {\n\
+         \x20   /// A router keeping at most `capacity` clients connected (at least one),\n\
+         \x20   /// with `deployment_url` giving each tenant's deployment URL.\n\
+         \x20   pub fn new(capacity: usize, deployment_url: impl Fn(&K) -> String + Send + Sync + 'static) -> Self {\n\
+         \x20       Self {\n\
+         \x20           deployment_url: Box::new(deployment_url),\n\
+         \x20           capacity: capacity.max(1),\n\
+         \x20           clients: tokio::sync::Mutex::new(TenantClients {\n\
+         \x20               clients: std::collections::HashMap::new(),\n\
+         \x20               tick: 0,\n\
+         \x20           }),\n\
+         \x20       }\n\
+         \x20   }\n\
+         \n\
+         \x20   /// The tenant's client, connecting to its deployment on first use.\n\
+         \x20   pub async fn client(&self, tenant: &K) -> Result<ConvexApiClient, ConvexError> {\n\
+         \x20       if let Some(client) = self.clients.lock().await.touch(tenant) {\n\
+         \x20           return Ok(client);\n\
+         \x20       }\n\
+         \x20       let url = (self.deployment_url)(tenant);\n\
+         \x20       let client = convex::ConvexClient::new(&url).await.map_err(ConvexError::Transport)?;\n\
+         \x20       let mut cache = self.clients.lock().await;\n\
+         \x20       // Another call may have connected the tenant in the meantime\n\
+         \x20       if let Some(existing) = cache.touch(tenant) {\n\
+         \x20           return Ok(existing);\n\
+         \x20       }\n\
+         \x20       if cache.clients.len() >= self.capacity {\n\
+         \x20           let oldest = cache.clients.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone());\n\
+         \x20           if let Some(oldest) = oldest {\n\
+         \x20               cache.clients.remove(&oldest);\n\
+         \x20           }\n\
+         \x20       }\n\
+         \x20       let client = ConvexApiClient::new(client);\n\
+         \x20       let tick = cache.tick;\n\
+         \x20       cache.clients.insert(tenant.clone(), (client.clone(), tick));\n\
+         \x20       Ok(client)\n\
+         \x20   }\n\
+         \n\
+         \x20   /// Drop the tenant's client, e.g. after its deployment moved. Returns\n\
+         \x20   /// whether it was connected.\n\
+         \x20   pub async fn evict(&self, tenant: &K) -> bool {\n\
+         \x20       self.clients.lock().await.clients.remove(tenant).is_some()\n\
+         \x20   }\n",
+    );
+    code.push_str(&methods);
+    code.push_str("}\n\n");
+    code
+}
+
+/// Generate `convex_router()` plus one Axum handler per public function.
+///
+/// Routes are `POST /api/{module}/{function}` with the args struct as the JSON
+/// body. Errors go through `ConvexHttpError`, which picks a status code from the
+/// `ConvexError` variant.
+fn generate_axum_router(functions: &[&ConvexFunction], ctx: &mut CodegenContext) -> String
+{
+    let gated = ctx.config.feature_gates.is_some();
+    let mut routes = String::new();
+    let mut handlers = String::new();
+
+    for func in functions {
+        if !matches!(func.type_.as_str(), "query" | "mutation" | "action") {
+            continue;
+        }
+        let method_name = call_method_name(func, ctx.config.method_naming);
+        let module = func.module_path.as_deref().unwrap_or(&func.file_name);
+        let route = format!(
+            ".route(\"/api/{module}/{}\", axum::routing::post(axum_handlers::{method_name}))",
+            func.name
+        );
+        if gated {
+            // Method-chain calls can't carry `#[cfg]`, so gated routes rebind `router`
+            routes.push_str(&function_attrs(func, ctx, "    "));
+            routes.push_str(&format!("    let router = router{route};\n"));
+        } else {
+            routes.push_str(&format!("        {route}\n"));
+        }
+
+        let (args_param, args_call) = if !func.takes_args() {
+            (String::new(), "")
+        } else {
+            (
+                format!(",\n        axum::Json(args): axum::Json<{}>", args_struct_name(func)),
+                "args",
+            )
+        };
+        let state_param = "axum::extract::State(api): axum::extract::State<ConvexApiClient>";
+        handlers.push_str(&function_attrs(func, ctx, "    "));
+
+        // Functions without `returns` give the result as JSON
+        let rt = get_return_type_str(func, ctx).unwrap_or_else(|| "serde_json::Value".to_string());
+        handlers.push_str(&format!(
+            "    pub async fn {method_name}(\n        {state_param}{args_param},\n    ) -> Result<axum::Json<{rt}>, \
+             ConvexHttpError> {{\n"
+        ));
+        handlers.push_str(&format!(
+            "        api.{method_name}({args_call}).await.map(axum::Json).map_err(ConvexHttpError)\n"
+        ));
+        handlers.push_str("    }\n\n");
+    }
+
+    let mut code = String::new();
+
+    code.push_str(
+        "/// `ConvexError` as an Axum response.\n\
+         ///\n\
+         /// Transport failures map to `502 Bad Gateway`, timeouts to `504 Gateway\n\
+         /// Timeout`, rejected args to `400 Bad Request`, errors thrown by the Convex\n\
+         /// function to `422 Unprocessable Entity`, and anything else to `500`. The body\n\
+         /// is `{ \"error\": message }`, plus `data` for `ConvexError::Server` and `path`\n\
+         /// for `ConvexError::ArgumentValidation`.\n\
+         #[derive(Debug)]\n\
+         pub struct ConvexHttpError(pub ConvexError);\n\
+         \n\
+         impl axum::response::IntoResponse for ConvexHttpError {\n\
+         \x20   fn into_response(self) -> axum::response::Response {\n\
+         \x20       let status = match &self.0 {\n\
+         \x20           ConvexError::Transport(_) => axum::http::StatusCode::BAD_GATEWAY,\n\
+         \x20           ConvexError::Timeout(_) => axum::http::StatusCode::GATEWAY_TIMEOUT,\n\
+         \x20           ConvexError::ArgumentValidation { .. } => axum::http::StatusCode::BAD_REQUEST,\n\
+         \x20           ConvexError::Function(_) | ConvexError::Server { .. } => axum::http::StatusCode::UNPROCESSABLE_ENTITY,\n\
+         \x20           _ => axum::http::StatusCode::INTERNAL_SERVER_ERROR,\n\
+         \x20       };\n\
+         \x20       let body = match self.0 {\n\
+         \x20           ConvexError::Server { message, data } => serde_json::json!({ \"error\": message, \"data\": data }),\n\
+         \x20           ConvexError::ArgumentValidation { message, path } => serde_json::json!({ \"error\": message, \"path\": path }),\n\
+         \x20           other => serde_json::json!({ \"error\": other.to_string() }),\n\
+         \x20       };\n\
+         \x20       (status, axum::Json(body)).into_response()\n\
+         \x20   }\n\
+         }\n\n",
+    );
+
+    code.push_str("/// Axum router exposing every public Convex function as `POST /api/{module}/{function}`.\n");
+    code.push_str("///\n");
+    code.push_str("/// Request bodies are the JSON form of the function's args struct; responses are\n");
+    code.push_str("/// the JSON-serialized return value.\n");
+    code.push_str("pub fn convex_router(client: ConvexApiClient) -> axum::Router {\n");
+    if gated {
+        code.push_str("    let router = axum::Router::<ConvexApiClient>::new();\n");
+        code.push_str(&routes);
+        code.push_str("    router.with_state(client)\n");
+    } else {
+        code.push_str("    axum::Router::new()\n");
+        code.push_str(&routes);
+        code.push_str("        .with_state(client)\n");
+    }
+    code.push_str("}\n\n");
+
+    code.push_str("/// Handlers behind `convex_router()`.\n");
+    code.push_str("pub mod axum_handlers {\n");
+    code.push_str("    #[allow(unused_imports)]\n    use super::*;\n\n");
+    code.push_str(&handlers);
+    code.push_str("}\n\n");
+
+    code
 }
 
 // =============================================================================
@@ -855,20 +4995,81 @@ fn generate_trait_method(func: &ConvexFunction, ctx: &mut CodegenContext) -> (St
 /// Generate the ConvexError enum in the output.
 fn generate_convex_error_type() -> String
 {
-    "/// Error type for typed Convex API calls.\n#[derive(Debug)]\npub enum ConvexError {\n\x20   /// Transport/connection \
-     error from the Convex SDK.\n\x20   Transport(anyhow::Error),\n\x20   /// The Convex function returned an error message \
-     (thrown string).\n\x20   Function(String),\n\x20   /// The Convex function returned a ConvexError (thrown ConvexError \
-     object).\n\x20   Server { message: String, data: serde_json::Value },\n\x20   /// Failed to deserialize the return \
-     value into the expected Rust type.\n\x20   Deserialization(serde_json::Error),\n}\n\nimpl std::fmt::Display for \
-     ConvexError {\n\x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n\x20       match self \
-     {\n\x20           ConvexError::Transport(e) => write!(f, \"transport error: {e}\"),\n\x20           \
-     ConvexError::Function(msg) => write!(f, \"function error: {msg}\"),\n\x20           ConvexError::Server { message, .. \
-     } => write!(f, \"{message}\"),\n\x20           ConvexError::Deserialization(e) => write!(f, \"deserialization error: \
-     {e}\"),\n\x20       }\n\x20   }\n}\n\nimpl std::error::Error for ConvexError {\n\x20   fn source(&self) -> \
-     Option<&(dyn std::error::Error + 'static)> {\n\x20       match self {\n\x20           ConvexError::Transport(e) => \
-     Some(e.as_ref()),\n\x20           ConvexError::Deserialization(e) => Some(e),\n\x20           _ => None,\n\x20       \
-     }\n\x20   }\n}\n\n"
-        .to_string()
+    r#"/// Error type for typed Convex API calls.
+///
+/// `Transport` and `Timeout` are worth retrying; the other variants fail the
+/// same way every time until the caller, the function, or the schema changes.
+#[derive(Debug)]
+pub enum ConvexError {
+    /// Transport/connection error from the Convex SDK.
+    Transport(anyhow::Error),
+    /// The Convex function returned an error message (thrown string).
+    Function(String),
+    /// The server rejected the args: they don't match the function's
+    /// validator. `path` is where in the args, e.g. `.settings.theme`, when the
+    /// server says.
+    ArgumentValidation { message: String, path: Option<String> },
+    /// The Convex function returned a ConvexError (thrown ConvexError object).
+    Server { message: String, data: serde_json::Value },
+    /// Failed to deserialize the return value into the expected Rust type.
+    /// `function` is the Convex path, e.g. `games:get`, when it is known, and
+    /// `path` is where in the value it failed, e.g. `players[2].score`.
+    Deserialization { function: Option<String>, path: String, source: serde_json::Error },
+    /// The call did not finish within its deadline.
+    Timeout(std::time::Duration),
+}
+
+impl ConvexError {
+    /// Classify an error message from `FunctionResult::ErrorMessage`.
+    pub fn from_message(message: String) -> Self {
+        let Some((_, rest)) = message.split_once("ArgumentValidationError: ") else {
+            return ConvexError::Function(message);
+        };
+        let path = rest
+            .lines()
+            .find_map(|line| line.strip_prefix("Path: "))
+            .map(|path| path.trim().to_string());
+        let message = rest.lines().next().unwrap_or_default().to_string();
+        ConvexError::ArgumentValidation { message, path }
+    }
+
+    /// Whether the same call may succeed if made again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ConvexError::Transport(_) | ConvexError::Timeout(_))
+    }
+}
+
+impl std::fmt::Display for ConvexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvexError::Transport(e) => write!(f, "transport error: {e}"),
+            ConvexError::Function(msg) => write!(f, "function error: {msg}"),
+            ConvexError::ArgumentValidation { message, path: Some(path) } => write!(f, "invalid args at {path}: {message}"),
+            ConvexError::ArgumentValidation { message, path: None } => write!(f, "invalid args: {message}"),
+            ConvexError::Server { message, .. } => write!(f, "{message}"),
+            ConvexError::Deserialization { function: Some(function), path, source } => {
+                write!(f, "deserialization error in the result of {function} at {path}: {source}")
+            }
+            ConvexError::Deserialization { function: None, path, source } => {
+                write!(f, "deserialization error at {path}: {source}")
+            }
+            ConvexError::Timeout(after) => write!(f, "timed out after {after:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ConvexError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvexError::Transport(e) => Some(e.as_ref()),
+            ConvexError::Deserialization { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+"#
+    .to_string()
 }
 
 /// Generate the json_to_convex_value helper function in the output.
@@ -899,6 +5100,153 @@ fn generate_json_to_convex_value_helper() -> String
         .to_string()
 }
 
+/// Generate `ValueShape` and the helpers that convert serialized args to
+/// `convex::Value`s by their validators rather than by what the JSON looks like.
+///
+/// Without the shape, a whole `v.number()` could go out as `Int64` and
+/// `v.bytes()` as an array of numbers.
+fn generate_value_shape_helpers(gated: bool) -> String
+{
+    let allow = if gated { "#[allow(dead_code)]\n" } else { "" };
+    format!(
+        r#"/// How an arg's validator encodes its value for Convex.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+enum ValueShape {{
+    /// `v.number()`: always `Float64`, even for whole numbers.
+    Float64,
+    /// `v.int64()`.
+    Int64,
+    /// `v.bytes()`: `Vec<u8>` serializes as an array of numbers.
+    Bytes,
+    /// Strings, ids, booleans, and null.
+    Plain,
+    /// A string literal; tells the variants of a union apart.
+    Literal(&'static str),
+    Array(&'static ValueShape),
+    Object(&'static [(&'static str, ValueShape)]),
+    Record(&'static ValueShape),
+    Union(&'static [ValueShape]),
+    /// `v.any()`: converted from the JSON alone.
+    Any,
+}}
+
+impl ValueShape {{
+    /// Whether `v` can be a value of this shape. A union uses its first variant that fits.
+    fn fits(&self, v: &serde_json::Value) -> bool {{
+        match self {{
+            ValueShape::Float64 | ValueShape::Int64 => v.is_number(),
+            ValueShape::Bytes | ValueShape::Array(_) => v.is_array(),
+            ValueShape::Plain => v.is_string() || v.is_boolean() || v.is_null(),
+            ValueShape::Literal(literal) => v.as_str() == Some(*literal),
+            ValueShape::Object(fields) => v.as_object().is_some_and(|map| {{
+                map.iter().all(|(k, v)| fields.iter().any(|(name, field)| *name == k.as_str() && field.fits(v)))
+            }}),
+            ValueShape::Record(_) => v.is_object(),
+            ValueShape::Union(variants) => variants.iter().any(|variant| variant.fits(v)),
+            ValueShape::Any => true,
+        }}
+    }}
+}}
+
+{allow}fn json_to_convex_value_as(v: serde_json::Value, shape: &ValueShape) -> convex::Value {{
+    match (shape, v) {{
+        (ValueShape::Float64, serde_json::Value::Number(n)) => convex::Value::Float64(n.as_f64().unwrap_or_default()),
+        (ValueShape::Int64, serde_json::Value::Number(n)) => match n.as_i64() {{
+            Some(i) => convex::Value::Int64(i),
+            None => json_to_convex_value(serde_json::Value::Number(n)),
+        }},
+        (ValueShape::Bytes, serde_json::Value::Array(items)) => {{
+            convex::Value::Bytes(items.iter().filter_map(serde_json::Value::as_u64).map(|b| b as u8).collect())
+        }}
+        (ValueShape::Array(element), serde_json::Value::Array(items)) => {{
+            convex::Value::Array(items.into_iter().map(|item| json_to_convex_value_as(item, element)).collect())
+        }}
+        (ValueShape::Object(fields), serde_json::Value::Object(map)) => convex::Value::Object(json_to_convex_args(map, fields)),
+        (ValueShape::Record(value), serde_json::Value::Object(map)) => {{
+            convex::Value::Object(map.into_iter().map(|(k, v)| (k, json_to_convex_value_as(v, value))).collect())
+        }}
+        (ValueShape::Union(variants), v) => match variants.iter().find(|variant| variant.fits(&v)) {{
+            Some(variant) => json_to_convex_value_as(v, variant),
+            None => json_to_convex_value(v),
+        }},
+        (_, v) => json_to_convex_value(v),
+    }}
+}}
+
+/// Convert serialized args (or object fields) by the shapes of their validators.
+/// Names without a shape fall back to `json_to_convex_value`.
+{allow}fn json_to_convex_args(
+    args: impl IntoIterator<Item = (String, serde_json::Value)>,
+    shapes: &[(&str, ValueShape)],
+) -> std::collections::BTreeMap<String, convex::Value> {{
+    args.into_iter()
+        .map(|(k, v)| {{
+            let value = match shapes.iter().find(|(name, _)| *name == k) {{
+                Some((_, shape)) => json_to_convex_value_as(v, shape),
+                None => json_to_convex_value(v),
+            }};
+            (k, value)
+        }})
+        .collect()
+}}
+
+"#
+    )
+}
+
+/// The `ValueShape` expression for a validator, used by the generated
+/// `json_to_convex_args` calls.
+///
+/// Unions of strings and literals collapse to `Plain`: the JSON already says
+/// how to encode them.
+fn value_shape_expr(data_type: &JsonValue) -> String
+{
+    match data_type["type"].as_str().unwrap_or("unknown") {
+        "number" => "ValueShape::Float64".to_string(),
+        "int64" => "ValueShape::Int64".to_string(),
+        "bytes" => "ValueShape::Bytes".to_string(),
+        "string" | "boolean" | "null" | "id" => "ValueShape::Plain".to_string(),
+        "literal" => match &data_type["value"] {
+            JsonValue::String(text) => format!("ValueShape::Literal({:?})", text),
+            JsonValue::Number(_) => "ValueShape::Float64".to_string(),
+            _ => "ValueShape::Plain".to_string(),
+        },
+        "array" => format!("ValueShape::Array(&{})", value_shape_expr(&data_type["elements"])),
+        "optional" => value_shape_expr(&data_type["inner"]),
+        "record" => format!("ValueShape::Record(&{})", value_shape_expr(&data_type["valueType"])),
+        "object" => match data_type["properties"].as_object() {
+            Some(props) if !props.is_empty() => format!("ValueShape::Object(&[{}])", field_shapes_expr(props)),
+            _ => "ValueShape::Any".to_string(),
+        },
+        "union" => {
+            let variants: Vec<String> = data_type["variants"]
+                .as_array()
+                .map(|variants| variants.iter().map(value_shape_expr).collect())
+                .unwrap_or_default();
+            let plain = |shape: &String| shape == "ValueShape::Plain" || shape.starts_with("ValueShape::Literal(");
+            if variants.is_empty() {
+                "ValueShape::Any".to_string()
+            } else if variants.iter().all(plain) {
+                "ValueShape::Plain".to_string()
+            } else {
+                format!("ValueShape::Union(&[{}])", variants.join(", "))
+            }
+        }
+        _ => "ValueShape::Any".to_string(),
+    }
+}
+
+/// `("name", shape), ...` for the fields of an object or the params of a function.
+fn field_shapes_expr<'a>(fields: impl IntoIterator<Item = (&'a String, &'a JsonValue)>) -> String
+{
+    fields
+        .into_iter()
+        .map(|(name, data_type)| format!("({:?}, {})", name, value_shape_expr(data_type)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Generate the convex_value_to_json helper function in the output.
 fn generate_convex_value_to_json_helper() -> String
 {
@@ -915,32 +5263,683 @@ fn generate_convex_value_to_json_helper() -> String
      \x20               obj.iter().map(|(k, v)| (k.clone(), convex_value_to_json(v))).collect();\n\
      \x20           serde_json::Value::Object(map)\n\
      \x20       }\n\
-     \x20       _ => serde_json::Value::Null,\n\
+     \x20       convex::Value::Bytes(b) => serde_json::json!(b),\n\
      \x20   }\n\
      }\n\n"
         .to_string()
 }
 
+/// Generate `decode_function_result`, the one conversion from `FunctionResult`
+/// to a typed return. The `ConvexApi` methods, `TypedSubscription`, and the
+/// `TryFrom` impls all go through it, naming the function when they know it.
+fn generate_decode_function_result_helper() -> String
+{
+    "/// Decode a function's result into its declared return type.\nfn decode_function_result<T: \
+     serde::de::DeserializeOwned>(\n\x20   result: convex::FunctionResult,\n\x20   function: Option<&str>,\n) -> Result<T, \
+     ConvexError> {\n\x20   match result {\n\x20       convex::FunctionResult::Value(value) => \
+     serde_path_to_error::deserialize(convex_value_to_json(&value))\n\x20           .map_err(|err| \
+     ConvexError::Deserialization { function: function.map(str::to_string), path: err.path().to_string(), source: \
+     err.into_inner() }),\n\x20       convex::FunctionResult::ErrorMessage(msg) => \
+     Err(ConvexError::from_message(msg)),\n\x20       convex::FunctionResult::ConvexError(err) => Err(ConvexError::Server { \
+     message: err.message, data: convex_value_to_json(&err.data) }),\n\x20   }\n}\n\n"
+        .to_string()
+}
+
 /// Generate the TypedSubscription wrapper struct and Stream impl.
 fn generate_typed_subscription_code() -> String
 {
-    "pub struct TypedSubscription<T> {\n\x20   inner: convex::QuerySubscription,\n\x20   _phantom: \
-     std::marker::PhantomData<fn() -> T>,\n}\n\nimpl<T> TypedSubscription<T> {\n\x20   pub fn new(inner: \
-     convex::QuerySubscription) -> Self {\n\x20       Self { inner, _phantom: std::marker::PhantomData }\n\x20   }\n\x20   \
-     pub fn into_inner(self) -> convex::QuerySubscription { self.inner }\n}\n\nimpl<T: serde::de::DeserializeOwned> \
+    "/// Identifies a query by its function and args, the way the Convex client\n\
+     /// keys its subscriptions.\n\
+     ///\n\
+     /// Unlike `convex::SubscriberId`, it is the same for every subscription to\n\
+     /// the same query, on any client and across reconnects, so it suits\n\
+     /// deduplicating subscriptions or keying results saved for resumption.\n\
+     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]\n\
+     pub struct QueryToken(String);\n\
+     \n\
+     impl QueryToken {\n\
+     \x20   /// The token of the function at `path`, e.g. `\"messages:list\"`, called with `args`.\n\
+     \x20   pub fn new(path: &str, args: &std::collections::BTreeMap<String, convex::Value>) -> Self {\n\
+     \x20       // The client canonicalizes the path to a `.js` module and sends the args in an array\n\
+     \x20       let (module, function) = path.rsplit_once(':').unwrap_or((path, \"default\"));\n\
+     \x20       let module = match std::path::Path::new(module).extension() {\n\
+     \x20           Some(_) => module.to_string(),\n\
+     \x20           None => format!(\"{module}.js\"),\n\
+     \x20       };\n\
+     \x20       let args: serde_json::Value = convex::Value::Array(vec![convex::Value::Object(args.clone())]).into();\n\
+     \x20       Self(serde_json::json!({ \"udfPath\": format!(\"{module}:{function}\"), \"args\": args }).to_string())\n\
+     \x20   }\n\
+     \n\
+     \x20   /// The token as the JSON string it is.\n\
+     \x20   pub fn as_str(&self) -> &str {\n\
+     \x20       &self.0\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl std::fmt::Display for QueryToken {\n\
+     \x20   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n\
+     \x20       f.write_str(&self.0)\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     pub struct TypedSubscription<T> {\n\x20   inner: convex::QuerySubscription,\n\x20   token: Option<QueryToken>,\n\x20   \
+     function: Option<&'static str>,\n\x20   _phantom: std::marker::PhantomData<fn() -> T>,\n}\n\nimpl<T> TypedSubscription<T> \
+     {\n\x20   pub fn new(inner: convex::QuerySubscription) -> Self {\n\x20       Self { inner, token: None, function: None, \
+     _phantom: std::marker::PhantomData }\n\x20   }\n\n\
+     \x20   /// Record the query this subscribes to, for `query_token`. The `subscribe_*`\n\
+     \x20   /// methods do this.\n\
+     \x20   pub fn with_query_token(mut self, token: QueryToken) -> Self {\n\
+     \x20       self.token = Some(token);\n\
+     \x20       self\n\
+     \x20   }\n\n\
+     \x20   /// Record the query's path, e.g. `\"messages:list\"`, for\n\
+     \x20   /// `ConvexError::Deserialization`. The `subscribe_*` methods do this.\n\
+     \x20   pub fn with_function(mut self, path: &'static str) -> Self {\n\
+     \x20       self.function = Some(path);\n\
+     \x20       self\n\
+     \x20   }\n\n\
+     \x20   /// This subscriber, e.g. to find its result in the `convex::QueryResults`\n\
+     \x20   /// of `ConvexClient::watch_all`. Every subscription gets a new one.\n\
+     \x20   pub fn subscriber_id(&self) -> &convex::SubscriberId {\n\
+     \x20       self.inner.id()\n\
+     \x20   }\n\n\
+     \x20   /// The query this subscribes to, or `None` for a subscription made with `new`\n\
+     \x20   /// alone.\n\
+     \x20   pub fn query_token(&self) -> Option<&QueryToken> {\n\
+     \x20       self.token.as_ref()\n\
+     \x20   }\n\n\
+     \x20   pub fn into_inner(self) -> convex::QuerySubscription { self.inner }\n}\n\nimpl<T: serde::de::DeserializeOwned> \
      futures_core::Stream for TypedSubscription<T> {\n\x20   type Item = Result<T, ConvexError>;\n\x20   fn \
      poll_next(\n\x20       self: std::pin::Pin<&mut Self>,\n\x20       cx: &mut std::task::Context<'_>,\n\x20   ) -> \
      std::task::Poll<Option<Self::Item>> {\n\x20       let this = self.get_mut();\n\x20       match std::pin::Pin::new(&mut \
-     this.inner).poll_next(cx) {\n\x20           std::task::Poll::Ready(Some(result)) => {\n\x20               let typed = \
-     match result {\n\x20                   convex::FunctionResult::Value(value) => {\n\x20                       let json \
-     = convex_value_to_json(&value);\n\x20                       \
-     serde_json::from_value(json).map_err(ConvexError::Deserialization)\n\x20                   }\n\x20                   \
-     convex::FunctionResult::ErrorMessage(msg) => Err(ConvexError::Function(msg)),\n\x20                   \
-     convex::FunctionResult::ConvexError(err) => Err(ConvexError::Server { message: err.message, data: \
-     convex_value_to_json(&err.data) }),\n\x20               };\n\x20               \
-     std::task::Poll::Ready(Some(typed))\n\x20           }\n\x20           std::task::Poll::Ready(None) => \
+     this.inner).poll_next(cx) {\n\x20           std::task::Poll::Ready(Some(result)) => {\n\x20               \
+     std::task::Poll::Ready(Some(decode_function_result(result, this.function)))\n\x20           }\n\x20           std::task::Poll::Ready(None) => \
      std::task::Poll::Ready(None),\n\x20           std::task::Poll::Pending => std::task::Poll::Pending,\n\x20       \
-     }\n\x20   }\n}\n\n"
+     }\n\x20   }\n}\n\n\
+     impl<T: serde::de::DeserializeOwned> TypedSubscription<T> {\n\
+     \x20   /// Wait for the next typed result. Returns `None` once the subscription ends.\n\
+     \x20   pub async fn next_result(&mut self) -> Option<Result<T, ConvexError>> {\n\
+     \x20       use futures_core::Stream as _;\n\
+     \x20       std::future::poll_fn(|cx| std::pin::Pin::new(&mut *self).poll_next(cx)).await\n\
+     \x20   }\n\
+     }\n\n"
+        .to_string()
+}
+
+/// Generate the SharedSubscription fan-out wrapper around TypedSubscription.
+///
+/// A background task drives the single server subscription and publishes each
+/// result into a `tokio::sync::watch` channel. The task exits (dropping the
+/// server subscription) once every receiver handle is gone.
+fn generate_shared_subscription_code() -> String
+{
+    "/// A `TypedSubscription` shared between many consumers.\n\
+     ///\n\
+     /// The server subscription is opened once and driven by a background task;\n\
+     /// every clone observes the latest result. The subscription is torn down when\n\
+     /// the last clone is dropped.\n\
+     pub struct SharedSubscription<T> {\n\
+     \x20   rx: tokio::sync::watch::Receiver<Option<Result<T, std::sync::Arc<ConvexError>>>>,\n\
+     }\n\
+     \n\
+     impl<T> Clone for SharedSubscription<T> {\n\
+     \x20   fn clone(&self) -> Self {\n\
+     \x20       Self { rx: self.rx.clone() }\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T> SharedSubscription<T>\n\
+     where\n\
+     \x20   T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,\n\
+     {\n\
+     \x20   /// Spawn the task that drives `sub` and fans its results out.\n\
+     \x20   pub fn new(mut sub: TypedSubscription<T>) -> Self {\n\
+     \x20       let (tx, rx) = tokio::sync::watch::channel(None);\n\
+     \x20       tokio::spawn(async move {\n\
+     \x20           use futures_core::Stream as _;\n\
+     \x20           use std::future::Future as _;\n\
+     \x20           loop {\n\
+     \x20               let mut closed = std::pin::pin!(tx.closed());\n\
+     \x20               let next = std::future::poll_fn(|cx| {\n\
+     \x20                   if closed.as_mut().poll(cx).is_ready() {\n\
+     \x20                       return std::task::Poll::Ready(None);\n\
+     \x20                   }\n\
+     \x20                   std::pin::Pin::new(&mut sub).poll_next(cx)\n\
+     \x20               })\n\
+     \x20               .await;\n\
+     \x20               match next {\n\
+     \x20                   Some(result) => {\n\
+     \x20                       if tx.send(Some(result.map_err(std::sync::Arc::new))).is_err() {\n\
+     \x20                           break;\n\
+     \x20                       }\n\
+     \x20                   }\n\
+     \x20                   // Either the stream ended or every receiver was dropped.\n\
+     \x20                   None => break,\n\
+     \x20               }\n\
+     \x20           }\n\
+     \x20       });\n\
+     \x20       Self { rx }\n\
+     \x20   }\n\
+     \n\
+     \x20   /// The most recent result, or `None` if nothing has arrived yet.\n\
+     \x20   pub fn latest(&self) -> Option<Result<T, std::sync::Arc<ConvexError>>> {\n\
+     \x20       self.rx.borrow().clone()\n\
+     \x20   }\n\
+     \n\
+     \x20   /// Wait for the next result. Returns `None` once the subscription has ended.\n\
+     \x20   pub async fn changed(&mut self) -> Option<Result<T, std::sync::Arc<ConvexError>>> {\n\
+     \x20       self.rx.changed().await.ok()?;\n\
+     \x20       self.rx.borrow_and_update().clone()\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T> TypedSubscription<T>\n\
+     where\n\
+     \x20   T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,\n\
+     {\n\
+     \x20   /// Convert into a `SharedSubscription` that can be cloned to many consumers.\n\
+     \x20   pub fn shared(self) -> SharedSubscription<T> {\n\
+     \x20       SharedSubscription::new(self)\n\
+     \x20   }\n\
+     }\n\n"
+        .to_string()
+}
+
+/// Generate `BufferedSubscription`, whose background task drains the server
+/// subscription into a bounded buffer.
+///
+/// The buffer holds raw `FunctionResult`s and decodes them when taken, so the
+/// results a slow consumer misses are never decoded.
+fn generate_buffered_subscription_code() -> String
+{
+    r#"/// How a `BufferedSubscription` holds results its consumer hasn't taken yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionBuffer {
+    /// Keep only the newest result, replacing an unread one.
+    KeepLatestOnly,
+    /// Keep up to this many results, dropping the oldest when full.
+    Bounded(usize),
+}
+
+struct SubscriptionBufferState {
+    results: std::collections::VecDeque<convex::FunctionResult>,
+    dropped: u64,
+    ended: bool,
+    waker: Option<std::task::Waker>,
+}
+
+/// A `TypedSubscription` that a background task keeps draining into a bounded
+/// buffer, so results can't pile up behind a slow consumer.
+///
+/// The task stops when this is dropped.
+pub struct BufferedSubscription<T> {
+    state: std::sync::Arc<std::sync::Mutex<SubscriptionBufferState>>,
+    task: tokio::task::JoinHandle<()>,
+    function: Option<&'static str>,
+    _phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> BufferedSubscription<T> {
+    /// Spawn the task that drains `sub` into a buffer.
+    pub fn new(sub: TypedSubscription<T>, buffer: SubscriptionBuffer) -> Self {
+        let capacity = match buffer {
+            SubscriptionBuffer::KeepLatestOnly => 1,
+            SubscriptionBuffer::Bounded(capacity) => capacity.max(1),
+        };
+        let state = std::sync::Arc::new(std::sync::Mutex::new(SubscriptionBufferState {
+            results: std::collections::VecDeque::new(),
+            dropped: 0,
+            ended: false,
+            waker: None,
+        }));
+        let task_state = state.clone();
+        let function = sub.function;
+        let mut inner = sub.into_inner();
+        let task = tokio::spawn(async move {
+            use futures_core::Stream as _;
+            loop {
+                let next = std::future::poll_fn(|cx| std::pin::Pin::new(&mut inner).poll_next(cx)).await;
+                let mut state = task_state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+                match next {
+                    Some(result) => {
+                        if state.results.len() == capacity {
+                            state.results.pop_front();
+                            state.dropped += 1;
+                        }
+                        state.results.push_back(result);
+                    }
+                    None => state.ended = true,
+                }
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+                if state.ended {
+                    break;
+                }
+            }
+        });
+        Self { state, task, function, _phantom: std::marker::PhantomData }
+    }
+
+    /// How many results were dropped because the consumer fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner).dropped
+    }
+
+    /// How many results are waiting to be taken.
+    pub fn pending(&self) -> usize {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner).results.len()
+    }
+}
+
+impl<T> Drop for BufferedSubscription<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> futures_core::Stream for BufferedSubscription<T> {
+    type Item = Result<T, ConvexError>;
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        match state.results.pop_front() {
+            Some(result) => std::task::Poll::Ready(Some(decode_function_result(result, self.function))),
+            None if state.ended => std::task::Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                std::task::Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> BufferedSubscription<T> {
+    /// Wait for the next typed result. Returns `None` once the subscription ends.
+    pub async fn next_result(&mut self) -> Option<Result<T, ConvexError>> {
+        use futures_core::Stream as _;
+        std::future::poll_fn(|cx| std::pin::Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl<T> TypedSubscription<T> {
+    /// Convert into a `BufferedSubscription` that holds results as `buffer` says.
+    pub fn buffered(self, buffer: SubscriptionBuffer) -> BufferedSubscription<T> {
+        BufferedSubscription::new(self, buffer)
+    }
+}
+
+"#
+    .to_string()
+}
+
+/// Generate `resilient_subscribe`, which keeps a `TypedSubscription` up by
+/// resubscribing after failures, with capped exponential backoff and jitter.
+///
+/// It is driven by `next_event()` rather than a background task, so it needs
+/// no `Send` bounds and stops when dropped; only the backoff sleep needs
+/// tokio's `time` feature. Failures the server will repeat, such as a query
+/// that throws, are passed on without resubscribing.
+fn generate_resilient_subscription_code() -> String
+{
+    r#"/// How `resilient_subscribe` waits between attempts.
+///
+/// The delay before attempt `n` is `initial * multiplier^(n - 1)`, at most
+/// `max`, with up to `jitter` of it taken off at random so that clients that
+/// lost the same deployment don't all come back at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubscriptionBackoff {
+    pub initial: std::time::Duration,
+    pub max: std::time::Duration,
+    pub multiplier: f64,
+    /// The fraction of each delay that is random, from 0.0 to 1.0.
+    pub jitter: f64,
+}
+
+impl Default for SubscriptionBackoff {
+    fn default() -> Self {
+        Self {
+            initial: std::time::Duration::from_millis(250),
+            max: std::time::Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl SubscriptionBackoff {
+    /// The delay before retry `attempt`, counting from 1.
+    pub fn delay(&self, attempt: u32) -> std::time::Duration {
+        use std::hash::{BuildHasher, Hasher};
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let capped = (self.initial.as_secs_f64() * self.multiplier.powi(exponent)).min(self.max.as_secs_f64());
+        // `RandomState` is seeded randomly, which is all jitter needs
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u32(attempt);
+        let random = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+        std::time::Duration::from_secs_f64(capped.max(0.0) * (1.0 - self.jitter.clamp(0.0, 1.0) * random))
+    }
+}
+
+/// What a `ResilientSubscription` has to report.
+#[derive(Debug)]
+pub enum SubscriptionEvent<T> {
+    /// A new result of the query.
+    Update(T),
+    /// The query failed in a way resubscribing won't fix, e.g. it threw or
+    /// returned something `T` can't hold. The subscription stays up, and the
+    /// next result may succeed.
+    Error(ConvexError),
+    /// The subscription is up, the first time or again after a failure.
+    Connected,
+    /// Subscribing failed or the subscription ended. Retry `attempt` starts
+    /// after `retry_in`; `error` is why, when there is one.
+    Reconnecting { attempt: u32, retry_in: std::time::Duration, error: Option<ConvexError> },
+}
+
+/// A subscription that resubscribes after failures. See `resilient_subscribe`.
+pub struct ResilientSubscription<T, F> {
+    make_sub: F,
+    backoff: SubscriptionBackoff,
+    current: Option<TypedSubscription<T>>,
+    /// Failed attempts since the last result.
+    failures: u32,
+    /// The delay to sleep before the next attempt.
+    retry_in: Option<std::time::Duration>,
+    /// An event to report before doing anything else.
+    pending: Option<SubscriptionEvent<T>>,
+}
+
+/// Keep the subscription `make_sub` makes up, making a new one whenever
+/// subscribing fails or the subscription ends:
+///
+/// ```ignore
+/// let mut sub = resilient_subscribe(|| api.subscribe_games_list(), SubscriptionBackoff::default());
+/// loop {
+///     match sub.next_event().await {
+///         SubscriptionEvent::Update(games) => render(games),
+///         SubscriptionEvent::Reconnecting { retry_in, .. } => show_offline(retry_in),
+///         _ => {}
+///     }
+/// }
+/// ```
+pub fn resilient_subscribe<T, F, Fut>(make_sub: F, backoff: SubscriptionBackoff) -> ResilientSubscription<T, F>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<TypedSubscription<T>, ConvexError>>,
+{
+    ResilientSubscription { make_sub, backoff, current: None, failures: 0, retry_in: None, pending: None }
+}
+
+impl<T, F, Fut> ResilientSubscription<T, F>
+where
+    T: serde::de::DeserializeOwned,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<TypedSubscription<T>, ConvexError>>,
+{
+    /// Wait for the next event. There is always another one: the subscription
+    /// never gives up, so drop it to stop.
+    pub async fn next_event(&mut self) -> SubscriptionEvent<T> {
+        loop {
+            if let Some(event) = self.pending.take() {
+                return event;
+            }
+            let Some(sub) = self.current.as_mut() else {
+                if let Some(delay) = self.retry_in {
+                    tokio::time::sleep(delay).await;
+                    self.retry_in = None;
+                }
+                match (self.make_sub)().await {
+                    Ok(sub) => {
+                        self.current = Some(sub);
+                        return SubscriptionEvent::Connected;
+                    }
+                    Err(error) => self.fail(Some(error)),
+                }
+                continue;
+            };
+            match sub.next_result().await {
+                Some(Ok(value)) => {
+                    self.failures = 0;
+                    return SubscriptionEvent::Update(value);
+                }
+                Some(Err(error)) if !error.is_retryable() => return SubscriptionEvent::Error(error),
+                Some(Err(error)) => self.fail(Some(error)),
+                None => self.fail(None),
+            }
+        }
+    }
+
+    /// Drop the current subscription and schedule the next attempt.
+    fn fail(&mut self, error: Option<ConvexError>) {
+        self.current = None;
+        self.failures = self.failures.saturating_add(1);
+        let retry_in = self.backoff.delay(self.failures);
+        self.retry_in = Some(retry_in);
+        self.pending = Some(SubscriptionEvent::Reconnecting { attempt: self.failures, retry_in, error });
+    }
+}
+
+"#
+    .to_string()
+}
+
+/// Generate `Snapshot` and `SnapshotSubscription`, which stamps each result of a
+/// typed query with its receive time.
+fn generate_snapshot_subscription_code() -> String
+{
+    "/// A subscription result with when it arrived, for local caches that track\n\
+     /// how stale their data is.\n\
+     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n\
+     pub struct Snapshot<T> {\n\
+     \x20   pub value: T,\n\
+     \x20   /// When this client received the result.\n\
+     \x20   pub received_at: std::time::SystemTime,\n\
+     }\n\
+     \n\
+     impl<T> Snapshot<T> {\n\
+     \x20   /// How long ago the result was received, zero if the clock went back.\n\
+     \x20   pub fn age(&self) -> std::time::Duration {\n\
+     \x20       self.received_at.elapsed().unwrap_or_default()\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     /// A `TypedSubscription` that yields each result as a [`Snapshot`].\n\
+     pub struct SnapshotSubscription<T> {\n\
+     \x20   inner: TypedSubscription<T>,\n\
+     }\n\
+     \n\
+     impl<T> TypedSubscription<T> {\n\
+     \x20   /// Yield each result as a `Snapshot` with its receive time.\n\
+     \x20   pub fn into_snapshots(self) -> SnapshotSubscription<T> {\n\
+     \x20       SnapshotSubscription { inner: self }\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T: serde::de::DeserializeOwned> futures_core::Stream for SnapshotSubscription<T> {\n\
+     \x20   type Item = Result<Snapshot<T>, ConvexError>;\n\
+     \x20   fn poll_next(\n\
+     \x20       self: std::pin::Pin<&mut Self>,\n\
+     \x20       cx: &mut std::task::Context<'_>,\n\
+     \x20   ) -> std::task::Poll<Option<Self::Item>> {\n\
+     \x20       std::pin::Pin::new(&mut self.get_mut().inner).poll_next(cx).map(|next| {\n\
+     \x20           next.map(|result| {\n\
+     \x20               result.map(|value| Snapshot { value, received_at: std::time::SystemTime::now() })\n\
+     \x20           })\n\
+     \x20       })\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T: serde::de::DeserializeOwned> SnapshotSubscription<T> {\n\
+     \x20   /// Wait for the next snapshot. Returns `None` once the subscription ends.\n\
+     \x20   pub async fn next_result(&mut self) -> Option<Result<Snapshot<T>, ConvexError>> {\n\
+     \x20       use futures_core::Stream as _;\n\
+     \x20       std::future::poll_fn(|cx| std::pin::Pin::new(&mut *self).poll_next(cx)).await\n\
+     \x20   }\n\
+     }\n\n"
+        .to_string()
+}
+
+/// Generate `ArcSubscription`, which decodes each result once into an `Arc<T>`.
+///
+/// `T` stays `DeserializeOwned` (serde's `rc` feature isn't needed); the `Arc`
+/// is added after decoding, so consumers share snapshots instead of deep-cloning.
+fn generate_arc_subscription_code() -> String
+{
+    "/// A `TypedSubscription` that yields each result as a shared, immutable\n\
+     /// `Arc<T>` snapshot, so fanning it out to many consumers clones a pointer\n\
+     /// instead of the data.\n\
+     pub struct ArcSubscription<T> {\n\
+     \x20   inner: TypedSubscription<T>,\n\
+     }\n\
+     \n\
+     impl<T> TypedSubscription<T> {\n\
+     \x20   /// Yield each result as an `Arc<T>` snapshot.\n\
+     \x20   pub fn into_arc(self) -> ArcSubscription<T> {\n\
+     \x20       ArcSubscription { inner: self }\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T: serde::de::DeserializeOwned> futures_core::Stream for ArcSubscription<T> {\n\
+     \x20   type Item = Result<std::sync::Arc<T>, ConvexError>;\n\
+     \x20   fn poll_next(\n\
+     \x20       self: std::pin::Pin<&mut Self>,\n\
+     \x20       cx: &mut std::task::Context<'_>,\n\
+     \x20   ) -> std::task::Poll<Option<Self::Item>> {\n\
+     \x20       std::pin::Pin::new(&mut self.get_mut().inner)\n\
+     \x20           .poll_next(cx)\n\
+     \x20           .map(|next| next.map(|result| result.map(std::sync::Arc::new)))\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     impl<T: serde::de::DeserializeOwned> ArcSubscription<T> {\n\
+     \x20   /// Wait for the next snapshot. Returns `None` once the subscription ends.\n\
+     \x20   pub async fn next_result(&mut self) -> Option<Result<std::sync::Arc<T>, ConvexError>> {\n\
+     \x20       use futures_core::Stream as _;\n\
+     \x20       std::future::poll_fn(|cx| std::pin::Pin::new(&mut *self).poll_next(cx)).await\n\
+     \x20   }\n\
+     \n\
+     \x20   /// Project each snapshot into an `Arc<U>`, e.g. the one slice of a large\n\
+     \x20   /// list a widget renders. `f` borrows the snapshot, so nothing it doesn't\n\
+     \x20   /// return is cloned.\n\
+     \x20   pub fn map_arc<U, F>(self, f: F) -> MapArc<T, F>\n\
+     \x20   where\n\
+     \x20       F: FnMut(&T) -> U + Unpin,\n\
+     \x20   {\n\
+     \x20       MapArc { inner: self, f }\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     /// The stream returned by [`ArcSubscription::map_arc`].\n\
+     pub struct MapArc<T, F> {\n\
+     \x20   inner: ArcSubscription<T>,\n\
+     \x20   f: F,\n\
+     }\n\
+     \n\
+     impl<T, U, F> futures_core::Stream for MapArc<T, F>\n\
+     where\n\
+     \x20   T: serde::de::DeserializeOwned,\n\
+     \x20   F: FnMut(&T) -> U + Unpin,\n\
+     {\n\
+     \x20   type Item = Result<std::sync::Arc<U>, ConvexError>;\n\
+     \x20   fn poll_next(\n\
+     \x20       self: std::pin::Pin<&mut Self>,\n\
+     \x20       cx: &mut std::task::Context<'_>,\n\
+     \x20   ) -> std::task::Poll<Option<Self::Item>> {\n\
+     \x20       let this = self.get_mut();\n\
+     \x20       let f = &mut this.f;\n\
+     \x20       std::pin::Pin::new(&mut this.inner)\n\
+     \x20           .poll_next(cx)\n\
+     \x20           .map(|next| next.map(|result| result.map(|snapshot| std::sync::Arc::new(f(&snapshot)))))\n\
+     \x20   }\n\
+     }\n\n"
+        .to_string()
+}
+
+/// Generate the `PaginationCursor` trait the per-query cursor newtypes implement.
+fn generate_pagination_cursor_trait() -> String
+{
+    "/// A typed continuation cursor of one paginated query.\npub trait PaginationCursor: Sized {\n\x20   /// The query the \
+     cursor belongs to, e.g. `\"messages:list\"`.\n\x20   const QUERY: &'static str;\n\n\x20   fn from_string(cursor: \
+     String) -> Self;\n\n\x20   fn as_str(&self) -> &str;\n}\n\n"
+        .to_string()
+}
+
+/// Generate the `CursorStore` trait that persists cursors keyed by query path,
+/// plus an in-memory impl for `HashMap`.
+fn generate_cursor_store_code() -> String
+{
+    "/// Storage for pagination cursors, keyed by query path, so a sync job can\n/// resume where it stopped after a \
+     restart.\npub trait CursorStore {\n\x20   type Error;\n\n\x20   /// The raw cursor saved for `query`, if any.\n\x20   \
+     fn load_raw(&self, query: &str) -> Result<Option<String>, Self::Error>;\n\n\x20   /// Save the raw cursor for `query`, \
+     replacing the previous one.\n\x20   fn save_raw(&mut self, query: &str, cursor: &str) -> Result<(), \
+     Self::Error>;\n\n\x20   /// The cursor saved for `C`'s query, if any.\n\x20   fn load<C: PaginationCursor>(&self) -> \
+     Result<Option<C>, Self::Error> {\n\x20       Ok(self.load_raw(C::QUERY)?.map(C::from_string))\n\x20   }\n\n\x20   /// \
+     Save `cursor` under its query.\n\x20   fn save<C: PaginationCursor>(&mut self, cursor: &C) -> Result<(), Self::Error> \
+     {\n\x20       self.save_raw(C::QUERY, cursor.as_str())\n\x20   }\n}\n\nimpl CursorStore for \
+     std::collections::HashMap<String, String> {\n\x20   type Error = std::convert::Infallible;\n\n\x20   fn \
+     load_raw(&self, query: &str) -> Result<Option<String>, Self::Error> {\n\x20       Ok(self.get(query).cloned())\n\x20   \
+     }\n\n\x20   fn save_raw(&mut self, query: &str, cursor: &str) -> Result<(), Self::Error> {\n\x20       \
+     self.insert(query.to_string(), cursor.to_string());\n\x20       Ok(())\n\x20   }\n}\n\n"
+        .to_string()
+}
+
+/// Generate Leptos/Dioxus adapters for TypedSubscription.
+///
+/// Each adapter is wrapped in `#[cfg(feature = "...")]` so the consuming crate
+/// only needs the framework it actually enables.
+fn generate_frontend_adapters_code() -> String
+{
+    "#[cfg(feature = \"leptos\")]\n\
+     impl<T> TypedSubscription<T>\n\
+     where\n\
+     \x20   T: serde::de::DeserializeOwned + Send + Sync + 'static,\n\
+     {\n\
+     \x20   /// Drive this subscription on the Leptos executor and expose the latest value\n\
+     \x20   /// as a signal. Errors leave the previous value in place.\n\
+     \x20   pub fn into_leptos_signal(mut self) -> leptos::prelude::Signal<Option<T>> {\n\
+     \x20       use leptos::prelude::Set as _;\n\
+     \x20       let (read, write) = leptos::prelude::signal(None);\n\
+     \x20       leptos::task::spawn_local(async move {\n\
+     \x20           while let Some(result) = self.next_result().await {\n\
+     \x20               if let Ok(value) = result {\n\
+     \x20                   write.set(Some(value));\n\
+     \x20               }\n\
+     \x20           }\n\
+     \x20       });\n\
+     \x20       read.into()\n\
+     \x20   }\n\
+     }\n\
+     \n\
+     /// Dioxus hook that subscribes once per component and tracks the latest value.\n\
+     ///\n\
+     /// `subscribe` is called when the component mounts, e.g.\n\
+     /// `use_typed_subscription(move || { let api = api.clone(); async move { api.subscribe_games_get_game().await } })`.\n\
+     /// Errors leave the previous value in place.\n\
+     #[cfg(feature = \"dioxus\")]\n\
+     pub fn use_typed_subscription<T, F, Fut>(mut subscribe: F) -> dioxus::prelude::Signal<Option<T>>\n\
+     where\n\
+     \x20   T: serde::de::DeserializeOwned + 'static,\n\
+     \x20   F: FnMut() -> Fut + 'static,\n\
+     \x20   Fut: std::future::Future<Output = Result<TypedSubscription<T>, ConvexError>> + 'static,\n\
+     {\n\
+     \x20   use dioxus::prelude::*;\n\
+     \x20   let mut value = use_signal(|| None);\n\
+     \x20   use_future(move || {\n\
+     \x20       let pending = subscribe();\n\
+     \x20       async move {\n\
+     \x20           let Ok(mut sub) = pending.await else { return };\n\
+     \x20           while let Some(result) = sub.next_result().await {\n\
+     \x20               if let Ok(latest) = result {\n\
+     \x20                   value.set(Some(latest));\n\
+     \x20               }\n\
+     \x20           }\n\
+     \x20       }\n\
+     \x20   });\n\
+     \x20   value\n\
+     }\n\n"
         .to_string()
 }
 
@@ -977,7 +5976,7 @@ fn to_pascal_case(s: &str) -> String
 }
 
 /// Convert a camelCase string to snake_case.
-fn to_snake_case(s: &str) -> String
+pub(crate) fn to_snake_case(s: &str) -> String
 {
     let mut result = String::new();
     for (i, c) in s.chars().enumerate() {
@@ -990,21 +5989,16 @@ fn to_snake_case(s: &str) -> String
 }
 
 /// If `name` is a Rust reserved keyword, return `r#name`; otherwise return it unchanged.
-fn escape_rust_keyword(name: &str) -> String
+pub(crate) fn escape_rust_keyword(name: &str) -> String
 {
     // https://doc.rust-lang.org/reference/keywords.html
     const KEYWORDS: &[&str] = &[
         // Strict keywords
-        "as", "break", "const", "continue", "crate", "else", "enum",
-        "extern", "false", "fn", "for", "if", "impl", "in", "let",
-        "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
-        "self", "Self", "static", "struct", "super", "trait", "true",
-        "type", "unsafe", "use", "where", "while",
-        // Edition 2018+ strict keywords
-        "async", "await", "dyn",
-        // Reserved for future use
-        "abstract", "become", "box", "do", "final", "macro",
-        "override", "priv", "typeof", "unsized", "virtual", "yield",
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+        "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+        "trait", "true", "type", "unsafe", "use", "where", "while", // Edition 2018+ strict keywords
+        "async", "await", "dyn", // Reserved for future use
+        "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof", "unsized", "virtual", "yield",
         "try",
     ];
     if KEYWORDS.contains(&name) {
@@ -0,0 +1,207 @@
+//! `convex-typegen.toml` support for [`Configuration::from_file`].
+//!
+//! Keys are the [`Configuration`] field names. The file is deserialized
+//! straight into `FileConfig`, so a syntax error, an unknown key, or a value
+//! of the wrong type is reported with its line and column.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::errors::ConvexTypeGeneratorError;
+use crate::{
+    Artifact, CargoIntegration, Configuration, CustomValidatorHandler, EmptyFunctionFile, ExtractionBackend, FeatureGates,
+    FieldOverride, MethodNaming,
+};
+
+/// Every key a config file may set. Anything left out keeps its default.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig
+{
+    schema_path: Option<PathBuf>,
+    out_file: Option<PathBuf>,
+    version_tag: Option<String>,
+    function_paths: Option<Vec<PathBuf>>,
+    model_input: Option<PathBuf>,
+    base_dir: Option<PathBuf>,
+    on_empty_function_file: Option<EmptyFunctionFile>,
+    helper_stubs: Option<HashMap<String, PathBuf>>,
+    custom_validators: Option<HashMap<String, CustomValidatorHandler>>,
+    extraction_backend: Option<ExtractionBackend>,
+    offline: Option<bool>,
+    bun_path: Option<PathBuf>,
+    field_overrides: Option<HashMap<String, FieldOverride>>,
+    display_fields: Option<HashMap<String, String>>,
+    collection_keys: Option<HashMap<String, String>>,
+    enum_labels: Option<HashMap<String, String>>,
+    default_missing_fields: Option<bool>,
+    typed_ids: Option<bool>,
+    flatten_system_fields: Option<bool>,
+    arg_defaults: Option<HashMap<String, JsonValue>>,
+    finite_number_args: Option<bool>,
+    deprecated_functions: Option<HashMap<String, String>>,
+    redacted_args: Option<HashSet<String>>,
+    function_timeouts: Option<HashMap<String, String>>,
+    function_priorities: Option<HashMap<String, String>>,
+    method_naming: Option<MethodNaming>,
+    split_api_by_file: Option<bool>,
+    split_output_by_table: Option<bool>,
+    internal_module: Option<bool>,
+    prune_unreachable: Option<bool>,
+    keep_tables: Option<HashSet<String>>,
+    artifacts: Option<Vec<Artifact>>,
+    previous_model: Option<PathBuf>,
+    emit_previous_tables: Option<bool>,
+    emit_shared_subscriptions: Option<bool>,
+    emit_arc_subscriptions: Option<bool>,
+    emit_snapshot_subscriptions: Option<bool>,
+    emit_buffered_subscriptions: Option<bool>,
+    emit_resilient_subscriptions: Option<bool>,
+    emit_cursor_store: Option<bool>,
+    emit_frontend_adapters: Option<bool>,
+    emit_tauri_commands: Option<bool>,
+    emit_axum_router: Option<bool>,
+    emit_tenant_router: Option<bool>,
+    emit_admin_client: Option<bool>,
+    emit_timeouts: Option<bool>,
+    emit_consistent_reads: Option<bool>,
+    emit_offline_queue: Option<bool>,
+    emit_import_writers: Option<bool>,
+    emit_fixtures: Option<bool>,
+    emit_function_refs: Option<bool>,
+    emit_schedule_requests: Option<bool>,
+    emit_enum_labels: Option<bool>,
+    doc_examples_module: Option<String>,
+    allow_dead_code: Option<bool>,
+    verify_syntax: Option<bool>,
+    max_output_lines: Option<usize>,
+    feature_gates: Option<FeatureGates>,
+    print_summary: Option<bool>,
+    report_file: Option<PathBuf>,
+    quiet: Option<bool>,
+    lint: Option<bool>,
+    strict: Option<bool>,
+    fail_fast: Option<bool>,
+    env_overrides: Option<bool>,
+    cargo_integration: Option<CargoIntegration>,
+}
+
+/// Read `path` into a [`Configuration`]. Relative paths in the file, including
+/// `base_dir`, are relative to the file's directory.
+pub(crate) fn load(path: &Path) -> Result<Configuration, ConvexTypeGeneratorError>
+{
+    let source = std::fs::read_to_string(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    })?;
+    let invalid = |details: String| ConvexTypeGeneratorError::InvalidConfigFile {
+        path: path.to_path_buf(),
+        details,
+    };
+    let file: FileConfig = toml::from_str(&source).map_err(|e| invalid(e.to_string()))?;
+
+    let absolute = std::path::absolute(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    })?;
+    let dir = absolute.parent().unwrap_or(Path::new("/")).to_path_buf();
+
+    let mut config = Configuration {
+        base_dir: Some(file.base_dir.map_or_else(|| dir.clone(), |base| dir.join(base))),
+        ..Default::default()
+    };
+    macro_rules! set {
+        ($($field:ident),* $(,)?) => {
+            $(if let Some(value) = file.$field {
+                config.$field = value;
+            })*
+        };
+    }
+    set!(
+        schema_path,
+        out_file,
+        function_paths,
+        on_empty_function_file,
+        helper_stubs,
+        custom_validators,
+        extraction_backend,
+        offline,
+        field_overrides,
+        display_fields,
+        collection_keys,
+        enum_labels,
+        default_missing_fields,
+        typed_ids,
+        flatten_system_fields,
+        arg_defaults,
+        finite_number_args,
+        deprecated_functions,
+        redacted_args,
+        function_timeouts,
+        function_priorities,
+        method_naming,
+        split_api_by_file,
+        split_output_by_table,
+        internal_module,
+        prune_unreachable,
+        keep_tables,
+        artifacts,
+        emit_previous_tables,
+        emit_shared_subscriptions,
+        emit_arc_subscriptions,
+        emit_snapshot_subscriptions,
+        emit_buffered_subscriptions,
+        emit_resilient_subscriptions,
+        emit_cursor_store,
+        emit_frontend_adapters,
+        emit_tauri_commands,
+        emit_axum_router,
+        emit_tenant_router,
+        emit_admin_client,
+        emit_timeouts,
+        emit_consistent_reads,
+        emit_offline_queue,
+        emit_import_writers,
+        emit_fixtures,
+        emit_function_refs,
+        emit_schedule_requests,
+        emit_enum_labels,
+        allow_dead_code,
+        verify_syntax,
+        print_summary,
+        quiet,
+        lint,
+        strict,
+        fail_fast,
+        env_overrides,
+        cargo_integration,
+    );
+    if file.version_tag.is_some() {
+        config.version_tag = file.version_tag;
+    }
+    if file.model_input.is_some() {
+        config.model_input = file.model_input;
+    }
+    if file.previous_model.is_some() {
+        config.previous_model = file.previous_model;
+    }
+    if file.bun_path.is_some() {
+        config.bun_path = file.bun_path;
+    }
+    if file.doc_examples_module.is_some() {
+        config.doc_examples_module = file.doc_examples_module;
+    }
+    if file.max_output_lines.is_some() {
+        config.max_output_lines = file.max_output_lines;
+    }
+    if file.feature_gates.is_some() {
+        config.feature_gates = file.feature_gates;
+    }
+    if file.report_file.is_some() {
+        config.report_file = file.report_file;
+    }
+    Ok(config)
+}
@@ -0,0 +1,388 @@
+//! Helpers for inspecting the JSON type descriptors produced by the extractor.
+//!
+//! Descriptors look like `{ "type": "object", "properties": { ... } }`; see
+//! `js/mocks/normalize.ts` for the full format.
+
+use std::collections::HashSet;
+
+use serde_json::Value as JsonValue;
+
+use crate::codegen::{table_struct_name, try_match_table_shape};
+use crate::types::{ConvexFunction, ConvexSchema, ConvexTable};
+
+/// Render a descriptor back into compact `v.*` validator syntax.
+///
+/// Object fields are listed by name only, so deeply nested schemas stay on one
+/// line (e.g. `v.object({ name, profile })`). Custom validators render as the
+/// call to their factory, e.g. `vEmail()`.
+pub(crate) fn render_validator(data_type: &JsonValue) -> String
+{
+    if let Some(name) = data_type["customValidator"].as_str() {
+        return format!("{name}()");
+    }
+    match data_type["type"].as_str().unwrap_or("unknown") {
+        "string" => "v.string()".to_string(),
+        "number" => "v.number()".to_string(),
+        "boolean" => "v.boolean()".to_string(),
+        "null" => "v.null()".to_string(),
+        "int64" => "v.int64()".to_string(),
+        "bytes" => "v.bytes()".to_string(),
+        "any" => "v.any()".to_string(),
+        "id" => format!("v.id(\"{}\")", data_type["tableName"].as_str().unwrap_or("?")),
+        "literal" => format!("v.literal({})", data_type["value"]),
+        "array" => format!("v.array({})", render_validator(&data_type["elements"])),
+        "optional" => format!("v.optional({})", render_validator(&data_type["inner"])),
+        "record" => format!(
+            "v.record({}, {})",
+            render_validator(&data_type["keyType"]),
+            render_validator(&data_type["valueType"])
+        ),
+        "union" => {
+            let variants: Vec<String> = data_type["variants"]
+                .as_array()
+                .map(|variants| variants.iter().map(render_validator).collect())
+                .unwrap_or_default();
+            format!("v.union({})", variants.join(", "))
+        }
+        "object" => match data_type["properties"].as_object() {
+            Some(props) if !props.is_empty() => {
+                let names: Vec<&str> = props.keys().map(String::as_str).collect();
+                format!("v.object({{ {} }})", names.join(", "))
+            }
+            _ => "v.object({})".to_string(),
+        },
+        other => format!("<{other}>"),
+    }
+}
+
+/// Simplify every descriptor of the schema and functions with [`normalize`].
+pub(crate) fn normalize_model(schema: &mut ConvexSchema, functions: &mut [ConvexFunction])
+{
+    for column in schema.tables.iter_mut().flat_map(|table| table.columns.iter_mut()) {
+        normalize(&mut column.data_type);
+    }
+    for func in functions {
+        for param in &mut func.params {
+            normalize(&mut param.data_type);
+        }
+        for data_type in [&mut func.return_type, &mut func.args_validator].into_iter().flatten() {
+            normalize(data_type);
+        }
+    }
+}
+
+/// Drop the tables that no function reaches, for
+/// [`Configuration::prune_unreachable`](crate::Configuration::prune_unreachable).
+///
+/// The functions' args and returns reach the tables whose ids or document
+/// shapes they contain, and each reached table reaches the tables of its own
+/// id fields. `keep` names tables to keep regardless.
+pub(crate) fn prune_unreachable(schema: &mut ConvexSchema, functions: &[ConvexFunction], keep: &HashSet<String>)
+{
+    let mut reached: HashSet<String> = keep.clone();
+    let mut pending: Vec<&JsonValue> = functions
+        .iter()
+        .flat_map(|func| {
+            func.params
+                .iter()
+                .map(|param| &param.data_type)
+                .chain(func.return_type.iter())
+                .chain(func.args_validator.iter())
+        })
+        .collect();
+    let mut expanded: HashSet<String> = HashSet::new();
+    loop {
+        while let Some(data_type) = pending.pop() {
+            collect_table_refs(data_type, &schema.tables, &mut reached);
+        }
+        // Follow the id fields of tables reached since the last pass
+        let next: Vec<&ConvexTable> = schema
+            .tables
+            .iter()
+            .filter(|table| reached.contains(&table.name) && expanded.insert(table.name.clone()))
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+        pending.extend(
+            next.iter()
+                .flat_map(|table| table.columns.iter().map(|column| &column.data_type)),
+        );
+    }
+    schema.tables.retain(|table| reached.contains(&table.name));
+}
+
+/// Add the tables whose ids or document shapes appear in `data_type`.
+fn collect_table_refs(data_type: &JsonValue, tables: &[ConvexTable], reached: &mut HashSet<String>)
+{
+    match data_type {
+        JsonValue::Object(map) => {
+            if data_type["type"] == "id" {
+                if let Some(table) = data_type["tableName"].as_str() {
+                    reached.insert(table.to_string());
+                }
+            }
+            if let Some(props) = data_type["properties"].as_object().filter(|_| data_type["type"] == "object") {
+                if let Some(struct_name) = try_match_table_shape(props, tables) {
+                    let table = tables.iter().find(|table| table_struct_name(&table.name) == struct_name);
+                    reached.extend(table.map(|table| table.name.clone()));
+                }
+            }
+            for value in map.values() {
+                collect_table_refs(value, tables, reached);
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                collect_table_refs(value, tables, reached);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Simplify the unions in a descriptor, innermost first, without changing what
+/// it accepts:
+///
+/// - a union variant that is itself a union is replaced by its variants
+///   (`v.union(v.union(a, b), c)` becomes `v.union(a, b, c)`)
+/// - structurally identical variants are kept once, in first-seen order
+/// - a union left with a single variant becomes that variant
+pub(crate) fn normalize(data_type: &mut JsonValue)
+{
+    match data_type["type"].as_str() {
+        Some("array") => normalize(&mut data_type["elements"]),
+        Some("optional") => normalize(&mut data_type["inner"]),
+        Some("record") => {
+            normalize(&mut data_type["keyType"]);
+            normalize(&mut data_type["valueType"]);
+        }
+        Some("object") => {
+            if let Some(props) = data_type["properties"].as_object_mut() {
+                props.values_mut().for_each(normalize);
+            }
+        }
+        Some("union") => {
+            let Some(variants) = data_type["variants"].as_array_mut() else {
+                return;
+            };
+            let mut flat: Vec<JsonValue> = Vec::new();
+            for mut variant in variants.drain(..) {
+                normalize(&mut variant);
+                let nested = match variant["type"].as_str() {
+                    Some("union") => variant["variants"].as_array_mut().map(std::mem::take),
+                    _ => None,
+                };
+                for variant in nested.unwrap_or_else(|| vec![variant]) {
+                    if !flat.contains(&variant) {
+                        flat.push(variant);
+                    }
+                }
+            }
+            if flat.len() == 1 {
+                *data_type = flat.remove(0);
+            } else {
+                *variants = flat;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The `T` of a descriptor that maps to `Option<T>`: the inner type of
+/// `v.optional(T)`, or the only other variant of `v.union(T, v.null())`.
+pub(crate) fn optional_inner(data_type: &JsonValue) -> Option<&JsonValue>
+{
+    match data_type["type"].as_str() {
+        Some("optional") => Some(&data_type["inner"]),
+        Some("union") => {
+            let variants = data_type["variants"].as_array()?;
+            let (nulls, others): (Vec<&JsonValue>, Vec<&JsonValue>) =
+                variants.iter().partition(|variant| variant["type"].as_str() == Some("null"));
+            match (nulls.len(), others.as_slice()) {
+                (1, [inner]) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the Rust type of `data_type` implements `Display`: scalars, ids,
+/// literals, and unions of string literals (generated as enums).
+pub(crate) fn is_displayable(data_type: &JsonValue) -> bool
+{
+    match data_type["type"].as_str() {
+        Some("string" | "number" | "int64" | "boolean" | "id" | "literal") => true,
+        Some("union") => data_type["variants"].as_array().is_some_and(|variants| {
+            variants
+                .iter()
+                .all(|variant| variant["type"].as_str() == Some("literal") && variant["value"].is_string())
+        }),
+        _ => false,
+    }
+}
+
+/// The element properties of an array-of-object descriptor, also behind
+/// `v.optional(...)`.
+pub(crate) fn object_array_properties(data_type: &JsonValue) -> Option<&serde_json::Map<String, JsonValue>>
+{
+    let array = optional_inner(data_type).unwrap_or(data_type);
+    if array["type"].as_str() != Some("array") || array["elements"]["type"].as_str() != Some("object") {
+        return None;
+    }
+    array["elements"]["properties"].as_object().filter(|props| !props.is_empty())
+}
+
+/// Whether an object property is a required `v.string()`, as a collection key must be.
+pub(crate) fn is_required_string(data_type: &JsonValue) -> bool
+{
+    data_type["type"].as_str() == Some("string")
+}
+
+/// The values of a union made only of string literals.
+pub(crate) fn string_literal_values(data_type: &JsonValue) -> Option<Vec<&str>>
+{
+    if data_type["type"].as_str() != Some("union") {
+        return None;
+    }
+    let variants = data_type["variants"].as_array().filter(|variants| !variants.is_empty())?;
+    variants
+        .iter()
+        .map(|variant| match variant["type"].as_str() {
+            Some("literal") => variant["value"].as_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether every value `data_type` accepts is a JSON object: an object, a
+/// record, `v.any()`, or a union of those. Convex calls a function with an
+/// object of args, so only such validators can be an args validator.
+pub(crate) fn is_object_like(data_type: &JsonValue) -> bool
+{
+    match data_type["type"].as_str().unwrap_or("unknown") {
+        "object" | "record" | "any" => true,
+        "union" => data_type["variants"]
+            .as_array()
+            .is_some_and(|variants| !variants.is_empty() && variants.iter().all(is_object_like)),
+        _ => false,
+    }
+}
+
+/// Whether the JSON `value` is something the validator `data_type` accepts.
+///
+/// Used to check arg defaults before they are baked into generated code.
+/// Object values may leave out optional fields but not add unknown ones, and
+/// an optional field may be `null`, which is how a `None` default serializes.
+pub(crate) fn accepts(data_type: &JsonValue, value: &JsonValue) -> bool
+{
+    accepts_value(data_type, value, true)
+}
+
+/// Whether the JSON `value` is a document Convex would store under the
+/// validator `data_type`.
+///
+/// Like [`accepts`], but an optional field is either left out or accepted by
+/// its inner validator: Convex rejects `null` for `v.optional(v.number())`.
+/// Used to check documents before they are written for `npx convex import`.
+pub(crate) fn accepts_document(data_type: &JsonValue, value: &JsonValue) -> bool
+{
+    accepts_value(data_type, value, false)
+}
+
+fn accepts_value(data_type: &JsonValue, value: &JsonValue, optional_null: bool) -> bool
+{
+    let accepts = |data_type: &JsonValue, value: &JsonValue| accepts_value(data_type, value, optional_null);
+    match data_type["type"].as_str().unwrap_or("unknown") {
+        "string" | "id" => value.is_string(),
+        "number" => value.is_number(),
+        "int64" => value.as_i64().is_some(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "literal" => *value == data_type["value"],
+        "array" => value
+            .as_array()
+            .is_some_and(|items| items.iter().all(|item| accepts(&data_type["elements"], item))),
+        "optional" => (optional_null && value.is_null()) || accepts(&data_type["inner"], value),
+        "record" => value
+            .as_object()
+            .is_some_and(|entries| entries.values().all(|entry| accepts(&data_type["valueType"], entry))),
+        "union" => data_type["variants"]
+            .as_array()
+            .is_some_and(|variants| variants.iter().any(|variant| accepts(variant, value))),
+        "object" => {
+            let (Some(props), Some(fields)) = (data_type["properties"].as_object(), value.as_object()) else {
+                return value.is_object();
+            };
+            fields.keys().all(|key| props.contains_key(key))
+                && props.iter().all(|(name, prop)| match fields.get(name) {
+                    Some(field) => accepts(prop, field),
+                    None => prop["type"].as_str() == Some("optional"),
+                })
+        }
+        _ => true,
+    }
+}
+
+/// A location inside a descriptor that maps to `serde_json::Value` instead of a
+/// concrete Rust type.
+#[derive(Debug, Clone)]
+pub(crate) struct UntypedHole
+{
+    /// Dotted path from the root descriptor (e.g. `profile.settings`, `tags[]`).
+    pub(crate) path: String,
+    /// Why the location couldn't be typed.
+    pub(crate) reason: String,
+}
+
+/// Walk a descriptor and collect every location that codegen maps to
+/// `serde_json::Value`.
+///
+/// `root` names the descriptor itself (e.g. the column or parameter name) and
+/// prefixes every reported path.
+pub(crate) fn untyped_holes(data_type: &JsonValue, root: &str) -> Vec<UntypedHole>
+{
+    let mut holes = Vec::new();
+    collect_untyped_holes(data_type, root, &mut holes);
+    holes
+}
+
+fn collect_untyped_holes(data_type: &JsonValue, path: &str, holes: &mut Vec<UntypedHole>)
+{
+    let mut hole = |reason: String| {
+        holes.push(UntypedHole {
+            path: path.to_string(),
+            reason,
+        })
+    };
+
+    match data_type["type"].as_str() {
+        Some("string" | "number" | "boolean" | "null" | "int64" | "bytes" | "id" | "literal") => {}
+        Some("any") => hole("v.any() maps to serde_json::Value".to_string()),
+        Some("array") => collect_untyped_holes(&data_type["elements"], &format!("{path}[]"), holes),
+        Some("optional") => collect_untyped_holes(&data_type["inner"], path, holes),
+        Some("record") => {
+            collect_untyped_holes(&data_type["keyType"], &format!("{path}{{key}}"), holes);
+            collect_untyped_holes(&data_type["valueType"], &format!("{path}{{value}}"), holes);
+        }
+        Some("union") => match data_type["variants"].as_array() {
+            Some(variants) if !variants.is_empty() => {
+                for (idx, variant) in variants.iter().enumerate() {
+                    collect_untyped_holes(variant, &format!("{path}|{idx}"), holes);
+                }
+            }
+            _ => hole("empty v.union() maps to serde_json::Value".to_string()),
+        },
+        Some("object") => match data_type["properties"].as_object() {
+            Some(props) if !props.is_empty() => {
+                for (name, field) in props {
+                    collect_untyped_holes(field, &format!("{path}.{name}"), holes);
+                }
+            }
+            _ => hole("empty v.object({}) maps to serde_json::Value".to_string()),
+        },
+        Some(other) => hole(format!("unrecognized validator `{other}` maps to serde_json::Value")),
+        None => hole("descriptor without a type maps to serde_json::Value".to_string()),
+    }
+}
@@ -0,0 +1,416 @@
+//! `// typegen:` comment directives in the schema and function files.
+//!
+//! `// typegen:default <json>` on the line above an arg of an exported
+//! function gives that arg a default, the same as an
+//! [`crate::Configuration::arg_defaults`] entry:
+//!
+//! ```ts
+//! export const search = query({
+//!   args: {
+//!     text: v.string(),
+//!     // typegen:default "en"
+//!     locale: v.optional(v.string()),
+//!   },
+//!   ...
+//! ```
+//!
+//! `// typegen:deprecated <note>` on the line above an exported function marks
+//! it deprecated, the same as a
+//! [`crate::Configuration::deprecated_functions`] entry:
+//!
+//! ```ts
+//! // typegen:deprecated use players:createV2
+//! export const create = mutation({ ... });
+//! ```
+//!
+//! `// typegen:redact` on the line above an arg of an exported function hides
+//! its value from the args struct's `Debug`, the same as a
+//! [`crate::Configuration::redacted_args`] entry:
+//!
+//! ```ts
+//! export const signIn = mutation({
+//!   args: {
+//!     email: v.string(),
+//!     // typegen:redact
+//!     password: v.string(),
+//!   },
+//!   ...
+//! ```
+//!
+//! `// typegen:timeout <duration>` on the line above an exported function gives
+//! its `ConvexApi` method a default timeout, the same as a
+//! [`crate::Configuration::function_timeouts`] entry:
+//!
+//! ```ts
+//! // typegen:timeout 30s
+//! export const generate = action({ ... });
+//! ```
+//!
+//! `// typegen:priority <high|normal|low>` on the line above an exported
+//! function sets the priority the generated client reports for it, the same as
+//! a [`crate::Configuration::function_priorities`] entry:
+//!
+//! ```ts
+//! // typegen:priority low
+//! export const rebuildIndex = action({ ... });
+//! ```
+//!
+//! `// typegen:format <date-time|date>` on the line above a field of a table in
+//! the schema types that string field as a date, the same as a
+//! [`crate::FieldOverride::format`] in `field_overrides`:
+//!
+//! ```ts
+//! events: defineTable({
+//!   // typegen:format date-time
+//!   startsAt: v.string(),
+//! }),
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::Value as JsonValue;
+
+use crate::ast::module_identity;
+use crate::errors::ConvexTypeGeneratorError;
+use crate::report::GenerationWarning;
+use crate::StringFormat;
+
+const DEFAULT_DIRECTIVE: &str = "// typegen:default";
+const DEPRECATED_DIRECTIVE: &str = "// typegen:deprecated";
+const REDACT_DIRECTIVE: &str = "// typegen:redact";
+const TIMEOUT_DIRECTIVE: &str = "// typegen:timeout";
+const PRIORITY_DIRECTIVE: &str = "// typegen:priority";
+const FORMAT_DIRECTIVE: &str = "// typegen:format";
+
+/// The directives of the schema and every function file.
+#[derive(Default)]
+pub(crate) struct Directives
+{
+    /// Keyed like `arg_defaults` (`"module:function.arg"`).
+    pub(crate) arg_defaults: HashMap<String, JsonValue>,
+    /// Keyed like `deprecated_functions` (`"module:function"`).
+    pub(crate) deprecated_functions: HashMap<String, String>,
+    /// Keyed like `redacted_args` (`"module:function.arg"`).
+    pub(crate) redacted_args: HashSet<String>,
+    /// Keyed like `function_timeouts` (`"module:function"`).
+    pub(crate) function_timeouts: HashMap<String, String>,
+    /// Keyed like `function_priorities` (`"module:function"`).
+    pub(crate) function_priorities: HashMap<String, String>,
+    /// Keyed like `field_overrides` (`"table.field"`).
+    pub(crate) field_formats: HashMap<String, StringFormat>,
+    /// Malformed or misplaced directives.
+    pub(crate) warnings: Vec<GenerationWarning>,
+}
+
+/// Collect the `typegen:` directives of the schema and every function file.
+pub(crate) fn collect(schema_path: &Path, function_paths: &[PathBuf]) -> Result<Directives, ConvexTypeGeneratorError>
+{
+    let mut directives = Directives::default();
+    scan_schema(&read(schema_path)?, schema_path, &mut directives);
+    for path in function_paths {
+        let (_, module_path) = module_identity(path);
+        scan(&read(path)?, &module_path, path, &mut directives);
+    }
+    Ok(directives)
+}
+
+fn read(path: &Path) -> Result<String, ConvexTypeGeneratorError>
+{
+    std::fs::read_to_string(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    })
+}
+
+/// Line-based scan of the schema: a `format` directive applies to the
+/// `name:` on the next code line when that line is a field of the closest
+/// `name: defineTable({` above it, found by counting brackets.
+fn scan_schema(source: &str, path: &Path, directives: &mut Directives)
+{
+    let misplaced = "`typegen:format` must be directly above a field of a table";
+    let warning = |line: usize, message: String| GenerationWarning {
+        location: format!("{}:{}", path.display(), line),
+        message,
+        lint: None,
+    };
+
+    let mut depth = 0usize;
+    // The table's name and the bracket depth of its fields
+    let mut table: Option<(&str, usize)> = None;
+    let mut pending: Option<(usize, StringFormat)> = None;
+    for (idx, text) in source.lines().enumerate() {
+        let line = idx + 1;
+        let text = text.trim();
+
+        if let Some(value) = text.strip_prefix(FORMAT_DIRECTIVE) {
+            match serde_json::from_value(JsonValue::String(value.trim().to_string())) {
+                Ok(format) => pending = Some((line, format)),
+                Err(_) => directives.warnings.push(warning(
+                    line,
+                    format!(
+                        "unknown `typegen:format` value {:?}; expected `date-time` or `date`",
+                        value.trim()
+                    ),
+                )),
+            }
+            continue;
+        }
+        if text.is_empty() || text.starts_with("//") {
+            continue;
+        }
+
+        if table.is_some_and(|(_, field_depth)| depth < field_depth) {
+            table = None;
+        }
+        if let Some((directive_line, format)) = pending.take() {
+            match (table, arg_name(text)) {
+                (Some((table, field_depth)), Some(field)) if depth == field_depth => {
+                    directives.field_formats.insert(format!("{table}.{field}"), format);
+                }
+                _ => directives.warnings.push(warning(directive_line, misplaced.to_string())),
+            }
+        }
+        let code = text.split("//").next().unwrap_or(text);
+        if let Some(name) = arg_name(text).filter(|_| code.contains("defineTable(")) {
+            // `defineTable({` opens two brackets before the fields
+            table = Some((name, depth + 2));
+        }
+        for c in code.chars() {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    if let Some((directive_line, _)) = pending {
+        directives.warnings.push(warning(directive_line, misplaced.to_string()));
+    }
+}
+
+/// Line-based scan: a `default` or `redact` directive applies to the `name:`
+/// on the next code line, inside the closest `export const` above it; a
+/// `deprecated`, `timeout`, or `priority` directive applies to the
+/// `export const` on the next code line.
+fn scan(source: &str, module_path: &str, path: &Path, directives: &mut Directives)
+{
+    let Directives {
+        arg_defaults: defaults,
+        deprecated_functions,
+        redacted_args,
+        function_timeouts,
+        function_priorities,
+        warnings,
+        ..
+    } = directives;
+    let warning = |line: usize, message: String| GenerationWarning {
+        location: format!("{}:{}", path.display(), line),
+        message,
+        lint: None,
+    };
+
+    let mut function: Option<&str> = None;
+    let mut pending: Option<(usize, JsonValue)> = None;
+    let mut pending_deprecation: Option<(usize, String)> = None;
+    let mut pending_redaction: Option<usize> = None;
+    let mut pending_timeout: Option<(usize, String)> = None;
+    let mut pending_priority: Option<(usize, String)> = None;
+    for (idx, text) in source.lines().enumerate() {
+        let line = idx + 1;
+        let text = text.trim();
+
+        if let Some(literal) = text.strip_prefix(DEFAULT_DIRECTIVE) {
+            match serde_json::from_str(literal.trim()) {
+                Ok(value) => pending = Some((line, value)),
+                Err(error) => warnings.push(warning(line, format!("invalid `typegen:default` value: {error}"))),
+            }
+            continue;
+        }
+        if let Some(note) = text.strip_prefix(DEPRECATED_DIRECTIVE) {
+            pending_deprecation = Some((line, note.trim().to_string()));
+            continue;
+        }
+        if let Some(timeout) = text.strip_prefix(TIMEOUT_DIRECTIVE) {
+            let timeout = timeout.trim();
+            match parse_duration(timeout) {
+                Some(_) => pending_timeout = Some((line, timeout.to_string())),
+                None => warnings.push(warning(
+                    line,
+                    format!("invalid `typegen:timeout` value {timeout:?}; expected e.g. `250ms`, `5s`, or `2m`"),
+                )),
+            }
+            continue;
+        }
+        if let Some(priority) = text.strip_prefix(PRIORITY_DIRECTIVE) {
+            let priority = priority.trim();
+            match parse_priority(priority) {
+                Some(_) => pending_priority = Some((line, priority.to_string())),
+                None => warnings.push(warning(
+                    line,
+                    format!("invalid `typegen:priority` value {priority:?}; expected `high`, `normal`, or `low`"),
+                )),
+            }
+            continue;
+        }
+        if text.strip_prefix(REDACT_DIRECTIVE).is_some_and(|rest| rest.trim().is_empty()) {
+            pending_redaction = Some(line);
+            continue;
+        }
+        if text.is_empty() || text.starts_with("//") {
+            continue;
+        }
+
+        let exported = exported_const(text);
+        if let Some(name) = exported {
+            function = Some(name);
+        }
+        if let Some((directive_line, note)) = pending_deprecation.take() {
+            match exported {
+                Some(name) => {
+                    deprecated_functions.insert(format!("{module_path}:{name}"), note);
+                }
+                None => warnings.push(warning(
+                    directive_line,
+                    "`typegen:deprecated` must be directly above an exported function".to_string(),
+                )),
+            }
+        }
+        if let Some((directive_line, timeout)) = pending_timeout.take() {
+            match exported {
+                Some(name) => {
+                    function_timeouts.insert(format!("{module_path}:{name}"), timeout);
+                }
+                None => warnings.push(warning(
+                    directive_line,
+                    "`typegen:timeout` must be directly above an exported function".to_string(),
+                )),
+            }
+        }
+        if let Some((directive_line, priority)) = pending_priority.take() {
+            match exported {
+                Some(name) => {
+                    function_priorities.insert(format!("{module_path}:{name}"), priority);
+                }
+                None => warnings.push(warning(
+                    directive_line,
+                    "`typegen:priority` must be directly above an exported function".to_string(),
+                )),
+            }
+        }
+        if let Some(directive_line) = pending_redaction.take() {
+            match (function, arg_name(text)) {
+                (Some(function), Some(arg)) => {
+                    redacted_args.insert(format!("{module_path}:{function}.{arg}"));
+                }
+                _ => warnings.push(warning(
+                    directive_line,
+                    "`typegen:redact` must be directly above an arg of an exported function".to_string(),
+                )),
+            }
+        }
+        let Some((directive_line, value)) = pending.take() else {
+            continue;
+        };
+        match (function, arg_name(text)) {
+            (Some(function), Some(arg)) => {
+                defaults.insert(format!("{module_path}:{function}.{arg}"), value);
+            }
+            _ => warnings.push(warning(
+                directive_line,
+                "`typegen:default` must be directly above an arg of an exported function".to_string(),
+            )),
+        }
+    }
+
+    if let Some((directive_line, _)) = pending {
+        warnings.push(warning(
+            directive_line,
+            "`typegen:default` must be directly above an arg of an exported function".to_string(),
+        ));
+    }
+    if let Some((directive_line, _)) = pending_deprecation {
+        warnings.push(warning(
+            directive_line,
+            "`typegen:deprecated` must be directly above an exported function".to_string(),
+        ));
+    }
+    if let Some((directive_line, _)) = pending_timeout {
+        warnings.push(warning(
+            directive_line,
+            "`typegen:timeout` must be directly above an exported function".to_string(),
+        ));
+    }
+    if let Some((directive_line, _)) = pending_priority {
+        warnings.push(warning(
+            directive_line,
+            "`typegen:priority` must be directly above an exported function".to_string(),
+        ));
+    }
+    if let Some(directive_line) = pending_redaction {
+        warnings.push(warning(
+            directive_line,
+            "`typegen:redact` must be directly above an arg of an exported function".to_string(),
+        ));
+    }
+}
+
+/// A duration written as a whole number of milliseconds, seconds, or minutes,
+/// such as `250ms`, `5s`, or `2m`. Zero is rejected, since every call would
+/// time out.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration>
+{
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let amount: u64 = text[..split].parse().ok().filter(|amount| *amount > 0)?;
+    match &text[split..] {
+        "ms" => Some(Duration::from_millis(amount)),
+        "s" => Some(Duration::from_secs(amount)),
+        "m" => amount.checked_mul(60).map(Duration::from_secs),
+        _ => None,
+    }
+}
+
+/// The `CallPriority` variant a priority is written as: `high`, `normal`, or
+/// `low`.
+pub(crate) fn parse_priority(text: &str) -> Option<&'static str>
+{
+    match text {
+        "high" => Some("High"),
+        "normal" => Some("Normal"),
+        "low" => Some("Low"),
+        _ => None,
+    }
+}
+
+/// `NAME` of an `export const NAME = ...` line.
+fn exported_const(line: &str) -> Option<&str>
+{
+    identifier(line.strip_prefix("export const ")?.trim_start())
+}
+
+/// The property name of a `name: ...` or `"name": ...` line.
+fn arg_name(line: &str) -> Option<&str>
+{
+    let (name, rest) = match line.strip_prefix(['"', '\'']) {
+        Some(quoted) => {
+            let end = quoted.find(['"', '\''])?;
+            (&quoted[..end], &quoted[end + 1..])
+        }
+        None => {
+            let name = identifier(line)?;
+            (name, &line[name.len()..])
+        }
+    };
+    rest.trim_start().starts_with(':').then_some(name)
+}
+
+/// The leading JS identifier of `text`.
+fn identifier(text: &str) -> Option<&str>
+{
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    (end > 0).then(|| &text[..end])
+}
@@ -0,0 +1,502 @@
+//! Embedded extraction — runs the schema and function files in QuickJS,
+//! inside the build, with no external JS runtime.
+//!
+//! This is the [`ExtractionBackend::Embedded`](crate::ExtractionBackend::Embedded)
+//! path, compiled in with the `embedded` feature. Each module is stripped of
+//! its TypeScript with oxc as it loads. Imports are served the way the Bun
+//! plugin in `js/extractor.ts` serves them: the Convex packages and
+//! `_generated` files by the mocks in `js/mocks`, custom validator modules by
+//! a module of their factories, and `helper_stubs` patterns by their stub
+//! file. `convex/values` is always `js/mocks/convex_values.ts`, so nothing is
+//! read from `node_modules`. The steps in `js/run.ts` produce the same records
+//! as under Bun, which [`extract::read_records`] converts.
+//!
+//! Files that start with `"use node"` need Node's APIs, so they are handed to
+//! Bun in a single run and listed in `GenerationReport::bun_fallbacks`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use oxc::allocator::Allocator;
+use oxc::codegen::Codegen;
+use oxc::parser::Parser;
+use oxc::semantic::SemanticBuilder;
+use oxc::span::SourceType;
+use oxc::transformer::{TransformOptions, Transformer};
+use regex::Regex;
+use rquickjs::function::Rest;
+use rquickjs::loader::{Loader, Resolver};
+use rquickjs::module::Declared;
+use rquickjs::prelude::Coerced;
+use rquickjs::{CatchResultExt, Context, Ctx, Error, Function, Module, Object, Promise, Runtime};
+
+use crate::errors::ConvexTypeGeneratorError;
+use crate::extract::ExtractOptions;
+use crate::report::{self, BunFallback};
+use crate::types::ConvexFunction;
+use crate::{ast, extract, CustomValidatorHandler, Extraction};
+
+/// Prefix of the module names that serve custom validator factories.
+const CUSTOM_MODULE_PREFIX: &str = "convex-typegen-custom:";
+
+/// Extract in QuickJS, handing `"use node"` files to Bun.
+pub(crate) fn extract(options: &ExtractOptions, function_paths: &[PathBuf]) -> Result<Extraction, ConvexTypeGeneratorError>
+{
+    let ExtractOptions {
+        schema_path,
+        helper_stubs,
+        custom_validators,
+        fail_fast,
+        ..
+    } = *options;
+    let (node_paths, embedded_paths): (Vec<PathBuf>, Vec<PathBuf>) =
+        function_paths.iter().cloned().partition(|path| uses_node(path));
+
+    let (schema, functions, mut resolve_trace, mut diagnostics) =
+        run(schema_path, &embedded_paths, helper_stubs, custom_validators, fail_fast)?;
+    // With `fail_fast` the first failure is all that's reported, so Bun needn't run
+    if node_paths.is_empty() || (fail_fast && !diagnostics.is_empty()) {
+        return Ok(Extraction {
+            schema,
+            functions,
+            bun_fallbacks: Vec::new(),
+            resolve_trace,
+            diagnostics,
+        });
+    }
+
+    // Bun evaluates the schema again; the embedded schema is kept
+    let (_, bun_functions, bun_trace, bun_diagnostics) = extract::extract(options, &node_paths)?;
+    resolve_trace.extend(bun_trace);
+    diagnostics.extend(bun_diagnostics);
+
+    // Put the functions back in config order
+    let mut by_module: HashMap<(String, String), Vec<ConvexFunction>> = HashMap::new();
+    for func in functions.into_iter().chain(bun_functions) {
+        let module_path = func.module_path.clone().unwrap_or_else(|| func.file_name.clone());
+        by_module.entry((func.file_name.clone(), module_path)).or_default().push(func);
+    }
+    let functions = function_paths
+        .iter()
+        .flat_map(|path| by_module.remove(&ast::module_identity(path)).unwrap_or_default())
+        .collect();
+
+    Ok(Extraction {
+        schema,
+        functions,
+        bun_fallbacks: node_paths
+            .into_iter()
+            .map(|file| BunFallback {
+                file,
+                reason: "the file starts with \"use node\"".to_string(),
+            })
+            .collect(),
+        resolve_trace,
+        diagnostics,
+    })
+}
+
+/// Whether the file's first statement is the `"use node"` directive, after
+/// any comments.
+fn uses_node(path: &Path) -> bool
+{
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let mut rest = source.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        if let Some(line) = rest.strip_prefix("//") {
+            rest = line.split_once('\n').map_or("", |(_, after)| after).trim_start();
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            rest = block.split_once("*/").map_or("", |(_, after)| after).trim_start();
+        } else {
+            return rest.starts_with("\"use node\"") || rest.starts_with("'use node'");
+        }
+    }
+}
+
+/// Run `js/run.ts` in a fresh QuickJS runtime and convert its records.
+fn run(
+    schema_path: &Path,
+    function_paths: &[PathBuf],
+    helper_stubs: &HashMap<String, PathBuf>,
+    custom_validators: &HashMap<String, CustomValidatorHandler>,
+    fail_fast: bool,
+) -> Result<extract::Extracted, ConvexTypeGeneratorError>
+{
+    let js_dir = dunce::simplified(Path::new(env!("CARGO_MANIFEST_DIR"))).join("js");
+    let failed = |details: String| ConvexTypeGeneratorError::ExtractionFailed {
+        causes: extract::diagnose(&details),
+        details,
+    };
+
+    let schema_abs = absolute(schema_path)?;
+    let function_abs = function_paths
+        .iter()
+        .map(|path| absolute(path).map(|path| path.display().to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let runtime = Runtime::new().map_err(|e| failed(format!("Failed to start QuickJS: {e}")))?;
+    let tracing = report::trace_resolve_from_env();
+    let resolver = ModuleResolver::new(&js_dir, helper_stubs, custom_validators, tracing).map_err(failed)?;
+    runtime.set_loader(resolver, ModuleLoader { js_dir: js_dir.clone() });
+    let context = Context::full(&runtime).map_err(|e| failed(format!("Failed to start QuickJS: {e}")))?;
+
+    let records = Rc::new(RefCell::new(String::new()));
+    context.with(|ctx| {
+        let result = (|| {
+            // The mocks read their settings from `process.env`, as under Bun
+            let env = serde_json::json!({
+                "TYPEGEN_CUSTOM_VALIDATORS": extract::custom_validators_json(custom_validators),
+                "TYPEGEN_TRACE_RESOLVE": if tracing { "1" } else { "0" },
+                "TYPEGEN_FAIL_FAST": if fail_fast { "1" } else { "0" },
+            });
+            let process = Object::new(ctx.clone())?;
+            process.set("env", ctx.json_parse(env.to_string())?)?;
+            ctx.globals().set("process", process)?;
+            ctx.globals().set("console", console(&ctx)?)?;
+
+            // Imports are traced through the same list as everything else
+            let trace: Object = Module::import(&ctx, js_dir.join("mocks/trace.ts").display().to_string())?.finish()?;
+            ctx.globals().set("__typegenTrace", trace.get::<_, Function>("trace")?)?;
+
+            let entry: Object = Module::import(&ctx, js_dir.join("run.ts").display().to_string())?.finish()?;
+            let run: Function = entry.get("run")?;
+            let sink = Rc::clone(&records);
+            let emit = Function::new(ctx.clone(), move |record: Object| -> rquickjs::Result<()> {
+                let line = record
+                    .ctx()
+                    .json_stringify(record.clone())?
+                    .map(|s| s.to_string())
+                    .transpose()?;
+                let mut sink = sink.borrow_mut();
+                sink.push_str(&line.unwrap_or_default());
+                sink.push('\n');
+                Ok(())
+            })?;
+            let done: Promise = run.call((schema_abs.display().to_string(), function_abs.clone(), emit))?;
+            done.finish::<()>()
+        })();
+        result.catch(&ctx).map_err(|e| failed(e.to_string()))
+    })?;
+
+    let records = records.borrow();
+    extract::read_records(records.as_bytes())?.ok_or_else(|| ConvexTypeGeneratorError::ExtractionFailed {
+        details: "the embedded extractor ended before its end record".to_string(),
+        causes: Vec::new(),
+    })
+}
+
+/// `console.*` for user code; the output goes to `log` at debug level, since
+/// stdout carries no records here.
+fn console<'js>(ctx: &Ctx<'js>) -> rquickjs::Result<Object<'js>>
+{
+    let console = Object::new(ctx.clone())?;
+    for name in ["log", "info", "warn", "error", "debug"] {
+        let print = Function::new(ctx.clone(), |args: Rest<Coerced<String>>| {
+            let words: Vec<String> = args.0.into_iter().map(|arg| arg.0).collect();
+            log::debug!(target: "convex_typegen", "{}", words.join(" "));
+        })?;
+        console.set(name, print)?;
+    }
+    Ok(console)
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, ConvexTypeGeneratorError>
+{
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .map_err(|e| ConvexTypeGeneratorError::IOError {
+            file: path.display().to_string(),
+            error: e,
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Module resolution — mirrors the Bun plugin in js/extractor.ts
+// ---------------------------------------------------------------------------
+
+/// Where an import is served from, checked in order.
+struct Rule
+{
+    filter: Regex,
+    /// The module name: a file, or a custom validator module.
+    target: String,
+    /// How the trace describes the module; `None` for imports Bun doesn't
+    /// intercept either.
+    served: Option<String>,
+}
+
+struct ModuleResolver
+{
+    rules: Vec<Rule>,
+    tracing: bool,
+}
+
+impl ModuleResolver
+{
+    fn new(
+        js_dir: &Path,
+        helper_stubs: &HashMap<String, PathBuf>,
+        custom_validators: &HashMap<String, CustomValidatorHandler>,
+        tracing: bool,
+    ) -> Result<Self, String>
+    {
+        let mock = |file: &str| js_dir.join("mocks").join(file).display().to_string();
+        let rule = |filter: &str, target: String, served: Option<String>| {
+            Regex::new(filter)
+                .map(|filter| Rule { filter, target, served })
+                .map_err(|e| format!("invalid import pattern \"{filter}\": {e}"))
+        };
+        let mut rules = vec![
+            rule(
+                "^convex/server$",
+                mock("convex_server.ts"),
+                Some("mocks/convex_server.ts".to_string()),
+            )?,
+            rule("^convex/values$", mock("convex_values.ts"), None)?,
+            rule(
+                "/_generated/api",
+                mock("convex_api.ts"),
+                Some("mocks/convex_api.ts".to_string()),
+            )?,
+            rule(
+                "/_generated/server",
+                mock("convex_server.ts"),
+                Some("mocks/convex_server.ts".to_string()),
+            )?,
+            rule(
+                "/_generated/dataModel",
+                mock("convex_api.ts"),
+                Some("mocks/convex_api.ts".to_string()),
+            )?,
+        ];
+
+        // Custom validator factories win over the helper stubs for the same module
+        let mut custom_modules: Vec<(&str, Vec<&str>)> = Vec::new();
+        let mut names: Vec<&String> = custom_validators.keys().collect();
+        names.sort();
+        for name in names {
+            let module = custom_validators[name].module.as_str();
+            match custom_modules.iter_mut().find(|(m, _)| *m == module) {
+                Some((_, names)) => names.push(name),
+                None => custom_modules.push((module, vec![name])),
+            }
+        }
+        for (module, names) in custom_modules {
+            let stem = module.trim_start_matches("./");
+            let stem = stem.strip_suffix(".ts").or_else(|| stem.strip_suffix(".js")).unwrap_or(stem);
+            let escaped = regex::escape(stem).replace('/', r"[/\\]");
+            rules.push(rule(
+                &format!(r"(^|[/\\]){escaped}(\.[jt]s)?$"),
+                format!("{CUSTOM_MODULE_PREFIX}{}", names.join(",")),
+                Some(format!("custom validators {}", names.join(", "))),
+            )?);
+        }
+
+        let mut patterns: Vec<(&String, &PathBuf)> = helper_stubs.iter().collect();
+        patterns.sort();
+        for (pattern, stub) in patterns {
+            // A `/`, escaped or not, also matches `\`
+            let filter = pattern.replace(r"\/", "/").replace('/', r"[/\\]");
+            rules.push(rule(
+                &filter,
+                stub.display().to_string(),
+                Some(format!("helper stub {}", stub.display())),
+            )?);
+        }
+
+        rules.extend([
+            rule(
+                "^@convex-dev/auth/server$",
+                mock("convex_auth_server.ts"),
+                Some("mocks/convex_auth_server.ts".to_string()),
+            )?,
+            rule(
+                "^(@convex-dev/auth|@auth/core)/providers/",
+                mock("convex_auth_providers.ts"),
+                Some("mocks/convex_auth_providers.ts".to_string()),
+            )?,
+            rule(
+                r"^convex-helpers/validators(\.js)?$",
+                mock("convex_helpers_validators.ts"),
+                Some("mocks/convex_helpers_validators.ts".to_string()),
+            )?,
+            rule(
+                "^@convex-dev/(rate-limiter|aggregate|workflow)$",
+                mock("convex_components.ts"),
+                Some("mocks/convex_components.ts".to_string()),
+            )?,
+            rule(
+                r"^@convex-dev/[\w-]+/convex\.config(\.js)?$",
+                mock("convex_component_config.ts"),
+                Some("mocks/convex_component_config.ts".to_string()),
+            )?,
+        ]);
+        Ok(Self { rules, tracing })
+    }
+
+    /// Record a served import in the resolution trace.
+    fn trace(ctx: &Ctx<'_>, specifier: &str, importer: &str, served: &str) -> rquickjs::Result<()>
+    {
+        let event = serde_json::json!({ "kind": "import", "specifier": specifier, "importer": importer, "served": served });
+        let trace: Function = ctx.globals().get("__typegenTrace")?;
+        trace.call((ctx.json_parse(event.to_string())?,))
+    }
+}
+
+impl Resolver for ModuleResolver
+{
+    fn resolve<'js>(&mut self, ctx: &Ctx<'js>, base: &str, name: &str) -> rquickjs::Result<String>
+    {
+        if let Some(rule) = self.rules.iter().find(|rule| rule.filter.is_match(name)) {
+            let target = if rule.target.starts_with(CUSTOM_MODULE_PREFIX) {
+                rule.target.clone()
+            } else {
+                resolve_file(Path::new(&rule.target)).ok_or_else(|| {
+                    Error::new_resolving_message(base, name, format!("Cannot find module \"{}\"", rule.target))
+                })?
+            };
+            if let Some(served) = rule.served.as_deref().filter(|_| self.tracing) {
+                Self::trace(ctx, name, base, served)?;
+            }
+            return Ok(target);
+        }
+
+        let path = Path::new(name);
+        let candidate = if path.is_absolute() {
+            path.to_path_buf()
+        } else if name.starts_with("./") || name.starts_with("../") {
+            Path::new(base).parent().unwrap_or(Path::new("")).join(path)
+        } else {
+            let message = format!("Cannot find package \"{name}\"; {}", extract::NO_PACKAGES);
+            return Err(Error::new_resolving_message(base, name, message));
+        };
+        resolve_file(&normalize(&candidate))
+            .ok_or_else(|| Error::new_resolving_message(base, name, format!("Cannot find module \"{name}\"")))
+    }
+}
+
+/// The file an import of `path` loads, trying the extensions and index files
+/// Bun tries. A `.js` import also finds the `.ts` file it was compiled from.
+fn resolve_file(path: &Path) -> Option<String>
+{
+    let mut candidates = vec![path.to_path_buf()];
+    if let Some(ext @ ("js" | "jsx" | "mjs")) = path.extension().and_then(|ext| ext.to_str()) {
+        let ts = match ext {
+            "jsx" => "tsx",
+            "mjs" => "mts",
+            _ => "ts",
+        };
+        candidates.push(path.with_extension(ts));
+    }
+    for ext in ["ts", "tsx", "js", "mjs", "json"] {
+        let mut with_ext = path.as_os_str().to_os_string();
+        with_ext.push(format!(".{ext}"));
+        candidates.push(PathBuf::from(with_ext));
+    }
+    for index in ["index.ts", "index.tsx", "index.js"] {
+        candidates.push(path.join(index));
+    }
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .map(|file| file.display().to_string())
+}
+
+/// `path` without `.` and `..` components, so every import of a file yields
+/// the same module name and the module runs once.
+fn normalize(path: &Path) -> PathBuf
+{
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// ---------------------------------------------------------------------------
+// Module loading — strips TypeScript with oxc
+// ---------------------------------------------------------------------------
+
+struct ModuleLoader
+{
+    js_dir: PathBuf,
+}
+
+impl Loader for ModuleLoader
+{
+    fn load<'js>(&mut self, ctx: &Ctx<'js>, name: &str) -> rquickjs::Result<Module<'js, Declared>>
+    {
+        if let Some(names) = name.strip_prefix(CUSTOM_MODULE_PREFIX) {
+            return Module::declare(ctx.clone(), name, custom_module(&self.js_dir, names));
+        }
+
+        let source = std::fs::read_to_string(name).map_err(|e| Error::new_loading_message(name, e.to_string()))?;
+        let code = if name.ends_with(".json") {
+            format!("export default {source};")
+        } else {
+            strip_types(name, &source).map_err(|message| Error::new_loading_message(name, message))?
+        };
+        Module::declare(ctx.clone(), name, code)
+    }
+}
+
+/// A module exporting each custom validator factory as
+/// `v.literal(CUSTOM_VALIDATOR_PREFIX + name)`, which normalize() swaps for
+/// its descriptor, so the real module never runs.
+fn custom_module(js_dir: &Path, names: &str) -> String
+{
+    let normalize_ts = js_dir.join("mocks/normalize.ts");
+    let mut code = format!(
+        "import {{ v }} from \"convex/values\";\nimport {{ CUSTOM_VALIDATOR_PREFIX }} from {};\n",
+        serde_json::Value::String(normalize_ts.display().to_string())
+    );
+    for name in names.split(',') {
+        code.push_str(&format!(
+            "export const {name} = () => v.literal(CUSTOM_VALIDATOR_PREFIX + {});\n",
+            serde_json::Value::String(name.to_string())
+        ));
+    }
+    code
+}
+
+/// The JavaScript of a TypeScript (or JavaScript) module. Syntax errors read
+/// like Bun's, with an `at <file>:<line>:<column>` line.
+fn strip_types(path: &str, source: &str) -> Result<String, String>
+{
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(path).unwrap_or_else(|_| SourceType::ts());
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    if let Some(error) = parsed.errors.first() {
+        let offset = error
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.first())
+            .map_or(0, |label| label.offset());
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+        return Err(format!("SyntaxError: {error}\n    at {path}:{line}:{column}"));
+    }
+
+    let mut program = parsed.program;
+    let scoping = SemanticBuilder::new().build(&program).semantic.into_scoping();
+    let transformed = Transformer::new(&allocator, Path::new(path), &TransformOptions::default())
+        .build_with_scoping(scoping, &mut program);
+    if let Some(error) = transformed.errors.first() {
+        return Err(format!("error: {error}\n    at {path}"));
+    }
+    Ok(Codegen::new().build(&program).code)
+}
@@ -0,0 +1,276 @@
+//! `CONVEX_TYPEGEN_*` environment-variable overrides for [`Configuration`].
+//!
+//! Each variable replaces one field, so CI can redirect the output or keep Bun
+//! from being downloaded without editing `build.rs`. Scalars take their plain
+//! text form; map-valued fields take a JSON object.
+
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+
+use crate::errors::ConvexTypeGeneratorError;
+use crate::{Configuration, FeatureGates};
+
+/// Every variable [`Configuration::from_env_overrides`] reads, in the order
+/// they are applied.
+pub const ENV_VARS: &[&str] = &[
+    "CONVEX_TYPEGEN_SCHEMA",
+    "CONVEX_TYPEGEN_OUT_FILE",
+    "CONVEX_TYPEGEN_VERSION_TAG",
+    "CONVEX_TYPEGEN_FUNCTIONS",
+    "CONVEX_TYPEGEN_MODEL_INPUT",
+    "CONVEX_TYPEGEN_BASE_DIR",
+    "CONVEX_TYPEGEN_ON_EMPTY_FUNCTION_FILE",
+    "CONVEX_TYPEGEN_HELPER_STUBS",
+    "CONVEX_TYPEGEN_CUSTOM_VALIDATORS",
+    "CONVEX_TYPEGEN_BACKEND",
+    "CONVEX_TYPEGEN_OFFLINE",
+    "CONVEX_TYPEGEN_BUN_PATH",
+    "CONVEX_TYPEGEN_FIELD_OVERRIDES",
+    "CONVEX_TYPEGEN_DISPLAY_FIELDS",
+    "CONVEX_TYPEGEN_COLLECTION_KEYS",
+    "CONVEX_TYPEGEN_ENUM_LABELS",
+    "CONVEX_TYPEGEN_DEFAULT_MISSING_FIELDS",
+    "CONVEX_TYPEGEN_TYPED_IDS",
+    "CONVEX_TYPEGEN_FLATTEN_SYSTEM_FIELDS",
+    "CONVEX_TYPEGEN_ARG_DEFAULTS",
+    "CONVEX_TYPEGEN_FINITE_NUMBER_ARGS",
+    "CONVEX_TYPEGEN_DEPRECATED_FUNCTIONS",
+    "CONVEX_TYPEGEN_REDACTED_ARGS",
+    "CONVEX_TYPEGEN_FUNCTION_TIMEOUTS",
+    "CONVEX_TYPEGEN_FUNCTION_PRIORITIES",
+    "CONVEX_TYPEGEN_METHOD_NAMING",
+    "CONVEX_TYPEGEN_SPLIT_API_BY_FILE",
+    "CONVEX_TYPEGEN_SPLIT_OUTPUT_BY_TABLE",
+    "CONVEX_TYPEGEN_INTERNAL_MODULE",
+    "CONVEX_TYPEGEN_PRUNE_UNREACHABLE",
+    "CONVEX_TYPEGEN_KEEP_TABLES",
+    "CONVEX_TYPEGEN_ARTIFACTS",
+    "CONVEX_TYPEGEN_PREVIOUS_MODEL",
+    "CONVEX_TYPEGEN_EMIT_PREVIOUS_TABLES",
+    "CONVEX_TYPEGEN_EMIT_SHARED_SUBSCRIPTIONS",
+    "CONVEX_TYPEGEN_EMIT_ARC_SUBSCRIPTIONS",
+    "CONVEX_TYPEGEN_EMIT_SNAPSHOT_SUBSCRIPTIONS",
+    "CONVEX_TYPEGEN_EMIT_BUFFERED_SUBSCRIPTIONS",
+    "CONVEX_TYPEGEN_EMIT_RESILIENT_SUBSCRIPTIONS",
+    "CONVEX_TYPEGEN_EMIT_CURSOR_STORE",
+    "CONVEX_TYPEGEN_EMIT_FRONTEND_ADAPTERS",
+    "CONVEX_TYPEGEN_EMIT_TAURI_COMMANDS",
+    "CONVEX_TYPEGEN_EMIT_AXUM_ROUTER",
+    "CONVEX_TYPEGEN_EMIT_TENANT_ROUTER",
+    "CONVEX_TYPEGEN_EMIT_ADMIN_CLIENT",
+    "CONVEX_TYPEGEN_EMIT_TIMEOUTS",
+    "CONVEX_TYPEGEN_EMIT_CONSISTENT_READS",
+    "CONVEX_TYPEGEN_EMIT_OFFLINE_QUEUE",
+    "CONVEX_TYPEGEN_EMIT_IMPORT_WRITERS",
+    "CONVEX_TYPEGEN_EMIT_FIXTURES",
+    "CONVEX_TYPEGEN_EMIT_FUNCTION_REFS",
+    "CONVEX_TYPEGEN_EMIT_SCHEDULE_REQUESTS",
+    "CONVEX_TYPEGEN_EMIT_ENUM_LABELS",
+    "CONVEX_TYPEGEN_DOC_EXAMPLES_MODULE",
+    "CONVEX_TYPEGEN_ALLOW_DEAD_CODE",
+    "CONVEX_TYPEGEN_VERIFY_SYNTAX",
+    "CONVEX_TYPEGEN_MAX_OUTPUT_LINES",
+    "CONVEX_TYPEGEN_FEATURE_GATES",
+    "CONVEX_TYPEGEN_PRINT_SUMMARY",
+    "CONVEX_TYPEGEN_REPORT_FILE",
+    "CONVEX_TYPEGEN_QUIET",
+    "CONVEX_TYPEGEN_LINT",
+    "CONVEX_TYPEGEN_STRICT",
+    "CONVEX_TYPEGEN_FAIL_FAST",
+    "CONVEX_TYPEGEN_CARGO_INTEGRATION",
+];
+
+/// Apply every set `CONVEX_TYPEGEN_*` variable to `config`. Unset and empty
+/// variables leave their field alone.
+pub(crate) fn apply(mut config: Configuration) -> Result<Configuration, ConvexTypeGeneratorError>
+{
+    if let Some(path) = var("CONVEX_TYPEGEN_SCHEMA") {
+        config.schema_path = PathBuf::from(path);
+    }
+    if let Some(path) = var("CONVEX_TYPEGEN_OUT_FILE") {
+        config.out_file = PathBuf::from(path);
+    }
+    if let Some(tag) = var("CONVEX_TYPEGEN_VERSION_TAG") {
+        config.version_tag = (tag != "none").then_some(tag);
+    }
+    if let Some(paths) = var("CONVEX_TYPEGEN_FUNCTIONS") {
+        config.function_paths = std::env::split_paths(&paths).collect();
+    }
+    if let Some(path) = var("CONVEX_TYPEGEN_MODEL_INPUT") {
+        config.model_input = (path != "none").then(|| PathBuf::from(path));
+    }
+    if let Some(path) = var("CONVEX_TYPEGEN_BASE_DIR") {
+        config.base_dir = Some(PathBuf::from(path));
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_ON_EMPTY_FUNCTION_FILE") {
+        config.on_empty_function_file = parse_name("CONVEX_TYPEGEN_ON_EMPTY_FUNCTION_FILE", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_HELPER_STUBS") {
+        config.helper_stubs = parse_json("CONVEX_TYPEGEN_HELPER_STUBS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_CUSTOM_VALIDATORS") {
+        config.custom_validators = parse_json("CONVEX_TYPEGEN_CUSTOM_VALIDATORS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_BACKEND") {
+        config.extraction_backend = parse_name("CONVEX_TYPEGEN_BACKEND", &value)?;
+    }
+    apply_flag("CONVEX_TYPEGEN_OFFLINE", &mut config.offline)?;
+    if let Some(path) = var("CONVEX_TYPEGEN_BUN_PATH") {
+        config.bun_path = (path != "none").then(|| PathBuf::from(path));
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_FIELD_OVERRIDES") {
+        config.field_overrides = parse_json("CONVEX_TYPEGEN_FIELD_OVERRIDES", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_DISPLAY_FIELDS") {
+        config.display_fields = parse_json("CONVEX_TYPEGEN_DISPLAY_FIELDS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_COLLECTION_KEYS") {
+        config.collection_keys = parse_json("CONVEX_TYPEGEN_COLLECTION_KEYS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_ENUM_LABELS") {
+        config.enum_labels = parse_json("CONVEX_TYPEGEN_ENUM_LABELS", &value)?;
+    }
+    apply_flag("CONVEX_TYPEGEN_DEFAULT_MISSING_FIELDS", &mut config.default_missing_fields)?;
+    apply_flag("CONVEX_TYPEGEN_TYPED_IDS", &mut config.typed_ids)?;
+    apply_flag("CONVEX_TYPEGEN_FLATTEN_SYSTEM_FIELDS", &mut config.flatten_system_fields)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_ARG_DEFAULTS") {
+        config.arg_defaults = parse_json("CONVEX_TYPEGEN_ARG_DEFAULTS", &value)?;
+    }
+    apply_flag("CONVEX_TYPEGEN_FINITE_NUMBER_ARGS", &mut config.finite_number_args)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_DEPRECATED_FUNCTIONS") {
+        config.deprecated_functions = parse_json("CONVEX_TYPEGEN_DEPRECATED_FUNCTIONS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_REDACTED_ARGS") {
+        config.redacted_args = parse_json("CONVEX_TYPEGEN_REDACTED_ARGS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_FUNCTION_TIMEOUTS") {
+        config.function_timeouts = parse_json("CONVEX_TYPEGEN_FUNCTION_TIMEOUTS", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_FUNCTION_PRIORITIES") {
+        config.function_priorities = parse_json("CONVEX_TYPEGEN_FUNCTION_PRIORITIES", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_METHOD_NAMING") {
+        config.method_naming = parse_name("CONVEX_TYPEGEN_METHOD_NAMING", &value)?;
+    }
+    apply_flag("CONVEX_TYPEGEN_SPLIT_API_BY_FILE", &mut config.split_api_by_file)?;
+    apply_flag("CONVEX_TYPEGEN_SPLIT_OUTPUT_BY_TABLE", &mut config.split_output_by_table)?;
+    apply_flag("CONVEX_TYPEGEN_INTERNAL_MODULE", &mut config.internal_module)?;
+    apply_flag("CONVEX_TYPEGEN_PRUNE_UNREACHABLE", &mut config.prune_unreachable)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_KEEP_TABLES") {
+        config.keep_tables = parse_json("CONVEX_TYPEGEN_KEEP_TABLES", &value)?;
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_ARTIFACTS") {
+        config.artifacts = parse_json("CONVEX_TYPEGEN_ARTIFACTS", &value)?;
+    }
+    if let Some(path) = var("CONVEX_TYPEGEN_PREVIOUS_MODEL") {
+        config.previous_model = (path != "none").then(|| PathBuf::from(path));
+    }
+    apply_flag("CONVEX_TYPEGEN_EMIT_PREVIOUS_TABLES", &mut config.emit_previous_tables)?;
+    apply_flag(
+        "CONVEX_TYPEGEN_EMIT_SHARED_SUBSCRIPTIONS",
+        &mut config.emit_shared_subscriptions,
+    )?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_ARC_SUBSCRIPTIONS", &mut config.emit_arc_subscriptions)?;
+    apply_flag(
+        "CONVEX_TYPEGEN_EMIT_SNAPSHOT_SUBSCRIPTIONS",
+        &mut config.emit_snapshot_subscriptions,
+    )?;
+    apply_flag(
+        "CONVEX_TYPEGEN_EMIT_BUFFERED_SUBSCRIPTIONS",
+        &mut config.emit_buffered_subscriptions,
+    )?;
+    apply_flag(
+        "CONVEX_TYPEGEN_EMIT_RESILIENT_SUBSCRIPTIONS",
+        &mut config.emit_resilient_subscriptions,
+    )?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_CURSOR_STORE", &mut config.emit_cursor_store)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_FRONTEND_ADAPTERS", &mut config.emit_frontend_adapters)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_TAURI_COMMANDS", &mut config.emit_tauri_commands)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_AXUM_ROUTER", &mut config.emit_axum_router)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_TENANT_ROUTER", &mut config.emit_tenant_router)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_ADMIN_CLIENT", &mut config.emit_admin_client)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_TIMEOUTS", &mut config.emit_timeouts)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_CONSISTENT_READS", &mut config.emit_consistent_reads)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_OFFLINE_QUEUE", &mut config.emit_offline_queue)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_IMPORT_WRITERS", &mut config.emit_import_writers)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_FIXTURES", &mut config.emit_fixtures)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_FUNCTION_REFS", &mut config.emit_function_refs)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_SCHEDULE_REQUESTS", &mut config.emit_schedule_requests)?;
+    apply_flag("CONVEX_TYPEGEN_EMIT_ENUM_LABELS", &mut config.emit_enum_labels)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_DOC_EXAMPLES_MODULE") {
+        config.doc_examples_module = (value != "none").then_some(value);
+    }
+    apply_flag("CONVEX_TYPEGEN_ALLOW_DEAD_CODE", &mut config.allow_dead_code)?;
+    apply_flag("CONVEX_TYPEGEN_VERIFY_SYNTAX", &mut config.verify_syntax)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_MAX_OUTPUT_LINES") {
+        config.max_output_lines = match value.as_str() {
+            "none" => None,
+            lines => Some(
+                lines
+                    .parse()
+                    .map_err(|_| invalid("CONVEX_TYPEGEN_MAX_OUTPUT_LINES", lines, "expected a line count or `none`"))?,
+            ),
+        };
+    }
+    if let Some(value) = var("CONVEX_TYPEGEN_FEATURE_GATES") {
+        config.feature_gates = match value.as_str() {
+            "none" => None,
+            "default" => Some(FeatureGates::default()),
+            json => Some(parse_json("CONVEX_TYPEGEN_FEATURE_GATES", json)?),
+        };
+    }
+    apply_flag("CONVEX_TYPEGEN_PRINT_SUMMARY", &mut config.print_summary)?;
+    if let Some(path) = var("CONVEX_TYPEGEN_REPORT_FILE") {
+        config.report_file = (path != "none").then(|| PathBuf::from(path));
+    }
+    apply_flag("CONVEX_TYPEGEN_QUIET", &mut config.quiet)?;
+    apply_flag("CONVEX_TYPEGEN_LINT", &mut config.lint)?;
+    apply_flag("CONVEX_TYPEGEN_STRICT", &mut config.strict)?;
+    apply_flag("CONVEX_TYPEGEN_FAIL_FAST", &mut config.fail_fast)?;
+    if let Some(value) = var("CONVEX_TYPEGEN_CARGO_INTEGRATION") {
+        config.cargo_integration = parse_json("CONVEX_TYPEGEN_CARGO_INTEGRATION", &value)?;
+    }
+
+    Ok(config)
+}
+
+/// The variable's value, or `None` when it is unset or empty.
+fn var(name: &str) -> Option<String>
+{
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Set `flag` from a `1`/`true`/`yes`/`on` or `0`/`false`/`no`/`off` variable.
+fn apply_flag(name: &str, flag: &mut bool) -> Result<(), ConvexTypeGeneratorError>
+{
+    if let Some(value) = var(name) {
+        *flag = match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => true,
+            "0" | "false" | "no" | "off" => false,
+            _ => return Err(invalid(name, &value, "expected true/false or 1/0")),
+        };
+    }
+    Ok(())
+}
+
+/// A unit enum variant by its snake_case name, e.g. `hybrid` or `error`.
+fn parse_name<T: DeserializeOwned>(name: &str, value: &str) -> Result<T, ConvexTypeGeneratorError>
+{
+    serde_json::from_value(serde_json::Value::String(value.to_ascii_lowercase()))
+        .map_err(|e| invalid(name, value, &e.to_string()))
+}
+
+fn parse_json<T: DeserializeOwned>(name: &str, value: &str) -> Result<T, ConvexTypeGeneratorError>
+{
+    serde_json::from_str(value).map_err(|e| invalid(name, value, &format!("invalid JSON: {e}")))
+}
+
+fn invalid(name: &str, value: &str, details: &str) -> ConvexTypeGeneratorError
+{
+    ConvexTypeGeneratorError::InvalidEnvOverride {
+        var: name.to_string(),
+        value: value.to_string(),
+        details: details.to_string(),
+    }
+}
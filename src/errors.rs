@@ -1,17 +1,27 @@
 use std::fmt;
+use std::path::PathBuf;
 
 /// Errors that can occur during the type generation process.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConvexTypeGeneratorError
 {
     /// The schema file could not be found at the specified path
-    MissingSchemaFile,
+    MissingSchemaFile
+    {
+        /// The resolved absolute path that was looked up
+        path: PathBuf,
+    },
 
     /// The Bun extractor process failed or returned invalid output
     ExtractionFailed
     {
         /// Details about the extraction failure
         details: String,
+        /// Failures recognized in Bun's output, each with the file it points
+        /// at and a suggested fix. Bun's raw output is left out of `details`
+        /// when this is non-empty.
+        causes: Vec<ExtractionCause>,
     },
 
     /// The provided path doesn't have a valid file name component
@@ -20,6 +30,10 @@ pub enum ConvexTypeGeneratorError
     /// The file name contains invalid Unicode characters
     InvalidUnicode(String),
 
+    /// `version_tag` has a character other than ASCII letters, digits, and `_`, or
+    /// doesn't start with a letter
+    InvalidVersionTag(String),
+
     /// Failed to serialize data to JSON
     SerializationFailed(serde_json::Error),
 
@@ -32,6 +46,126 @@ pub enum ConvexTypeGeneratorError
         error: std::io::Error,
     },
 
+    /// Files in `function_paths` that yielded no functions, with
+    /// [`EmptyFunctionFile::Error`](crate::EmptyFunctionFile::Error) set
+    EmptyFunctionFiles
+    {
+        /// The files, in `function_paths` order
+        files: Vec<PathBuf>,
+    },
+
+    /// The generated code doesn't parse as Rust (a convex-typegen bug)
+    InvalidGeneratedCode
+    {
+        /// 1-based line of the parse error in the generated file
+        line: usize,
+        /// The parser's message
+        message: String,
+        /// The generated lines around the error, numbered
+        snippet: String,
+    },
+
+    /// The generated code is longer than `Configuration::max_output_lines`
+    OutputTooLarge
+    {
+        /// Lines in the generated code
+        lines: usize,
+        /// The configured budget
+        max_lines: usize,
+        /// The tables and functions that contribute the most lines, largest first
+        largest: Vec<String>,
+        /// Settings that would shrink the output
+        suggestions: Vec<String>,
+    },
+
+    /// Parts of the schema or functions map to `serde_json::Value`, with
+    /// `Configuration::strict` set
+    UntypedFields
+    {
+        /// Each location with why it is untyped, e.g. `table users.settings:
+        /// v.any() maps to serde_json::Value`
+        locations: Vec<String>,
+    },
+
+    /// A config file loaded with `Configuration::from_file` is invalid
+    InvalidConfigFile
+    {
+        /// The file
+        path: PathBuf,
+        /// What is wrong, with its line and column
+        details: String,
+    },
+
+    /// A `CONVEX_TYPEGEN_*` environment variable has a value that doesn't parse
+    InvalidEnvOverride
+    {
+        /// The variable's name
+        var: String,
+        /// Its value
+        value: String,
+        /// What was expected
+        details: String,
+    },
+
+    /// `previous_model` is unset while a migration scaffold is configured, or
+    /// isn't a model JSON snapshot
+    InvalidPreviousModel
+    {
+        /// What is wrong, with the file where there is one
+        details: String,
+    },
+
+    /// The `model_input` file isn't a model JSON as written by
+    /// `Artifact::ModelJson`
+    InvalidModel
+    {
+        /// The model file
+        path: PathBuf,
+        /// What is wrong
+        details: String,
+    },
+
+    /// A snapshot export read with `export::SnapshotExport` isn't a ZIP, lacks
+    /// the requested table, or has a document that doesn't decode
+    InvalidExport
+    {
+        /// The export file
+        path: PathBuf,
+        /// What is wrong, with the entry and line for documents
+        details: String,
+    },
+
+    /// A document written with `import::ImportWriter` doesn't match its
+    /// table's validator
+    InvalidImportDocument
+    {
+        /// The table
+        table: String,
+        /// The line the document would have been written to
+        line: usize,
+        /// The document, as JSON
+        document: String,
+    },
+
+    /// A JSON Schema given to `json_schema::to_convex_validator` has a part
+    /// with no Convex validator equivalent
+    UnsupportedJsonSchema
+    {
+        /// Where in the schema, as a JSON pointer
+        path: String,
+        /// Why it can't be converted
+        details: String,
+    },
+
+    /// Extraction failed for one or more tables, functions, or files. Every
+    /// problem of the run is listed, unless `Configuration::fail_fast` is set
+    Diagnostics
+    {
+        /// The problems, in file order, followed by what the checks after
+        /// extraction found in the rest of the model
+        diagnostics: Vec<Diagnostic>,
+    },
+
     /// The schema file has invalid structure or content
     InvalidSchema
     {
@@ -42,14 +176,92 @@ pub enum ConvexTypeGeneratorError
     },
 }
 
+/// One problem in [`ConvexTypeGeneratorError::Diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic
+{
+    /// The file the problem is in, when it is in one
+    pub file: Option<PathBuf>,
+    /// 1-based line in `file`, when known
+    pub line: Option<usize>,
+    /// What is wrong, naming the table, export, or field, e.g. ``export `me`:
+    /// `authedQuery` is not a Convex registrar``
+    pub message: String,
+    /// A one-line suggested fix, when there is one
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Diagnostic
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: ", file.display())?,
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)?;
+        match &self.suggestion {
+            Some(suggestion) => write!(f, " (fix: {suggestion})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A failure recognized in the output of a Bun extractor run that exited
+/// non-zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionCause
+{
+    /// What kind of failure it is
+    pub kind: ExtractionCauseKind,
+    /// The file Bun blamed, when it named one
+    pub file: Option<PathBuf>,
+    /// 1-based line in `file`, when Bun gave one
+    pub line: Option<usize>,
+    /// Bun's message, e.g. `Could not resolve: "./lib/stripe"`
+    pub message: String,
+    /// A one-line suggested fix, e.g. `add a helper_stubs entry for "./lib/stripe"`
+    pub suggestion: String,
+}
+
+/// The failure signatures [`ExtractionCause`] recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionCauseKind
+{
+    /// An import that Bun couldn't resolve to a file or package
+    ModuleNotFound,
+    /// A file that doesn't parse
+    SyntaxError,
+    /// `await` at the top level of a module, which the extractor can't load
+    TopLevelAwait,
+}
+
+impl fmt::Display for ExtractionCause
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: ", file.display())?,
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            _ => {}
+        }
+        write!(f, "{} (fix: {})", self.message, self.suggestion)
+    }
+}
+
 impl fmt::Display for ConvexTypeGeneratorError
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
         match self {
-            Self::MissingSchemaFile => write!(f, "Schema file not found"),
-            Self::ExtractionFailed { details } => {
-                write!(f, "Type extraction failed: {}", details)
+            Self::MissingSchemaFile { path } => write!(f, "Schema file not found: {}", path.display()),
+            Self::ExtractionFailed { details, causes } => {
+                write!(f, "Type extraction failed: {}", details)?;
+                for cause in causes {
+                    write!(f, "\n- {cause}")?;
+                }
+                Ok(())
             }
             Self::InvalidPath(path) => {
                 write!(f, "Invalid path: {}", path)
@@ -57,12 +269,80 @@ impl fmt::Display for ConvexTypeGeneratorError
             Self::InvalidUnicode(path) => {
                 write!(f, "Path contains invalid Unicode: {}", path)
             }
+            Self::InvalidVersionTag(tag) => {
+                write!(f, "Invalid version tag {tag:?}: use ASCII letters, digits, and `_`, starting with a letter")
+            }
             Self::SerializationFailed(err) => {
                 write!(f, "Failed to serialize: {}", err)
             }
             Self::IOError { file, error } => {
                 write!(f, "IO error while reading '{}': {}", file, error)
             }
+            Self::EmptyFunctionFiles { files } => {
+                let files: Vec<String> = files.iter().map(|file| file.display().to_string()).collect();
+                write!(f, "No query, mutation, or action exports found in: {}", files.join(", "))
+            }
+            Self::InvalidGeneratedCode { line, message, snippet } => {
+                write!(
+                    f,
+                    "Generated code doesn't parse (line {line}: {message}); this is a convex-typegen bug, please report it \
+                     with the schema that triggered it\n{snippet}"
+                )
+            }
+            Self::OutputTooLarge {
+                lines,
+                max_lines,
+                largest,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "Generated code is {lines} lines, over the max_output_lines budget of {max_lines}"
+                )?;
+                if !largest.is_empty() {
+                    write!(f, "\nLargest: {}", largest.join(", "))?;
+                }
+                for suggestion in suggestions {
+                    write!(f, "\n- {suggestion}")?;
+                }
+                Ok(())
+            }
+            Self::UntypedFields { locations } => {
+                write!(f, "Strict mode rejects these locations, which map to serde_json::Value:")?;
+                for location in locations {
+                    write!(f, "\n- {location}")?;
+                }
+                Ok(())
+            }
+            Self::InvalidConfigFile { path, details } => {
+                write!(f, "Invalid config file {}: {details}", path.display())
+            }
+            Self::InvalidEnvOverride { var, value, details } => {
+                write!(f, "Invalid value {value:?} for {var}: {details}")
+            }
+            Self::InvalidPreviousModel { details } => {
+                write!(f, "Invalid previous model: {details}")
+            }
+            Self::InvalidModel { path, details } => {
+                write!(f, "Invalid model {}: {details}", path.display())
+            }
+            Self::InvalidExport { path, details } => {
+                write!(f, "Invalid snapshot export {}: {details}", path.display())
+            }
+            Self::InvalidImportDocument { table, line, document } => {
+                write!(f, "Document for line {line} doesn't match the `{table}` table: {document}")
+            }
+            Self::UnsupportedJsonSchema { path, details } => {
+                write!(f, "Can't convert JSON Schema at {path} to a Convex validator: {details}")
+            }
+            Self::Diagnostics { diagnostics } => {
+                let noun = if diagnostics.len() == 1 { "problem" } else { "problems" };
+                write!(f, "Type extraction found {} {noun}:", diagnostics.len())?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n- {diagnostic}")?;
+                }
+                Ok(())
+            }
             Self::InvalidSchema { context, details } => {
                 write!(f, "Invalid schema at {}: {}", context, details)
             }
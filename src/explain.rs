@@ -0,0 +1,85 @@
+//! Human-readable dump of the resolved model, used by [`crate::explain`] and
+//! the `convex-typegen explain` command.
+
+use std::fmt::Write;
+
+use serde_json::Value as JsonValue;
+
+use crate::codegen::{args_struct_path, call_method_name, table_struct_name, TypeResolver};
+use crate::descriptors::{render_validator, untyped_holes};
+use crate::types::{ConvexFunction, ConvexSchema};
+use crate::{Configuration, MethodNaming};
+
+/// Render every table, field, and function with its validator and Rust type.
+///
+/// Locations that fall back to `serde_json::Value` get a `⚠` line underneath
+/// explaining why.
+pub(crate) fn render(config: &Configuration, schema: &ConvexSchema, functions: &[ConvexFunction]) -> String
+{
+    let mut resolver = TypeResolver::new(&schema.tables, config);
+    let mut out = String::new();
+
+    let _ = writeln!(out, "Schema: {}", config.schema_path.display());
+
+    let _ = writeln!(out, "\nTables ({})", schema.tables.len());
+    for table in &schema.tables {
+        let _ = writeln!(out, "  {} → {}", table.name, table_struct_name(&table.name));
+        for column in &table.columns {
+            let rust_type = resolver.column_type(table, column);
+            push_entry(&mut out, "    ", &column.name, &column.data_type, &rust_type);
+        }
+    }
+
+    let _ = writeln!(out, "\nFunctions ({})", functions.len());
+    for func in functions {
+        let module = func.module_path.as_deref().unwrap_or(&func.file_name);
+        let method = if func.type_.starts_with("internal") {
+            "no client method".to_string()
+        } else if func.type_ == "query" {
+            format!(
+                "{}, {}",
+                subscribe_method_name(func),
+                call_method_name(func, config.method_naming)
+            )
+        } else {
+            call_method_name(func, config.method_naming)
+        };
+        let _ = writeln!(out, "  {} {}:{} → {}", func.type_, module, func.name, method);
+
+        if let Some(args) = func.whole_args() {
+            let rust_type = format!("{}({})", args_struct_path(func, config), resolver.whole_args_type(func, args));
+            push_entry(&mut out, "    ", "args", args, &rust_type);
+        } else if func.params.is_empty() {
+            let _ = writeln!(out, "    args: (none) → {}", args_struct_path(func, config));
+        } else {
+            let _ = writeln!(out, "    args → {}", args_struct_path(func, config));
+            for param in &func.params {
+                let rust_type = resolver.param_type(func, param);
+                push_entry(&mut out, "      ", &param.name, &param.data_type, &rust_type);
+            }
+        }
+
+        match (&func.return_type, resolver.return_type(func)) {
+            (Some(data_type), Some(rust_type)) => push_entry(&mut out, "    ", "returns", data_type, &rust_type),
+            _ => {
+                let _ = writeln!(out, "    returns: (untyped) → serde_json::Value");
+            }
+        }
+    }
+
+    out
+}
+
+/// Write `name: validator → rust_type`, followed by any untyped-hole warnings.
+fn push_entry(out: &mut String, indent: &str, name: &str, data_type: &JsonValue, rust_type: &str)
+{
+    let _ = writeln!(out, "{indent}{name}: {} → {rust_type}", render_validator(data_type));
+    for hole in untyped_holes(data_type, name) {
+        let _ = writeln!(out, "{indent}  ⚠ {}: {}", hole.path, hole.reason);
+    }
+}
+
+fn subscribe_method_name(func: &ConvexFunction) -> String
+{
+    call_method_name(func, MethodNaming::Bare).replacen("query_", "subscribe_", 1)
+}
@@ -0,0 +1,141 @@
+//! Reading Convex snapshot exports into the generated table structs, for
+//! typed ETL from a backup without a live deployment.
+//!
+//! An export (`npx convex export`, or a download from the dashboard) is a ZIP
+//! with one `{table}/documents.jsonl` per table, one document per line:
+//!
+//! ```no_run
+//! # #[derive(serde::Deserialize)]
+//! # struct GamesTable {}
+//! use convex_typegen::export::SnapshotExport;
+//!
+//! let mut export = SnapshotExport::open("snapshot.zip")?;
+//! for game in export.documents::<GamesTable>("games")? {
+//!     let game = game?;
+//!     // ...
+//! }
+//! # Ok::<(), convex_typegen::errors::ConvexTypeGeneratorError>(())
+//! ```
+//!
+//! Documents are streamed from the archive, so a table never has to fit in
+//! memory. They decode as plain JSON through the struct's `Deserialize`, the
+//! same as a query result.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::errors::ConvexTypeGeneratorError;
+
+const DOCUMENTS: &str = "documents.jsonl";
+
+/// An opened snapshot export.
+pub struct SnapshotExport
+{
+    path: PathBuf,
+    archive: zip::ZipArchive<BufReader<File>>,
+}
+
+impl SnapshotExport
+{
+    /// Open the export ZIP at `path`.
+    ///
+    /// # Errors
+    /// Fails when the file can't be read or isn't a ZIP archive.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path).map_err(|error| ConvexTypeGeneratorError::IOError {
+            file: path.display().to_string(),
+            error,
+        })?;
+        let archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| ConvexTypeGeneratorError::InvalidExport {
+            path: path.clone(),
+            details: e.to_string(),
+        })?;
+        Ok(Self { path, archive })
+    }
+
+    /// The tables in the export, sorted, including system tables such as
+    /// `_storage`. Tables of components are not included.
+    pub fn tables(&self) -> Vec<String>
+    {
+        let mut tables: Vec<String> = self
+            .archive
+            .file_names()
+            .filter_map(|name| name.strip_suffix(DOCUMENTS)?.strip_suffix('/'))
+            .filter(|table| !table.is_empty() && !table.contains('/'))
+            .map(str::to_string)
+            .collect();
+        tables.sort();
+        tables
+    }
+
+    /// Stream the documents of `table`, each decoded as a `T` (e.g. `GamesTable`).
+    ///
+    /// # Errors
+    /// Fails when the export has no such table. Each item fails on its own
+    /// when its line can't be read or doesn't decode as a `T`.
+    pub fn documents<T: DeserializeOwned>(&mut self, table: &str) -> Result<Documents<'_, T>, ConvexTypeGeneratorError>
+    {
+        let entry = format!("{table}/{DOCUMENTS}");
+        let file = self
+            .archive
+            .by_name(&entry)
+            .map_err(|e| ConvexTypeGeneratorError::InvalidExport {
+                path: self.path.clone(),
+                details: format!("no table `{table}` ({e})"),
+            })?;
+        Ok(Documents {
+            path: &self.path,
+            entry,
+            lines: BufReader::new(file).lines(),
+            line: 0,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// The documents of one table, in export order. See [`SnapshotExport::documents`].
+pub struct Documents<'a, T>
+{
+    path: &'a Path,
+    entry: String,
+    lines: Lines<BufReader<zip::read::ZipFile<'a, BufReader<File>>>>,
+    line: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Iterator for Documents<'_, T>
+{
+    type Item = Result<T, ConvexTypeGeneratorError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        loop {
+            let text = self.lines.next()?;
+            self.line += 1;
+            let text = match text {
+                Ok(text) => text,
+                Err(error) => {
+                    return Some(Err(ConvexTypeGeneratorError::IOError {
+                        file: format!("{}:{}", self.path.display(), self.entry),
+                        error,
+                    }))
+                }
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+            return Some(
+                serde_json::from_str(&text).map_err(|e| ConvexTypeGeneratorError::InvalidExport {
+                    path: self.path.to_path_buf(),
+                    details: format!("{} line {}: {e}", self.entry, self.line),
+                }),
+            );
+        }
+    }
+}
@@ -1,41 +1,73 @@
 //! Bun-based type extraction — spawns `bun run` with the extractor script
 //! and parses the JSON output into [`ConvexSchema`] + [`ConvexFunctions`].
+//!
+//! The extractor prints NDJSON, one record per table, function, and trace
+//! event, followed by an `end` record. Each line is converted as it arrives,
+//! so only one item's JSON is held at a time, and a malformed item fails the
+//! run without waiting for the rest of the output.
 
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::thread;
 use std::time::Duration;
 
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 
-use crate::bun_installer;
-use crate::errors::ConvexTypeGeneratorError;
-use crate::types::{ConvexColumn, ConvexFunction, ConvexFunctionParam, ConvexSchema, ConvexTable};
+use crate::errors::{ConvexTypeGeneratorError, Diagnostic, ExtractionCause, ExtractionCauseKind};
+use crate::report::{self, ResolveEvent};
+use crate::types::{
+    ConvexColumn, ConvexFunction, ConvexFunctionParam, ConvexIndex, ConvexSchema, ConvexTable, SchemaOptions,
+};
+use crate::{bun_installer, cache, Configuration, CustomValidatorHandler};
 
 // ---------------------------------------------------------------------------
 // Deserialization types for Bun's JSON output
 // ---------------------------------------------------------------------------
 
+/// One line of Bun's output.
 #[derive(Deserialize)]
-struct BunOutput
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record
 {
-    schema: SchemaOutput,
-    functions: Vec<FunctionOutput>,
+    /// `defineSchema`'s options; absent when the schema has none.
+    Options
+    {
+        options: SchemaOptions,
+    },
+    Table(TableOutput),
+    Function(FunctionOutput),
+    /// Only present when the run was traced.
+    Trace
+    {
+        event: ResolveEvent,
+    },
+    /// A function file that failed to load; the run goes on with the rest.
+    Error
+    {
+        file: PathBuf,
+        message: String,
+    },
+    /// The last line, so output cut short by a crash isn't taken as complete.
+    End,
 }
 
 #[derive(Deserialize)]
-struct SchemaOutput
+struct TableOutput
 {
-    tables: Vec<TableOutput>,
+    name: String,
+    columns: Vec<ColumnOutput>,
+    #[serde(default)]
+    indexes: Vec<IndexOutput>,
 }
 
 #[derive(Deserialize)]
-struct TableOutput
+struct IndexOutput
 {
     name: String,
-    columns: Vec<ColumnOutput>,
+    fields: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -56,6 +88,10 @@ struct FunctionOutput
     file_name: String,
     #[serde(default)]
     module_path: Option<String>,
+    #[serde(default)]
+    args_validator: Option<JsonValue>,
+    #[serde(default)]
+    component: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -69,25 +105,68 @@ struct ParamOutput
 // Public extraction function
 // ---------------------------------------------------------------------------
 
+/// The settings every extraction backend shares, borrowed from the
+/// [`Configuration`].
+///
+/// The function files are passed separately, since the hybrid and embedded
+/// backends hand Bun only the files they couldn't handle themselves.
+#[derive(Clone, Copy)]
+pub(crate) struct ExtractOptions<'a>
+{
+    pub(crate) schema_path: &'a Path,
+    pub(crate) helper_stubs: &'a HashMap<String, PathBuf>,
+    pub(crate) custom_validators: &'a HashMap<String, CustomValidatorHandler>,
+    pub(crate) bun_path: Option<&'a Path>,
+    pub(crate) offline: bool,
+    pub(crate) quiet: bool,
+    pub(crate) fail_fast: bool,
+}
+
+impl<'a> ExtractOptions<'a>
+{
+    pub(crate) fn new(config: &'a Configuration) -> Self
+    {
+        Self {
+            schema_path: &config.schema_path,
+            helper_stubs: &config.helper_stubs,
+            custom_validators: &config.custom_validators,
+            bun_path: config.bun_path.as_deref(),
+            offline: config.offline,
+            quiet: config.quiet,
+            fail_fast: config.fail_fast,
+        }
+    }
+}
+
 /// Run the Bun extractor against the given schema and function files.
 ///
 /// The extractor uses mock Convex packages so that `v.*` calls produce JSON
 /// descriptors instead of actual validators. The result is parsed into the
-/// same types that [`crate::codegen`] expects.
-pub(crate) fn extract(
-    schema_path: &Path,
-    function_paths: &[PathBuf],
-    helper_stubs: &HashMap<String, PathBuf>,
-) -> Result<(ConvexSchema, Vec<ConvexFunction>), ConvexTypeGeneratorError>
-{
-    let js_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("js");
+/// same types that [`crate::codegen`] expects, along with the resolution trace
+/// when `CONVEX_TYPEGEN_TRACE_RESOLVE` is set. With `fail_fast`, Bun stops at
+/// the first function file that fails to load.
+pub(crate) fn extract(options: &ExtractOptions, function_paths: &[PathBuf]) -> Result<Extracted, ConvexTypeGeneratorError>
+{
+    let ExtractOptions {
+        schema_path,
+        helper_stubs,
+        custom_validators,
+        bun_path,
+        offline,
+        quiet,
+        fail_fast,
+    } = *options;
+    let js_dir = dunce::simplified(Path::new(env!("CARGO_MANIFEST_DIR"))).join("js");
     let extractor = js_dir.join("extractor.ts");
 
     // Serialize helper stubs as JSON for the Bun plugin
     let stubs_json = serde_json::to_string(helper_stubs).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
         details: format!("Failed to serialize helper stubs: {e}"),
+        causes: Vec::new(),
     })?;
 
+    let custom_json = custom_validators_json(custom_validators);
+
     let schema_abs = if schema_path.is_absolute() {
         schema_path.to_path_buf()
     } else {
@@ -100,14 +179,26 @@ pub(crate) fn extract(
     };
 
     // Get or download the bun binary
-    let bun_path = bun_installer::get_bun_path()?;
+    let bun_path = bun_installer::get_bun_path(bun_path, offline, quiet)?;
 
     // The extractor registers its own plugin via Bun.plugin() — no --preload needed
     let mut cmd = Command::new(&bun_path);
     cmd.arg("run")
         .arg(&extractor)
         .arg(&schema_abs)
-        .env("TYPEGEN_HELPER_STUBS", &stubs_json);
+        .env("TYPEGEN_HELPER_STUBS", &stubs_json)
+        .env("TYPEGEN_CUSTOM_VALIDATORS", &custom_json);
+    if report::trace_resolve_from_env() {
+        cmd.env("TYPEGEN_TRACE_RESOLVE", "1");
+    }
+    if fail_fast {
+        cmd.env("TYPEGEN_FAIL_FAST", "1");
+    }
+    // Keep Bun's transpiler cache next to the Bun binary, where `clean_cache`
+    // finds it, unless the user already pointed it somewhere
+    if std::env::var_os("BUN_RUNTIME_TRANSPILER_CACHE_PATH").is_none() {
+        cmd.env("BUN_RUNTIME_TRANSPILER_CACHE_PATH", cache::extraction_dir());
+    }
 
     // Set NODE_PATH so bun can resolve `convex/values` (which is NOT mocked)
     // even when the mock files live in a different location (e.g. nix store).
@@ -133,15 +224,17 @@ pub(crate) fn extract(
         cmd.arg(abs);
     }
 
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
     // Retry on ETXTBSY ("Text file busy") which can happen if another thread
     // just finished writing the bun binary.
-    let output = {
+    let mut child = {
         let mut last_err = None;
         let mut result = None;
         for attempt in 0..5u64 {
-            match cmd.output() {
-                Ok(out) => {
-                    result = Some(out);
+            match cmd.spawn() {
+                Ok(child) => {
+                    result = Some(child);
                     break;
                 }
                 Err(e) => {
@@ -153,6 +246,7 @@ pub(crate) fn extract(
                     }
                     return Err(ConvexTypeGeneratorError::ExtractionFailed {
                         details: format!("Failed to spawn bun ({}): {e}", bun_path.display()),
+                        causes: Vec::new(),
                     });
                 }
             }
@@ -163,28 +257,201 @@ pub(crate) fn extract(
                 bun_path.display(),
                 last_err.map(|e| e.to_string()).unwrap_or_default()
             ),
+            causes: Vec::new(),
         })?
     };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(ConvexTypeGeneratorError::ExtractionFailed {
-            details: format!("bun exited with {}: {stderr}", output.status),
-        });
+    // Drain stderr on its own thread, so a chatty extractor can't fill the
+    // pipe and stall while stdout is read
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+    let stderr = thread::spawn(move || {
+        let mut stderr = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut stderr);
+        String::from_utf8_lossy(&stderr).into_owned()
+    });
+
+    let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let parsed = read_records(stdout);
+    if parsed.is_err() {
+        // A malformed item: stop the extractor instead of waiting for output
+        // that would be discarded anyway
+        let _ = child.kill();
     }
+    let status = child.wait().map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
+        details: format!("Failed to wait for bun: {e}"),
+        causes: Vec::new(),
+    })?;
+    let stderr = stderr.join().unwrap_or_default();
 
-    let bun_output: BunOutput =
-        serde_json::from_slice(&output.stdout).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
-            details: format!("Failed to parse bun output: {e}"),
-        })?;
+    if !status.success() && parsed.is_ok() {
+        let causes = diagnose(&stderr);
+        let details = if causes.is_empty() {
+            format!("bun exited with {status}: {stderr}")
+        } else {
+            format!("bun exited with {status}")
+        };
+        return Err(ConvexTypeGeneratorError::ExtractionFailed { details, causes });
+    }
+    parsed?.ok_or_else(|| ConvexTypeGeneratorError::ExtractionFailed {
+        details: format!("bun output ended before its end record: {stderr}"),
+        causes: Vec::new(),
+    })
+}
+
+/// Ends the message of a package import the embedded backend has no mock
+/// for; installing the package doesn't help there.
+pub(crate) const NO_PACKAGES: &str = "the embedded backend only loads files and mocks";
+
+/// Custom validators as `{ name: { module, descriptor } }`, with the
+/// descriptors already tagged for codegen.
+pub(crate) fn custom_validators_json(custom_validators: &HashMap<String, CustomValidatorHandler>) -> String
+{
+    serde_json::Value::Object(
+        custom_validators
+            .iter()
+            .map(|(name, handler)| {
+                let handler = serde_json::json!({ "module": handler.module, "descriptor": handler.descriptor_for(name) });
+                (name.clone(), handler)
+            })
+            .collect(),
+    )
+    .to_string()
+}
+
+/// Recognize the common failure signatures in the stderr of a failed Bun run:
+/// unresolved imports, syntax errors, and top-level `await`. Bun prints each
+/// as an `error: ...` line, usually followed by `at <file>:<line>:<column>`.
+/// QuickJS reports a module that fails to resolve or load as a
+/// `ReferenceError: ...` with the same kind of message.
+pub(crate) fn diagnose(stderr: &str) -> Vec<ExtractionCause>
+{
+    let lines: Vec<&str> = stderr.lines().map(str::trim).collect();
+    let mut causes: Vec<ExtractionCause> = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(message) = line
+            .strip_prefix("error: ")
+            .or_else(|| line.strip_prefix("SyntaxError: "))
+            .or_else(|| line.strip_prefix("ResolveMessage: "))
+            .or_else(|| line.strip_prefix("ReferenceError: "))
+        else {
+            continue;
+        };
+        let lower = message.to_lowercase();
+
+        let (kind, suggestion) = if lower.contains("cannot find module")
+            || lower.contains("cannot find package")
+            || lower.contains("could not resolve")
+        {
+            let specifier = first_quoted(message).unwrap_or_default();
+            let suggestion = if specifier.starts_with('.') || specifier.starts_with('/') {
+                format!("add a helper_stubs entry for \"{specifier}\", or create the file")
+            } else if message.contains(NO_PACKAGES) {
+                format!("add a helper_stubs entry for \"{specifier}\"")
+            } else {
+                format!("add a helper_stubs entry for \"{specifier}\", or install the package next to the Convex files")
+            };
+            (ExtractionCauseKind::ModuleNotFound, suggestion)
+        } else if lower.contains("top-level await") || lower.contains("\"await\" can only be used inside") {
+            let suggestion = "move the `await` into a function, or replace the module with a helper_stubs entry";
+            (ExtractionCauseKind::TopLevelAwait, suggestion.to_string())
+        } else if line.starts_with("SyntaxError")
+            || lower.starts_with("unexpected")
+            || lower.starts_with("expected")
+            || lower.contains("syntax")
+        {
+            (
+                ExtractionCauseKind::SyntaxError,
+                "fix the syntax at this location".to_string(),
+            )
+        } else {
+            continue;
+        };
 
-    // Convert to the shared types that codegen expects
-    let schema = ConvexSchema {
-        tables: bun_output
-            .schema
-            .tables
-            .into_iter()
-            .map(|t| ConvexTable {
+        // `Cannot find module "x" from "/abs/file.ts"` names the importer inline
+        let mut file = message
+            .split_once(" from ")
+            .and_then(|(_, importer)| first_quoted(importer))
+            .map(PathBuf::from);
+        let mut line_no = None;
+        for next in lines.iter().skip(idx + 1).take(3) {
+            if next.starts_with("error: ") {
+                break;
+            }
+            if let Some((at_file, at_line)) = next.strip_prefix("at ").and_then(parse_location) {
+                file = Some(at_file);
+                line_no = Some(at_line);
+                break;
+            }
+        }
+
+        let cause = ExtractionCause {
+            kind,
+            file,
+            line: line_no,
+            message: message.to_string(),
+            suggestion,
+        };
+        if !causes.contains(&cause) {
+            causes.push(cause);
+        }
+    }
+    causes
+}
+
+/// The first `"..."` or `'...'` string in `text`.
+fn first_quoted(text: &str) -> Option<String>
+{
+    let start = text.find(['"', '\''])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+    rest.find(quote).map(|end| rest[..end].to_string())
+}
+
+/// Split `/abs/file.ts:12:5` into the file and its line.
+fn parse_location(location: &str) -> Option<(PathBuf, usize)>
+{
+    let location = location.trim_matches(|c| c == '(' || c == ')');
+    let mut parts = location.rsplitn(3, ':');
+    let last = parts.next()?;
+    let middle = parts.next()?;
+    match parts.next() {
+        Some(file) => Some((PathBuf::from(file), middle.parse().ok()?)),
+        None => Some((PathBuf::from(middle), last.parse().ok()?)),
+    }
+}
+
+/// Extraction output: the schema, the functions, the resolution trace, and
+/// the function files that failed to load.
+pub(crate) type Extracted = (ConvexSchema, Vec<ConvexFunction>, Vec<ResolveEvent>, Vec<Diagnostic>);
+
+/// Convert Bun's NDJSON output, line by line, into the shared types that
+/// codegen expects. `None` when the output stops before the end record.
+pub(crate) fn read_records(stdout: impl BufRead) -> Result<Option<Extracted>, ConvexTypeGeneratorError>
+{
+    let mut schema = ConvexSchema {
+        tables: Vec::new(),
+        options: SchemaOptions::default(),
+    };
+    let mut functions = Vec::new();
+    let mut trace = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for (index, line) in stdout.lines().enumerate() {
+        let line = line.map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
+            details: format!("Failed to read bun output: {e}"),
+            causes: Vec::new(),
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line).map_err(|e| ConvexTypeGeneratorError::ExtractionFailed {
+            details: format!("Failed to parse bun output line {}{}: {e}", index + 1, describe_record(&line)),
+            causes: Vec::new(),
+        })?;
+        match record {
+            Record::Options { options } => schema.options = options,
+            Record::Table(t) => schema.tables.push(ConvexTable {
                 name: t.name,
                 columns: t
                     .columns
@@ -194,29 +461,68 @@ pub(crate) fn extract(
                         data_type: c.data_type,
                     })
                     .collect(),
-            })
-            .collect(),
-    };
+                indexes: t
+                    .indexes
+                    .into_iter()
+                    .map(|i| ConvexIndex {
+                        name: i.name,
+                        fields: i.fields,
+                    })
+                    .collect(),
+            }),
+            Record::Function(f) => functions.push(ConvexFunction {
+                name: f.name,
+                type_: f.type_,
+                params: f
+                    .params
+                    .into_iter()
+                    .map(|p| ConvexFunctionParam {
+                        name: p.name,
+                        data_type: p.data_type,
+                    })
+                    .collect(),
+                return_type: f.return_type,
+                file_name: f.file_name,
+                module_path: f.module_path,
+                args_validator: f.args_validator,
+                component: f.component,
+            }),
+            Record::Trace { event } => trace.push(event),
+            Record::Error { file, message } => diagnostics.push(load_failure(file, message)),
+            Record::End => return Ok(Some((schema, functions, trace, diagnostics))),
+        }
+    }
+    Ok(None)
+}
+
+/// A function file that failed to load, with the line and suggested fix that
+/// [`diagnose`] finds in the message.
+fn load_failure(file: PathBuf, message: String) -> Diagnostic
+{
+    let cause = diagnose(&message).into_iter().next();
+    Diagnostic {
+        line: cause.as_ref().and_then(|cause| cause.line),
+        suggestion: cause.map(|cause| cause.suggestion),
+        file: Some(file),
+        message,
+    }
+}
 
-    let functions = bun_output
-        .functions
-        .into_iter()
-        .map(|f| ConvexFunction {
-            name: f.name,
-            type_: f.type_,
-            params: f
-                .params
-                .into_iter()
-                .map(|p| ConvexFunctionParam {
-                    name: p.name,
-                    data_type: p.data_type,
-                })
-                .collect(),
-            return_type: f.return_type,
-            file_name: f.file_name,
-            module_path: f.module_path,
-        })
-        .collect();
-
-    Ok((schema, functions))
+/// ` (table users)` or ` (function games:list)` for an output line that
+/// didn't parse, when the line says which item it is.
+fn describe_record(line: &str) -> String
+{
+    let Ok(value) = serde_json::from_str::<JsonValue>(line) else {
+        return String::new();
+    };
+    let name = value["name"].as_str().unwrap_or("?");
+    match value["record"].as_str() {
+        Some("table") => format!(" (table {name})"),
+        Some("function") => {
+            let module = value["module_path"].as_str().or(value["file_name"].as_str()).unwrap_or("?");
+            format!(" (function {module}:{name})")
+        }
+        Some(record) => format!(" ({record})"),
+        None => String::new(),
+    }
 }
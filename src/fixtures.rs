@@ -0,0 +1,119 @@
+//! Deterministic sample documents behind the generated fixture builders.
+//!
+//! With `emit_fixtures` on, every table gets a `{Table}TableFixture` builder.
+//! Fields it isn't given come from [`sample_document`]:
+//!
+//! ```no_run
+//! # #[derive(serde::Deserialize)]
+//! # struct GamesTable {}
+//! # #[derive(Default)]
+//! # struct GamesTableFixture {}
+//! # impl GamesTableFixture {
+//! #     fn builder() -> Self { Self::default() }
+//! #     fn win_count(self, _: f64) -> Self { self }
+//! #     fn build(self) -> GamesTable { GamesTable {} }
+//! # }
+//! let game = GamesTableFixture::builder().win_count(3.0).build();
+//! ```
+//!
+//! Each value is derived from the seed and the field's path alone, so adding a
+//! field to a table doesn't change the values of the others, and a snapshot
+//! stays stable until the field it shows changes. Optional fields are left
+//! unset, arrays and records empty, and unions take one of their variants.
+//! Strings with a date `format` get a day in January 2024.
+
+use serde_json::{Map, Value as JsonValue};
+
+/// A document of `table` matching `validator` (an object descriptor without
+/// the system fields, as generated), with `_id` and `_creationTime` filled in.
+///
+/// # Panics
+/// Panics when `validator` isn't valid JSON; generated validators always are.
+pub fn sample_document(table: &str, validator: &str, seed: u64) -> JsonValue
+{
+    let validator: JsonValue = serde_json::from_str(validator).expect("generated validators are valid JSON");
+    let mut document = match sample(&validator, seed, table) {
+        JsonValue::Object(fields) => fields,
+        _ => Map::new(),
+    };
+    document.insert("_id".to_string(), JsonValue::String(sample_id(seed, table)));
+    // 2024-01-01T00:00:00Z plus a second per seed
+    let creation_time = 1_704_067_200_000.0 + (seed % 1_000_000) as f64 * 1000.0;
+    document.insert("_creationTime".to_string(), creation_time.into());
+    JsonValue::Object(document)
+}
+
+/// A value for `data_type` at `path` (`table.field.nested`).
+fn sample(data_type: &JsonValue, seed: u64, path: &str) -> JsonValue
+{
+    let hash = hash(seed, path);
+    match data_type["type"].as_str().unwrap_or("any") {
+        "string" => match data_type["format"].as_str() {
+            // A day in January 2024, with a time for `date-time`
+            Some("date-time") => JsonValue::String(format!(
+                "2024-01-{:02}T{:02}:{:02}:{:02}.000Z",
+                hash % 31 + 1,
+                hash / 31 % 24,
+                hash / 744 % 60,
+                hash / 44_640 % 60
+            )),
+            Some("date") => JsonValue::String(format!("2024-01-{:02}", hash % 31 + 1)),
+            _ => {
+                let name = path.rsplit('.').next().unwrap_or(path);
+                JsonValue::String(format!("{name}-{}", hash % 1000))
+            }
+        },
+        "number" | "float64" => ((hash % 1000) as f64).into(),
+        "int64" => ((hash % 1000) as i64).into(),
+        "boolean" => (hash % 2 == 1).into(),
+        "id" => JsonValue::String(sample_id(seed, path)),
+        "literal" => data_type["value"].clone(),
+        "object" => JsonValue::Object(
+            data_type["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(_, field)| field["type"].as_str() != Some("optional"))
+                .map(|(name, field)| (name.clone(), sample(field, seed, &format!("{path}.{name}"))))
+                .collect(),
+        ),
+        "array" | "bytes" => JsonValue::Array(Vec::new()),
+        "record" => JsonValue::Object(Map::new()),
+        "union" => match data_type["variants"].as_array().filter(|variants| !variants.is_empty()) {
+            Some(variants) => sample(&variants[(hash % variants.len() as u64) as usize], seed, path),
+            None => JsonValue::Null,
+        },
+        _ => JsonValue::Null,
+    }
+}
+
+/// A 32-character id in the Convex format (lowercase Crockford base32).
+fn sample_id(seed: u64, path: &str) -> String
+{
+    const ALPHABET: &[u8] = b"0123456789abcdefghjkmnpqrstvwxyz";
+    let mut state = hash(seed, path);
+    (0..32)
+        .map(|_| {
+            state = splitmix64(state);
+            ALPHABET[(state % 32) as usize] as char
+        })
+        .collect()
+}
+
+/// FNV-1a of `path`, mixed with `seed`. Stable across platforms and Rust
+/// releases, unlike `DefaultHasher`.
+fn hash(seed: u64, path: &str) -> u64
+{
+    let fnv = path.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    splitmix64(fnv ^ splitmix64(seed))
+}
+
+fn splitmix64(state: u64) -> u64
+{
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
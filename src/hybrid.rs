@@ -0,0 +1,109 @@
+//! Hybrid extraction — the AST backend for every file it understands, Bun for
+//! the rest.
+//!
+//! Each file is first parsed statically. Files the AST path can't resolve
+//! (spread imports from packages, wrapper factories, ...) are collected and
+//! handed to a single Bun run, and the results are merged back in file order.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::ast::{self, ModuleCache};
+use crate::errors::ConvexTypeGeneratorError;
+use crate::extract::ExtractOptions;
+use crate::report::BunFallback;
+use crate::types::ConvexFunction;
+use crate::{extract, Extraction};
+
+/// Extract with the AST backend, falling back to Bun per file.
+///
+/// Returns the merged schema and functions plus the files that needed Bun and
+/// the trace of that Bun run.
+pub(crate) fn extract(options: &ExtractOptions, function_paths: &[PathBuf]) -> Result<Extraction, ConvexTypeGeneratorError>
+{
+    let ExtractOptions {
+        schema_path,
+        custom_validators,
+        ..
+    } = *options;
+    let mut modules = ModuleCache::new(custom_validators);
+    let mut fallbacks = Vec::new();
+
+    let ast_schema = match ast::extract_schema(schema_path, &mut modules) {
+        Ok(schema) => Some(schema),
+        Err(e) => {
+            fallbacks.push(BunFallback {
+                file: schema_path.to_path_buf(),
+                reason: reasons(&e),
+            });
+            None
+        }
+    };
+
+    let mut per_file: Vec<Option<Vec<ConvexFunction>>> = Vec::with_capacity(function_paths.len());
+    let mut bun_paths: Vec<PathBuf> = Vec::new();
+    for path in function_paths {
+        match ast::extract_functions(path, &mut modules) {
+            Ok(functions) => per_file.push(Some(functions)),
+            Err(e) => {
+                fallbacks.push(BunFallback {
+                    file: path.clone(),
+                    reason: reasons(&e),
+                });
+                bun_paths.push(path.clone());
+                per_file.push(None);
+            }
+        }
+    }
+
+    if fallbacks.is_empty() {
+        if let Some(schema) = ast_schema {
+            return Ok(Extraction {
+                schema,
+                functions: per_file.into_iter().flatten().flatten().collect(),
+                bun_fallbacks: fallbacks,
+                resolve_trace: Vec::new(),
+                diagnostics: Vec::new(),
+            });
+        }
+    }
+
+    // Bun always evaluates the schema, so one run covers both the schema and
+    // every function file the AST path gave up on.
+    let (bun_schema, bun_functions, resolve_trace, diagnostics) = extract::extract(options, &bun_paths)?;
+
+    let mut bun_by_module: HashMap<(String, String), Vec<ConvexFunction>> = HashMap::new();
+    for func in bun_functions {
+        let module_path = func.module_path.clone().unwrap_or_else(|| func.file_name.clone());
+        bun_by_module
+            .entry((func.file_name.clone(), module_path))
+            .or_default()
+            .push(func);
+    }
+
+    let functions = function_paths
+        .iter()
+        .zip(per_file)
+        .flat_map(|(path, functions)| {
+            functions.unwrap_or_else(|| bun_by_module.remove(&ast::module_identity(path)).unwrap_or_default())
+        })
+        .collect();
+
+    Ok(Extraction {
+        schema: ast_schema.unwrap_or(bun_schema),
+        functions,
+        bun_fallbacks: fallbacks,
+        resolve_trace,
+        diagnostics,
+    })
+}
+
+/// The messages of the AST errors of one file, as a fallback reason.
+fn reasons(errors: &[ast::AstError]) -> String
+{
+    errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
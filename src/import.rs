@@ -0,0 +1,137 @@
+//! Writing `npx convex import` files from the generated insert structs, for
+//! Rust ETL jobs that load data into a deployment.
+//!
+//! With `emit_import_writers` on, every table gets a `{Table}Insert` struct
+//! that implements [`ImportDocument`]. An [`ImportWriter`] writes them as
+//! JSONL, one document per line, checking each against the table's validator
+//! first:
+//!
+//! ```no_run
+//! # #[derive(serde::Serialize)]
+//! # struct GamesInsert {}
+//! # impl convex_typegen::import::ImportDocument for GamesInsert {
+//! #     const TABLE: &'static str = "games";
+//! #     const VALIDATOR: &'static str = "{\"type\":\"object\",\"properties\":{}}";
+//! # }
+//! use convex_typegen::import::ImportWriter;
+//!
+//! let mut writer = ImportWriter::<GamesInsert, _>::create("games.jsonl")?;
+//! writer.write(&GamesInsert {})?;
+//! writer.finish()?;
+//! // npx convex import --table games games.jsonl
+//! # Ok::<(), convex_typegen::errors::ConvexTypeGeneratorError>(())
+//! ```
+//!
+//! Documents are written as plain JSON through the struct's `Serialize`, the
+//! same as mutation args.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::descriptors::accepts_document;
+use crate::errors::ConvexTypeGeneratorError;
+
+/// A new document for one table, as generated for `{Table}Insert`.
+pub trait ImportDocument: Serialize
+{
+    /// The table's name in the schema.
+    const TABLE: &'static str;
+    /// The table's validator, as a JSON type descriptor without the system
+    /// fields.
+    const VALIDATOR: &'static str;
+}
+
+/// Writes documents of one table in the JSONL format `npx convex import` reads.
+pub struct ImportWriter<T, W: Write>
+{
+    out: W,
+    validator: JsonValue,
+    line: usize,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: ImportDocument> ImportWriter<T, BufWriter<File>>
+{
+    /// Create (or truncate) the file at `path` and write to it.
+    ///
+    /// # Errors
+    /// Fails when the file can't be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+            file: path.display().to_string(),
+            error,
+        })?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<T: ImportDocument, W: Write> ImportWriter<T, W>
+{
+    /// Write to `out`.
+    pub fn new(out: W) -> Self
+    {
+        Self {
+            out,
+            validator: serde_json::from_str(T::VALIDATOR).expect("generated validators are valid JSON"),
+            line: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Validate `document` and write it as the next line.
+    ///
+    /// # Errors
+    /// Fails when the document doesn't match the table's validator, in which
+    /// case nothing is written, or when writing fails.
+    pub fn write(&mut self, document: &T) -> Result<(), ConvexTypeGeneratorError>
+    {
+        let value = serde_json::to_value(document).map_err(ConvexTypeGeneratorError::SerializationFailed)?;
+        if !accepts_document(&self.validator, &value) {
+            return Err(ConvexTypeGeneratorError::InvalidImportDocument {
+                table: T::TABLE.to_string(),
+                line: self.line + 1,
+                document: value.to_string(),
+            });
+        }
+        writeln!(self.out, "{value}").map_err(|error| self.io_error(error))?;
+        self.line += 1;
+        Ok(())
+    }
+
+    /// The number of documents written so far.
+    pub fn len(&self) -> usize
+    {
+        self.line
+    }
+
+    /// Whether no documents have been written yet.
+    pub fn is_empty(&self) -> bool
+    {
+        self.line == 0
+    }
+
+    /// Flush and return the underlying writer.
+    ///
+    /// # Errors
+    /// Fails when flushing fails.
+    pub fn finish(mut self) -> Result<W, ConvexTypeGeneratorError>
+    {
+        self.out.flush().map_err(|error| self.io_error(error))?;
+        Ok(self.out)
+    }
+
+    fn io_error(&self, error: std::io::Error) -> ConvexTypeGeneratorError
+    {
+        ConvexTypeGeneratorError::IOError {
+            file: format!("{} import", T::TABLE),
+            error,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Convex validator source from JSON Schema, for types that start out in Rust.
+//!
+//! Message formats and other contract types are sometimes defined in Rust and
+//! must be mirrored in the Convex schema. [`to_convex_validator`] turns the
+//! type's JSON Schema, e.g. from `schemars`, into the `v.*` source to paste
+//! into `schema.ts`:
+//!
+//! ```
+//! use convex_typegen::json_schema::to_convex_validator;
+//! use serde_json::json;
+//!
+//! // serde_json::to_value(schemars::schema_for!(Ping))?
+//! let schema = json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "note": { "type": ["string", "null"] },
+//!         "seq": { "type": "integer", "format": "int64" }
+//!     },
+//!     "required": ["seq"]
+//! });
+//! assert_eq!(
+//!     to_convex_validator(&schema)?,
+//!     "v.object({\n    note: v.optional(v.union(v.string(), v.null())),\n    seq: v.int64(),\n})"
+//! );
+//! # Ok::<(), convex_typegen::errors::ConvexTypeGeneratorError>(())
+//! ```
+//!
+//! Generating Rust from the result gives back the same shapes: `i64` and `u64`
+//! become `v.int64()`, other integers `v.number()`, and `Option` fields a union
+//! with `v.null()`, optional when serde may leave them out. Tuples, recursive
+//! types, and schemas combined with `allOf` have no Convex equivalent and are
+//! an error.
+
+use serde_json::Value as JsonValue;
+
+use crate::errors::ConvexTypeGeneratorError;
+
+/// Render `schema` (a JSON Schema document, draft 7 or 2020-12) as Convex
+/// validator source. Objects are spread over one line per field.
+pub fn to_convex_validator(schema: &JsonValue) -> Result<String, ConvexTypeGeneratorError>
+{
+    let mut refs = Vec::new();
+    render(schema, schema, "#", 0, &mut refs)
+}
+
+fn render(
+    root: &JsonValue,
+    schema: &JsonValue,
+    path: &str,
+    depth: usize,
+    refs: &mut Vec<String>,
+) -> Result<String, ConvexTypeGeneratorError>
+{
+    let unsupported = |details: &str| ConvexTypeGeneratorError::UnsupportedJsonSchema {
+        path: path.to_string(),
+        details: details.to_string(),
+    };
+
+    let schema = match schema {
+        JsonValue::Bool(true) => return Ok("v.any()".to_string()),
+        JsonValue::Bool(false) => return Err(unsupported("the `false` schema matches no value")),
+        JsonValue::Object(schema) => schema,
+        _ => return Err(unsupported("a schema must be an object or a boolean")),
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+        if refs.iter().any(|seen| seen == reference) {
+            return Err(unsupported(&format!("`{reference}` is recursive")));
+        }
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+            .ok_or_else(|| unsupported(&format!("`{reference}` doesn't point into this schema")))?;
+        refs.push(reference.to_string());
+        let rendered = render(root, target, reference, depth, refs);
+        refs.pop();
+        return rendered;
+    }
+
+    if let Some(value) = schema.get("const") {
+        return literal(value).ok_or_else(|| unsupported("only string, number, and boolean constants are supported"));
+    }
+    if let Some(values) = schema.get("enum").and_then(JsonValue::as_array) {
+        let literals = values
+            .iter()
+            .map(|value| match value {
+                JsonValue::Null => Some("v.null()".to_string()),
+                value => literal(value),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| unsupported("only string, number, boolean, and null enum values are supported"))?;
+        return Ok(union(literals));
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(keyword).and_then(JsonValue::as_array) {
+            let variants = variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| render(root, variant, &format!("{path}/{keyword}/{i}"), depth, refs))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(union(variants));
+        }
+    }
+    if let Some(parts) = schema.get("allOf").and_then(JsonValue::as_array) {
+        return match parts.as_slice() {
+            [only] => render(root, only, &format!("{path}/allOf/0"), depth, refs),
+            _ => Err(unsupported("`allOf` with more than one schema has no Convex equivalent")),
+        };
+    }
+
+    match schema.get("type") {
+        None => Ok("v.any()".to_string()),
+        Some(JsonValue::Array(types)) => {
+            let variants = types
+                .iter()
+                .map(|ty| match ty.as_str() {
+                    Some(ty) => render_type(root, schema, ty, path, depth, refs),
+                    None => Err(unsupported("`type` entries must be strings")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(union(variants))
+        }
+        Some(JsonValue::String(ty)) => render_type(root, schema, ty, path, depth, refs),
+        Some(_) => Err(unsupported("`type` must be a string or an array")),
+    }
+}
+
+/// Render one `type` of `schema`, which may list several.
+fn render_type(
+    root: &JsonValue,
+    schema: &serde_json::Map<String, JsonValue>,
+    ty: &str,
+    path: &str,
+    depth: usize,
+    refs: &mut Vec<String>,
+) -> Result<String, ConvexTypeGeneratorError>
+{
+    let unsupported = |details: &str| ConvexTypeGeneratorError::UnsupportedJsonSchema {
+        path: path.to_string(),
+        details: details.to_string(),
+    };
+
+    match ty {
+        "string" => Ok("v.string()".to_string()),
+        "number" => Ok("v.number()".to_string()),
+        "boolean" => Ok("v.boolean()".to_string()),
+        "null" => Ok("v.null()".to_string()),
+        // Only 64-bit integers can exceed f64's exact range
+        "integer" => match schema.get("format").and_then(JsonValue::as_str) {
+            Some("int64" | "uint64") => Ok("v.int64()".to_string()),
+            _ => Ok("v.number()".to_string()),
+        },
+        "array" => {
+            if schema.contains_key("prefixItems") || schema.get("items").is_some_and(JsonValue::is_array) {
+                return Err(unsupported("tuples have no Convex equivalent"));
+            }
+            let items = match schema.get("items") {
+                Some(items) => render(root, items, &format!("{path}/items"), depth, refs)?,
+                None => "v.any()".to_string(),
+            };
+            Ok(format!("v.array({items})"))
+        }
+        "object" => {
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            let additional = schema.get("additionalProperties").filter(|value| value.is_object());
+            match (properties, additional) {
+                (None, Some(values)) => {
+                    let values = render(root, values, &format!("{path}/additionalProperties"), depth, refs)?;
+                    Ok(format!("v.record(v.string(), {values})"))
+                }
+                (Some(properties), _) if !properties.is_empty() => {
+                    let required: Vec<&str> = schema
+                        .get("required")
+                        .and_then(JsonValue::as_array)
+                        .map(|names| names.iter().filter_map(JsonValue::as_str).collect())
+                        .unwrap_or_default();
+                    let indent = "    ".repeat(depth + 1);
+                    let mut code = "v.object({\n".to_string();
+                    for (name, property) in properties {
+                        let mut value = render(root, property, &format!("{path}/properties/{name}"), depth + 1, refs)?;
+                        if !required.contains(&name.as_str()) {
+                            value = format!("v.optional({value})");
+                        }
+                        code.push_str(&format!("{indent}{}: {value},\n", property_key(name)));
+                    }
+                    code.push_str(&format!("{}}})", "    ".repeat(depth)));
+                    Ok(code)
+                }
+                _ => Ok("v.object({})".to_string()),
+            }
+        }
+        other => Err(unsupported(&format!("unknown type `{other}`"))),
+    }
+}
+
+fn literal(value: &JsonValue) -> Option<String>
+{
+    match value {
+        JsonValue::String(_) | JsonValue::Number(_) | JsonValue::Bool(_) => Some(format!("v.literal({value})")),
+        _ => None,
+    }
+}
+
+fn union(mut variants: Vec<String>) -> String
+{
+    if variants.len() == 1 {
+        return variants.remove(0);
+    }
+    format!("v.union({})", variants.join(", "))
+}
+
+/// `name` as an object key, quoted unless it is a plain identifier.
+fn property_key(name: &str) -> String
+{
+    let mut chars = name.chars();
+    let identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        JsonValue::String(name.to_string()).to_string()
+    }
+}
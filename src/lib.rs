@@ -25,17 +25,433 @@
 //! }
 //! ```
 
+mod artifacts;
+mod ast;
 mod bun_installer;
+mod cache;
 mod codegen;
+mod config_file;
+mod descriptors;
+mod directives;
+#[cfg(feature = "embedded")]
+mod embedded;
+mod env;
 pub mod errors;
+mod explain;
+pub mod export;
 mod extract;
+pub mod fixtures;
+mod hybrid;
+pub mod import;
+pub mod json_schema;
+mod lint;
+mod logging;
+pub mod manifest;
+mod migration;
+pub mod plan;
+pub mod report;
 pub(crate) mod types;
+mod verify;
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use codegen::generate_code;
-use errors::ConvexTypeGeneratorError;
+pub use cache::{clean_cache, CacheKind, CleanReport};
+use codegen::{render_code, table_files_dir, GeneratedCode};
+pub use env::ENV_VARS;
+use errors::{ConvexTypeGeneratorError, Diagnostic};
+use plan::{GenerationPlan, PlannedFile};
+use report::GenerationReport;
+use serde::Deserialize;
+
+/// How schema and function files are turned into type descriptors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionBackend
+{
+    /// Run the files with Bun against mocked Convex packages (default).
+    ///
+    /// Handles arbitrary helper code, wrapper factories, and computed
+    /// validators. Bun is downloaded on first use.
+    #[default]
+    Bun,
+
+    /// Parse the files statically in Rust — no JS runtime is needed.
+    ///
+    /// Understands `v.*` validators, `const` bindings, object spreads, relative
+    /// imports, `defineSchema`/`defineTable`, and the Convex function
+    /// registrars. Files that need real evaluation (wrapper factories such as
+    /// `authedQuery(...)`, helpers imported from packages) fail with an error
+    /// naming the construct. `helper_stubs` are ignored.
+    Ast,
+
+    /// Parse each file statically and fall back to Bun only for the files
+    /// the AST backend can't resolve.
+    ///
+    /// All fallback files share a single Bun run. The files that needed Bun
+    /// are listed in [`GenerationReport::bun_fallbacks`].
+    Hybrid,
+
+    /// Run the files in a QuickJS engine compiled into the crate — no
+    /// external JS runtime. Needs the `embedded` feature.
+    ///
+    /// Evaluates helper code and wrapper factories like Bun, against the same
+    /// mocks, with a mocked `convex/values`. Packages without a mock need a
+    /// `helper_stubs` entry. Files that start with `"use node"` still go to
+    /// Bun and are listed in [`GenerationReport::bun_fallbacks`].
+    Embedded,
+}
+
+/// Serde adjustments for one table field, set in
+/// [`Configuration::field_overrides`].
+///
+/// Every protection except `raw_json` and `format` is implemented with
+/// `serde_with`, so the consuming crate needs `serde_with` as a dependency once
+/// one is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldOverride
+{
+    /// Accept either a JSON string or a JSON number.
+    ///
+    /// `String` fields (such as ids) keep numbers as their decimal text;
+    /// `f64`/`i64` fields also parse numeric strings, so integers beyond the
+    /// f64-exact range can be sent as strings. Ignored for other types.
+    pub string_or_number: bool,
+
+    /// Fall back to `Default::default()` when the field is missing or fails to
+    /// deserialize (`serde_with::DefaultOnError`). The field's Rust type must
+    /// implement `Default`.
+    pub default_on_error: bool,
+
+    /// How a `v.record(...)` field treats duplicate keys. Ignored for other types.
+    pub duplicate_keys: DuplicateKeys,
+
+    /// Keep the field as its raw JSON text, typed `RawJson<T>`, and parse it
+    /// into `T` only when `.parse()` is called.
+    ///
+    /// For large fields, such as rich-text documents, that most readers never
+    /// look at. The other settings are ignored for such a field. The consuming
+    /// crate needs `serde_json` with the `raw_value` feature.
+    pub raw_json: bool,
+
+    /// Type a `v.string()` field that holds ISO 8601 text as a `chrono` date,
+    /// keeping the stored string unchanged. Also set with a
+    /// `// typegen:format date-time` comment above the field in the schema.
+    /// Ignored for other types. The consuming crate needs `chrono` with the
+    /// `serde` feature.
+    pub format: Option<StringFormat>,
+}
+
+/// The date type a string field is read as, set in [`FieldOverride::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StringFormat
+{
+    /// An RFC 3339 timestamp such as `2024-01-31T09:30:00Z`, read as
+    /// `chrono::DateTime<chrono::Utc>`.
+    DateTime,
+
+    /// A calendar date such as `2024-01-31`, read as `chrono::NaiveDate`.
+    Date,
+}
+
+impl StringFormat
+{
+    /// The directive and config spelling, such as `date-time`.
+    pub(crate) fn name(self) -> &'static str
+    {
+        match self {
+            StringFormat::DateTime => "date-time",
+            StringFormat::Date => "date",
+        }
+    }
+
+    pub(crate) fn rust_type(self) -> &'static str
+    {
+        match self {
+            StringFormat::DateTime => "chrono::DateTime<chrono::Utc>",
+            StringFormat::Date => "chrono::NaiveDate",
+        }
+    }
+}
+
+/// Duplicate-key handling for `HashMap` fields generated from `v.record(...)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKeys
+{
+    /// The last occurrence wins, as with plain serde (default).
+    #[default]
+    LastWins,
+
+    /// The first occurrence wins (`serde_with::MapFirstKeyWins`).
+    FirstWins,
+
+    /// Fail deserialization on a duplicate key (`serde_with::MapPreventDuplicates`).
+    Reject,
+}
+
+/// How the generator treats a project validator factory such as `vEmail()`,
+/// set in [`Configuration::custom_validators`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomValidatorHandler
+{
+    /// The module the factory is imported from, matched against the end of the
+    /// import specifier: `lib/validators` matches both `"./lib/validators"` and
+    /// `"../lib/validators.js"`.
+    ///
+    /// During extraction, imports of the factory from that module are replaced,
+    /// so the module never runs. Under Bun the replacement exports only the
+    /// custom validators of that module, so keep other helpers elsewhere.
+    pub module: String,
+
+    /// The descriptor every call produces, in the extractor's format, e.g.
+    /// `{ "type": "string" }`. Arguments to the call are ignored.
+    pub descriptor: serde_json::Value,
+
+    /// The Rust type the validator maps to, e.g. `crate::Email` (default: None,
+    /// meaning the type of `descriptor`).
+    ///
+    /// The type must serialize to and deserialize from what `descriptor`
+    /// accepts.
+    #[serde(default)]
+    pub rust_type: Option<String>,
+}
+
+impl CustomValidatorHandler
+{
+    /// Whether the import specifier `source` names [`Self::module`].
+    pub(crate) fn matches(&self, source: &str) -> bool
+    {
+        let strip = |path: &str| path.trim_end_matches(".js").trim_end_matches(".ts").to_string();
+        let (source, module) = (strip(source), strip(self.module.trim_start_matches("./")));
+        source == module || source.ends_with(&format!("/{module}"))
+    }
+
+    /// The descriptor a call to the factory `name` produces, tagged with
+    /// `customValidator` so codegen can find the handler again.
+    pub(crate) fn descriptor_for(&self, name: &str) -> serde_json::Value
+    {
+        let mut descriptor = self.descriptor.clone();
+        if let Some(fields) = descriptor.as_object_mut() {
+            fields.insert("customValidator".to_string(), serde_json::Value::String(name.to_string()));
+        }
+        descriptor
+    }
+}
+
+/// What [`generate`] does when a file in [`Configuration::function_paths`]
+/// yields no functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyFunctionFile
+{
+    /// Add a warning to the report and keep going (default).
+    #[default]
+    Warn,
+
+    /// Fail with [`ConvexTypeGeneratorError::EmptyFunctionFiles`] before
+    /// writing anything.
+    Error,
+}
+
+/// How [`Configuration::method_naming`] names the `ConvexApi` methods of
+/// mutations and actions. Queries are always `query_{file}_{fn}` and
+/// `subscribe_{file}_{fn}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MethodNaming
+{
+    /// `games_win_game`, with no kind prefix (default).
+    #[default]
+    Bare,
+
+    /// `mutation_games_win_game` and `action_games_start`. The bare names stay
+    /// on `ConvexApi` as deprecated methods forwarding to the new ones, so
+    /// callers can move over before they are removed.
+    Prefixed,
+
+    /// The prefixed names only, once no caller uses the bare ones.
+    PrefixedOnly,
+}
+
+/// Cargo feature names that gate generated code per function kind, set in
+/// [`Configuration::feature_gates`].
+///
+/// A gated kind's args structs, `ConvexApi` methods, Tauri commands, and Axum
+/// routes are wrapped in `#[cfg(feature = "...")]`. `None` leaves that kind
+/// ungated. The consuming crate must declare every named feature in its
+/// `Cargo.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeatureGates
+{
+    /// Feature for queries (default: `convex-queries`).
+    pub queries: Option<String>,
+
+    /// Feature for mutations (default: `convex-mutations`).
+    pub mutations: Option<String>,
+
+    /// Feature for actions (default: `convex-actions`).
+    pub actions: Option<String>,
+
+    /// Feature for `internalQuery`/`internalMutation`/`internalAction` args
+    /// structs (default: `convex-internal`).
+    pub internal: Option<String>,
+}
+
+impl Default for FeatureGates
+{
+    fn default() -> Self
+    {
+        Self {
+            queries: Some("convex-queries".to_string()),
+            mutations: Some("convex-mutations".to_string()),
+            actions: Some("convex-actions".to_string()),
+            internal: Some("convex-internal".to_string()),
+        }
+    }
+}
+
+impl FeatureGates
+{
+    /// The feature gating a function of the given kind (`query`, `internalAction`, ...).
+    pub(crate) fn for_kind(&self, kind: &str) -> Option<&str>
+    {
+        let feature = match kind {
+            "query" => &self.queries,
+            "mutation" => &self.mutations,
+            "action" => &self.actions,
+            kind if kind.starts_with("internal") => &self.internal,
+            _ => return None,
+        };
+        feature.as_deref()
+    }
+}
+
+/// The `cargo:` directives [`generate`] prints for the build script it runs in,
+/// set in [`Configuration::cargo_integration`].
+///
+/// Nothing is printed by default, which suits generating into committed
+/// sources from the CLI or a test. A build script can turn on the rerun
+/// directives instead of listing its inputs by hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CargoIntegration
+{
+    /// Print `cargo:rerun-if-changed` for every input file: the schema, the
+    /// function files, the `helper_stubs` files, `model_input`, and
+    /// `previous_model`.
+    pub rerun_if_changed: bool,
+
+    /// Print `cargo:rerun-if-env-changed` for every variable in [`ENV_VARS`],
+    /// unless [`Configuration::env_overrides`] is off.
+    pub rerun_if_env_changed: bool,
+
+    /// Further directives to print, without the `cargo:` prefix, e.g.
+    /// `"rustc-cfg=convex_types"`.
+    pub directives: Vec<String>,
+}
+
+impl CargoIntegration
+{
+    /// Every rerun directive, for build scripts.
+    pub fn build_script() -> Self
+    {
+        Self {
+            rerun_if_changed: true,
+            rerun_if_env_changed: true,
+            directives: Vec::new(),
+        }
+    }
+}
+
+/// An extra file [`generate`] writes next to the Rust code, set in
+/// [`Configuration::artifacts`].
+///
+/// Artifacts are rendered from the same extraction as the Rust code, so Bun
+/// runs once however many are configured. Like `out_file`, a file that already
+/// holds its contents is left untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Artifact
+{
+    /// The extracted schema and functions as pretty-printed JSON: the type
+    /// descriptors the Rust code is generated from.
+    ModelJson
+    {
+        /// Where to write the JSON.
+        path: PathBuf,
+    },
+
+    /// A TypeScript `.d.ts` of the Rust-visible surface, for documentation.
+    ///
+    /// Table and args interfaces carry the generated Rust names, each field
+    /// notes its Rust type, and a `ConvexApi` interface lists the client
+    /// methods.
+    TypeScriptDeclarations
+    {
+        /// Where to write the declarations.
+        path: PathBuf,
+    },
+
+    /// A Rust module of `migrate_{table}_v1_to_v2(old: {Table}TableV1) ->
+    /// {Table}Table` stubs for the tables that changed since
+    /// [`Configuration::previous_model`].
+    ///
+    /// Fields that kept their validator are copied, new optional fields start
+    /// as `None`, and everything else is a `todo!()` that says what changed.
+    /// The file is rewritten on every build, so copy it into the crate before
+    /// editing it.
+    MigrationScaffold
+    {
+        /// Where to write the module.
+        path: PathBuf,
+    },
+
+    /// A binary that calls every query whose args are all optional against the
+    /// deployment URL given as its argument, and reports each one that fails.
+    ///
+    /// Run against staging, it shows where the deployed functions have drifted
+    /// from the generated types: a validation error or a return value that no
+    /// longer deserializes. Write it to e.g. `src/bin/convex_smoke.rs`; it needs
+    /// `tokio` with the `macros` and `rt-multi-thread` features.
+    SmokeTest
+    {
+        /// Where to write the binary's source.
+        path: PathBuf,
+        /// Path the generated module is reachable at from the binary, e.g.
+        /// `"my_app::convex_types"`.
+        module: String,
+    },
+}
+
+impl Artifact
+{
+    /// The file this artifact is written to.
+    pub fn path(&self) -> &Path
+    {
+        match self {
+            Artifact::ModelJson { path }
+            | Artifact::TypeScriptDeclarations { path }
+            | Artifact::MigrationScaffold { path }
+            | Artifact::SmokeTest { path, .. } => path,
+        }
+    }
+
+    fn path_mut(&mut self) -> &mut PathBuf
+    {
+        match self {
+            Artifact::ModelJson { path }
+            | Artifact::TypeScriptDeclarations { path }
+            | Artifact::MigrationScaffold { path }
+            | Artifact::SmokeTest { path, .. } => path,
+        }
+    }
+}
 
 /// Configuration options for the type generator.
 #[derive(Debug, Clone)]
@@ -45,11 +461,54 @@ pub struct Configuration
     pub schema_path: PathBuf,
 
     /// Output file path for generated Rust types (default: "src/convex_types.rs")
+    ///
+    /// The file starts with a comment naming its inputs and their digests, the
+    /// settings that differ from the defaults, and how to regenerate it. It
+    /// ends with a one-line JSON manifest that [`check_freshness`] reads.
     pub out_file: PathBuf,
 
+    /// Suffix for every generated type name, e.g. `"V7"` for `GamesTableV7`
+    /// (default: None).
+    ///
+    /// The code is written next to `out_file` with the tag in its name, e.g.
+    /// `convex_types_v7.rs`, so a service can include two schema versions side
+    /// by side during a rollout. The code is wrapped in a module named after the
+    /// tag, e.g. `v7`, and only the types are re-exported with the suffix, so
+    /// functions and constants are reached through the module, e.g.
+    /// `v7::convex_router`. Tags are ASCII letters, digits, and `_`, starting
+    /// with a letter.
+    pub version_tag: Option<String>,
+
     /// Paths to Convex function files for generating function argument types
     pub function_paths: Vec<PathBuf>,
 
+    /// A model JSON written by another crate's [`Artifact::ModelJson`] to
+    /// generate from instead of extracting (default: None).
+    ///
+    /// Lets the crates of a workspace share one extraction: a producer crate
+    /// writes the model, e.g. to [`shared_model_path`], and the others
+    /// generate their own code from it without running Bun. `schema_path`,
+    /// `function_paths`, and the extraction settings are then unused, and no
+    /// `// typegen:` comments are read, so set their config equivalents here.
+    /// See [`generate_from_model`].
+    pub model_input: Option<PathBuf>,
+
+    /// Directory that relative paths in this configuration are resolved against
+    /// (default: None, meaning `CARGO_MANIFEST_DIR` when it is set, as it is for
+    /// build scripts, and the current directory otherwise).
+    ///
+    /// Applies to `schema_path`, `out_file`, `function_paths`, `model_input`,
+    /// and the `helper_stubs` files, so generation behaves the same whether cargo runs
+    /// from the workspace root or a member crate. Errors and the report show
+    /// the resolved absolute paths.
+    pub base_dir: Option<PathBuf>,
+
+    /// What to do when a function file exports no query, mutation, or action
+    /// (default: Warn). This usually means a typo in a wrapper import or a
+    /// renamed file. The files are listed in
+    /// [`GenerationReport::empty_function_files`] either way.
+    pub on_empty_function_file: EmptyFunctionFile,
+
     /// Map of import pattern (regex) → stub file path.
     ///
     /// Used to redirect project-specific helper imports to no-op stubs during
@@ -58,6 +517,530 @@ pub struct Configuration
     ///
     /// Example: `{ "helpers/result" => PathBuf::from("convex/helpers/result_stub.ts") }`
     pub helper_stubs: HashMap<String, PathBuf>,
+
+    /// Project validator factories, keyed by the name they're imported under
+    /// (default: empty).
+    ///
+    /// Example: `{ "vEmail" => CustomValidatorHandler { module: "lib/validators".into(),
+    /// descriptor: json!({ "type": "string" }), rust_type: Some("crate::Email".into()) } }`
+    ///
+    /// Both extraction backends replace calls to these factories with the
+    /// handler's descriptor, and codegen maps them to its Rust type. This is
+    /// for factories that Bun can't run or that the AST backend can't
+    /// evaluate, and for mapping a validator to a domain type.
+    pub custom_validators: HashMap<String, CustomValidatorHandler>,
+
+    /// Which extractor reads the schema and function files (default: Bun).
+    pub extraction_backend: ExtractionBackend,
+
+    /// Never download Bun (default: false).
+    ///
+    /// The Bun and Hybrid backends, and the Embedded one for `"use node"`
+    /// files, then need `bun` on `PATH` or a binary cached by an earlier run,
+    /// and fail otherwise. The Ast backend never needs Bun.
+    /// Building with the `no-download` cargo feature makes this permanent.
+    pub offline: bool,
+
+    /// The Bun binary the Bun and Hybrid backends run (default: None, meaning
+    /// `bun` on `PATH` or a downloaded one).
+    ///
+    /// Bun publishes no build for some platforms, such as 32-bit ARM; point
+    /// this at a binary you built or use the Ast backend there.
+    pub bun_path: Option<PathBuf>,
+
+    /// Per-field serde protections, keyed by `"table.field"` using the names
+    /// from the schema (default: empty).
+    ///
+    /// Example: `{ "users.legacyId" => FieldOverride { string_or_number: true, ..Default::default() } }`
+    ///
+    /// Keys that match no table field are reported as warnings.
+    pub field_overrides: HashMap<String, FieldOverride>,
+
+    /// The field each table's struct prints with `Display`, keyed by table
+    /// name using the names from the schema (default: empty).
+    ///
+    /// Example: `{ "users" => "name" }` makes `UsersTable` display as its
+    /// name, e.g. in logs. An optional field that is unset displays as `_id`.
+    ///
+    /// Tables and fields that don't exist, and fields whose type has no
+    /// `Display` (objects, arrays, records), are reported as warnings.
+    pub display_fields: HashMap<String, String>,
+
+    /// The key field of array-of-object columns, keyed by `"table.field"`
+    /// using the names from the schema (default: empty).
+    ///
+    /// Every such column gets an `iter_{field}()` helper on its table struct,
+    /// and a `find_{entry}_by_{key}(&str)` one when its objects have a key.
+    /// Without an entry, the key is the first required string field named
+    /// `id`, `key`, `name`, or `slug`. An empty key turns the finder off.
+    ///
+    /// Example: `{ "players.achievements" => "title" }`
+    ///
+    /// Keys that match no array-of-object column, and key fields that aren't
+    /// required strings, are reported as warnings.
+    pub collection_keys: HashMap<String, String>,
+
+    /// UI labels for the values of literal enum fields, keyed by
+    /// `"table.field.value"` using the names from the schema (default: empty).
+    /// Only used with [`Configuration::emit_enum_labels`].
+    ///
+    /// Example: `{ "games.status.active" => "In progress" }`
+    ///
+    /// Keys that match no value of a literal enum table field are reported as
+    /// warnings.
+    pub enum_labels: HashMap<String, String>,
+
+    /// Put `#[serde(default)]` on the `Option`, `Vec`, and `HashMap` fields of
+    /// table structs and their nested object structs (default: true).
+    ///
+    /// Documents stored, or JSON cached, before such a field was added to the
+    /// schema then deserialize with `None` or an empty collection instead of
+    /// failing.
+    pub default_missing_fields: bool,
+
+    /// Type `_id` fields and `v.id("table")` values as `ConvexId<{Table}Table>`
+    /// instead of `String` (default: false).
+    ///
+    /// Ids of tables outside the schema, such as `_storage`, stay `String`.
+    /// Deserializing an id fails unless the string has the Convex id format.
+    pub typed_ids: bool,
+
+    /// Put `_id` and `_creationTime` in a shared `SystemFields` struct,
+    /// flattened into every table struct as `system` (default: false).
+    ///
+    /// Each table struct implements `AsRef<SystemFields>`, so middleware and
+    /// other code that takes documents of any table can read their ids without
+    /// a code path per table. With [`Configuration::typed_ids`] it is
+    /// `SystemFields<GamesTable>`, keeping the id typed.
+    pub flatten_system_fields: bool,
+
+    /// Defaults for optional function args, keyed by `"module:function.arg"`
+    /// (default: empty).
+    ///
+    /// Args structs with a default get a `new()` that takes the required args
+    /// and fills the optional ones, plus `Default` when every arg is optional.
+    /// The wire format is unchanged. A `// typegen:default <json>` comment above
+    /// an arg in the function file does the same; entries here take precedence.
+    ///
+    /// Example: `{ "users:search.locale" => json!("en") }`
+    ///
+    /// Keys that match no optional arg, and values the arg's validator rejects,
+    /// are reported as warnings.
+    pub arg_defaults: HashMap<String, serde_json::Value>,
+
+    /// Type `v.number()` args as `Finite` instead of `f64` (default: false).
+    ///
+    /// JSON has no NaN or infinities, so `serde_json` serializes an `f64` arg
+    /// holding one as `null`, and the server gets `null` where the number was:
+    /// a `v.number()` arg fails validation, and one that also accepts `null`
+    /// stores it. Convex itself accepts non-finite numbers; they are lost on
+    /// the client. `Finite::new(x)` returns an error for them instead, so they
+    /// are caught where the value is made. Optional
+    /// numbers and arrays of numbers become `Option<Finite>` and
+    /// `Vec<Finite>`. Fields of object args stay `f64`, since their types are
+    /// shared with tables and returns.
+    pub finite_number_args: bool,
+
+    /// Functions to mark deprecated, keyed by `"module:function"`, with the
+    /// note to show (default: empty).
+    ///
+    /// The args struct and the `ConvexApi` methods get `#[deprecated(note = ...)]`,
+    /// so Rust callers are pointed at the replacement by a compiler warning. A
+    /// `// typegen:deprecated <note>` comment above the function's `export const`
+    /// does the same; entries here take precedence.
+    ///
+    /// Example: `{ "players:create" => "use players:createV2" }`
+    ///
+    /// Keys that match no function are reported as warnings.
+    pub deprecated_functions: HashMap<String, String>,
+
+    /// Args whose values `Debug` prints as `***`, keyed by
+    /// `"module:function.arg"` (default: empty).
+    ///
+    /// The args struct gets a hand-written `Debug` instead of the derived one,
+    /// so logging the args of e.g. a sign-in mutation doesn't leak the
+    /// password. Serializing and sending the args is unchanged. A
+    /// `// typegen:redact` comment above an arg in the function file does the
+    /// same.
+    ///
+    /// Example: `{ "auth:signIn.password" }`
+    ///
+    /// Keys that match no function arg are reported as warnings.
+    pub redacted_args: HashSet<String>,
+
+    /// Default timeouts of query, mutation, and action methods, keyed by
+    /// `"module:function"` (default: empty). Values are a whole number of
+    /// milliseconds, seconds, or minutes, such as `"250ms"`, `"5s"`, or `"2m"`.
+    ///
+    /// The function's `ConvexApi` method fails with `ConvexError::Timeout` once
+    /// the timeout passes, and a `*_with_timeout` method on `ConvexApiClient`
+    /// sets another deadline for one call. A `// typegen:timeout 5s` comment
+    /// above the function's `export const` does the same; entries here take
+    /// precedence. The consuming crate needs `tokio` with the `time` feature.
+    ///
+    /// Example: `{ "reports:generate" => "30s" }`
+    ///
+    /// Keys that match no function, and values that aren't durations, are
+    /// reported as warnings.
+    pub function_timeouts: HashMap<String, String>,
+
+    /// Priorities of query, mutation, and action calls, keyed by
+    /// `"module:function"` (default: empty). Values are `"high"`, `"normal"`,
+    /// or `"low"`.
+    ///
+    /// The generated code gets a `CallPriority` enum, a `{METHOD}_PRIORITY`
+    /// const on `ConvexApiClient` for each listed function, and
+    /// `ConvexApiClient::priority_of(path)`, which gives any function's
+    /// priority and `Normal` for the rest. The client sends calls as before;
+    /// the priority is for the application's own scheduling, such as which
+    /// calls to hold back under load. A `// typegen:priority low` comment
+    /// above the function's `export const` does the same; entries here take
+    /// precedence.
+    ///
+    /// Example: `{ "search:rebuildIndex" => "low" }`
+    ///
+    /// Keys that match no function, and values that aren't priorities, are
+    /// reported as warnings.
+    pub function_priorities: HashMap<String, String>,
+
+    /// How mutation and action methods are named (default: Bare).
+    ///
+    /// Queries get `query_` and `subscribe_` prefixes, while mutations and
+    /// actions get bare `{file}_{fn}` names that don't say which kind they
+    /// call. [`MethodNaming::Prefixed`] adds `mutation_` and `action_` and
+    /// keeps the bare names as deprecated aliases for a release or two;
+    /// [`MethodNaming::PrefixedOnly`] drops them. Tauri commands, Axum
+    /// handlers, and the other generated methods follow the new names.
+    pub method_naming: MethodNaming,
+
+    /// Split `ConvexApi` into one trait per function file (default: false).
+    ///
+    /// `games.ts` gets `GamesApi` with its functions' methods, and `ConvexApi`
+    /// becomes their supertrait, implemented for anything that implements
+    /// them all. A service can then depend on, and a test mock, only the
+    /// files it uses. Calling a method on a `ConvexApiClient` needs its file's
+    /// trait in scope.
+    pub split_api_by_file: bool,
+
+    /// Write each table's types to its own file (default: false).
+    ///
+    /// The files go in a directory named after `out_file`, e.g.
+    /// `src/convex_types/users.rs` for `src/convex_types.rs`, which
+    /// `include!`s them, so the generated names stay the same. Only files
+    /// whose code changed are rewritten, so an edit to one table leaves the
+    /// other tables' files untouched. `.rs` files in the directory that no
+    /// table produces any more are removed, so the directory must be left to
+    /// the generator. Tables whose names differ only in case, like `Users` and
+    /// `users`, are an error, since their files would collide on
+    /// case-insensitive filesystems.
+    pub split_output_by_table: bool,
+
+    /// Put the args structs of internal functions in `pub mod internal`
+    /// (default: false).
+    ///
+    /// Internal functions have no `ConvexApi` method, but their args structs
+    /// otherwise sit next to the public ones, where they are easy to mistake
+    /// for something a client can call. `internal::CronsCleanupArgs` says
+    /// where it belongs; nested types stay at the top level.
+    pub internal_module: bool,
+
+    /// Generate only the tables the functions use (default: false).
+    ///
+    /// A table is kept when a function's args or return type has an id of it
+    /// or its document shape, when a kept table has an id of it, or when it is
+    /// listed in [`Configuration::keep_tables`]. The rest are left out with
+    /// their nested types, so a service that calls a few functions doesn't
+    /// compile types for every table in the schema.
+    pub prune_unreachable: bool,
+
+    /// Tables [`Configuration::prune_unreachable`] keeps even when no function
+    /// uses them (default: empty).
+    ///
+    /// Names that match no table are reported as warnings.
+    pub keep_tables: HashSet<String>,
+
+    /// Extra files to write from the same extraction (default: empty).
+    ///
+    /// Example: `vec![Artifact::ModelJson { path: "target/convex-model.json".into() }]`
+    ///
+    /// Relative paths are resolved like `out_file`. Artifacts are written only
+    /// after the Rust code passes its checks.
+    pub artifacts: Vec<Artifact>,
+
+    /// A model JSON snapshot from an earlier schema version, as written by
+    /// [`Artifact::ModelJson`] (default: None).
+    ///
+    /// Tables whose columns differ from it get a migration stub in
+    /// [`Artifact::MigrationScaffold`]. Point it at a committed copy, not at the
+    /// `ModelJson` artifact itself, which each build overwrites.
+    pub previous_model: Option<PathBuf>,
+
+    /// Keep the previous shape of each changed table in the generated code as
+    /// `{Table}TableV1`, with its nested types suffixed the same way, so the
+    /// migration scaffold compiles (default: false). Needs `previous_model`.
+    pub emit_previous_tables: bool,
+
+    /// Emit `SharedSubscription<T>` so one typed subscription can be fanned out
+    /// to many consumers (default: false).
+    ///
+    /// The generated code uses `tokio::sync::watch` and `tokio::spawn`, so the
+    /// consuming crate needs `tokio` with the `rt` and `sync` features.
+    pub emit_shared_subscriptions: bool,
+
+    /// Emit `ArcSubscription<T>`, which yields each result as a shared `Arc<T>`
+    /// snapshot, plus `map_arc` to project snapshots (default: false).
+    ///
+    /// `TypedSubscription::into_arc()` converts a subscription. Consumers that
+    /// receive the same large result then share one allocation instead of each
+    /// deep-cloning it.
+    pub emit_arc_subscriptions: bool,
+
+    /// Emit `Snapshot<T>`, a subscription result with the time it was received,
+    /// and `SnapshotSubscription<T>`, which yields them (default: false).
+    ///
+    /// `TypedSubscription::into_snapshots()` converts a subscription. Snapshots
+    /// serialize, so a local cache can store them and later tell how stale
+    /// they are.
+    pub emit_snapshot_subscriptions: bool,
+
+    /// Emit `BufferedSubscription<T>`, which a background task keeps draining
+    /// into a bounded buffer (default: false).
+    ///
+    /// `TypedSubscription::buffered(SubscriptionBuffer::KeepLatestOnly)` keeps
+    /// only the newest result, for UIs that render the latest snapshot;
+    /// `SubscriptionBuffer::Bounded(n)` keeps the newest `n`. `dropped()` counts
+    /// the results a slow consumer missed. The generated code uses
+    /// `tokio::spawn`, so the consuming crate needs `tokio` with the `rt`
+    /// feature.
+    pub emit_buffered_subscriptions: bool,
+
+    /// Emit `resilient_subscribe`, which resubscribes after a subscription
+    /// fails, with capped exponential backoff and jitter (default: false).
+    ///
+    /// `next_event()` yields results along with `Connected` and `Reconnecting`
+    /// events for showing the connection state. The backoff sleeps with
+    /// `tokio::time`, so the consuming crate needs `tokio` with the `time`
+    /// feature.
+    pub emit_resilient_subscriptions: bool,
+
+    /// Emit a `CursorStore` trait that saves and loads the typed cursors of
+    /// paginated queries by query path (default: false).
+    ///
+    /// Each paginated query always gets a `{File}{Fn}Cursor` newtype and a
+    /// `with_cursor()` args helper; the store lets a sync job persist them
+    /// between runs. `HashMap<String, String>` implements it in memory.
+    pub emit_cursor_store: bool,
+
+    /// Emit adapters that bind a `TypedSubscription<T>` to frontend reactive
+    /// state (default: false).
+    ///
+    /// Each adapter is gated behind a cargo feature of the consuming crate:
+    /// `leptos` enables `TypedSubscription::into_leptos_signal()` and `dioxus`
+    /// enables the `use_typed_subscription()` hook.
+    pub emit_frontend_adapters: bool,
+
+    /// Emit a `tauri_commands` module with a `#[tauri::command]` wrapper for every
+    /// public query, mutation, and action (default: false).
+    ///
+    /// The commands read the `ConvexApiClient` from Tauri managed state.
+    pub emit_tauri_commands: bool,
+
+    /// Emit `convex_router(client) -> axum::Router`, a JSON REST facade that
+    /// exposes every public function as `POST /api/{module}/{function}`
+    /// (default: false).
+    pub emit_axum_router: bool,
+
+    /// Emit `ConvexApiRouter<K>`, which routes every public function to the
+    /// deployment of a tenant key `K` (default: false).
+    ///
+    /// Its methods are the `ConvexApi` methods with a leading `tenant: &K`.
+    /// Clients connect on first use and only the most recently used ones stay
+    /// open. The generated code uses `tokio::sync::Mutex`, so the consuming
+    /// crate needs `tokio` with the `sync` feature.
+    pub emit_tenant_router: bool,
+
+    /// Emit `ConvexAdminClient`, a `ConvexApiClient` authenticated with a
+    /// deployment admin key, for tests and admin tooling (default: false).
+    ///
+    /// `ConvexAdminClient::with_admin_key(url, key)` connects and
+    /// authenticates. `table_size::<T>()` and `document_counts()` read the
+    /// schema's tables through the deployment's `_system` queries, which only
+    /// admins may call.
+    pub emit_admin_client: bool,
+
+    /// Emit a `*_with_timeout(args, timeout)` variant of every `ConvexApi`
+    /// method on `ConvexApiClient` (default: false).
+    ///
+    /// A call that misses its deadline fails with `ConvexError::Timeout`
+    /// instead of waiting on a dead connection. The generated code uses
+    /// `tokio::time::timeout`, so the consuming crate needs `tokio` with the
+    /// `time` feature.
+    pub emit_timeouts: bool,
+
+    /// Emit `ConsistentSession`, from `ConvexApiClient::consistent_session`,
+    /// which reads several queries as of one server state (default: false).
+    ///
+    /// It has a `query_*` method per public query, and `snapshot` returns all
+    /// the added queries' results from the same server transition, e.g. for a
+    /// report that must not mix data from before and after a write.
+    pub emit_consistent_reads: bool,
+
+    /// Emit an `OfflineQueue` that saves mutation calls to a JSONL file while
+    /// the client is offline and replays them in order on reconnect (default:
+    /// false).
+    ///
+    /// Every public mutation's args struct implements `QueuedMutation`. Calls
+    /// the server rejects on replay go to a conflict hook that skips, retries,
+    /// or stops.
+    pub emit_offline_queue: bool,
+
+    /// Emit a `{Table}Insert` struct for every table, without the system
+    /// fields, that writes `npx convex import` files through
+    /// `convex_typegen::import::ImportWriter` (default: false).
+    ///
+    /// The consuming crate needs `convex-typegen` as a regular dependency.
+    pub emit_import_writers: bool,
+
+    /// Emit a `{Table}TableFixture` builder for every table, for tests and
+    /// demos: `GamesTableFixture::builder().win_count(3.0).build()` (default:
+    /// false).
+    ///
+    /// Fields the builder isn't given get deterministic values derived from
+    /// its seed by `convex_typegen::fixtures`, so the consuming crate needs
+    /// `convex-typegen` as a dependency (a dev-dependency is enough when only
+    /// tests use the fixtures).
+    pub emit_fixtures: bool,
+
+    /// Emit `FunctionRef`, the path, kind, and visibility of a function, with
+    /// an associated const for every query, mutation, and action, internal
+    /// ones included: `FunctionRef::GAMES_GET_GAME` (default: false).
+    ///
+    /// It replaces path strings where code names a function, such as
+    /// scheduling through an action, logging, or allowlists, and serializes as
+    /// the path.
+    pub emit_function_refs: bool,
+
+    /// Emit `ScheduleRequest`, a call to a mutation or action with typed args
+    /// and a delay, for Rust code that enqueues work through a scheduling
+    /// mutation (default: false).
+    ///
+    /// The args struct of every mutation and action implements `Schedulable`,
+    /// which picks its `FunctionRef`, so this turns on `FunctionRef` as well.
+    /// A request serializes as `{ fn, args, delayMs }`, or `runAt` instead of
+    /// `delayMs`.
+    pub emit_schedule_requests: bool,
+
+    /// Give the enum of every table field that is a union of string literals
+    /// a `label()` method and a `LABELS` table (default: false).
+    ///
+    /// Labels come from [`Configuration::enum_labels`]. Values without an
+    /// entry are labeled from the value itself, so `in_progress` and
+    /// `inProgress` become "In progress".
+    pub emit_enum_labels: bool,
+
+    /// Module path the generated file is reachable at from outside the crate,
+    /// e.g. `"my_app::convex_types"` (default: None).
+    ///
+    /// When set, the `ConvexApi` trait is documented with `no_run` examples of a
+    /// typed query, a subscription, and a mutation with optional args, written
+    /// against this schema's generated names so `cargo test --doc` compiles
+    /// them. The subscription example uses `futures::StreamExt`.
+    pub doc_examples_module: Option<String>,
+
+    /// Put `#[allow(dead_code)]` on the generated nested types (inline
+    /// objects, enums, and id enums) (default: false).
+    ///
+    /// For crates that use only part of the API, where unused nested types
+    /// would otherwise cause `dead_code` warnings.
+    pub allow_dead_code: bool,
+
+    /// Parse the generated code before writing it and fail with the offending
+    /// snippet if it isn't valid Rust (default: true).
+    ///
+    /// On failure the previous output file is left untouched.
+    pub verify_syntax: bool,
+
+    /// Fail instead of writing a generated file longer than this many lines
+    /// (default: None).
+    ///
+    /// The error names the largest tables and functions and suggests settings
+    /// that shrink the output. Per-item sizes are in the report either way.
+    pub max_output_lines: Option<usize>,
+
+    /// Wrap generated code for each function kind in a cargo feature so
+    /// binary-size-sensitive builds can compile only what they call
+    /// (default: None, nothing is gated).
+    ///
+    /// Example: `Some(FeatureGates { internal: None, ..Default::default() })`
+    /// gates queries, mutations, and actions behind `convex-queries`,
+    /// `convex-mutations`, and `convex-actions`.
+    pub feature_gates: Option<FeatureGates>,
+
+    /// Print a one-line summary (table, function, and warning counts, duration,
+    /// and output path) to stderr after generation (default: false).
+    ///
+    /// Setting `CONVEX_TYPEGEN_VERBOSE=1` prints the summary plus every table,
+    /// function, and warning, regardless of this flag.
+    pub print_summary: bool,
+
+    /// Write the [`GenerationReport`] as JSON to this file after each
+    /// [`generate`] (default: None).
+    ///
+    /// The format is stable and versioned (see [`GenerationReport::to_json`]),
+    /// so CI can collect the files of many services, or fail a build when the
+    /// warnings grow. The file is rewritten on every run.
+    pub report_file: Option<PathBuf>,
+
+    /// Drop every message below errors (default: false): the Bun download
+    /// notices, the summary, and the verbose listing, even when
+    /// `print_summary` or `CONVEX_TYPEGEN_VERBOSE` asks for them.
+    ///
+    /// Messages go through the `log` crate under the `convex_typegen` target,
+    /// or to stderr when no logger is installed, as in a build script.
+    pub quiet: bool,
+
+    /// Run the best-practice lints and add their findings to
+    /// [`GenerationReport::warnings`] (default: false).
+    ///
+    /// Flags nested optional unions, `v.any()` in args, records not keyed by
+    /// `v.string()`, and list queries filtering on an id column without a
+    /// matching index.
+    pub lint: bool,
+
+    /// Fail generation when any table field, arg, or return type, or part of
+    /// one, would be `serde_json::Value` (default: false).
+    ///
+    /// `v.any()`, empty objects, and validators the extractor couldn't resolve
+    /// all become `Value`. The error lists every location, the same ones
+    /// reported as warnings otherwise, so untyped holes can't slip into the
+    /// API. Tables left out by [`Configuration::prune_unreachable`] don't
+    /// count.
+    pub strict: bool,
+
+    /// Stop at the first table, function, or file that can't be extracted
+    /// (default: false).
+    ///
+    /// By default every problem is collected, and generation fails with all of
+    /// them in [`ConvexTypeGeneratorError::Diagnostics`], so one run shows
+    /// everything to fix. The list also holds what the checks after extraction
+    /// find in the rest of the model, such as [`Configuration::strict`]
+    /// locations. With `fail_fast`, every backend stops extracting at the first
+    /// problem, so later files aren't loaded, and it is returned on its own as
+    /// [`ConvexTypeGeneratorError::ExtractionFailed`].
+    pub fail_fast: bool,
+
+    /// Apply the `CONVEX_TYPEGEN_*` environment variables in [`ENV_VARS`] before
+    /// generating (default: true).
+    ///
+    /// A set variable wins over the value in this struct, which wins over the
+    /// default. Set this to false to ignore the environment entirely.
+    pub env_overrides: bool,
+
+    /// The `cargo:` directives [`generate`] prints (default: none).
+    ///
+    /// Example: `CargoIntegration::build_script()` reruns the build script when
+    /// an input file or a `CONVEX_TYPEGEN_*` variable changes.
+    pub cargo_integration: CargoIntegration,
 }
 
 impl Default for Configuration
@@ -68,8 +1051,184 @@ impl Default for Configuration
             schema_path: PathBuf::from("convex/schema.ts"),
             out_file: PathBuf::from("src/convex_types.rs"),
             function_paths: Vec::new(),
+            model_input: None,
+            base_dir: None,
+            on_empty_function_file: EmptyFunctionFile::Warn,
             helper_stubs: HashMap::new(),
+            custom_validators: HashMap::new(),
+            extraction_backend: ExtractionBackend::Bun,
+            offline: false,
+            bun_path: None,
+            field_overrides: HashMap::new(),
+            display_fields: HashMap::new(),
+            collection_keys: HashMap::new(),
+            enum_labels: HashMap::new(),
+            default_missing_fields: true,
+            typed_ids: false,
+            flatten_system_fields: false,
+            arg_defaults: HashMap::new(),
+            finite_number_args: false,
+            deprecated_functions: HashMap::new(),
+            redacted_args: HashSet::new(),
+            function_timeouts: HashMap::new(),
+            function_priorities: HashMap::new(),
+            method_naming: MethodNaming::Bare,
+            split_api_by_file: false,
+            split_output_by_table: false,
+            internal_module: false,
+            prune_unreachable: false,
+            keep_tables: HashSet::new(),
+            artifacts: Vec::new(),
+            previous_model: None,
+            emit_previous_tables: false,
+            emit_shared_subscriptions: false,
+            emit_arc_subscriptions: false,
+            emit_snapshot_subscriptions: false,
+            emit_buffered_subscriptions: false,
+            emit_resilient_subscriptions: false,
+            emit_cursor_store: false,
+            emit_frontend_adapters: false,
+            emit_tauri_commands: false,
+            emit_axum_router: false,
+            emit_tenant_router: false,
+            emit_admin_client: false,
+            emit_timeouts: false,
+            emit_consistent_reads: false,
+            emit_offline_queue: false,
+            emit_import_writers: false,
+            emit_fixtures: false,
+            emit_function_refs: false,
+            emit_schedule_requests: false,
+            emit_enum_labels: false,
+            doc_examples_module: None,
+            version_tag: None,
+            allow_dead_code: false,
+            verify_syntax: true,
+            max_output_lines: None,
+            feature_gates: None,
+            print_summary: false,
+            report_file: None,
+            quiet: false,
+            lint: false,
+            strict: false,
+            fail_fast: false,
+            env_overrides: true,
+            cargo_integration: CargoIntegration::default(),
+        }
+    }
+}
+
+impl Configuration
+{
+    /// Load a configuration from a TOML file such as `convex-typegen.toml`.
+    ///
+    /// Keys are this struct's field names. Keys the file leaves out keep their
+    /// defaults, and relative paths in the file, including `base_dir`, are
+    /// resolved against the file's directory. Override individual fields in
+    /// code with struct update syntax:
+    ///
+    /// ```no_run
+    /// # use convex_typegen::Configuration;
+    /// let config = Configuration {
+    ///     out_file: format!("{}/convex_types.rs", std::env::var("OUT_DIR").unwrap()).into(),
+    ///     ..Configuration::from_file("convex-typegen.toml").unwrap()
+    /// };
+    /// ```
+    ///
+    /// # Errors
+    /// Fails with [`ConvexTypeGeneratorError::IOError`] when the file can't be
+    /// read and [`ConvexTypeGeneratorError::InvalidConfigFile`] when it isn't
+    /// valid TOML, has an unknown key, or has a value of the wrong type.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        config_file::load(path.as_ref())
+    }
+
+    /// Replace fields with the values of any set `CONVEX_TYPEGEN_*` variables.
+    ///
+    /// Paths and the doc examples module are taken as text and
+    /// `CONVEX_TYPEGEN_FUNCTIONS` is a `PATH`-style list. Flags accept
+    /// `true`/`false` or `1`/`0`. `CONVEX_TYPEGEN_BACKEND`,
+    /// `CONVEX_TYPEGEN_ON_EMPTY_FUNCTION_FILE`, and
+    /// `CONVEX_TYPEGEN_METHOD_NAMING` take a variant name such as `ast`,
+    /// `error`, or `prefixed`. `CONVEX_TYPEGEN_MAX_OUTPUT_LINES`,
+    /// `CONVEX_TYPEGEN_DOC_EXAMPLES_MODULE`, and `CONVEX_TYPEGEN_FEATURE_GATES`
+    /// accept `none`; the feature gates also accept `default` or a JSON object.
+    /// Helper stubs, custom validators, field overrides, and arg defaults are
+    /// JSON objects keyed like their fields, and `CONVEX_TYPEGEN_REDACTED_ARGS` and
+    /// `CONVEX_TYPEGEN_KEEP_TABLES` are JSON arrays. `CONVEX_TYPEGEN_CARGO_INTEGRATION`
+    /// is a JSON object with the [`CargoIntegration`] fields. Empty variables are
+    /// ignored.
+    ///
+    /// [`generate`] and [`explain`] call this unless
+    /// [`Configuration::env_overrides`] is false.
+    ///
+    /// # Errors
+    /// Fails with [`ConvexTypeGeneratorError::InvalidEnvOverride`] on a value
+    /// that doesn't parse.
+    pub fn from_env_overrides(self) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        env::apply(self)
+    }
+
+    /// Make every configured path absolute by joining relative ones onto
+    /// [`Configuration::base_dir`], without Windows verbatim (`\\?\`) prefixes.
+    fn resolve_paths(mut self) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let base_dir = match self
+            .base_dir
+            .take()
+            .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+        {
+            Some(dir) => std::path::absolute(&dir).map_err(|error| ConvexTypeGeneratorError::IOError {
+                file: dir.display().to_string(),
+                error,
+            })?,
+            None => std::env::current_dir()?,
+        };
+        let base_dir = dunce::simplified(&base_dir).to_path_buf();
+
+        // `canonicalize()` on Windows gives `\\?\C:\...` paths, which Bun can't
+        // import from, so they are turned back into plain `C:\...` paths
+        let resolve = |path: &mut PathBuf| {
+            let absolute = base_dir.join(&*path);
+            *path = dunce::simplified(&absolute).to_path_buf();
+        };
+        resolve(&mut self.schema_path);
+        resolve(&mut self.out_file);
+        self.function_paths.iter_mut().for_each(resolve);
+        self.helper_stubs.values_mut().for_each(resolve);
+        self.artifacts.iter_mut().map(Artifact::path_mut).for_each(resolve);
+        self.previous_model.iter_mut().for_each(resolve);
+        self.model_input.iter_mut().for_each(resolve);
+        self.bun_path.iter_mut().for_each(resolve);
+        self.report_file.iter_mut().for_each(resolve);
+
+        self.base_dir = Some(base_dir);
+        Ok(self)
+    }
+
+    /// Check `version_tag` and move `out_file` to its versioned name, e.g.
+    /// `convex_types.rs` to `convex_types_v7.rs`.
+    fn apply_version_tag(mut self) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let Some(tag) = &self.version_tag else {
+            return Ok(self);
+        };
+        // The tag, lowercased, also names the module the code is wrapped in
+        let module = syn::parse_str::<syn::Ident>(&tag.to_ascii_lowercase());
+        if module.is_err() || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ConvexTypeGeneratorError::InvalidVersionTag(tag.clone()));
+        }
+        let stem = self.out_file.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        let stem = stem.ok_or_else(|| ConvexTypeGeneratorError::InvalidPath(self.out_file.display().to_string()))?;
+        let mut name = format!("{stem}_{}", tag.to_ascii_lowercase());
+        if let Some(extension) = self.out_file.extension() {
+            name.push('.');
+            name.push_str(&extension.to_string_lossy());
         }
+        self.out_file.set_file_name(name);
+        Ok(self)
     }
 }
 
@@ -79,24 +1238,562 @@ impl Default for Configuration
 /// * `config` - Configuration options for the type generation process
 ///
 /// # Returns
-/// * `Ok(())` if type generation succeeds
+/// * `Ok(GenerationReport)` describing what was generated if type generation succeeds
 /// * `Err(ConvexTypeGeneratorError)` if an error occurs during generation
 ///
 /// # Errors
 /// This function can fail for several reasons:
 /// * Schema file not found
+/// * [`Configuration::model_input`] isn't a model JSON
+/// * A `CONVEX_TYPEGEN_*` variable has an invalid value
 /// * Bun extractor script fails
+/// * A function file yields no functions and
+///   [`Configuration::on_empty_function_file`] is [`EmptyFunctionFile::Error`]
+/// * Something maps to `serde_json::Value` and [`Configuration::strict`] is set
+/// * The generated code doesn't parse and [`Configuration::verify_syntax`] is set
+/// * The generated code is longer than [`Configuration::max_output_lines`]
 /// * IO errors when writing the output file
 /// * Network errors when downloading bun (first run only)
-pub fn generate(config: Configuration) -> Result<(), ConvexTypeGeneratorError>
+pub fn generate(config: Configuration) -> Result<GenerationReport, ConvexTypeGeneratorError>
 {
-    if !config.schema_path.exists() {
-        return Err(ConvexTypeGeneratorError::MissingSchemaFile);
+    let started = Instant::now();
+    let Rendered {
+        config,
+        mut report,
+        code,
+        table_files,
+        artifacts,
+    } = render(config)?;
+    for directive in cargo_directives(&config) {
+        println!("cargo:{directive}");
     }
 
-    let (schema, functions) = extract::extract(&config.schema_path, &config.function_paths, &config.helper_stubs)?;
+    // Rewriting identical code would still bump the mtime, and cargo would
+    // rebuild everything that `include!`s the file
+    if write_if_changed(&config.out_file, &code)? {
+        report.rewritten_files.push(config.out_file.clone());
+    }
+    if config.split_output_by_table {
+        let dir = table_files_dir(&config.out_file);
+        std::fs::create_dir_all(&dir)?;
+        for (path, file) in &table_files {
+            if write_if_changed(path, file)? {
+                report.rewritten_files.push(path.clone());
+            }
+        }
+        for path in stale_table_files(&dir, &report.table_files)? {
+            std::fs::remove_file(&path)?;
+            report.removed_files.push(path);
+        }
+    }
+    report.unchanged = report.rewritten_files.is_empty() && report.removed_files.is_empty();
+    artifacts::write(&artifacts)?;
 
-    generate_code(&config.out_file, (schema, functions))?;
+    report.duration = started.elapsed();
+    if let Some(path) = &config.report_file {
+        report::write_json(&report, path)?;
+    }
+    let verbose = report::verbose_from_env();
+    if (config.print_summary || verbose) && !config.quiet {
+        report.print(verbose);
+    }
+
+    Ok(report)
+}
+
+/// Generates Rust types into `out_file` from a model JSON written by another
+/// crate's [`Artifact::ModelJson`], without extracting.
+///
+/// Shorthand for [`generate`] with [`Configuration::model_input`] set and the
+/// other settings at their defaults. Relative paths resolve against
+/// `CARGO_MANIFEST_DIR`.
+///
+/// ```no_run
+/// // build.rs of a crate that shares the model of the `app` crate
+/// let model = convex_typegen::shared_model_path("app");
+/// println!("cargo:rerun-if-changed={}", model.display());
+/// let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("convex_types.rs");
+/// convex_typegen::generate_from_model(model, out).expect("generating from the shared model");
+/// ```
+///
+/// # Errors
+/// Fails when the model can't be read or isn't a model JSON, and for the same
+/// reasons as [`generate`] after extraction.
+pub fn generate_from_model(
+    model_path: impl Into<PathBuf>,
+    out_file: impl Into<PathBuf>,
+) -> Result<GenerationReport, ConvexTypeGeneratorError>
+{
+    generate(Configuration {
+        model_input: Some(model_path.into()),
+        out_file: out_file.into(),
+        ..Default::default()
+    })
+}
+
+/// Where the crates of a workspace share the model named `name`:
+/// `convex-typegen/{name}.json` in the target directory.
+///
+/// The target directory is `CARGO_TARGET_DIR` when set. In a build script
+/// it is otherwise found from `OUT_DIR`, so members of one workspace agree on
+/// it whichever directory they build from; elsewhere it is `./target`. The
+/// producer crate writes the model there with [`Artifact::ModelJson`], and the
+/// others read it with [`generate_from_model`]. A consumer must build after
+/// the producer, e.g. by listing it under `[build-dependencies]`.
+pub fn shared_model_path(name: &str) -> PathBuf
+{
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            // Cargo tags the root of every target directory it creates
+            let out_dir = PathBuf::from(std::env::var_os("OUT_DIR")?);
+            out_dir
+                .ancestors()
+                .find(|dir| dir.join("CACHEDIR.TAG").is_file())
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|| PathBuf::from("target"));
+    target_dir.join("convex-typegen").join(format!("{name}.json"))
+}
+
+/// The `cargo:` directives [`Configuration::cargo_integration`] asks for,
+/// without the prefix.
+fn cargo_directives(config: &Configuration) -> Vec<String>
+{
+    let cargo = &config.cargo_integration;
+    let mut directives = Vec::new();
+    if cargo.rerun_if_changed {
+        directives.extend(
+            input_paths(config)
+                .into_iter()
+                .map(|path| format!("rerun-if-changed={}", path.display())),
+        );
+    }
+    if cargo.rerun_if_env_changed && config.env_overrides {
+        directives.extend(ENV_VARS.iter().map(|var| format!("rerun-if-env-changed={var}")));
+    }
+    directives.extend(cargo.directives.iter().cloned());
+    directives
+}
+
+/// The files generation reads: the schema and function files (or the model
+/// that replaces them), the helper stubs, and the previous model.
+fn input_paths(config: &Configuration) -> Vec<&Path>
+{
+    let inputs: Vec<&Path> = match &config.model_input {
+        // A model replaces the source files
+        Some(model) => vec![model.as_path()],
+        None => std::iter::once(config.schema_path.as_path())
+            .chain(config.function_paths.iter().map(PathBuf::as_path))
+            .chain(config.helper_stubs.values().map(PathBuf::as_path))
+            .collect(),
+    };
+    inputs.into_iter().chain(config.previous_model.as_deref()).collect()
+}
+
+/// Checks whether `out_file` still matches its inputs, without extracting or
+/// generating anything.
+///
+/// Every generated Rust file ends with a manifest of the digests of its
+/// inputs (see [`Configuration::out_file`]), its settings, and its own
+/// contents. This compares them with the files on disk, so CI can tell a
+/// stale committed file from a fresh one without Bun. Files reached through
+/// relative imports aren't inputs, as with `rerun-if-changed`; a change to
+/// them alone goes unnoticed. Artifacts carry no manifest and aren't
+/// checked; [`generate_dry_run`] covers them.
+///
+/// # Errors
+/// Fails when the configuration is invalid or an input or the file exists but
+/// can't be read.
+pub fn check_freshness(config: Configuration) -> Result<manifest::Freshness, ConvexTypeGeneratorError>
+{
+    let config = if config.env_overrides {
+        config.from_env_overrides()?
+    } else {
+        config
+    };
+    let config = config.resolve_paths()?.apply_version_tag()?;
+    manifest::check(&config)
+}
+
+/// Runs generation without writing anything, and returns what would be
+/// written with a diff against each file's current contents.
+///
+/// Use it for `check` jobs in CI, which fail when the committed output is
+/// stale ([`GenerationPlan::is_unchanged`]), or to preview a change in an
+/// editor. The summary is never printed.
+///
+/// # Errors
+/// Fails for the same reasons as [`generate`], except that nothing is written.
+pub fn generate_dry_run(config: Configuration) -> Result<GenerationPlan, ConvexTypeGeneratorError>
+{
+    let started = Instant::now();
+    let Rendered {
+        config,
+        mut report,
+        code,
+        table_files,
+        artifacts,
+    } = render(config)?;
+
+    let code = PlannedFile::new(config.out_file.clone(), code);
+    let table_files: Vec<PlannedFile> = table_files
+        .into_iter()
+        .map(|(path, file)| PlannedFile::new(path, file))
+        .collect();
+    let stale_files = if config.split_output_by_table {
+        stale_table_files(&table_files_dir(&config.out_file), &report.table_files)?
+    } else {
+        Vec::new()
+    };
+    report.unchanged = code.is_unchanged() && table_files.iter().all(PlannedFile::is_unchanged) && stale_files.is_empty();
+    report.duration = started.elapsed();
+
+    Ok(GenerationPlan {
+        code,
+        table_files,
+        stale_files,
+        artifacts: artifacts
+            .into_iter()
+            .map(|(artifact, contents)| PlannedFile::new(artifact.path().to_path_buf(), contents))
+            .collect(),
+        report,
+    })
+}
+
+/// Write `contents` to `path` unless the file already holds them. Returns
+/// whether it was written.
+fn write_if_changed(path: &Path, contents: &str) -> std::io::Result<bool>
+{
+    if std::fs::read(path).is_ok_and(|existing| existing == contents.as_bytes()) {
+        return Ok(false);
+    }
+    std::fs::write(path, contents)?;
+    Ok(true)
+}
+
+/// The table files in `dir` that aren't among `table_files`, sorted: tables
+/// that were removed or renamed. Only `.rs` files that start with the header
+/// of a table file count, so hand-written modules next to the generated ones
+/// are left alone. A missing `dir` has none.
+fn stale_table_files(dir: &Path, table_files: &[PathBuf]) -> std::io::Result<Vec<PathBuf>>
+{
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut stale = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "rs") && !table_files.contains(&path) && is_table_file(&path)? {
+            stale.push(path);
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// Whether the file at `path` starts with the header `split_by_table` gives
+/// table files, e.g. "// The `users` table, generated by convex-typegen".
+fn is_table_file(path: &Path) -> std::io::Result<bool>
+{
+    use std::io::BufRead;
 
-    Ok(())
+    let mut first_line = Vec::new();
+    std::io::BufReader::new(std::fs::File::open(path)?).read_until(b'\n', &mut first_line)?;
+    let first_line = String::from_utf8_lossy(&first_line);
+    Ok(first_line.starts_with("// The `") && first_line.contains("` table, generated by convex-typegen"))
+}
+
+/// Everything a generation writes, rendered but not yet written.
+struct Rendered
+{
+    /// The config with overrides applied and paths resolved.
+    config: Configuration,
+    report: GenerationReport,
+    code: String,
+    /// With `split_output_by_table`, each table's file and its code.
+    table_files: Vec<(PathBuf, String)>,
+    artifacts: Vec<(Artifact, String)>,
+}
+
+/// Extract, check, and render the code and artifacts for `config`.
+fn render(config: Configuration) -> Result<Rendered, ConvexTypeGeneratorError>
+{
+    let config = if config.env_overrides {
+        config.from_env_overrides()?
+    } else {
+        config
+    };
+    let mut config = config.resolve_paths()?.apply_version_tag()?;
+    if config.model_input.is_none() && !config.schema_path.exists() {
+        return Err(ConvexTypeGeneratorError::MissingSchemaFile {
+            path: config.schema_path,
+        });
+    }
+
+    // Before directives from the source files are merged into the settings
+    let manifest = manifest::Manifest::read(&config)?;
+
+    let Extraction {
+        mut schema,
+        mut functions,
+        bun_fallbacks,
+        resolve_trace,
+        mut diagnostics,
+    } = run_extraction(&config)?;
+
+    let mut report = report::build(config.out_file.clone(), &schema, &functions);
+    report.bun_fallbacks = bun_fallbacks;
+    report.resolve_trace = resolve_trace;
+    report.empty_function_files = report::empty_function_files(&config.function_paths, &functions);
+    // A file that failed to extract is already reported
+    report
+        .empty_function_files
+        .retain(|file| !diagnostics.iter().any(|diagnostic| diagnostic.file.as_ref() == Some(file)));
+    let empty_files_fail = config.on_empty_function_file == EmptyFunctionFile::Error;
+    if empty_files_fail && !diagnostics.is_empty() {
+        diagnostics.extend(report.empty_function_files.iter().map(|file| Diagnostic {
+            file: Some(file.clone()),
+            line: None,
+            message: "no query, mutation, or action exports".to_string(),
+            suggestion: None,
+        }));
+    } else if empty_files_fail && !report.empty_function_files.is_empty() {
+        return Err(ConvexTypeGeneratorError::EmptyFunctionFiles {
+            files: report.empty_function_files,
+        });
+    }
+    for file in &report.empty_function_files {
+        report.warnings.push(report::empty_function_file_warning(file));
+    }
+    // A model comes without the source files the directives are in
+    let directives = match config.model_input {
+        Some(_) => directives::Directives::default(),
+        None => directives::collect(&config.schema_path, &config.function_paths)?,
+    };
+    for (key, format) in directives.field_formats {
+        config.field_overrides.entry(key).or_default().format.get_or_insert(format);
+    }
+    report
+        .warnings
+        .extend(report::unknown_field_overrides(&config.field_overrides, &schema));
+    report
+        .warnings
+        .extend(report::field_format_warnings(&config.field_overrides, &schema));
+    report
+        .warnings
+        .extend(report::display_field_warnings(&config.display_fields, &schema));
+    report
+        .warnings
+        .extend(report::collection_key_warnings(&config.collection_keys, &schema));
+    report.warnings.extend(report::enum_label_warnings(
+        &config.enum_labels,
+        config.emit_enum_labels,
+        &schema,
+    ));
+    report.warnings.extend(directives.warnings);
+    for (key, value) in directives.arg_defaults {
+        config.arg_defaults.entry(key).or_insert(value);
+    }
+    for (key, note) in directives.deprecated_functions {
+        config.deprecated_functions.entry(key).or_insert(note);
+    }
+    config.redacted_args.extend(directives.redacted_args);
+    for (key, timeout) in directives.function_timeouts {
+        config.function_timeouts.entry(key).or_insert(timeout);
+    }
+    for (key, priority) in directives.function_priorities {
+        config.function_priorities.entry(key).or_insert(priority);
+    }
+    report
+        .warnings
+        .extend(report::arg_default_warnings(&config.arg_defaults, &functions));
+    report
+        .warnings
+        .extend(report::unknown_deprecated_functions(&config.deprecated_functions, &functions));
+    report
+        .warnings
+        .extend(report::unknown_redacted_args(&config.redacted_args, &functions));
+    report
+        .warnings
+        .extend(report::function_timeout_warnings(&config.function_timeouts, &functions));
+    report
+        .warnings
+        .extend(report::function_priority_warnings(&config.function_priorities, &functions));
+    report
+        .warnings
+        .extend(report::unknown_keep_tables(&config.keep_tables, &schema));
+    if config.lint {
+        report.warnings.extend(lint::lint(&schema, &functions));
+    }
+    // After the lints, which report the unions as written
+    descriptors::normalize_model(&mut schema, &mut functions);
+    if config.prune_unreachable {
+        descriptors::prune_unreachable(&mut schema, &functions, &config.keep_tables);
+        report
+            .tables
+            .retain(|table| schema.tables.iter().any(|kept| kept.name == table.name));
+    }
+    if config.strict {
+        let holes = report::untyped_hole_warnings(&schema, &functions);
+        if !diagnostics.is_empty() {
+            diagnostics.extend(holes.iter().map(|hole| Diagnostic {
+                file: None,
+                line: None,
+                message: format!("strict: {hole}"),
+                suggestion: None,
+            }));
+        } else if !holes.is_empty() {
+            return Err(ConvexTypeGeneratorError::UntypedFields {
+                locations: holes.iter().map(ToString::to_string).collect(),
+            });
+        }
+    }
+    if !diagnostics.is_empty() {
+        return Err(ConvexTypeGeneratorError::Diagnostics { diagnostics });
+    }
+
+    let previous = config.previous_model.as_deref().map(migration::load).transpose()?;
+    report.schema_fingerprint = codegen::schema_fingerprint(&schema);
+    report.model_changes = previous.as_ref().map(|previous| report::model_changes(previous, &schema));
+    let artifacts = artifacts::render(&config, &schema, &functions, previous.as_ref())?;
+    let (GeneratedCode { code, table_files }, size) = render_code(&config, (schema, functions), previous.as_ref())?;
+    let code = manifest.stamp(&code);
+    let dir = table_files_dir(&config.out_file);
+    let table_files: Vec<(PathBuf, String)> = table_files
+        .into_iter()
+        .map(|(table, file)| (dir.join(format!("{table}.rs")), file))
+        .collect();
+    report.lines = code.lines().count() + table_files.iter().map(|(_, file)| file.lines().count()).sum::<usize>();
+    report.table_files = table_files.iter().map(|(path, _)| path.clone()).collect();
+    report::record_sizes(&mut report, &size);
+    report.artifacts = artifacts.iter().map(|(artifact, _)| artifact.path().to_path_buf()).collect();
+
+    Ok(Rendered {
+        config,
+        report,
+        code,
+        table_files,
+        artifacts,
+    })
+}
+
+/// Describes how the generator resolves a schema without writing any code.
+///
+/// The returned report lists every table, field, and function together with
+/// its Convex validator and the Rust type it maps to, and flags each location
+/// that falls back to `serde_json::Value`. This is what the
+/// `convex-typegen explain` command prints.
+///
+/// # Errors
+/// Fails for the same reasons as [`generate`], except that nothing is written.
+pub fn explain(config: Configuration) -> Result<String, ConvexTypeGeneratorError>
+{
+    let config = if config.env_overrides {
+        config.from_env_overrides()?
+    } else {
+        config
+    };
+    let config = config.resolve_paths()?;
+    if config.model_input.is_none() && !config.schema_path.exists() {
+        return Err(ConvexTypeGeneratorError::MissingSchemaFile {
+            path: config.schema_path,
+        });
+    }
+
+    let Extraction {
+        mut schema,
+        mut functions,
+        diagnostics,
+        ..
+    } = run_extraction(&config)?;
+    if !diagnostics.is_empty() {
+        return Err(ConvexTypeGeneratorError::Diagnostics { diagnostics });
+    }
+    descriptors::normalize_model(&mut schema, &mut functions);
+    if config.prune_unreachable {
+        descriptors::prune_unreachable(&mut schema, &functions, &config.keep_tables);
+    }
+
+    Ok(explain::render(&config, &schema, &functions))
+}
+
+/// What [`run_extraction`] found.
+struct Extraction
+{
+    schema: types::ConvexSchema,
+    functions: types::ConvexFunctions,
+    /// The files that fell back to Bun (hybrid and embedded backends only).
+    bun_fallbacks: Vec<report::BunFallback>,
+    /// The Bun run's resolution trace, when `CONVEX_TYPEGEN_TRACE_RESOLVE` is set.
+    resolve_trace: Vec<report::ResolveEvent>,
+    /// The tables, functions, and files that couldn't be extracted, which are
+    /// left out of `schema` and `functions`. Always empty with
+    /// [`Configuration::fail_fast`].
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Extract the schema and functions with the configured backend, or read
+/// them from [`Configuration::model_input`].
+fn run_extraction(config: &Configuration) -> Result<Extraction, ConvexTypeGeneratorError>
+{
+    if let Some(model) = &config.model_input {
+        let (schema, functions) = artifacts::load_model(model)?;
+        return Ok(Extraction {
+            schema,
+            functions,
+            bun_fallbacks: Vec::new(),
+            resolve_trace: Vec::new(),
+            diagnostics: Vec::new(),
+        });
+    }
+    let options = extract::ExtractOptions::new(config);
+    let extraction = match config.extraction_backend {
+        ExtractionBackend::Bun => {
+            let (schema, functions, resolve_trace, diagnostics) = extract::extract(&options, &config.function_paths)?;
+            Extraction {
+                schema,
+                functions,
+                bun_fallbacks: Vec::new(),
+                resolve_trace,
+                diagnostics,
+            }
+        }
+        ExtractionBackend::Ast => {
+            let (schema, functions, diagnostics) = ast::extract(
+                &config.schema_path,
+                &config.function_paths,
+                &config.custom_validators,
+                config.fail_fast,
+            )?;
+            Extraction {
+                schema,
+                functions,
+                bun_fallbacks: Vec::new(),
+                resolve_trace: Vec::new(),
+                diagnostics,
+            }
+        }
+        ExtractionBackend::Hybrid => hybrid::extract(&options, &config.function_paths)?,
+        #[cfg(feature = "embedded")]
+        ExtractionBackend::Embedded => embedded::extract(&options, &config.function_paths)?,
+        #[cfg(not(feature = "embedded"))]
+        ExtractionBackend::Embedded => {
+            return Err(ConvexTypeGeneratorError::ExtractionFailed {
+                details: "ExtractionBackend::Embedded needs convex-typegen's `embedded` feature".to_string(),
+                causes: Vec::new(),
+            })
+        }
+    };
+
+    // Without `fail_fast`, Bun reports the files it couldn't load and carries on
+    match extraction.diagnostics.first() {
+        Some(first) if config.fail_fast => Err(ConvexTypeGeneratorError::ExtractionFailed {
+            details: first.to_string(),
+            causes: Vec::new(),
+        }),
+        _ => Ok(extraction),
+    }
 }
@@ -0,0 +1,219 @@
+//! Opt-in lints for Convex best practices ([`crate::Configuration::lint`]).
+//!
+//! Every lint works on the extracted descriptors, so it runs the same way for
+//! every extraction backend. Findings are added to
+//! [`GenerationReport::warnings`](crate::report::GenerationReport::warnings)
+//! with [`GenerationWarning::lint`] set to the lint name.
+
+use serde_json::Value as JsonValue;
+
+use crate::descriptors::render_validator;
+use crate::report::{function_path, GenerationWarning};
+use crate::types::{ConvexFunction, ConvexSchema, ConvexTable};
+
+/// `v.optional(...)` wrapping another optional or a nullable union.
+pub(crate) const NESTED_OPTIONAL: &str = "nested-optional";
+/// `v.union(...)` with a `v.union(...)` variant.
+pub(crate) const NESTED_UNION: &str = "nested-union";
+/// `v.any()` anywhere in a function's args.
+pub(crate) const ANY_IN_ARGS: &str = "any-in-args";
+/// `v.record(...)` keyed by something other than `v.string()`.
+pub(crate) const RECORD_KEY: &str = "record-key";
+/// A list query filtering on an id column that no index starts with.
+pub(crate) const UNINDEXED_LOOKUP: &str = "unindexed-lookup";
+
+/// Run every lint over the schema and functions.
+pub(crate) fn lint(schema: &ConvexSchema, functions: &[ConvexFunction]) -> Vec<GenerationWarning>
+{
+    let mut warnings = Vec::new();
+
+    for table in &schema.tables {
+        for column in &table.columns {
+            lint_descriptor(
+                &column.data_type,
+                &column.name,
+                &format!("table {}", table.name),
+                &mut warnings,
+            );
+        }
+    }
+
+    for func in functions {
+        let location = format!("function {}", function_path(func));
+        for param in &func.params {
+            lint_descriptor(&param.data_type, &format!("args.{}", param.name), &location, &mut warnings);
+            if contains_any(&param.data_type) {
+                warnings.push(GenerationWarning {
+                    location: format!("{location} args.{}", param.name),
+                    message: "v.any() in args skips Convex argument validation; declare the expected shape".to_string(),
+                    lint: Some(ANY_IN_ARGS),
+                });
+            }
+        }
+        if let Some(return_type) = &func.return_type {
+            lint_descriptor(return_type, "returns", &location, &mut warnings);
+        }
+        if is_list_query(func) {
+            lint_unindexed_lookups(func, &location, &schema.tables, &mut warnings);
+        }
+    }
+
+    warnings
+}
+
+// ---------------------------------------------------------------------------
+// Descriptor lints
+// ---------------------------------------------------------------------------
+
+/// Walk a descriptor for the structural lints. `path` is dotted like the
+/// untyped-hole paths (`profile.settings`, `tags[]`).
+fn lint_descriptor(data_type: &JsonValue, path: &str, location: &str, warnings: &mut Vec<GenerationWarning>)
+{
+    let mut warn = |lint: &'static str, message: String| {
+        let location = if path.is_empty() {
+            location.to_string()
+        } else if location.starts_with("table ") {
+            format!("{location}.{path}")
+        } else {
+            format!("{location} {path}")
+        };
+        warnings.push(GenerationWarning {
+            location,
+            message,
+            lint: Some(lint),
+        });
+    };
+
+    match data_type["type"].as_str() {
+        Some("optional") => {
+            let inner = &data_type["inner"];
+            if inner["type"] == "optional" || is_nullable_union(inner) {
+                warn(
+                    NESTED_OPTIONAL,
+                    format!(
+                        "{} nests optionality; absent and null both become None in Rust",
+                        render_validator(data_type)
+                    ),
+                );
+            }
+            lint_descriptor(inner, path, location, warnings);
+        }
+        Some("union") => {
+            let variants = data_type["variants"].as_array().map(Vec::as_slice).unwrap_or_default();
+            if variants.iter().any(|variant| variant["type"] == "union") {
+                warn(
+                    NESTED_UNION,
+                    format!(
+                        "{} has a nested v.union(); flatten it into one union",
+                        render_validator(data_type)
+                    ),
+                );
+            }
+            for (idx, variant) in variants.iter().enumerate() {
+                lint_descriptor(variant, &format!("{path}|{idx}"), location, warnings);
+            }
+        }
+        Some("record") => {
+            let key_type = &data_type["keyType"];
+            if key_type["type"] != "string" {
+                warn(
+                    RECORD_KEY,
+                    format!(
+                        "record keyed by {}; Convex stores record keys as strings, so key by v.string()",
+                        render_validator(key_type)
+                    ),
+                );
+            }
+            lint_descriptor(&data_type["valueType"], &format!("{path}{{value}}"), location, warnings);
+        }
+        Some("array") => lint_descriptor(&data_type["elements"], &format!("{path}[]"), location, warnings),
+        Some("object") => {
+            if let Some(props) = data_type["properties"].as_object() {
+                for (name, field) in props {
+                    lint_descriptor(field, &format!("{path}.{name}"), location, warnings);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether a union has a `v.null()` or optional variant.
+fn is_nullable_union(data_type: &JsonValue) -> bool
+{
+    data_type["type"] == "union"
+        && data_type["variants"]
+            .as_array()
+            .is_some_and(|variants| variants.iter().any(|v| v["type"] == "null" || v["type"] == "optional"))
+}
+
+fn contains_any(data_type: &JsonValue) -> bool
+{
+    match data_type["type"].as_str() {
+        Some("any") => true,
+        Some("optional") => contains_any(&data_type["inner"]),
+        Some("array") => contains_any(&data_type["elements"]),
+        Some("record") => contains_any(&data_type["keyType"]) || contains_any(&data_type["valueType"]),
+        Some("union") => data_type["variants"]
+            .as_array()
+            .is_some_and(|variants| variants.iter().any(contains_any)),
+        Some("object") => data_type["properties"]
+            .as_object()
+            .is_some_and(|props| props.values().any(contains_any)),
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Index lints
+// ---------------------------------------------------------------------------
+
+/// A query that lists documents: named `list*` or returning an array.
+fn is_list_query(func: &ConvexFunction) -> bool
+{
+    matches!(func.type_.as_str(), "query" | "internalQuery")
+        && (func.name.starts_with("list") || func.return_type.as_ref().is_some_and(|ret| ret["type"] == "array"))
+}
+
+/// Flag `v.id("T")` args that match an id column of the same name which no
+/// index on its table starts with.
+fn lint_unindexed_lookups(
+    func: &ConvexFunction,
+    location: &str,
+    tables: &[ConvexTable],
+    warnings: &mut Vec<GenerationWarning>,
+)
+{
+    for param in &func.params {
+        let Some(target) = id_table(&param.data_type) else {
+            continue;
+        };
+        for table in tables {
+            let references = table
+                .columns
+                .iter()
+                .any(|column| column.name == param.name && id_table(&column.data_type) == Some(target));
+            let indexed = table.indexes.iter().any(|index| index.fields.first() == Some(&param.name));
+            if references && !indexed {
+                warnings.push(GenerationWarning {
+                    location: format!("{location} args.{}", param.name),
+                    message: format!(
+                        "no index on {} starts with `{}`; listing by it scans the whole table",
+                        table.name, param.name
+                    ),
+                    lint: Some(UNINDEXED_LOOKUP),
+                });
+            }
+        }
+    }
+}
+
+/// The table of a (possibly optional) `v.id(...)` descriptor.
+fn id_table(data_type: &JsonValue) -> Option<&str>
+{
+    match data_type["type"].as_str()? {
+        "id" => data_type["tableName"].as_str(),
+        "optional" => id_table(&data_type["inner"]),
+        _ => None,
+    }
+}
@@ -0,0 +1,28 @@
+//! Where the generator's messages go.
+//!
+//! Messages go through the `log` facade under the `convex_typegen` target, so
+//! tools that install a logger can filter or capture them. Without a logger,
+//! as in a build script, they are written to stderr so cargo still shows them.
+//! `Configuration::quiet` drops everything below `Error`.
+
+use log::{Level, LevelFilter};
+
+/// Log `message` at `level`, unless `quiet` drops it.
+pub(crate) fn emit(level: Level, quiet: bool, message: std::fmt::Arguments<'_>)
+{
+    if quiet && level > Level::Error {
+        return;
+    }
+    if to_stderr() {
+        eprintln!("{message}");
+    } else {
+        log::log!(target: "convex_typegen", level, "{message}");
+    }
+}
+
+/// Whether messages bypass `log` because no logger is installed. Loggers set
+/// the max level when they are installed; until then it is `Off`.
+pub(crate) fn to_stderr() -> bool
+{
+    log::max_level() == LevelFilter::Off
+}
@@ -0,0 +1,240 @@
+//! `convex-typegen` command-line interface.
+//!
+//! ```text
+//! convex-typegen explain [<schema.ts> [function.ts ...]]
+//! convex-typegen generate [config.toml]
+//! convex-typegen check [config.toml]
+//! convex-typegen clean [all|bun|extraction|locks]
+//! ```
+//!
+//! Both commands start from `convex-typegen.toml` in the current directory when
+//! it exists. `explain` without a schema uses the file's schema and function
+//! files. When a schema but no function files are given, every `.ts` file next
+//! to the schema is used (skipping `schema.ts` and `_`-prefixed files such as
+//! `_generated`).
+//!
+//! `check` writes nothing and fails when the generated file is out of date, for
+//! CI. It compares the inputs with the manifest at the end of the file, so it
+//! needs neither Bun nor an extraction. A file without a manifest is
+//! generated in memory instead, and the diff of each file that would change is
+//! printed.
+//!
+//! `clean` clears the caches below the target directory, all of them by
+//! default.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use convex_typegen::manifest::StaleReason;
+use convex_typegen::{check_freshness, clean_cache, explain, generate, generate_dry_run, CacheKind, Configuration};
+
+const USAGE: &str = "usage: convex-typegen explain [<schema.ts> [function.ts ...]]
+       convex-typegen generate [config.toml]
+       convex-typegen check [config.toml]
+       convex-typegen clean [all|bun|extraction|locks]";
+
+/// The config file the commands pick up from the current directory.
+const CONFIG_FILE: &str = "convex-typegen.toml";
+
+fn main() -> ExitCode
+{
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("explain") => run_explain(args.get(1), args.get(2..).unwrap_or_default()),
+        Some("generate") if args.len() <= 2 => run_generate(args.get(1).map_or(CONFIG_FILE, String::as_str)),
+        Some("check") if args.len() <= 2 => run_check(args.get(1).map_or(CONFIG_FILE, String::as_str)),
+        Some("clean") if args.len() <= 2 => run_clean(args.get(1).map_or("all", String::as_str)),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run_explain(schema: Option<&String>, function_args: &[String]) -> ExitCode
+{
+    let config = match base_config() {
+        Ok(config) => config,
+        Err(e) => return fail(&e),
+    };
+    let config = match schema {
+        Some(schema) => {
+            // Paths on the command line are relative to where the command runs,
+            // not to whichever crate `cargo run` was started from
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let schema_path = cwd.join(schema);
+            let function_paths = if function_args.is_empty() {
+                discover_function_files(&schema_path)
+            } else {
+                function_args.iter().map(|path| cwd.join(path)).collect()
+            };
+            Configuration {
+                schema_path,
+                function_paths,
+                ..config
+            }
+        }
+        None if Path::new(CONFIG_FILE).is_file() => config,
+        None => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match explain(config) {
+        Ok(report) => {
+            print!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => fail(&e),
+    }
+}
+
+fn run_generate(config_path: &str) -> ExitCode
+{
+    let config = match Configuration::from_file(config_path) {
+        Ok(config) => config,
+        Err(e) => return fail(&e),
+    };
+    match generate(Configuration {
+        print_summary: true,
+        ..config
+    }) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => fail(&e),
+    }
+}
+
+fn run_check(config_path: &str) -> ExitCode
+{
+    let config = match Configuration::from_file(config_path) {
+        Ok(config) => config,
+        Err(e) => return fail(&e),
+    };
+    let freshness = match check_freshness(config.clone()) {
+        Ok(freshness) => freshness,
+        Err(e) => return fail(&e),
+    };
+    if freshness.is_fresh() {
+        println!("{} is up to date", freshness.out_file.display());
+        return ExitCode::SUCCESS;
+    }
+    if freshness.stale != [StaleReason::NoManifest] {
+        for reason in &freshness.stale {
+            eprintln!("{}: {reason}", freshness.out_file.display());
+        }
+        eprintln!("error: generated files are out of date; run `convex-typegen generate`");
+        return ExitCode::FAILURE;
+    }
+
+    let plan = match generate_dry_run(config) {
+        Ok(plan) => plan,
+        Err(e) => return fail(&e),
+    };
+    if plan.is_unchanged() {
+        println!("{} is up to date", plan.code.path.display());
+        return ExitCode::SUCCESS;
+    }
+    for file in plan.changed_files() {
+        print!("{}", file.diff);
+    }
+    for path in &plan.stale_files {
+        eprintln!("{}: no longer generated", path.display());
+    }
+    eprintln!("error: generated files are out of date; run `convex-typegen generate`");
+    ExitCode::FAILURE
+}
+
+fn run_clean(kind: &str) -> ExitCode
+{
+    let kind = match kind {
+        "all" => CacheKind::All,
+        "bun" => CacheKind::Bun,
+        "extraction" => CacheKind::Extraction,
+        "locks" => CacheKind::Locks,
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match clean_cache(kind) {
+        Ok(report) => {
+            for path in &report.removed {
+                println!("removed {}", path.display());
+            }
+            for lock in &report.held_locks {
+                println!("kept {} (held by a running build)", lock.display());
+            }
+            println!("freed {}", format_bytes(report.freed_bytes));
+            ExitCode::SUCCESS
+        }
+        Err(e) => fail(&e),
+    }
+}
+
+/// `bytes` in the largest unit that keeps it at or above 1, e.g. `95.3 MiB`.
+fn format_bytes(bytes: u64) -> String
+{
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// `convex-typegen.toml` from the current directory, or the defaults with
+/// paths relative to the current directory.
+fn base_config() -> Result<Configuration, convex_typegen::errors::ConvexTypeGeneratorError>
+{
+    if Path::new(CONFIG_FILE).is_file() {
+        Configuration::from_file(CONFIG_FILE)
+    } else {
+        Ok(Configuration {
+            base_dir: std::env::current_dir().ok(),
+            ..Default::default()
+        })
+    }
+}
+
+fn fail(error: &impl std::fmt::Display) -> ExitCode
+{
+    eprintln!("error: {error}");
+    ExitCode::FAILURE
+}
+
+/// Collect the `.ts` files that sit next to the schema file.
+fn discover_function_files(schema_path: &Path) -> Vec<PathBuf>
+{
+    let dir = schema_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            path.is_file()
+                && name.ends_with(".ts")
+                && !name.ends_with(".d.ts")
+                && !name.starts_with('_')
+                && name != "schema.ts"
+        })
+        .collect();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,350 @@
+//! The header and trailer of the generated Rust file, and
+//! [`crate::check_freshness`].
+//!
+//! The header tells a reader what the file was generated from: the
+//! convex-typegen version, each input with its digest, the settings that
+//! differ from the defaults, and how to regenerate it. The trailer repeats the
+//! digests as one line of JSON, together with digests of the settings and of
+//! the file above it, so a committed file can be checked against its inputs
+//! without extracting anything.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::errors::ConvexTypeGeneratorError;
+use crate::Configuration;
+
+/// The first line of the trailer. The line after it is the manifest JSON.
+const TRAILER_MARKER: &str = "// convex-typegen manifest";
+
+/// Bumped when the manifest JSON changes shape.
+const MANIFEST_VERSION: u64 = 1;
+
+/// FNV-1a of `bytes`, as 16 hex digits.
+pub(crate) fn digest(bytes: &[u8]) -> String
+{
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Digest of a text file, ignoring carriage returns so a checkout with CRLF
+/// line endings matches.
+fn text_digest(bytes: &[u8]) -> String
+{
+    let bytes: Vec<u8> = bytes.iter().copied().filter(|byte| *byte != b'\r').collect();
+    digest(&bytes)
+}
+
+/// What a generated file is generated from.
+pub(crate) struct Manifest
+{
+    /// Input digests by path relative to `base_dir`, with `/` separators.
+    inputs: BTreeMap<String, String>,
+    /// The settings that differ from the defaults, for the header.
+    settings: Vec<String>,
+    /// Digest of every setting that shapes the generated code.
+    settings_digest: String,
+}
+
+impl Manifest
+{
+    /// Read and digest the inputs of `config`, whose paths must be resolved.
+    /// Inputs that don't exist (yet) are left out.
+    pub(crate) fn read(config: &Configuration) -> Result<Self, ConvexTypeGeneratorError>
+    {
+        let base_dir = config.base_dir.as_deref().unwrap_or(Path::new(""));
+        let mut inputs = BTreeMap::new();
+        for path in crate::input_paths(config) {
+            let bytes = match std::fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => {
+                    return Err(ConvexTypeGeneratorError::IOError {
+                        file: path.display().to_string(),
+                        error,
+                    })
+                }
+            };
+            inputs.insert(relative_path(path, base_dir), text_digest(&bytes));
+        }
+
+        let (settings, entries) = settings(config);
+        let settings_digest = digest(entries.join("\n").as_bytes());
+        Ok(Manifest {
+            inputs,
+            settings,
+            settings_digest,
+        })
+    }
+
+    /// `code` with the header before it and the trailer after it.
+    pub(crate) fn stamp(&self, code: &str) -> String
+    {
+        let mut out = String::new();
+        out.push_str("// This file is generated by convex-typegen. Do not modify directly.\n");
+        out.push_str(
+            "// You can find more information about convex-typegen at https://github.com/JamalLyons/convex-typegen\n",
+        );
+        out.push_str("//\n");
+        out.push_str(&format!(
+            "// Generated by convex-typegen {} from:\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+        for (path, digest) in &self.inputs {
+            out.push_str(&format!("//   {path} ({digest})\n"));
+        }
+        if self.settings.is_empty() {
+            out.push_str("// Settings: defaults\n");
+        } else {
+            out.push_str("// Settings:\n");
+            for setting in &self.settings {
+                out.push_str(&format!("//   {setting}\n"));
+            }
+        }
+        out.push_str("//\n");
+        out.push_str("// Regenerate it by building the crate whose build script calls `convex_typegen::generate`,\n");
+        out.push_str("// or with `convex-typegen generate`. `convex-typegen check` compares the inputs with the\n");
+        out.push_str("// manifest at the end of this file.\n\n");
+        out.push_str(code);
+
+        let manifest = json!({
+            "version": MANIFEST_VERSION,
+            "generator": env!("CARGO_PKG_VERSION"),
+            "inputs": self.inputs,
+            "settings": self.settings_digest,
+            "output": text_digest(out.as_bytes()),
+        });
+        out.push_str(&format!("{TRAILER_MARKER}\n// {manifest}\n"));
+        out
+    }
+}
+
+/// `path` relative to `base_dir` with `/` separators, or all of it when it
+/// lies outside.
+fn relative_path(path: &Path, base_dir: &Path) -> String
+{
+    let relative = path.strip_prefix(base_dir).unwrap_or(path);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// The settings that differ from the defaults, for the header, and one line
+/// per setting that shapes the generated code, for the digest. Settings that
+/// only affect how generation runs, such as `print_summary` or `bun_path`, are
+/// left out of both.
+fn settings(config: &Configuration) -> (Vec<String>, Vec<String>)
+{
+    let defaults = Configuration::default();
+    let mut summary = Vec::new();
+    let mut entries = Vec::new();
+
+    macro_rules! scalars {
+        ($($field:ident),* $(,)?) => {$(
+            let line = format!("{} = {:?}", stringify!($field), config.$field);
+            if config.$field != defaults.$field {
+                summary.push(line.clone());
+            }
+            entries.push(line);
+        )*};
+    }
+    scalars!(
+        extraction_backend,
+        method_naming,
+        version_tag,
+        doc_examples_module,
+        feature_gates,
+        default_missing_fields,
+        typed_ids,
+        flatten_system_fields,
+        finite_number_args,
+        split_api_by_file,
+        split_output_by_table,
+        internal_module,
+        prune_unreachable,
+        emit_previous_tables,
+        emit_shared_subscriptions,
+        emit_arc_subscriptions,
+        emit_snapshot_subscriptions,
+        emit_buffered_subscriptions,
+        emit_resilient_subscriptions,
+        emit_cursor_store,
+        emit_frontend_adapters,
+        emit_tauri_commands,
+        emit_axum_router,
+        emit_tenant_router,
+        emit_admin_client,
+        emit_timeouts,
+        emit_consistent_reads,
+        emit_offline_queue,
+        emit_import_writers,
+        emit_fixtures,
+        emit_function_refs,
+        emit_schedule_requests,
+        emit_enum_labels,
+        allow_dead_code,
+    );
+
+    // Hash maps and sets iterate in no fixed order, so their lines are sorted
+    macro_rules! collections {
+        ($($field:ident => $lines:expr),* $(,)?) => {$(
+            let mut lines: Vec<String> = $lines;
+            if !lines.is_empty() {
+                let noun = if lines.len() == 1 { "entry" } else { "entries" };
+                summary.push(format!("{}: {} {noun}", stringify!($field), lines.len()));
+            }
+            lines.sort();
+            entries.extend(lines.into_iter().map(|line| format!("{}.{line}", stringify!($field))));
+        )*};
+    }
+    collections!(
+        helper_stubs => config.helper_stubs.keys().cloned().collect(),
+        custom_validators => map_lines(&config.custom_validators, |handler| format!("{handler:?}")),
+        field_overrides => map_lines(&config.field_overrides, |field| format!("{field:?}")),
+        display_fields => map_lines(&config.display_fields, String::clone),
+        collection_keys => map_lines(&config.collection_keys, String::clone),
+        enum_labels => map_lines(&config.enum_labels, String::clone),
+        arg_defaults => map_lines(&config.arg_defaults, ToString::to_string),
+        deprecated_functions => map_lines(&config.deprecated_functions, String::clone),
+        redacted_args => config.redacted_args.iter().cloned().collect(),
+        function_timeouts => map_lines(&config.function_timeouts, String::clone),
+        function_priorities => map_lines(&config.function_priorities, String::clone),
+        keep_tables => config.keep_tables.iter().cloned().collect(),
+    );
+
+    (summary, entries)
+}
+
+/// One `key = value` line per entry of `map`.
+fn map_lines<V>(map: &HashMap<String, V>, value: impl Fn(&V) -> String) -> Vec<String>
+{
+    map.iter().map(|(key, entry)| format!("{key} = {}", value(entry))).collect()
+}
+
+/// Whether a generated file still matches its inputs, from
+/// [`crate::check_freshness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Freshness
+{
+    /// The generated file that was checked.
+    pub out_file: PathBuf,
+    /// Why the file is stale, empty when it is up to date.
+    pub stale: Vec<StaleReason>,
+}
+
+impl Freshness
+{
+    /// Whether the file matches its inputs, settings, and convex-typegen
+    /// version, and hasn't been edited since it was generated.
+    pub fn is_fresh(&self) -> bool
+    {
+        self.stale.is_empty()
+    }
+}
+
+/// Why [`Freshness::stale`] holds a file stale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaleReason
+{
+    /// The file doesn't exist.
+    Missing,
+    /// The file has no manifest, e.g. because an older convex-typegen
+    /// generated it. Only generating tells whether it is up to date.
+    NoManifest,
+    /// The file was edited after it was generated.
+    Edited,
+    /// Another version of convex-typegen generated the file.
+    GeneratorVersion(String),
+    /// Settings that shape the generated code changed.
+    Settings,
+    /// An input changed, by path relative to `base_dir`.
+    InputChanged(String),
+    /// An input the file wasn't generated from, such as a new function file.
+    InputAdded(String),
+    /// An input the file was generated from is gone from the configuration
+    /// or the disk.
+    InputRemoved(String),
+}
+
+impl fmt::Display for StaleReason
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            StaleReason::Missing => write!(f, "the file doesn't exist"),
+            StaleReason::NoManifest => write!(f, "the file has no convex-typegen manifest"),
+            StaleReason::Edited => write!(f, "the file was edited after it was generated"),
+            StaleReason::GeneratorVersion(version) => write!(
+                f,
+                "generated by convex-typegen {version}, this is {}",
+                env!("CARGO_PKG_VERSION")
+            ),
+            StaleReason::Settings => write!(f, "the settings changed"),
+            StaleReason::InputChanged(path) => write!(f, "{path} changed"),
+            StaleReason::InputAdded(path) => write!(f, "{path} is a new input"),
+            StaleReason::InputRemoved(path) => write!(f, "{path} is no longer an input"),
+        }
+    }
+}
+
+/// Compare `config.out_file` with the inputs recorded in its manifest.
+pub(crate) fn check(config: &Configuration) -> Result<Freshness, ConvexTypeGeneratorError>
+{
+    let freshness = |stale| Freshness {
+        out_file: config.out_file.clone(),
+        stale,
+    };
+    let text = match std::fs::read(&config.out_file) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).replace('\r', ""),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(freshness(vec![StaleReason::Missing]));
+        }
+        Err(error) => {
+            return Err(ConvexTypeGeneratorError::IOError {
+                file: config.out_file.display().to_string(),
+                error,
+            })
+        }
+    };
+    let Some((body, recorded)) = parse_trailer(&text) else {
+        return Ok(freshness(vec![StaleReason::NoManifest]));
+    };
+
+    let mut stale = Vec::new();
+    if recorded["output"].as_str() != Some(&digest(body.as_bytes())) {
+        stale.push(StaleReason::Edited);
+    }
+    let generator = recorded["generator"].as_str().unwrap_or_default();
+    if generator != env!("CARGO_PKG_VERSION") {
+        stale.push(StaleReason::GeneratorVersion(generator.to_string()));
+    }
+    let current = Manifest::read(config)?;
+    if recorded["settings"].as_str() != Some(&current.settings_digest) {
+        stale.push(StaleReason::Settings);
+    }
+    let recorded_inputs = recorded["inputs"].as_object().cloned().unwrap_or_default();
+    for (path, digest) in &current.inputs {
+        match recorded_inputs.get(path).and_then(JsonValue::as_str) {
+            Some(recorded) if recorded == digest => {}
+            Some(_) => stale.push(StaleReason::InputChanged(path.clone())),
+            None => stale.push(StaleReason::InputAdded(path.clone())),
+        }
+    }
+    for path in recorded_inputs.keys().filter(|path| !current.inputs.contains_key(*path)) {
+        stale.push(StaleReason::InputRemoved(path.clone()));
+    }
+    Ok(freshness(stale))
+}
+
+/// The text above the trailer and the manifest JSON, if `text` ends with a
+/// trailer this version can read.
+fn parse_trailer(text: &str) -> Option<(&str, JsonValue)>
+{
+    let start = text.rfind(&format!("{TRAILER_MARKER}\n"))?;
+    let line = text[start + TRAILER_MARKER.len() + 1..].lines().next()?;
+    let manifest: JsonValue = serde_json::from_str(line.strip_prefix("// ")?).ok()?;
+    (manifest["version"] == MANIFEST_VERSION).then_some((&text[..start], manifest))
+}
@@ -0,0 +1,214 @@
+//! Diffing the schema against a previous model snapshot, and the migration
+//! scaffold rendered from the diff; see [`crate::Artifact::MigrationScaffold`].
+
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::artifacts::ts_type;
+use crate::codegen::{table_field_name, table_struct_name, to_snake_case};
+use crate::errors::ConvexTypeGeneratorError;
+use crate::types::{ConvexColumn, ConvexSchema, ConvexTable};
+
+/// The part of a model JSON snapshot the diff reads.
+#[derive(Deserialize)]
+struct Model
+{
+    schema: ConvexSchema,
+}
+
+/// Read the schema out of a model JSON snapshot.
+pub(crate) fn load(path: &Path) -> Result<ConvexSchema, ConvexTypeGeneratorError>
+{
+    let json = std::fs::read_to_string(path).map_err(|error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    })?;
+    let model: Model = serde_json::from_str(&json).map_err(|e| ConvexTypeGeneratorError::InvalidPreviousModel {
+        details: format!("{}: {e}", path.display()),
+    })?;
+    Ok(model.schema)
+}
+
+/// Each table in both schemas whose columns differ, as `(previous, current)`.
+/// Column order doesn't count as a change.
+pub(crate) fn changed_tables<'a>(
+    previous: &'a ConvexSchema,
+    schema: &'a ConvexSchema,
+) -> Vec<(&'a ConvexTable, &'a ConvexTable)>
+{
+    schema
+        .tables
+        .iter()
+        .filter_map(|table| {
+            let old = previous.tables.iter().find(|old| old.name == table.name)?;
+            let same = old.columns.len() == table.columns.len()
+                && table
+                    .columns
+                    .iter()
+                    .all(|column| find_column(old, &column.name).is_some_and(|o| o.data_type == column.data_type));
+            (!same).then_some((old, table))
+        })
+        .collect()
+}
+
+/// The migration scaffold module: one `migrate_{table}_v1_to_v2` per changed
+/// table, with notes for tables that were added or removed. `typed_ids` is
+/// [`crate::Configuration::typed_ids`], under which ids of a changed table
+/// differ in type between the versions, and `flatten_system_fields` is
+/// [`crate::Configuration::flatten_system_fields`].
+pub(crate) fn scaffold(
+    previous: &ConvexSchema,
+    schema: &ConvexSchema,
+    typed_ids: bool,
+    flatten_system_fields: bool,
+) -> String
+{
+    let mut out = String::from(
+        "// Migration scaffold generated by convex-typegen. It is rewritten on every build:\n// copy it into your crate, \
+         next to the generated types, and resolve each `todo!()`.\n// `{Table}TableV1` is the previous shape; set \
+         `emit_previous_tables` to generate it.\n\nuse super::*;\n",
+    );
+
+    for table in &schema.tables {
+        if !previous.tables.iter().any(|old| old.name == table.name) {
+            let _ = writeln!(out, "\n// New table `{}`: nothing to migrate.", table.name);
+        }
+    }
+    for old in &previous.tables {
+        if !schema.tables.iter().any(|table| table.name == old.name) {
+            let _ = writeln!(
+                out,
+                "\n// Removed table `{}`: its documents need a decision of their own.",
+                old.name
+            );
+        }
+    }
+
+    let changed = changed_tables(previous, schema);
+    if changed.is_empty() {
+        out.push_str("\n// No table changed since the previous model.\n");
+    }
+    let mut converts_shapes = false;
+    for (old, table) in changed {
+        converts_shapes |= push_migration(&mut out, old, table, &schema.tables, typed_ids, flatten_system_fields);
+    }
+
+    if converts_shapes {
+        out.push_str(
+            "\n/// Convert between the previous and current types of a field whose validator\n/// didn't change. The \
+             nested types differ only in name.\nfn same_shape<A: serde::Serialize, B: serde::de::DeserializeOwned>(value: \
+             A) -> B {\n\x20   let json = serde_json::to_value(value).expect(\"generated types serialize\");\n\x20   \
+             serde_json::from_value(json).expect(\"same validator, same shape\")\n}\n",
+        );
+    }
+    out
+}
+
+/// Write the migration of one table. Returns whether it uses `same_shape`.
+fn push_migration(
+    out: &mut String,
+    old: &ConvexTable,
+    table: &ConvexTable,
+    tables: &[ConvexTable],
+    typed_ids: bool,
+    flatten_system_fields: bool,
+) -> bool
+{
+    let current = table_struct_name(&table.name);
+    let mut converts_shapes = typed_ids;
+
+    let _ = writeln!(out, "\n/// Migrate a `{}` document to the current schema.", table.name);
+    out.push_str("#[allow(unreachable_code)]\n");
+    let _ = writeln!(
+        out,
+        "pub fn migrate_{}_v1_to_v2(old: {current}V1) -> {current} {{",
+        to_snake_case(&table.name)
+    );
+    let _ = writeln!(out, "    {current} {{");
+    match (flatten_system_fields, typed_ids) {
+        (true, true) => out.push_str("        system: same_shape(old.system),\n"),
+        (true, false) => out.push_str("        system: old.system,\n"),
+        (false, true) => out.push_str("        id: same_shape(old.id),\n"),
+        (false, false) => out.push_str("        id: old.id,\n"),
+    }
+    if !flatten_system_fields {
+        out.push_str("        creation_time: old.creation_time,\n");
+    }
+    for column in &table.columns {
+        let field = table_field_name(&column.name);
+        match find_column(old, &column.name) {
+            Some(previous) if previous.data_type == column.data_type => {
+                if is_plain(&column.data_type, typed_ids) {
+                    let _ = writeln!(out, "        {field}: old.{field},");
+                } else {
+                    let _ = writeln!(out, "        {field}: same_shape(old.{field}),");
+                    converts_shapes = true;
+                }
+            }
+            Some(previous) => {
+                let _ = writeln!(
+                    out,
+                    "        // TODO: `{}` was `{}`, now `{}`",
+                    column.name,
+                    ts_type(&previous.data_type, tables),
+                    ts_type(&column.data_type, tables)
+                );
+                let _ = writeln!(out, "        {field}: todo!(\"convert `{}`\"),", column.name);
+            }
+            None if column.data_type["type"] == "optional" => {
+                let _ = writeln!(out, "        {field}: None, // new optional field");
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "        // TODO: `{}` is new: `{}`",
+                    column.name,
+                    ts_type(&column.data_type, tables)
+                );
+                let _ = writeln!(out, "        {field}: todo!(\"fill in `{}`\"),", column.name);
+            }
+        }
+    }
+    for column in &old.columns {
+        if find_column(table, &column.name).is_none() {
+            let _ = writeln!(
+                out,
+                "        // Removed: `{}` (`{}`), dropped with `old`",
+                column.name,
+                ts_type(&column.data_type, tables)
+            );
+        }
+    }
+    out.push_str("    }\n}\n");
+    converts_shapes
+}
+
+fn find_column<'a>(table: &'a ConvexTable, name: &str) -> Option<&'a ConvexColumn>
+{
+    table.columns.iter().find(|column| column.name == name)
+}
+
+/// Whether a descriptor maps to the same Rust type in both versions, i.e. it
+/// has no nested type named after its table. Typed ids may name a previous
+/// table struct, so they aren't plain.
+fn is_plain(data_type: &JsonValue, typed_ids: bool) -> bool
+{
+    match data_type["type"].as_str().unwrap_or("any") {
+        "string" | "number" | "boolean" | "int64" | "bytes" | "null" | "any" => true,
+        "id" => !typed_ids,
+        "array" => is_plain(&data_type["elements"], typed_ids),
+        "optional" => is_plain(&data_type["inner"], typed_ids),
+        "record" => is_plain(&data_type["keyType"], typed_ids) && is_plain(&data_type["valueType"], typed_ids),
+        "union" => data_type["variants"].as_array().is_some_and(|variants| {
+            variants.len() == 2
+                && variants.iter().any(|variant| variant["type"] == "null")
+                && variants
+                    .iter()
+                    .all(|variant| variant["type"] == "null" || is_plain(variant, typed_ids))
+        }),
+        _ => false,
+    }
+}
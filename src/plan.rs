@@ -0,0 +1,248 @@
+//! What a generation would write, from [`crate::generate_dry_run`].
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::report::GenerationReport;
+
+/// Changed lines past which a diff shows the whole file as replaced, to keep
+/// diffing a rewritten file cheap.
+const MAX_DIFF_EDITS: usize = 2000;
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT_LINES: usize = 3;
+
+/// The result of a successful [`crate::generate_dry_run`] call. Nothing has
+/// been written.
+#[derive(Debug, Clone)]
+pub struct GenerationPlan
+{
+    /// The generated Rust code, for `Configuration::out_file`.
+    pub code: PlannedFile,
+    /// With `Configuration::split_output_by_table`, each table's file, in
+    /// schema order.
+    pub table_files: Vec<PlannedFile>,
+    /// Table files of tables that no longer exist, which generation would
+    /// remove.
+    pub stale_files: Vec<PathBuf>,
+    /// One entry per `Configuration::artifacts` entry, in config order.
+    pub artifacts: Vec<PlannedFile>,
+    /// The report [`crate::generate`] would return. `unchanged` tells whether
+    /// the code files are up to date.
+    pub report: GenerationReport,
+}
+
+impl GenerationPlan
+{
+    /// Whether every file already holds what generation would write, as a
+    /// `check` job in CI wants.
+    pub fn is_unchanged(&self) -> bool
+    {
+        self.code.is_unchanged()
+            && self.table_files.iter().all(PlannedFile::is_unchanged)
+            && self.stale_files.is_empty()
+            && self.artifacts.iter().all(PlannedFile::is_unchanged)
+    }
+
+    /// The files generation would write or rewrite. `stale_files` lists the
+    /// ones it would remove.
+    pub fn changed_files(&self) -> impl Iterator<Item = &PlannedFile>
+    {
+        std::iter::once(&self.code)
+            .chain(&self.table_files)
+            .chain(&self.artifacts)
+            .filter(|file| !file.is_unchanged())
+    }
+}
+
+/// One file generation would write.
+#[derive(Debug, Clone)]
+pub struct PlannedFile
+{
+    /// Where it would be written.
+    pub path: PathBuf,
+    /// What would be written.
+    pub contents: String,
+    /// What the file holds now, or `None` when it doesn't exist or isn't
+    /// UTF-8.
+    pub existing: Option<String>,
+    /// A unified diff from `existing` to `contents`, empty when they match.
+    /// A missing file diffs from `/dev/null`.
+    pub diff: String,
+}
+
+impl PlannedFile
+{
+    /// Compare `contents` against the file at `path`.
+    pub(crate) fn new(path: PathBuf, contents: String) -> Self
+    {
+        let existing = std::fs::read_to_string(&path).ok();
+        let diff = if existing.as_deref() == Some(contents.as_str()) {
+            String::new()
+        } else {
+            let old_name = match existing {
+                Some(_) => path.display().to_string(),
+                None => "/dev/null".to_string(),
+            };
+            unified_diff(
+                existing.as_deref().unwrap_or(""),
+                &contents,
+                &old_name,
+                &path.display().to_string(),
+            )
+        };
+        Self {
+            path,
+            contents,
+            existing,
+            diff,
+        }
+    }
+
+    /// Whether the file already holds `contents`.
+    pub fn is_unchanged(&self) -> bool
+    {
+        self.existing.as_deref() == Some(self.contents.as_str())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edit
+{
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// A unified diff of `old` and `new` with [`CONTEXT_LINES`] of context.
+fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String
+{
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = edit_script(&old, &new);
+
+    let mut out = format!("--- {old_name}\n+++ {new_name}\n");
+    // Line positions in old and new before each edit
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut old_line, mut new_line) = (0, 0);
+    for edit in &edits {
+        positions.push((old_line, new_line));
+        match edit {
+            Edit::Keep => (old_line, new_line) = (old_line + 1, new_line + 1),
+            Edit::Delete => old_line += 1,
+            Edit::Insert => new_line += 1,
+        }
+    }
+    positions.push((old_line, new_line));
+
+    let mut index = 0;
+    while let Some(first_change) = edits[index..].iter().position(|edit| *edit != Edit::Keep) {
+        let start = (index + first_change).saturating_sub(CONTEXT_LINES).max(index);
+        // Extend the hunk while the next change is close enough to share context
+        let mut end = index + first_change;
+        loop {
+            while end < edits.len() && edits[end] != Edit::Keep {
+                end += 1;
+            }
+            let kept = edits[end..].iter().take_while(|edit| **edit == Edit::Keep).count();
+            if end + kept < edits.len() && kept <= 2 * CONTEXT_LINES {
+                end += kept;
+            } else {
+                end = (end + kept.min(CONTEXT_LINES)).min(edits.len());
+                break;
+            }
+        }
+
+        let (old_start, new_start) = positions[start];
+        let (old_end, new_end) = positions[end];
+        let range = |start: usize, len: usize| {
+            if len == 0 {
+                format!("{start},0")
+            } else {
+                format!("{},{len}", start + 1)
+            }
+        };
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(old_start, old_end - old_start),
+            range(new_start, new_end - new_start)
+        );
+        for (edit, &(old_line, new_line)) in edits[start..end].iter().zip(&positions[start..end]) {
+            let (prefix, line) = match edit {
+                Edit::Keep => (' ', old[old_line]),
+                Edit::Delete => ('-', old[old_line]),
+                Edit::Insert => ('+', new[new_line]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        index = end;
+    }
+    out
+}
+
+/// The shortest edit script from `old` to `new` (Myers' algorithm), or a
+/// full replacement past [`MAX_DIFF_EDITS`].
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit>
+{
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max_edits = (n + m).min(MAX_DIFF_EDITS as isize);
+    let offset = max_edits + 1;
+    let mut v = vec![0_isize; 2 * offset as usize + 1];
+    let at = |k: isize| (offset + k) as usize;
+    // The furthest x on each diagonal after every step, kept for backtracking
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: {
+        for d in 0..=max_edits {
+            for k in (-d..=d).step_by(2) {
+                let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                    v[at(k + 1)]
+                } else {
+                    v[at(k - 1)] + 1
+                };
+                let mut y = x - k;
+                while x < n && y < m && old[x as usize] == new[y as usize] {
+                    (x, y) = (x + 1, y + 1);
+                }
+                v[at(k)] = x;
+                if x >= n && y >= m {
+                    trace.push(v[at(-d)..=at(d)].to_vec());
+                    break 'search;
+                }
+            }
+            trace.push(v[at(-d)..=at(d)].to_vec());
+        }
+        let mut edits = vec![Edit::Delete; old.len()];
+        edits.extend(vec![Edit::Insert; new.len()]);
+        return edits;
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[d as usize - 1];
+        let furthest = |k: isize| previous[(k + d - 1) as usize];
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && furthest(k - 1) < furthest(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = furthest(previous_k);
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Keep);
+            (x, y) = (x - 1, y - 1);
+        }
+        edits.push(if x == previous_x { Edit::Insert } else { Edit::Delete });
+        (x, y) = (previous_x, previous_y);
+    }
+    edits.extend((0..x).map(|_| Edit::Keep));
+    edits.reverse();
+    edits
+}
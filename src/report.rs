@@ -0,0 +1,954 @@
+//! Summary of a generation run: what was generated and what needs attention.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::Level;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+
+use crate::ast::module_identity;
+use crate::codegen::{table_struct_name, OutputSize, Owner};
+use crate::descriptors::{
+    accepts, is_displayable, is_required_string, object_array_properties, optional_inner, render_validator,
+    string_literal_values, untyped_holes, UntypedHole,
+};
+use crate::directives::{parse_duration, parse_priority};
+use crate::errors::ConvexTypeGeneratorError;
+use crate::types::{ConvexFunction, ConvexFunctionParam, ConvexSchema, ConvexTable};
+use crate::{logging, migration, FieldOverride};
+
+/// Environment variable that turns on the per-table/per-function listing.
+pub const VERBOSE_ENV: &str = "CONVEX_TYPEGEN_VERBOSE";
+
+/// Environment variable that makes the Bun extractor record its resolution
+/// decisions in [`GenerationReport::resolve_trace`].
+pub const TRACE_RESOLVE_ENV: &str = "CONVEX_TYPEGEN_TRACE_RESOLVE";
+
+/// The result of a successful [`crate::generate`] call.
+#[derive(Debug, Clone)]
+pub struct GenerationReport
+{
+    /// Where the generated code was written.
+    pub out_file: PathBuf,
+    /// Whether `out_file` already held the generated code and was left
+    /// untouched, so cargo has nothing to rebuild. With
+    /// `Configuration::split_output_by_table`, the table files must also be
+    /// up to date, with none left to remove.
+    pub unchanged: bool,
+    /// With `Configuration::split_output_by_table`, each table's file, in
+    /// schema order.
+    pub table_files: Vec<PathBuf>,
+    /// The code files that were written because their contents changed, out
+    /// of `out_file` and `table_files`.
+    pub rewritten_files: Vec<PathBuf>,
+    /// Table files of tables that no longer exist, which were removed.
+    pub removed_files: Vec<PathBuf>,
+    /// One entry per schema table, in schema order.
+    pub tables: Vec<TableReport>,
+    /// One entry per extracted function, in extraction order.
+    pub functions: Vec<FunctionReport>,
+    /// Lines in the generated file, and in the table files when split.
+    pub lines: usize,
+    /// Locations that generated but deserve a second look.
+    pub warnings: Vec<GenerationWarning>,
+    /// Files the hybrid and embedded backends had to hand to Bun (empty for
+    /// other backends).
+    pub bun_fallbacks: Vec<BunFallback>,
+    /// What the Bun extractor intercepted and resolved, in the order it
+    /// happened. Empty unless `CONVEX_TYPEGEN_TRACE_RESOLVE` is set and Bun ran.
+    pub resolve_trace: Vec<ResolveEvent>,
+    /// Files in `function_paths` that yielded no functions, in config order.
+    pub empty_function_files: Vec<PathBuf>,
+    /// Files written from `Configuration::artifacts`, in config order.
+    pub artifacts: Vec<PathBuf>,
+    /// `CONVEX_SCHEMA_FINGERPRINT` of the generated code.
+    pub schema_fingerprint: String,
+    /// How the schema differs from `Configuration::previous_model`, when set.
+    pub model_changes: Option<ModelChanges>,
+    /// Wall-clock time spent in extraction and codegen.
+    pub duration: Duration,
+}
+
+/// A generated table struct.
+#[derive(Debug, Clone)]
+pub struct TableReport
+{
+    /// The table name in the schema (e.g. `"users"`).
+    pub name: String,
+    /// The generated struct name (e.g. `"UsersTable"`).
+    pub struct_name: String,
+    /// Number of user-defined fields.
+    pub fields: usize,
+    /// Lines of the table struct and the nested types its fields introduced.
+    pub lines: usize,
+    /// Number of generated types (structs, enums, aliases) among those lines.
+    pub types: usize,
+}
+
+/// A Convex function picked up from the function files.
+#[derive(Debug, Clone)]
+pub struct FunctionReport
+{
+    /// `query`, `mutation`, `action`, or their `internal*` variants.
+    pub kind: String,
+    /// Convex path of the function (e.g. `"users:byName"`).
+    pub path: String,
+    /// Number of declared arguments.
+    pub args: usize,
+    /// Whether a `returns:` validator was declared.
+    pub typed_return: bool,
+    /// Lines of the args struct and the nested types its args and return
+    /// introduced. The shared `ConvexApi` code is not counted.
+    pub lines: usize,
+    /// Number of generated types (structs, enums, aliases) among those lines.
+    pub types: usize,
+}
+
+/// A file the hybrid or embedded backend handed to Bun.
+#[derive(Debug, Clone)]
+pub struct BunFallback
+{
+    /// The schema or function file.
+    pub file: PathBuf,
+    /// The construct the AST backend couldn't resolve, or why the embedded
+    /// backend couldn't run the file.
+    pub reason: String,
+}
+
+/// The tables that differ between `Configuration::previous_model` and the
+/// schema, each list in schema order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelChanges
+{
+    /// Tables only in the schema.
+    pub added_tables: Vec<String>,
+    /// Tables only in the previous model.
+    pub removed_tables: Vec<String>,
+    /// Tables in both whose columns differ.
+    pub changed_tables: Vec<String>,
+}
+
+/// One decision the Bun extractor made while loading the Convex files.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResolveEvent
+{
+    /// An import the plugin intercepted, and the mock or helper stub it served.
+    Import
+    {
+        /// The specifier as written (e.g. `"./_generated/server"`).
+        specifier: String,
+        /// The importing file, when Bun reports one.
+        importer: Option<String>,
+        /// What was served instead (e.g. `"mocks/convex_server.ts"`).
+        served: String,
+    },
+    /// An export of a function file, and the registrar it resolved to.
+    Export
+    {
+        /// Module path of the file (e.g. `"model/users"`).
+        file: String,
+        /// The exported binding.
+        name: String,
+        /// `query`, `mutation`, ..., or `None` when it isn't a Convex function.
+        registrar: Option<String>,
+    },
+    /// A validator that became `any`.
+    Any
+    {
+        /// Where it is, in the same form as warning locations (e.g. `table users.meta`).
+        location: String,
+        /// Why, e.g. `declared with v.any()`.
+        reason: String,
+    },
+}
+
+impl fmt::Display for ResolveEvent
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            ResolveEvent::Import {
+                specifier,
+                importer,
+                served,
+            } => {
+                write!(f, "import {specifier:?}")?;
+                if let Some(importer) = importer {
+                    write!(f, " from {importer}")?;
+                }
+                write!(f, " → {served}")
+            }
+            ResolveEvent::Export { file, name, registrar } => match registrar {
+                Some(registrar) => write!(f, "export {file}:{name} → {registrar}"),
+                None => write!(f, "export {file}:{name} skipped (not a Convex function)"),
+            },
+            ResolveEvent::Any { location, reason } => write!(f, "any {location}: {reason}"),
+        }
+    }
+}
+
+/// Something the generator handled but the user may want to tighten up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationWarning
+{
+    /// Where the issue is (e.g. `table users.metadata`).
+    pub location: String,
+    /// What the issue is.
+    pub message: String,
+    /// The lint that raised this warning (e.g. `"record-key"`), or `None` for
+    /// warnings every run produces.
+    pub lint: Option<&'static str>,
+}
+
+impl fmt::Display for GenerationWarning
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}: {}", self.location, self.message)?;
+        if let Some(lint) = self.lint {
+            write!(f, " [{lint}]")?;
+        }
+        Ok(())
+    }
+}
+
+impl GenerationReport
+{
+    /// The report as JSON, as [`crate::Configuration::report_file`] writes it.
+    ///
+    /// `version` is bumped only when a key is removed or changes meaning, so
+    /// consumers can rely on the keys below while new ones may appear. Paths are
+    /// absolute, `duration_ms` is whole milliseconds, `model_changes` is `null`
+    /// without a previous model, and `lint` is `null` for warnings every run
+    /// produces. The resolve trace is left out.
+    ///
+    /// ```json
+    /// {
+    ///   "version": 1,
+    ///   "out_file": "/app/src/convex_types.rs",
+    ///   "unchanged": false,
+    ///   "table_files": [],
+    ///   "rewritten_files": ["/app/src/convex_types.rs"],
+    ///   "removed_files": [],
+    ///   "lines": 1520,
+    ///   "duration_ms": 840,
+    ///   "schema_fingerprint": "5f0c6a1d2e9b4c73",
+    ///   "tables": [{ "name": "users", "struct_name": "UsersTable", "fields": 4, "lines": 90, "types": 2 }],
+    ///   "functions": [{ "kind": "query", "path": "users:get", "args": 1, "typed_return": true, "lines": 30, "types": 1 }],
+    ///   "warnings": [{ "location": "table users.meta", "message": "...", "lint": null }],
+    ///   "bun_fallbacks": [{ "file": "/app/convex/auth.ts", "reason": "..." }],
+    ///   "empty_function_files": [],
+    ///   "artifacts": [],
+    ///   "model_changes": { "added_tables": [], "removed_tables": [], "changed_tables": ["users"] }
+    /// }
+    /// ```
+    pub fn to_json(&self) -> JsonValue
+    {
+        let paths = |paths: &[PathBuf]| -> Vec<String> { paths.iter().map(|path| path.display().to_string()).collect() };
+        json!({
+            "version": 1,
+            "out_file": self.out_file.display().to_string(),
+            "unchanged": self.unchanged,
+            "table_files": paths(&self.table_files),
+            "rewritten_files": paths(&self.rewritten_files),
+            "removed_files": paths(&self.removed_files),
+            "lines": self.lines,
+            "duration_ms": u64::try_from(self.duration.as_millis()).unwrap_or(u64::MAX),
+            "schema_fingerprint": self.schema_fingerprint,
+            "tables": self.tables.iter().map(|table| json!({
+                "name": table.name,
+                "struct_name": table.struct_name,
+                "fields": table.fields,
+                "lines": table.lines,
+                "types": table.types,
+            })).collect::<Vec<_>>(),
+            "functions": self.functions.iter().map(|func| json!({
+                "kind": func.kind,
+                "path": func.path,
+                "args": func.args,
+                "typed_return": func.typed_return,
+                "lines": func.lines,
+                "types": func.types,
+            })).collect::<Vec<_>>(),
+            "warnings": self.warnings.iter().map(|warning| json!({
+                "location": warning.location,
+                "message": warning.message,
+                "lint": warning.lint,
+            })).collect::<Vec<_>>(),
+            "bun_fallbacks": self.bun_fallbacks.iter().map(|fallback| json!({
+                "file": fallback.file.display().to_string(),
+                "reason": fallback.reason,
+            })).collect::<Vec<_>>(),
+            "empty_function_files": paths(&self.empty_function_files),
+            "artifacts": paths(&self.artifacts),
+            "model_changes": self.model_changes.as_ref().map(|changes| json!({
+                "added_tables": changes.added_tables,
+                "removed_tables": changes.removed_tables,
+                "changed_tables": changes.changed_tables,
+            })),
+        })
+    }
+
+    /// One-line summary, e.g. `3 tables, 5 functions, 1 warning in 0.84s → src/convex_types.rs`.
+    /// Ends in `(unchanged)` when the file was left as it was.
+    pub fn summary(&self) -> String
+    {
+        let bun = if self.bun_fallbacks.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} via Bun)", plural(self.bun_fallbacks.len(), "file"))
+        };
+        let unchanged = if self.unchanged { " (unchanged)" } else { "" };
+        format!(
+            "{}, {}, {} in {:.2}s{bun} → {}{unchanged}",
+            plural(self.tables.len(), "table"),
+            plural(self.functions.len(), "function"),
+            plural(self.warnings.len(), "warning"),
+            self.duration.as_secs_f64(),
+            self.out_file.display()
+        )
+    }
+
+    /// Log the summary, followed by the per-item listing when `verbose` is
+    /// set; warnings at `Warn`, the rest at `Info`. Colors are used only when
+    /// the lines go straight to a terminal stderr and `NO_COLOR` is unset.
+    pub(crate) fn print(&self, verbose: bool)
+    {
+        let color = logging::to_stderr() && std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        let paint = |code: &str, text: &str| {
+            if color {
+                format!("\x1b[{code}m{text}\x1b[0m")
+            } else {
+                text.to_string()
+            }
+        };
+        let info = |line: String| logging::emit(Level::Info, false, format_args!("{line}"));
+
+        let status = if self.warnings.is_empty() {
+            paint("1;32", "convex-typegen")
+        } else {
+            paint("1;33", "convex-typegen")
+        };
+        info(format!("{status}: {}", self.summary()));
+
+        if !verbose {
+            return;
+        }
+        for table in &self.tables {
+            info(format!(
+                "  {} {} → {} ({}, {})",
+                paint("36", "table"),
+                table.name,
+                table.struct_name,
+                plural(table.fields, "field"),
+                plural(table.lines, "line")
+            ));
+        }
+        for func in &self.functions {
+            let returns = if func.typed_return { "typed return" } else { "untyped return" };
+            info(format!(
+                "  {} {} ({}, {returns}, {})",
+                paint("36", &func.kind),
+                func.path,
+                plural(func.args, "arg"),
+                plural(func.lines, "line")
+            ));
+        }
+        for fallback in &self.bun_fallbacks {
+            info(format!(
+                "  {} {}: {}",
+                paint("35", "bun"),
+                fallback.file.display(),
+                fallback.reason
+            ));
+        }
+        for event in &self.resolve_trace {
+            info(format!("  {} {event}", paint("35", "trace")));
+        }
+        for warning in &self.warnings {
+            logging::emit(Level::Warn, false, format_args!("  {} {warning}", paint("33", "warning")));
+        }
+    }
+}
+
+/// Whether `CONVEX_TYPEGEN_VERBOSE` is set to something other than `0`/empty.
+pub(crate) fn verbose_from_env() -> bool
+{
+    std::env::var(VERBOSE_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Whether `CONVEX_TYPEGEN_TRACE_RESOLVE` is set to something other than `0`/empty.
+pub(crate) fn trace_resolve_from_env() -> bool
+{
+    std::env::var(TRACE_RESOLVE_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// A warning for every table field, arg, and return type, or part of one,
+/// that maps to `serde_json::Value`.
+pub(crate) fn untyped_hole_warnings(schema: &ConvexSchema, functions: &[ConvexFunction]) -> Vec<GenerationWarning>
+{
+    let mut warnings = Vec::new();
+    let mut push = |location: String, hole: UntypedHole| {
+        warnings.push(GenerationWarning {
+            location,
+            message: hole.reason,
+            lint: None,
+        })
+    };
+
+    for table in &schema.tables {
+        for column in &table.columns {
+            for hole in untyped_holes(&column.data_type, &column.name) {
+                push(format!("table {}.{}", table.name, hole.path), hole);
+            }
+        }
+    }
+    for func in functions {
+        let path = function_path(func);
+        for param in &func.params {
+            for hole in untyped_holes(&param.data_type, &param.name) {
+                push(format!("function {path} args.{}", hole.path), hole);
+            }
+        }
+        if let Some(args) = func.whole_args() {
+            for hole in untyped_holes(args, "args") {
+                push(format!("function {path} {}", hole.path), hole);
+            }
+        }
+        if let Some(return_type) = &func.return_type {
+            for hole in untyped_holes(return_type, "returns") {
+                push(format!("function {path} {}", hole.path), hole);
+            }
+        }
+    }
+    warnings
+}
+
+/// Build the report for an extraction result. `duration` is filled in by the caller.
+pub(crate) fn build(out_file: PathBuf, schema: &ConvexSchema, functions: &[ConvexFunction]) -> GenerationReport
+{
+    let mut warnings = untyped_hole_warnings(schema, functions);
+
+    let tables = schema
+        .tables
+        .iter()
+        .map(|table| TableReport {
+            name: table.name.clone(),
+            struct_name: table_struct_name(&table.name),
+            fields: table.columns.len(),
+            lines: 0,
+            types: 0,
+        })
+        .collect();
+
+    let functions = functions
+        .iter()
+        .map(|func| {
+            let path = function_path(func);
+            if let (Some(args), None) = (&func.args_validator, func.whole_args()) {
+                warnings.push(GenerationWarning {
+                    location: format!("function {path} args"),
+                    message: format!(
+                        "{} never matches the object of args Convex calls a function with; no args are generated",
+                        render_validator(args)
+                    ),
+                    lint: None,
+                });
+            }
+            FunctionReport {
+                kind: func.type_.clone(),
+                path,
+                args: func.params.len(),
+                typed_return: func.return_type.is_some(),
+                lines: 0,
+                types: 0,
+            }
+        })
+        .collect();
+
+    GenerationReport {
+        out_file,
+        unchanged: false,
+        table_files: Vec::new(),
+        rewritten_files: Vec::new(),
+        removed_files: Vec::new(),
+        tables,
+        functions,
+        lines: 0,
+        warnings,
+        bun_fallbacks: Vec::new(),
+        resolve_trace: Vec::new(),
+        empty_function_files: Vec::new(),
+        artifacts: Vec::new(),
+        schema_fingerprint: String::new(),
+        model_changes: None,
+        duration: Duration::ZERO,
+    }
+}
+
+/// The tables that were added, removed, or changed since `previous`.
+pub(crate) fn model_changes(previous: &ConvexSchema, schema: &ConvexSchema) -> ModelChanges
+{
+    let names = |tables: &[ConvexTable], other: &ConvexSchema| -> Vec<String> {
+        tables
+            .iter()
+            .filter(|table| !other.tables.iter().any(|o| o.name == table.name))
+            .map(|table| table.name.clone())
+            .collect()
+    };
+    ModelChanges {
+        added_tables: names(&schema.tables, previous),
+        removed_tables: names(&previous.tables, schema),
+        changed_tables: migration::changed_tables(previous, schema)
+            .into_iter()
+            .map(|(_, table)| table.name.clone())
+            .collect(),
+    }
+}
+
+/// Write `report` as JSON to `path` for [`crate::Configuration::report_file`],
+/// creating its directory.
+pub(crate) fn write_json(report: &GenerationReport, path: &Path) -> Result<(), ConvexTypeGeneratorError>
+{
+    let io_error = |error| ConvexTypeGeneratorError::IOError {
+        file: path.display().to_string(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let json = serde_json::to_string_pretty(&report.to_json()).map_err(ConvexTypeGeneratorError::SerializationFailed)?;
+    std::fs::write(path, json + "\n").map_err(io_error)
+}
+
+/// Fill in the per-table and per-function line and type counts once the code
+/// is generated.
+pub(crate) fn record_sizes(report: &mut GenerationReport, size: &OutputSize)
+{
+    for table in &mut report.tables {
+        let item = size.items.get(&Owner::Table(table.name.clone())).copied().unwrap_or_default();
+        table.lines = item.lines;
+        table.types = item.types;
+    }
+    for func in &mut report.functions {
+        let item = size
+            .items
+            .get(&Owner::Function(func.path.clone()))
+            .copied()
+            .unwrap_or_default();
+        func.lines = item.lines;
+        func.types = item.types;
+    }
+}
+
+/// Warn about `field_overrides` keys that don't name a table field, sorted by key.
+pub(crate) fn unknown_field_overrides(
+    overrides: &HashMap<String, FieldOverride>,
+    schema: &ConvexSchema,
+) -> Vec<GenerationWarning>
+{
+    let mut unknown: Vec<&String> = overrides
+        .keys()
+        .filter(|key| {
+            !schema.tables.iter().any(|table| {
+                table
+                    .columns
+                    .iter()
+                    .any(|column| **key == format!("{}.{}", table.name, column.name))
+            })
+        })
+        .collect();
+    unknown.sort();
+
+    unknown
+        .into_iter()
+        .map(|key| GenerationWarning {
+            location: format!("field_overrides[{key:?}]"),
+            message: "no table field with this name; expected \"table.field\"".to_string(),
+            lint: None,
+        })
+        .collect()
+}
+
+/// Warn about `field_overrides` whose `format` is set on a field that isn't a
+/// string (or an optional or array of strings), sorted by key.
+pub(crate) fn field_format_warnings(
+    overrides: &HashMap<String, FieldOverride>,
+    schema: &ConvexSchema,
+) -> Vec<GenerationWarning>
+{
+    let mut warnings: Vec<GenerationWarning> = schema
+        .tables
+        .iter()
+        .flat_map(|table| table.columns.iter().map(move |column| (table, column)))
+        .filter_map(|(table, column)| {
+            let key = format!("{}.{}", table.name, column.name);
+            let format = overrides.get(&key)?.format?;
+            let mut data_type = &column.data_type;
+            while let Some(inner) = match data_type["type"].as_str() {
+                Some("optional") => Some(&data_type["inner"]),
+                Some("array") => Some(&data_type["elements"]),
+                _ => None,
+            } {
+                data_type = inner;
+            }
+            (data_type["type"].as_str() != Some("string")).then(|| GenerationWarning {
+                location: format!("field_overrides[{key:?}]"),
+                message: format!("format `{}` applies only to string fields; ignored", format.name()),
+                lint: None,
+            })
+        })
+        .collect();
+    warnings.sort_by(|a, b| a.location.cmp(&b.location));
+    warnings
+}
+
+/// Warn about `display_fields` entries that won't be used, sorted by table:
+/// tables and fields that don't exist, and fields whose type has no `Display`.
+pub(crate) fn display_field_warnings(
+    display_fields: &HashMap<String, String>,
+    schema: &ConvexSchema,
+) -> Vec<GenerationWarning>
+{
+    let mut tables: Vec<&String> = display_fields.keys().collect();
+    tables.sort();
+
+    tables
+        .into_iter()
+        .filter_map(|table_name| {
+            let field = &display_fields[table_name];
+            let column = schema
+                .tables
+                .iter()
+                .find(|table| table.name == *table_name)
+                .map(|table| table.columns.iter().find(|column| column.name == *field));
+            let message = match column {
+                None => "no table with this name".to_string(),
+                Some(None) => format!("`{table_name}` has no field `{field}`"),
+                Some(Some(column)) => {
+                    let data_type = optional_inner(&column.data_type).unwrap_or(&column.data_type);
+                    if is_displayable(data_type) {
+                        return None;
+                    }
+                    format!(
+                        "`{field}` is {}, which has no Display; use a string, number, boolean, id, or literal field",
+                        render_validator(data_type)
+                    )
+                }
+            };
+            Some(GenerationWarning {
+                location: format!("display_fields[{table_name:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `collection_keys` entries that won't be used, sorted by key:
+/// keys that name no array-of-object column, and key fields that aren't
+/// required strings of its objects.
+pub(crate) fn collection_key_warnings(keys: &HashMap<String, String>, schema: &ConvexSchema) -> Vec<GenerationWarning>
+{
+    let mut columns: Vec<&String> = keys.keys().collect();
+    columns.sort();
+
+    columns
+        .into_iter()
+        .filter_map(|key| {
+            let field = &keys[key];
+            let props = schema.tables.iter().find_map(|table| {
+                let column = table
+                    .columns
+                    .iter()
+                    .find(|column| *key == format!("{}.{}", table.name, column.name))?;
+                Some(object_array_properties(&column.data_type))
+            });
+            let message = match props {
+                None => "no table field with this name; expected \"table.field\"".to_string(),
+                Some(None) => "not an array of objects".to_string(),
+                Some(Some(_)) if field.is_empty() => return None,
+                Some(Some(props)) => match props.get(field) {
+                    None => format!("its objects have no field `{field}`"),
+                    Some(data_type) if !is_required_string(data_type) => {
+                        format!("`{field}` is {}, not a required v.string()", render_validator(data_type))
+                    }
+                    Some(_) => return None,
+                },
+            };
+            Some(GenerationWarning {
+                location: format!("collection_keys[{key:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `enum_labels` entries that won't be used, sorted by key: keys
+/// that name no value of a literal enum table field, and every entry when
+/// `emit_enum_labels` is off.
+pub(crate) fn enum_label_warnings(
+    labels: &HashMap<String, String>,
+    enabled: bool,
+    schema: &ConvexSchema,
+) -> Vec<GenerationWarning>
+{
+    if !enabled && !labels.is_empty() {
+        return vec![GenerationWarning {
+            location: "enum_labels".to_string(),
+            message: "emit_enum_labels is off, so no labels are generated".to_string(),
+            lint: None,
+        }];
+    }
+    let mut keys: Vec<&String> = labels.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let column = schema.tables.iter().find_map(|table| {
+                let rest = key.strip_prefix(&table.name)?.strip_prefix('.')?;
+                table.columns.iter().find_map(|column| {
+                    let value = rest.strip_prefix(&column.name)?.strip_prefix('.')?;
+                    Some((column, value))
+                })
+            });
+            let message = match column {
+                None => "no table field with this name; expected \"table.field.value\"".to_string(),
+                Some((column, value)) => {
+                    let data_type = optional_inner(&column.data_type).unwrap_or(&column.data_type);
+                    match string_literal_values(data_type) {
+                        None => format!(
+                            "`{}` is {}, not a union of string literals",
+                            column.name,
+                            render_validator(data_type)
+                        ),
+                        Some(values) if !values.contains(&value) => {
+                            format!("{} has no literal {value:?}", render_validator(data_type))
+                        }
+                        Some(_) => return None,
+                    }
+                }
+            };
+            Some(GenerationWarning {
+                location: format!("enum_labels[{key:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `arg_defaults` entries that won't be used, sorted by key: keys
+/// that name no function arg, args that aren't optional, and values the arg's
+/// validator rejects.
+pub(crate) fn arg_default_warnings(
+    defaults: &HashMap<String, JsonValue>,
+    functions: &[ConvexFunction],
+) -> Vec<GenerationWarning>
+{
+    let mut keys: Vec<&String> = defaults.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let param = functions
+                .iter()
+                .find_map(|func| func.params.iter().find(|param| arg_default_key(func, param) == **key));
+            let message = match param {
+                None => "no function arg with this name; expected \"module:function.arg\"".to_string(),
+                Some(param) => match optional_inner(&param.data_type) {
+                    None => "only optional args take defaults".to_string(),
+                    Some(inner) if !accepts(inner, &defaults[key]) => {
+                        format!("{} doesn't match {}", defaults[key], render_validator(inner))
+                    }
+                    Some(_) => return None,
+                },
+            };
+            Some(GenerationWarning {
+                location: format!("arg_defaults[{key:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `deprecated_functions` keys that name no function, sorted by key.
+pub(crate) fn unknown_deprecated_functions(
+    deprecated: &HashMap<String, String>,
+    functions: &[ConvexFunction],
+) -> Vec<GenerationWarning>
+{
+    let mut unknown: Vec<&String> = deprecated
+        .keys()
+        .filter(|key| !functions.iter().any(|func| function_path(func) == **key))
+        .collect();
+    unknown.sort();
+
+    unknown
+        .into_iter()
+        .map(|key| GenerationWarning {
+            location: format!("deprecated_functions[{key:?}]"),
+            message: "no function with this name; expected \"module:function\"".to_string(),
+            lint: None,
+        })
+        .collect()
+}
+
+/// Warn about `function_timeouts` keys that name no function and values that
+/// aren't durations, sorted by key.
+pub(crate) fn function_timeout_warnings(
+    timeouts: &HashMap<String, String>,
+    functions: &[ConvexFunction],
+) -> Vec<GenerationWarning>
+{
+    let mut keys: Vec<&String> = timeouts.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let message = if !functions.iter().any(|func| function_path(func) == *key) {
+                "no function with this name; expected \"module:function\"".to_string()
+            } else if parse_duration(&timeouts[key]).is_none() {
+                format!(
+                    "invalid timeout {:?}; expected e.g. \"250ms\", \"5s\", or \"2m\"",
+                    timeouts[key]
+                )
+            } else {
+                return None;
+            };
+            Some(GenerationWarning {
+                location: format!("function_timeouts[{key:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `function_priorities` keys that name no function and values that
+/// aren't priorities, sorted by key.
+pub(crate) fn function_priority_warnings(
+    priorities: &HashMap<String, String>,
+    functions: &[ConvexFunction],
+) -> Vec<GenerationWarning>
+{
+    let mut keys: Vec<&String> = priorities.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let message = if !functions.iter().any(|func| function_path(func) == *key) {
+                "no function with this name; expected \"module:function\"".to_string()
+            } else if parse_priority(&priorities[key]).is_none() {
+                format!(
+                    "invalid priority {:?}; expected \"high\", \"normal\", or \"low\"",
+                    priorities[key]
+                )
+            } else {
+                return None;
+            };
+            Some(GenerationWarning {
+                location: format!("function_priorities[{key:?}]"),
+                message,
+                lint: None,
+            })
+        })
+        .collect()
+}
+
+/// Warn about `redacted_args` keys that name no function arg, sorted by key.
+pub(crate) fn unknown_redacted_args(redacted: &HashSet<String>, functions: &[ConvexFunction]) -> Vec<GenerationWarning>
+{
+    let mut unknown: Vec<&String> = redacted
+        .iter()
+        .filter(|key| {
+            !functions
+                .iter()
+                .any(|func| func.params.iter().any(|param| arg_default_key(func, param) == **key))
+        })
+        .collect();
+    unknown.sort();
+
+    unknown
+        .into_iter()
+        .map(|key| GenerationWarning {
+            location: format!("redacted_args[{key:?}]"),
+            message: "no function arg with this name; expected \"module:function.arg\"".to_string(),
+            lint: None,
+        })
+        .collect()
+}
+
+/// Warnings for [`Configuration::keep_tables`](crate::Configuration::keep_tables)
+/// entries that name no table.
+pub(crate) fn unknown_keep_tables(keep: &HashSet<String>, schema: &ConvexSchema) -> Vec<GenerationWarning>
+{
+    let mut unknown: Vec<&String> = keep
+        .iter()
+        .filter(|name| !schema.tables.iter().any(|table| table.name == **name))
+        .collect();
+    unknown.sort();
+
+    unknown
+        .into_iter()
+        .map(|name| GenerationWarning {
+            location: format!("keep_tables[{name:?}]"),
+            message: "no table with this name".to_string(),
+            lint: None,
+        })
+        .collect()
+}
+
+/// The `function_paths` entries no extracted function came from.
+pub(crate) fn empty_function_files(function_paths: &[PathBuf], functions: &[ConvexFunction]) -> Vec<PathBuf>
+{
+    function_paths
+        .iter()
+        .filter(|path| {
+            let (file_name, module_path) = module_identity(path);
+            !functions.iter().any(|func| {
+                func.file_name == file_name && func.module_path.as_deref().unwrap_or(&func.file_name) == module_path
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// The warning for a function file that yielded no functions.
+pub(crate) fn empty_function_file_warning(file: &Path) -> GenerationWarning
+{
+    GenerationWarning {
+        location: format!("function file {}", file.display()),
+        message: "no query, mutation, or action exports found; check the registrar imports".to_string(),
+        lint: None,
+    }
+}
+
+/// Convex path of a function (e.g. `"users:byName"`).
+pub(crate) fn function_path(func: &ConvexFunction) -> String
+{
+    format!("{}:{}", func.module_path.as_deref().unwrap_or(&func.file_name), func.name)
+}
+
+/// `arg_defaults` and `redacted_args` key of a function arg (e.g.
+/// `"users:search.locale"`).
+pub(crate) fn arg_default_key(func: &ConvexFunction, param: &ConvexFunctionParam) -> String
+{
+    format!("{}.{}", function_path(func), param.name)
+}
+
+fn plural(count: usize, noun: &str) -> String
+{
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::descriptors::is_object_like;
+
 /// The convex schema.
 ///
 /// A schema can contain many tables. <https://docs.convex.dev/database/schemas>
@@ -13,6 +15,34 @@ use serde_json::Value as JsonValue;
 pub(crate) struct ConvexSchema
 {
     pub(crate) tables: Vec<ConvexTable>,
+    /// The options object passed as `defineSchema`'s second argument.
+    #[serde(default)]
+    pub(crate) options: SchemaOptions,
+}
+
+/// Options from `defineSchema(tables, options)`.
+///
+/// <https://docs.convex.dev/api/modules/server#defineschema>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct SchemaOptions
+{
+    /// Whether Convex validates documents against the schema. When off, the
+    /// generated table types describe the intended shape, not a guarantee.
+    pub(crate) schema_validation: bool,
+    /// Whether `v.id(...)` is limited to tables declared in the schema.
+    pub(crate) strict_table_name_types: bool,
+}
+
+impl Default for SchemaOptions
+{
+    fn default() -> Self
+    {
+        Self {
+            schema_validation: true,
+            strict_table_name_types: true,
+        }
+    }
 }
 
 /// A table in the convex schema.
@@ -25,6 +55,19 @@ pub(crate) struct ConvexTable
     pub(crate) name: String,
     /// The columns in the table.
     pub(crate) columns: Vec<ConvexColumn>,
+    /// Database indexes declared with `.index(name, fields)`.
+    #[serde(default)]
+    pub(crate) indexes: Vec<ConvexIndex>,
+}
+
+/// A database index on a table.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ConvexIndex
+{
+    /// The index name (e.g. `"by_author"`).
+    pub(crate) name: String,
+    /// The indexed fields, in order.
+    pub(crate) fields: Vec<String>,
 }
 
 /// A column in the convex schema.
@@ -58,6 +101,31 @@ pub(crate) struct ConvexFunction
     /// (backwards compatible with older extractors).
     #[serde(default)]
     pub(crate) module_path: Option<String>,
+    /// The args validator when it isn't an object of fields, e.g. a union of
+    /// objects or a record. `params` is then empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) args_validator: Option<JsonValue>,
+    /// The component whose client created the function in the app, e.g.
+    /// `"rateLimiter"` for the rate limiter's `hookAPI()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) component: Option<String>,
+}
+
+impl ConvexFunction
+{
+    /// The args validator the args struct wraps whole: one that isn't an
+    /// object of fields but still only accepts objects. Any other non-object
+    /// validator can't be called and gets no args.
+    pub(crate) fn whole_args(&self) -> Option<&JsonValue>
+    {
+        self.args_validator.as_ref().filter(|args| is_object_like(args))
+    }
+
+    /// Whether calls take an args struct.
+    pub(crate) fn takes_args(&self) -> bool
+    {
+        !self.params.is_empty() || self.whole_args().is_some()
+    }
 }
 
 /// A parameter in a convex function.
@@ -0,0 +1,44 @@
+//! Syntax check for the generated file ([`crate::Configuration::verify_syntax`]).
+//!
+//! A codegen bug that emits invalid Rust would otherwise surface as a compiler
+//! error deep inside the consumer's `include!`. Parsing the output with `syn`
+//! first turns that into a generation error that points at the bad lines.
+//! It only catches syntax errors; `tests/compile_test.rs` type-checks the output
+//! of every opt-in template with `cargo check`.
+
+use crate::errors::ConvexTypeGeneratorError;
+
+/// Lines of context shown on each side of the offending line.
+const CONTEXT_LINES: usize = 3;
+
+/// Parse `code` as a Rust file, failing with the offending snippet.
+pub(crate) fn check_syntax(code: &str) -> Result<(), ConvexTypeGeneratorError>
+{
+    let Err(error) = syn::parse_file(code) else {
+        return Ok(());
+    };
+
+    let line = error.span().start().line;
+    Err(ConvexTypeGeneratorError::InvalidGeneratedCode {
+        line,
+        message: error.to_string(),
+        snippet: snippet(code, line),
+    })
+}
+
+/// The lines around `line` (1-based), numbered, with the offending one marked.
+fn snippet(code: &str, line: usize) -> String
+{
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    code.lines()
+        .enumerate()
+        .map(|(idx, text)| (idx + 1, text))
+        .skip(first - 1)
+        .take(line + CONTEXT_LINES + 1 - first)
+        .map(|(number, text)| {
+            let marker = if number == line { ">" } else { " " };
+            format!("{marker} {number:>5} | {text}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
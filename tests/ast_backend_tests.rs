@@ -0,0 +1,919 @@
+use std::fs;
+use std::path::PathBuf;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::{generate, Configuration, CustomValidatorHandler, ExtractionBackend};
+use serde_json::json;
+use tempfile::TempDir;
+
+const SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+
+    export const status = v.union(v.literal("active"), v.literal("banned"));
+
+    const profile = v.object({
+        bio: v.optional(v.string()),
+        age: v.number(),
+    });
+
+    export default defineSchema({
+        users: defineTable({
+            name: v.string(),
+            status,
+            profile,
+        }).index("by_name", ["name"]),
+        posts: defineTable(v.object({ author: v.id("users"), body: v.string() }))
+            .searchIndex("search_body", { searchField: "body" }),
+    });
+"#;
+
+/// Write the schema, function files, and helper modules into a temp dir and
+/// return a configuration that uses `backend`.
+fn setup(
+    backend: ExtractionBackend,
+    schema_content: &str,
+    function_files: &[(&str, &str)],
+    helper_files: &[(&str, &str)],
+) -> (TempDir, Configuration)
+{
+    let temp_dir = TempDir::with_prefix("convex_ast_test").expect("Failed to create temp directory");
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, schema_content).expect("Failed to write test schema");
+
+    // Bun resolves `./_generated/server` through its own mock; this stub only
+    // needs to exist on disk.
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).expect("Failed to create _generated dir");
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { query, mutation, action, internalQuery, internalMutation, internalAction, httpAction } from "convex/server";"#,
+    )
+    .expect("Failed to write _generated/server stub");
+
+    let write = |content: &str, filename: &str| {
+        let path = temp_dir.path().join(filename);
+        fs::create_dir_all(path.parent().expect("file has a parent")).expect("Failed to create dir");
+        fs::write(&path, content).expect("Failed to write file");
+        path
+    };
+    for (content, filename) in helper_files {
+        write(content, filename);
+    }
+    let function_paths: Vec<PathBuf> = function_files
+        .iter()
+        .map(|(content, filename)| write(content, filename))
+        .collect();
+
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths,
+        extraction_backend: backend,
+        ..Default::default()
+    };
+    (temp_dir, config)
+}
+
+/// Generate code with the AST backend and return the output (or the error).
+fn generate_with_ast(schema_content: &str, function_files: &[(&str, &str)]) -> Result<String, ConvexTypeGeneratorError>
+{
+    let (_temp_dir, config) = setup(ExtractionBackend::Ast, schema_content, function_files, &[]);
+    let out_file = config.out_file.clone();
+    generate(config)?;
+    Ok(fs::read_to_string(out_file).expect("Failed to read generated code"))
+}
+
+#[test]
+fn test_ast_backend_schema()
+{
+    let code = generate_with_ast(SCHEMA, &[]).expect("AST extraction failed");
+
+    assert!(code.contains("pub struct UsersTable"), "missing UsersTable, got:\n{code}");
+    assert!(code.contains("pub struct PostsTable"), "missing PostsTable, got:\n{code}");
+    assert!(code.contains("pub name: String"), "missing name field, got:\n{code}");
+    assert!(code.contains("pub author: String"), "missing id field, got:\n{code}");
+    assert!(
+        code.contains("Active"),
+        "const validator should resolve to an enum, got:\n{code}"
+    );
+    assert!(code.contains("pub age: f64"), "object const should resolve, got:\n{code}");
+}
+
+#[test]
+fn test_ast_backend_functions()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query, mutation } from "./_generated/server";
+        import { status } from "./schema";
+
+        const base = { name: v.string() };
+
+        export const byName = query({
+            args: { ...base, status },
+            returns: v.array(v.string()),
+            handler: async (ctx, args) => {
+                const label = `user ${args.name}`;
+                return [label];
+            },
+        });
+
+        export const rename = mutation({
+            args: v.object({ id: v.id("users"), name: v.optional(v.string()) }),
+            async handler(ctx, { id, name }) {},
+        });
+
+        export const LIMIT = 10;
+    "#;
+
+    let code = generate_with_ast(SCHEMA, &[(functions, "users.ts")]).expect("AST extraction failed");
+
+    assert!(
+        code.contains("pub struct UsersByNameArgs"),
+        "missing query args, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct UsersRenameArgs"),
+        "missing mutation args, got:\n{code}"
+    );
+    assert!(
+        code.contains("fn query_users_by_name"),
+        "missing typed query method, got:\n{code}"
+    );
+    assert!(code.contains("fn users_rename"), "missing mutation method, got:\n{code}");
+    assert!(code.contains("Vec<String>"), "typed return should resolve, got:\n{code}");
+}
+
+#[test]
+fn test_ast_backend_rejects_wrapper_factories()
+{
+    let functions = r#"
+        import { authedQuery } from "./lib/auth";
+        export const me = authedQuery({ args: {}, handler: async () => null });
+    "#;
+
+    match generate_with_ast(SCHEMA, &[(functions, "me.ts")]) {
+        Err(ConvexTypeGeneratorError::Diagnostics { diagnostics }) => {
+            let [diagnostic] = diagnostics.as_slice() else {
+                panic!("Expected one diagnostic, got {diagnostics:?}");
+            };
+            assert!(
+                diagnostic.message.contains("export `me`"),
+                "error should name the export, got: {diagnostic}"
+            );
+            assert!(
+                diagnostic.to_string().contains("ExtractionBackend::Bun"),
+                "error should point at Bun, got: {diagnostic}"
+            );
+        }
+        other => panic!("Expected Diagnostics, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_ast_backend_number_literals()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            levels: defineTable({
+                level: v.union(
+                    v.literal(0b101),
+                    v.literal(0o17),
+                    v.literal(0x1F),
+                    v.literal(1e-5),
+                    v.literal(2.5e+3),
+                    v.literal(1_000),
+                ),
+            }),
+        });
+    "#;
+
+    let code = generate_with_ast(schema, &[]).expect("number literals should be read");
+    for value in ["5", "15", "31", "0.00001", "2500", "1000"] {
+        assert!(
+            code.contains(&format!("#[serde(rename = \"{value}\")]")),
+            "missing literal {value}, got:\n{code}"
+        );
+    }
+
+    let bad = schema.replace("0b101", "0b102");
+    match generate_with_ast(&bad, &[]) {
+        Err(error @ ConvexTypeGeneratorError::Diagnostics { .. }) => {
+            assert!(error.to_string().contains("invalid number `0b102`"), "got: {error}");
+        }
+        other => panic!("Expected Diagnostics, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_ast_backend_empty_schema()
+{
+    let code = generate_with_ast("", &[]).expect("Empty schema should succeed");
+    assert!(!code.contains("UsersTable"), "empty schema should produce no tables");
+}
+
+#[test]
+fn test_ast_backend_schema_options()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        const validate = false;
+        export default defineSchema(
+            { notes: defineTable({ body: v.string() }) },
+            { schemaValidation: validate, strictTableNameTypes: false },
+        );
+    "#;
+
+    let code = generate_with_ast(schema, &[]).expect("schema options should be accepted");
+
+    assert!(
+        code.contains("// defineSchema options: schemaValidation: false, strictTableNameTypes: false"),
+        "options should be recorded, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// Advisory: the schema sets `schemaValidation: false`"),
+        "tables should be marked advisory, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_ast_backend_rejects_computed_schema_options()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({ notes: defineTable({ body: v.string() }) }, { schemaValidation: !isDev });
+    "#;
+
+    match generate_with_ast(schema, &[]) {
+        Err(error @ ConvexTypeGeneratorError::Diagnostics { .. }) => {
+            assert!(
+                error.to_string().contains("schemaValidation"),
+                "error should name the option, got: {error}"
+            );
+        }
+        other => panic!("Expected Diagnostics, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_ast_backend_reports_every_problem()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        import { vMoney } from "money-kit";
+        export default defineSchema({
+            users: defineTable({ name: v.string() }),
+            orders: defineTable({ total: vMoney() }),
+            invoices: defineTable(makeInvoice()),
+        });
+    "#;
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        import { authedQuery } from "./lib/auth";
+        export const me = authedQuery({ args: {}, handler: async () => null });
+        export const list = query({ args: {}, handler: async () => [] });
+        export const admins = authedQuery({ args: {}, handler: async () => [] });
+    "#;
+
+    let (_temp_dir, config) = setup(ExtractionBackend::Ast, schema, &[(functions, "users.ts")], &[]);
+    let Err(ConvexTypeGeneratorError::Diagnostics { diagnostics }) = generate(config.clone()) else {
+        panic!("Expected Diagnostics");
+    };
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(messages.len(), 4, "got: {messages:#?}");
+    let expected = ["table `orders`", "table `invoices`", "export `admins`", "export `me`"];
+    for (message, expected) in messages.iter().zip(expected) {
+        assert!(message.contains(expected), "expected `{expected}`, got: {message}");
+    }
+    assert!(diagnostics[0].file.as_ref().is_some_and(|file| file.ends_with("schema.ts")));
+    assert!(diagnostics[2].file.as_ref().is_some_and(|file| file.ends_with("users.ts")));
+    assert!(!config.out_file.exists(), "nothing should be written");
+
+    match generate(Configuration {
+        fail_fast: true,
+        ..config
+    }) {
+        Err(ConvexTypeGeneratorError::ExtractionFailed { details, .. }) => {
+            assert!(details.contains("table `orders`"), "got: {details}");
+        }
+        other => panic!("Expected ExtractionFailed, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_chained_optional_matches_v_optional()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            users: defineTable({
+                nickname: v.string().optional(),
+                tags: v.array(v.string()).optional(),
+                age: v.optional(v.number()),
+            }),
+        });
+    "#;
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const search = query({ args: { term: v.string().optional() }, handler: async () => null });
+    "#;
+
+    for backend in [ExtractionBackend::Ast, ExtractionBackend::Bun] {
+        let (_temp_dir, config) = setup(backend, schema, &[(functions, "users.ts")], &[]);
+        let out_file = config.out_file.clone();
+        generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+        let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+        for field in [
+            "pub nickname: Option<String>",
+            "pub tags: Option<Vec<String>>",
+            "pub age: Option<f64>",
+            "pub term: Option<String>",
+        ] {
+            assert!(code.contains(field), "{backend:?}: expected `{field}`, got:\n{code}");
+        }
+    }
+}
+
+/// Generate `functions` as `shapes.ts` with every backend, returning each
+/// backend's code and report warnings.
+fn generate_shapes(functions: &str) -> Vec<(ExtractionBackend, String, Vec<String>)>
+{
+    [ExtractionBackend::Ast, ExtractionBackend::Bun]
+        .into_iter()
+        .map(|backend| {
+            let (_temp_dir, config) = setup(backend, SCHEMA, &[(functions, "shapes.ts")], &[]);
+            let out_file = config.out_file.clone();
+            let report = generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+            let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+            (backend, code, report.warnings.iter().map(ToString::to_string).collect())
+        })
+        .collect()
+}
+
+#[test]
+fn test_top_level_scalar_validators()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query, mutation } from "./_generated/server";
+        export const name = query({ args: {}, returns: v.string(), handler: async () => "" });
+        export const clear = mutation({ args: {}, returns: v.null(), handler: async () => null });
+        export const count = query({ args: v.number(), handler: async () => null });
+    "#;
+
+    for (backend, code, warnings) in generate_shapes(functions) {
+        for expected in [
+            "fn query_shapes_name(&self) -> impl std::future::Future<Output = Result<String, ConvexError>>",
+            "fn shapes_clear(&self) -> impl std::future::Future<Output = Result<(), ConvexError>>",
+            // Convex always calls with an object of args, so a scalar never matches
+            "fn query_shapes_count(&self)",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+        assert!(
+            !code.contains("ShapesCountArgs("),
+            "{backend:?}: scalar args get no args struct"
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("shapes:count args: v.number()") && w.contains("no args are generated")),
+            "{backend:?}: expected a warning for the scalar args, got {warnings:?}"
+        );
+    }
+}
+
+#[test]
+fn test_top_level_array_validators()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const names = query({ args: {}, returns: v.array(v.string()), handler: async () => [] });
+        export const tags = query({ args: v.array(v.string()), handler: async () => null });
+    "#;
+
+    for (backend, code, warnings) in generate_shapes(functions) {
+        assert!(
+            code.contains(
+                "fn query_shapes_names(&self) -> impl std::future::Future<Output = Result<Vec<String>, ConvexError>>"
+            ),
+            "{backend:?}: an array of scalars should return a Vec, got:\n{code}"
+        );
+        assert!(
+            code.contains("fn query_shapes_tags(&self)"),
+            "{backend:?}: array args can't match, so the call takes none, got:\n{code}"
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("shapes:tags args: v.array(v.string())") && w.contains("no args are generated")),
+            "{backend:?}: expected a warning for the array args, got {warnings:?}"
+        );
+    }
+}
+
+#[test]
+fn test_top_level_record_validators()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query, mutation } from "./_generated/server";
+        export const scores = query({ args: {}, returns: v.record(v.string(), v.number()), handler: async () => ({}) });
+        export const tally = mutation({ args: v.record(v.string(), v.number()), handler: async () => null });
+    "#;
+
+    for (backend, code, _) in generate_shapes(functions) {
+        for expected in [
+            "Result<std::collections::HashMap<String, f64>, ConvexError>",
+            "#[serde(transparent)]\npub struct ShapesTallyArgs(pub std::collections::HashMap<String, f64>);",
+            "fn shapes_tally(&self, args: ShapesTallyArgs)",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+#[test]
+fn test_top_level_union_validators()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query, mutation } from "./_generated/server";
+        export const either = query({ args: {}, returns: v.union(v.string(), v.number()), handler: async () => "" });
+        export const create = mutation({
+            args: v.union(v.object({ kind: v.literal("a"), x: v.number() }), v.object({ kind: v.literal("b") })),
+            handler: async () => null,
+        });
+        export const pick = query({ args: v.union(v.string(), v.object({ id: v.string() })), handler: async () => null });
+    "#;
+
+    for (backend, code, warnings) in generate_shapes(functions) {
+        for expected in [
+            "Result<ShapesEitherReturn, ConvexError>",
+            "pub struct ShapesCreateArgs(pub ShapesCreateInput);",
+            "fn shapes_create(&self, args: ShapesCreateArgs)",
+            // A union with a variant that isn't an object can't always be sent
+            "fn query_shapes_pick(&self)",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("shapes:pick args") && w.contains("no args are generated")),
+            "{backend:?}: expected a warning for the mixed union args, got {warnings:?}"
+        );
+    }
+}
+
+#[test]
+fn test_top_level_any_validators()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query, mutation } from "./_generated/server";
+        export const blob = query({ args: {}, returns: v.any(), handler: async () => null });
+        export const raw = mutation({ args: v.any(), handler: async () => null });
+        export const loose = mutation({
+            args: v.union(v.object({ kind: v.literal("a") }), v.any()),
+            handler: async () => null,
+        });
+    "#;
+
+    for (backend, code, _) in generate_shapes(functions) {
+        for expected in [
+            "Result<serde_json::Value, ConvexError>",
+            // Args are always an object, so the struct can't hold a scalar the call would reject
+            "#[serde(transparent)]\npub struct ShapesRawArgs(pub serde_json::Map<String, serde_json::Value>);",
+            "pub struct ShapesLooseArgs(pub serde_json::Map<String, serde_json::Value>);",
+            "fn shapes_raw(&self, args: ShapesRawArgs)",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+#[test]
+fn test_validator_factories()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        const withTimestamps = <T extends Record<string, any>>(fields: T) => ({
+            ...fields,
+            createdAt: v.number(),
+            updatedAt: v.optional(v.number()),
+        });
+        const money = (currency = v.string()) => v.object({ amount: v.number(), currency });
+        function tagged(tag: string) {
+            return v.object({ kind: v.literal(tag), note: v.string() });
+        }
+
+        export default defineSchema({
+            orders: defineTable(withTimestamps({ total: money(), label: tagged("order") })),
+            refunds: defineTable({ ...withTimestamps({}), amount: money(v.literal("usd")) }),
+        });
+    "#;
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        const page = (item) => v.object({ items: v.array(item), cursor: v.optional(v.string()) });
+        export const list = query({ args: {}, returns: page(v.id("orders")), handler: async () => null });
+    "#;
+
+    for backend in [ExtractionBackend::Ast, ExtractionBackend::Bun] {
+        let (_temp_dir, config) = setup(backend, schema, &[(functions, "orders.ts")], &[]);
+        let out_file = config.out_file.clone();
+        generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+        let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+        for expected in [
+            "pub created_at: f64",
+            "pub updated_at: Option<f64>",
+            // Objects returned by a factory are named after the field they land in
+            "pub total: OrdersTotal",
+            "pub label: OrdersLabel",
+            "pub amount: RefundsAmount",
+            "pub struct OrdersListReturn",
+            "pub items: Vec<String>",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+#[test]
+fn test_literal_unions_from_shared_constants()
+{
+    let constants = r#"
+        export const STATUSES = ["draft", "published", "archived"] as const;
+        export const PRIORITIES = ["low", "high"] as const;
+    "#;
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        import { STATUSES, PRIORITIES } from "./constants";
+
+        export default defineSchema({
+            posts: defineTable({
+                status: v.union(...STATUSES.map(v.literal)),
+                priority: v.union(...PRIORITIES.map((p) => v.literal(p)), v.literal("urgent")),
+            }),
+        });
+    "#;
+
+    for backend in [ExtractionBackend::Ast, ExtractionBackend::Bun] {
+        let (_temp_dir, config) = setup(backend, schema, &[], &[(constants, "constants.ts")]);
+        let out_file = config.out_file.clone();
+        generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+        let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+        for expected in [
+            "pub enum PostsStatus",
+            "Draft,",
+            "Published,",
+            "Archived,",
+            "pub enum PostsPriority",
+            "Low,",
+            "Urgent,",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+#[test]
+fn test_convex_helpers_validators()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        import { literals, nullable, partial } from "convex-helpers/validators";
+
+        export default defineSchema({
+            tasks: defineTable({
+                state: literals("todo", "done"),
+                note: nullable(v.string()),
+                ...partial({ dueAt: v.number(), owner: v.string() }),
+            }),
+        });
+    "#;
+    let functions = r#"
+        import { v } from "convex/values";
+        import { literals, nullable } from "convex-helpers/validators";
+        import { query } from "./_generated/server";
+        export const title = query({
+            args: { id: v.id("tasks") },
+            returns: nullable(v.string()),
+            handler: async () => null,
+        });
+        export const state = query({ args: {}, returns: nullable(literals("todo", "done")), handler: async () => null });
+    "#;
+
+    for backend in [ExtractionBackend::Ast, ExtractionBackend::Bun] {
+        let (_temp_dir, config) = setup(backend, schema, &[(functions, "tasks.ts")], &[]);
+        let out_file = config.out_file.clone();
+        generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+        let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+        for expected in [
+            "pub enum TasksState",
+            "Todo,",
+            "pub note: Option<String>,",
+            "pub due_at: Option<f64>,",
+            "pub owner: Option<String>,",
+            "Result<Option<String>, ConvexError>",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+#[test]
+fn test_custom_validators()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        import { vEmail, vMoney } from "./lib/validators";
+
+        export default defineSchema({
+            users: defineTable({
+                email: vEmail(),
+                backup: v.optional(vEmail()),
+                aliases: v.array(vEmail()),
+                balance: vMoney("usd"),
+            }),
+        });
+    "#;
+    // Neither backend may run this module: the package doesn't exist
+    let validators = r#"
+        import { isEmail } from "validator-kit";
+        export const vEmail = () => isEmail();
+        export const vMoney = (currency) => isEmail(currency);
+    "#;
+
+    for backend in [ExtractionBackend::Ast, ExtractionBackend::Bun] {
+        let (_temp_dir, mut config) = setup(backend, schema, &[], &[(validators, "lib/validators.ts")]);
+        config.custom_validators.insert(
+            "vEmail".to_string(),
+            CustomValidatorHandler {
+                module: "lib/validators".to_string(),
+                descriptor: json!({ "type": "string" }),
+                rust_type: Some("crate::Email".to_string()),
+            },
+        );
+        config.custom_validators.insert(
+            "vMoney".to_string(),
+            CustomValidatorHandler {
+                module: "lib/validators".to_string(),
+                descriptor: json!({
+                    "type": "object",
+                    "properties": { "amount": { "type": "number" }, "currency": { "type": "string" } },
+                }),
+                rust_type: None,
+            },
+        );
+        let out_file = config.out_file.clone();
+        generate(config).unwrap_or_else(|e| panic!("{backend:?} extraction failed: {e}"));
+        let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+        for expected in [
+            "pub email: crate::Email",
+            "pub backup: Option<crate::Email>",
+            "pub aliases: Vec<crate::Email>",
+            // Without a Rust type, the descriptor is mapped as usual
+            "pub balance: UsersBalance",
+            "pub currency: String",
+        ] {
+            assert!(code.contains(expected), "{backend:?}: expected `{expected}`, got:\n{code}");
+        }
+    }
+}
+
+// =============================================================================
+// Hybrid backend
+// =============================================================================
+
+const AUTH_HELPER: &str = r#"
+    import { query } from "../_generated/server";
+    export const authedQuery = (config) => query(config);
+"#;
+
+#[test]
+fn test_hybrid_backend_skips_bun_for_static_files()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const get = query({ args: { id: v.id("users") }, handler: async () => null });
+    "#;
+
+    let (_temp_dir, config) = setup(ExtractionBackend::Hybrid, SCHEMA, &[(functions, "users.ts")], &[]);
+    let report = generate(config).expect("Hybrid extraction failed");
+
+    assert!(
+        report.bun_fallbacks.is_empty(),
+        "no file should need Bun: {:?}",
+        report.bun_fallbacks
+    );
+    assert_eq!(report.tables.len(), 2);
+    assert_eq!(report.functions.len(), 1);
+}
+
+#[test]
+fn test_hybrid_backend_falls_back_to_bun()
+{
+    let static_functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const get = query({ args: { id: v.id("users") }, handler: async () => null });
+    "#;
+    let wrapped_functions = r#"
+        import { v } from "convex/values";
+        import { authedQuery } from "./lib/auth";
+        export const me = authedQuery({ args: { verbose: v.boolean() }, handler: async () => null });
+    "#;
+
+    let (_temp_dir, config) = setup(
+        ExtractionBackend::Hybrid,
+        SCHEMA,
+        &[(static_functions, "users.ts"), (wrapped_functions, "session.ts")],
+        &[(AUTH_HELPER, "lib/auth.ts")],
+    );
+    let out_file = config.out_file.clone();
+    let report = generate(config).expect("Hybrid extraction failed");
+    let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+    assert_eq!(report.bun_fallbacks.len(), 1, "only session.ts should need Bun");
+    assert!(report.bun_fallbacks[0].file.ends_with("session.ts"));
+    assert!(report.summary().contains("(1 file via Bun)"), "got: {}", report.summary());
+    assert!(
+        code.contains("pub struct UsersGetArgs"),
+        "AST-extracted function missing, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct SessionMeArgs"),
+        "Bun-extracted function missing, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Convex Auth
+// =============================================================================
+
+const AUTH_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { authTables } from "@convex-dev/auth/server";
+    import { v } from "convex/values";
+
+    export default defineSchema({
+        ...authTables,
+        users: defineTable({ email: v.optional(v.string()), role: v.string() }).index("email", ["email"]),
+    });
+"#;
+
+#[test]
+fn test_ast_backend_auth_tables()
+{
+    let code = generate_with_ast(AUTH_SCHEMA, &[]).expect("AST extraction failed");
+
+    assert!(
+        code.contains("pub struct AuthSessionsTable"),
+        "missing auth table, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub provider_account_id: String,"),
+        "missing authAccounts field, got:\n{code}"
+    );
+    // A `users` table after the spread replaces the one from authTables
+    assert!(code.contains("pub role: String,"), "users override missing, got:\n{code}");
+    assert!(
+        !code.contains("pub is_anonymous"),
+        "authTables users should be replaced, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_convex_auth_functions_are_typed()
+{
+    let auth = r#"
+        import { convexAuth } from "@convex-dev/auth/server";
+        import { Password } from "@convex-dev/auth/providers/Password";
+        export const { auth, signIn, signOut, store, isAuthenticated } = convexAuth({ providers: [Password] });
+    "#;
+
+    let (_temp_dir, config) = setup(ExtractionBackend::Hybrid, AUTH_SCHEMA, &[(auth, "auth.ts")], &[]);
+    let out_file = config.out_file.clone();
+    generate(config).expect("Hybrid extraction failed");
+    let code = fs::read_to_string(out_file).expect("Failed to read generated code");
+
+    assert!(
+        code.contains("pub struct AuthSignInArgs"),
+        "missing signIn args, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub provider: Option<String>,"),
+        "missing provider arg, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub refresh_token: Option<String>,"),
+        "missing refreshToken arg, got:\n{code}"
+    );
+    assert!(
+        code.contains("async fn auth_sign_out(&self)"),
+        "missing signOut, got:\n{code}"
+    );
+    assert!(
+        code.contains("async fn query_auth_is_authenticated(&self) -> Result<bool, ConvexError>"),
+        "isAuthenticated should be typed, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub started: Option<bool>,"),
+        "signIn result should be typed, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Lints
+// =============================================================================
+
+const LINT_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+
+    export default defineSchema({
+        users: defineTable({
+            name: v.string(),
+            nickname: v.optional(v.union(v.null(), v.string())),
+        }),
+        posts: defineTable({
+            authorId: v.id("users"),
+            teamId: v.id("users"),
+            votes: v.record(v.id("users"), v.number()),
+            kind: v.union(v.literal("a"), v.union(v.literal("b"), v.literal("c"))),
+        }).index("by_team", ["teamId", "authorId"]),
+    });
+"#;
+
+const LINT_FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { query, mutation } from "./_generated/server";
+
+    export const listByAuthor = query({ args: { authorId: v.id("users") }, handler: async () => [] });
+    export const listByTeam = query({ args: { teamId: v.id("users") }, handler: async () => [] });
+    export const save = mutation({ args: { payload: v.any() }, handler: async () => null });
+"#;
+
+#[test]
+fn test_lints_flag_best_practice_violations()
+{
+    let (_temp_dir, mut config) = setup(ExtractionBackend::Ast, LINT_SCHEMA, &[(LINT_FUNCTIONS, "posts.ts")], &[]);
+    config.lint = true;
+    let report = generate(config).expect("generation failed");
+
+    let lint_at = |lint: &str| -> Vec<String> {
+        report
+            .warnings
+            .iter()
+            .filter(|w| w.lint == Some(lint))
+            .map(|w| w.location.clone())
+            .collect()
+    };
+
+    assert_eq!(lint_at("nested-optional"), ["table users.nickname"]);
+    assert_eq!(lint_at("nested-union"), ["table posts.kind"]);
+    assert_eq!(lint_at("record-key"), ["table posts.votes"]);
+    assert_eq!(lint_at("any-in-args"), ["function posts:save args.payload"]);
+    assert_eq!(
+        lint_at("unindexed-lookup"),
+        ["function posts:listByAuthor args.authorId"],
+        "by_team covers teamId but nothing starts with authorId"
+    );
+}
+
+#[test]
+fn test_lints_are_opt_in()
+{
+    let (_temp_dir, config) = setup(ExtractionBackend::Ast, LINT_SCHEMA, &[(LINT_FUNCTIONS, "posts.ts")], &[]);
+    let report = generate(config).expect("generation failed");
+
+    assert!(
+        report.warnings.iter().all(|w| w.lint.is_none()),
+        "lints should not run by default: {:?}",
+        report.warnings
+    );
+}
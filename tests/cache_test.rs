@@ -0,0 +1,44 @@
+//! Runs in its own test binary: it points `CARGO_TARGET_DIR` at a temp dir.
+
+use std::fs;
+
+use convex_typegen::{clean_cache, CacheKind};
+use tempfile::TempDir;
+
+#[test]
+fn test_clean_cache_keeps_held_locks()
+{
+    let temp_dir = TempDir::with_prefix("convex_typegen_cache").expect("Failed to create temp directory");
+    std::env::set_var("CARGO_TARGET_DIR", temp_dir.path());
+    let cache = temp_dir.path().join(".convex-typegen-cache");
+
+    // An old version with a lock left by a crashed download
+    let old = cache.join("bun/1.0.0");
+    fs::create_dir_all(&old).unwrap();
+    fs::write(old.join("bun"), [0u8; 100]).unwrap();
+    fs::write(old.join(".lock"), "4242").unwrap();
+    // The current version, mid-download in another build
+    let current = cache.join("bun/1.2.6");
+    fs::create_dir_all(&current).unwrap();
+    let held = fs::File::create(current.join(".lock")).unwrap();
+    held.lock().unwrap();
+    fs::create_dir_all(cache.join("extract")).unwrap();
+    fs::write(cache.join("extract/transpiled"), [0u8; 20]).unwrap();
+
+    let report = clean_cache(CacheKind::Locks).unwrap();
+    assert_eq!(report.removed, vec![old.join(".lock")]);
+    assert_eq!(report.held_locks, vec![current.join(".lock")]);
+    assert!(old.join("bun").exists());
+
+    let report = clean_cache(CacheKind::All).unwrap();
+    assert_eq!(report.removed, vec![old.clone(), cache.join("extract")]);
+    // The lock file holds the pid of whoever took it last, here this test
+    let pid_len = std::process::id().to_string().len() as u64;
+    assert_eq!(report.freed_bytes, 120 + pid_len);
+    assert!(!old.exists());
+    assert!(current.join(".lock").exists());
+
+    // Nothing left to clean isn't an error
+    let report = clean_cache(CacheKind::Extraction).unwrap();
+    assert!(report.removed.is_empty());
+}
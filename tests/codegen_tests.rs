@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::PathBuf;
 
-use convex_typegen::{generate, Configuration};
+use convex_typegen::{generate, Configuration, DuplicateKeys, FeatureGates, FieldOverride, MethodNaming, StringFormat};
 use tempfile::TempDir;
 
 /// Set up a test environment with a schema file and optional function files.
@@ -52,12 +52,31 @@ fn generate_and_read(schema_content: &str, function_files: Option<Vec<(&str, &st
         schema_path,
         out_file: output_path.clone(),
         function_paths,
-        helper_stubs: std::collections::HashMap::new(),
+        ..Default::default()
     };
     generate(config).expect("Code generation failed");
     fs::read_to_string(output_path).expect("Failed to read generated code")
 }
 
+/// Generate code with a customized configuration and return the output string.
+fn generate_and_read_with_config(
+    schema_content: &str,
+    function_files: Option<Vec<(&str, &str)>>,
+    configure: impl FnOnce(&mut Configuration),
+) -> String
+{
+    let (_temp_dir, schema_path, output_path, function_paths) = setup_test_env(schema_content, function_files);
+    let mut config = Configuration {
+        schema_path,
+        out_file: output_path.clone(),
+        function_paths,
+        ..Default::default()
+    };
+    configure(&mut config);
+    generate(config).expect("Code generation failed");
+    fs::read_to_string(output_path).expect("Failed to read generated code")
+}
+
 // =============================================================================
 // Basic types
 // =============================================================================
@@ -378,6 +397,45 @@ fn test_tagged_union()
     assert!(code.contains("delta: f64"), "missing delta field in Scroll");
 }
 
+#[test]
+fn test_tagged_union_kind_accessor()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            events: defineTable({
+                action: v.union(
+                    v.object({ type: v.literal("click"), x: v.number() }),
+                    v.object({ type: v.literal("key_press") }),
+                ),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(code.contains("impl EventsAction {"), "missing accessor impl, got:\n{code}");
+    assert!(
+        code.contains("pub const KINDS: &'static [&'static str] = &[\"click\", \"key_press\"];"),
+        "missing KINDS, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub fn kind(&self) -> &'static str"),
+        "missing kind(), got:\n{code}"
+    );
+    assert!(
+        code.contains("Self::Click { .. } => \"click\","),
+        "struct variant arm should ignore fields, got:\n{code}"
+    );
+    assert!(
+        code.contains("Self::KeyPress => \"key_press\","),
+        "unit variant arm should use the original tag, got:\n{code}"
+    );
+}
+
 #[test]
 fn test_nullable_union()
 {
@@ -453,6 +511,45 @@ fn test_untagged_three_primitives()
     assert!(code.contains("Boolean(bool)"), "missing Boolean variant");
 }
 
+#[test]
+fn test_literals_and_object_union()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            items: defineTable({
+                sync: v.union(
+                    v.literal("none"),
+                    v.object({ kind: v.string(), data: v.number() }),
+                ),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(
+        code.contains("pub enum ItemsSync {\n    None,\n    Config(ItemsSyncConfig),\n}"),
+        "literals should be unit variants beside a Config variant, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct ItemsSyncConfig {"),
+        "missing ItemsSyncConfig, got:\n{code}"
+    );
+    // Untagged unit variants would serialize as null
+    assert!(
+        code.contains("Self::None => serializer.serialize_str(\"none\"),"),
+        "literals should serialize as strings, got:\n{code}"
+    );
+    assert!(
+        code.contains("\"none\" => Ok(Self::None),"),
+        "literals should deserialize from strings, got:\n{code}"
+    );
+}
+
 #[test]
 fn test_untagged_string_and_object()
 {
@@ -1190,6 +1287,69 @@ fn test_function_return_literal_union()
     assert!(code.contains("Skipped"), "missing Skipped variant");
 }
 
+#[test]
+fn test_id_union_enum()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            users: defineTable({ name: v.string() }),
+            bots: defineTable({ name: v.string() }),
+            messages: defineTable({
+                author: v.union(v.id("users"), v.id("bots")),
+                replyTo: v.union(v.id("users"), v.id("bots"), v.null()),
+            }),
+        });
+    "#;
+    let code = generate_and_read(schema, None);
+
+    assert!(
+        code.contains("pub author: MessagesAuthor"),
+        "id union should get its own enum, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub reply_to: Option<MessagesReplyTo>"),
+        "nullable id union should be Option<enum>, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub enum MessagesAuthor {"),
+        "missing MessagesAuthor, got:\n{code}"
+    );
+    assert!(code.contains("    Users(UsersId),"), "missing Users variant, got:\n{code}");
+    assert!(code.contains("    Bots(BotsId),"), "missing Bots variant, got:\n{code}");
+    assert!(code.contains("    Unknown(String),"), "missing Unknown variant, got:\n{code}");
+    assert_eq!(
+        code.matches("pub struct UsersId(pub String);").count(),
+        1,
+        "both unions should share one UsersId, got:\n{code}"
+    );
+    assert!(
+        !code.contains("#[serde(untagged)]"),
+        "id unions should not be untagged string enums, got:\n{code}"
+    );
+    assert!(
+        code.contains("String::deserialize(deserializer).map(Self::Unknown)"),
+        "deserialization should pass the raw id through, got:\n{code}"
+    );
+    assert!(
+        code.contains("(Self::Unknown(id), \"bots\") => Self::Bots(id.into()),"),
+        "resolve should move unknown ids into the named table's variant, got:\n{code}"
+    );
+    assert!(
+        code.contains("Self::Users(_) => Some(\"users\"),"),
+        "table_hint should name the table, got:\n{code}"
+    );
+
+    let code = generate_and_read_with_config(schema, None, |config| config.typed_ids = true);
+    assert!(
+        code.contains("    Users(ConvexId<UsersTable>),"),
+        "typed ids should be used in id unions, got:\n{code}"
+    );
+    assert!(!code.contains("UsersId"), "{code}");
+}
+
 // -----------------------------------------------------------------------------
 // Multiple tables with similar unions (verify distinct enum names)
 // -----------------------------------------------------------------------------
@@ -1226,6 +1386,77 @@ fn test_multiple_tables_same_field_different_union()
     assert!(code.contains("Flagged"), "missing Flagged in CommentsStatus");
 }
 
+// -----------------------------------------------------------------------------
+// Union simplification (nested, duplicate, and single-variant unions)
+// -----------------------------------------------------------------------------
+
+#[test]
+fn test_nested_unions_are_flattened()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        const base = v.union(v.literal("draft"), v.literal("published"));
+
+        export default defineSchema({
+            posts: defineTable({
+                status: v.union(base, v.literal("archived")),
+                note: v.union(v.union(v.string(), v.null()), v.null()),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(code.contains("pub status: PostsStatus"), "got:\n{code}");
+    for variant in ["Draft", "Published", "Archived"] {
+        assert!(
+            code.contains(variant),
+            "missing {variant} in the flattened enum, got:\n{code}"
+        );
+    }
+    assert!(code.contains("Copy"), "the flattened union is all literals, got:\n{code}");
+    assert_eq!(
+        code.matches("pub enum ").count(),
+        1,
+        "no enum for the inner union, got:\n{code}"
+    );
+    assert!(code.contains("pub note: Option<String>"), "got:\n{code}");
+}
+
+#[test]
+fn test_duplicate_and_single_variant_unions_collapse()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            items: defineTable({
+                label: v.union(v.string(), v.string(), v.null()),
+                size: v.union(v.number()),
+                tags: v.array(v.union(v.string(), v.string())),
+                value: v.union(v.number(), v.string(), v.number()),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(code.contains("pub label: Option<String>"), "got:\n{code}");
+    assert!(code.contains("pub size: f64"), "got:\n{code}");
+    assert!(code.contains("pub tags: Vec<String>"), "got:\n{code}");
+    assert!(code.contains("pub enum ItemsValue"), "got:\n{code}");
+    assert_eq!(
+        code.matches("Number(f64)").count(),
+        1,
+        "duplicate variants should be kept once, got:\n{code}"
+    );
+}
+
 // =============================================================================
 // Record type
 // =============================================================================
@@ -1253,6 +1484,142 @@ fn test_record_type()
     );
 }
 
+// =============================================================================
+// Deep container nesting
+// =============================================================================
+
+#[test]
+fn test_deep_container_nesting_in_columns()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            boards: defineTable({
+                grid: v.array(v.array(v.array(v.object({
+                    x: v.number(),
+                    tag: v.union(v.literal("a"), v.literal("b")),
+                })))),
+                layers: v.record(v.string(), v.array(v.union(
+                    v.object({ type: v.literal("line"), width: v.number() }),
+                    v.object({ type: v.literal("dot") }),
+                ))),
+                weights: v.record(
+                    v.union(v.literal("low"), v.literal("high")),
+                    v.array(v.object({ w: v.number() })),
+                ),
+                cells: v.optional(v.array(v.record(v.string(), v.array(v.union(v.string(), v.number()))))),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    // Arrays, records, and optionals are transparent: the innermost named type
+    // takes the field's name, however deep it sits
+    assert!(code.contains("pub grid: Vec<Vec<Vec<BoardsGrid>>>,"), "{code}");
+    assert!(code.contains("pub struct BoardsGrid {"), "{code}");
+    assert!(code.contains("pub tag: BoardsGridTag,"), "{code}");
+    assert!(
+        code.contains("pub layers: std::collections::HashMap<String, Vec<BoardsLayers>>,"),
+        "{code}"
+    );
+    assert!(code.contains("pub enum BoardsLayers {"), "{code}");
+    assert!(
+        code.contains("pub cells: Option<Vec<std::collections::HashMap<String, Vec<BoardsCells>>>>,"),
+        "{code}"
+    );
+    assert!(code.contains("pub enum BoardsCells {"), "{code}");
+    assert!(!code.contains("Element"), "no stuttering element names:\n{code}");
+
+    // A record's key type gets its own name, so it doesn't clash with the value's
+    assert!(
+        code.contains("pub weights: std::collections::HashMap<BoardsWeightsKey, Vec<BoardsWeights>>,"),
+        "{code}"
+    );
+    assert!(
+        code.contains(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\npub enum BoardsWeightsKey {"
+        ),
+        "literal enums must be usable as map keys:\n{code}"
+    );
+    assert!(code.contains("pub struct BoardsWeights {\n    pub w: f64,"), "{code}");
+}
+
+#[test]
+fn test_deep_container_nesting_in_args_and_returns()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({});
+        "#,
+        Some(vec![(
+            r#"
+            import { mutation } from "./_generated/server";
+            import { v } from "convex/values";
+
+            export const paint = mutation({
+                args: {
+                    strokes: v.array(v.array(v.object({
+                        x: v.number(),
+                        color: v.union(v.literal("red"), v.literal("blue")),
+                    }))),
+                    byLayer: v.record(v.string(), v.array(v.array(v.number()))),
+                },
+                returns: v.array(v.record(v.string(), v.array(v.object({ ok: v.boolean() })))),
+                handler: async () => [],
+            });
+            "#,
+            "boards.ts",
+        )]),
+    );
+
+    assert!(code.contains("pub strokes: Vec<Vec<BoardsPaintStrokes>>,"), "{code}");
+    assert!(code.contains("pub color: BoardsPaintStrokesColor,"), "{code}");
+    assert!(code.contains("std::collections::HashMap<String, Vec<Vec<f64>>>,"), "{code}");
+    assert!(
+        code.contains("Result<Vec<std::collections::HashMap<String, Vec<BoardsPaintReturn>>>, ConvexError>"),
+        "{code}"
+    );
+    assert!(code.contains("pub struct BoardsPaintReturn {\n    pub ok: bool,"), "{code}");
+    assert!(
+        code.contains(r#"("strokes", ValueShape::Array(&ValueShape::Array(&ValueShape::Object(&[("x", ValueShape::Float64), ("color", ValueShape::Plain)]))))"#),
+        "{code}"
+    );
+}
+
+#[test]
+fn test_colliding_nested_names_get_distinct_types()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            boards: defineTable({
+                a: v.object({ bC: v.object({ n: v.number() }) }),
+                aB: v.object({ c: v.object({ s: v.string() }) }),
+                table: v.object({ name: v.string() }),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    // `a.bC` and `aB.c` both spell `BoardsABC`
+    assert!(code.contains("pub struct BoardsABC {\n    pub n: f64,"), "{code}");
+    assert!(code.contains("pub struct BoardsABC2 {\n    pub s: String,"), "{code}");
+    assert!(code.contains("pub c: BoardsABC2,"), "{code}");
+    // A nested type never takes a table struct's name
+    assert!(code.contains("pub table: BoardsTable2,"), "{code}");
+    assert_eq!(code.matches("pub struct BoardsTable {").count(), 1, "{code}");
+}
+
 // =============================================================================
 // Special types
 // =============================================================================
@@ -1297,6 +1664,46 @@ fn test_bytes_type()
     assert!(code.contains("pub data: Vec<u8>"), "bytes should be Vec<u8>");
 }
 
+#[test]
+fn test_any_fields_get_typed_accessors()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            widgets: defineTable({
+                settings: v.any(),
+                extra: v.optional(v.any()),
+                layout: v.object({ name: v.string(), payload: v.any() }),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(
+        code.contains(
+            "    pub fn settings_as<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {\n        \
+             T::deserialize(&self.settings)\n    }"
+        ),
+        "got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "    pub fn extra_as<T: serde::de::DeserializeOwned>(&self) -> Result<Option<T>, serde_json::Error> {\n        \
+             self.extra.as_ref().map(T::deserialize).transpose()\n    }"
+        ),
+        "got:\n{code}"
+    );
+    assert!(
+        code.contains("impl WidgetsLayout {\n    /// `payload` deserialized into `T`"),
+        "got:\n{code}"
+    );
+    assert!(!code.contains("fn name_as"), "typed fields need no accessor");
+}
+
 // =============================================================================
 // Schema-level shared validators (cross-file references)
 // =============================================================================
@@ -1512,74 +1919,277 @@ fn test_untyped_query_no_return()
         "untyped query subscribe should return raw QuerySubscription"
     );
     assert!(
-        code.contains("Result<convex::FunctionResult, ConvexError>"),
-        "untyped query should return FunctionResult"
+        code.contains("Result<serde_json::Value, ConvexError>"),
+        "untyped query should return the decoded JSON value"
     );
 }
 
-// =============================================================================
-// Optional args: BTreeMap From impl skips None fields
-// =============================================================================
-
 #[test]
-fn test_optional_args_skip_none_in_btreemap()
+fn test_missing_collection_fields_default()
 {
-    let code = generate_and_read(
-        r#"
+    let schema = r#"
         import { defineSchema, defineTable } from "convex/server";
         import { v } from "convex/values";
-
         export default defineSchema({
-            messages: defineTable({
-                text: v.optional(v.string()),
-                mediaId: v.optional(v.string()),
+            posts: defineTable({
+                title: v.string(),
+                tags: v.array(v.string()),
+                meta: v.object({ votes: v.record(v.string(), v.number()), note: v.optional(v.string()) }),
             }),
         });
-        "#,
-        Some(vec![(
-            r#"
-            import { v } from "convex/values";
-            import { mutation } from "./_generated/server";
+    "#;
+    let code = generate_and_read(schema, None);
 
-            export const upsert = mutation({
-                args: {
-                    chatId: v.string(),
-                    text: v.optional(v.string()),
-                    mediaId: v.optional(v.string()),
-                },
-                returns: v.null(),
-                handler: async (ctx, args) => {},
-            });
-            "#,
-            "messages.ts",
-        )]),
+    assert!(
+        code.contains("    #[serde(default)]\n    pub tags: Vec<String>,"),
+        "arrays should default to empty, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[serde(default)]\n    pub votes: std::collections::HashMap<String, f64>,"),
+        "records in nested objects should default to empty, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[serde(default)]\n    pub note: Option<String>,"),
+        "optional fields should default to None, got:\n{code}"
+    );
+    assert!(
+        !code.contains("#[serde(default)]\n    pub title"),
+        "required scalars must stay required"
+    );
+
+    let strict = generate_and_read_with_config(schema, None, |config| config.default_missing_fields = false);
+    assert!(
+        !strict.contains("#[serde(default)]"),
+        "defaults should be opt-out, got:\n{strict}"
+    );
+}
+
+#[test]
+fn test_display_fields()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string() }),
+            posts: defineTable({ title: v.optional(v.string()), body: v.string() }),
+        });
+        "#,
+        None,
+        |config| {
+            config.display_fields.insert("users".to_string(), "name".to_string());
+            config.display_fields.insert("posts".to_string(), "title".to_string());
+        },
+    );
+
+    assert!(
+        code.contains(
+            "impl std::fmt::Display for UsersTable {\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> \
+             std::fmt::Result {\n        std::fmt::Display::fmt(&self.name, f)\n    }\n}"
+        ),
+        "missing Display for UsersTable, got:\n{code}"
+    );
+    // An unset optional field falls back to the id
+    assert!(
+        code.contains(
+            "        match &self.title {\n            Some(value) => std::fmt::Display::fmt(value, f),\n            None \
+             => std::fmt::Display::fmt(&self.id, f),\n        }"
+        ),
+        "optional display field should fall back to _id, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_collection_helpers()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            players: defineTable({
+                achievements: v.array(v.object({ name: v.string(), points: v.number() })),
+                entries: v.optional(v.array(v.object({ title: v.string() }))),
+                notes: v.array(v.object({ text: v.string() })),
+            }),
+        });
+        "#,
+        None,
+        |config| {
+            config
+                .collection_keys
+                .insert("players.entries".to_string(), "title".to_string());
+        },
+    );
+
+    assert!(
+        code.contains(
+            "    pub fn iter_achievements(&self) -> impl Iterator<Item = &PlayersAchievements> {\n        \
+             self.achievements.iter()\n    }"
+        ),
+        "missing iter_achievements, got:\n{code}"
+    );
+    // `name` is picked up as the key without configuration
+    assert!(
+        code.contains(
+            "    pub fn find_achievement_by_name(&self, name: &str) -> Option<&PlayersAchievements> {\n        \
+             self.achievements.iter().find(|entry| entry.name == name)\n    }"
+        ),
+        "missing find_achievement_by_name, got:\n{code}"
+    );
+    // Optional columns flatten, and configured keys are used
+    assert!(
+        code.contains("        self.entries.iter().flatten().find(|entry| entry.title == title)\n"),
+        "missing find_entry_by_title, got:\n{code}"
+    );
+    // Objects without a key only get the iterator
+    assert!(code.contains("pub fn iter_notes(&self)"), "missing iter_notes, got:\n{code}");
+    assert!(!code.contains("fn find_note_by"), "notes have no key, got:\n{code}");
+}
+
+#[test]
+fn test_enum_labels()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({
+                status: v.union(v.literal("active"), v.literal("in_progress"), v.literal("HTTP")),
+                mode: v.optional(v.union(v.literal("quickMatch"), v.literal("ranked"))),
+            }),
+        });
+    "#;
+    let code = generate_and_read_with_config(schema, None, |config| {
+        config.emit_enum_labels = true;
+        config
+            .enum_labels
+            .insert("games.status.active".to_string(), "In \"play\"".to_string());
+    });
+
+    assert!(
+        code.contains(
+            "    pub const LABELS: &'static [(Self, &'static str)] = &[\n        (Self::Active, \"In \\\"play\\\"\"),\n        (Self::InProgress, \"In progress\"),\n        (Self::HTTP, \"HTTP\"),\n    ];\n"
+        ),
+        "missing GamesStatus::LABELS, got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub fn label(&self) -> &'static str {\n"),
+        "missing label(), got:\n{code}"
+    );
+    // Optional fields label their inner enum
+    assert!(
+        code.contains("            Self::QuickMatch => \"Quick match\",\n"),
+        "got:\n{code}"
     );
 
-    // Required field should use unconditional map.insert
+    let default_code = generate_and_read(schema, None);
+    assert!(!default_code.contains("LABELS"), "labels should only be emitted when enabled");
+}
+
+#[test]
+fn test_fixture_builders_opt_in()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({ winCount: v.number(), note: v.optional(v.string()) }),
+        });
+    "#;
+    let code = generate_and_read_with_config(schema, None, |config| config.emit_fixtures = true);
+
+    assert!(
+        code.contains("pub struct GamesTableFixture {\n    seed: u64,\n"),
+        "missing GamesTableFixture, got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub fn win_count(mut self, value: f64) -> Self {\n        self.win_count = Some(value);\n"),
+        "missing win_count setter, got:\n{code}"
+    );
+    // Optional fields take the value itself
+    assert!(
+        code.contains("    pub fn note(mut self, value: String) -> Self {"),
+        "got:\n{code}"
+    );
+    assert!(code.contains("            document.note = Some(value);\n"), "got:\n{code}");
     assert!(
-        code.contains(r#"map.insert("chatId".to_string()"#),
-        "required field should use unconditional insert"
+        code.contains("convex_typegen::fixtures::sample_document(\"games\", Self::VALIDATOR, self.seed)"),
+        "unset fields should be sampled, got:\n{code}"
     );
 
-    // Optional fields should use `if let Some(val)` to skip None
+    let default_code = generate_and_read(schema, None);
     assert!(
-        code.contains(r#"if let Some(val) = _args.text {"#),
-        "optional text field should use if let Some(val)"
+        !default_code.contains("Fixture"),
+        "fixtures should only be emitted when enabled"
+    );
+}
+
+// =============================================================================
+// Optional args: the serialized struct (and so the BTreeMap) skips None fields
+// =============================================================================
+
+#[test]
+fn test_optional_args_skip_none_in_btreemap()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            messages: defineTable({
+                text: v.optional(v.string()),
+                mediaId: v.optional(v.string()),
+            }),
+        });
+        "#,
+        Some(vec![(
+            r#"
+            import { v } from "convex/values";
+            import { mutation } from "./_generated/server";
+
+            export const upsert = mutation({
+                args: {
+                    chatId: v.string(),
+                    text: v.optional(v.string()),
+                    mediaId: v.optional(v.string()),
+                },
+                returns: v.null(),
+                handler: async (ctx, args) => {},
+            });
+            "#,
+            "messages.ts",
+        )]),
     );
+
+    // Required field is always serialized
     assert!(
-        code.contains(r#"if let Some(val) = _args.mediaId {"#),
-        "optional mediaId field should use if let Some(val)"
+        code.contains("    pub chatId: String,"),
+        "required field should have no skip attribute, got:\n{code}"
     );
+    assert!(!code.contains("Option::is_none\")]\n    pub chatId"));
 
-    // The unconditional pattern should NOT appear for optional fields
+    // Optional fields skip None
+    assert!(
+        code.contains("    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub text: Option<String>,"),
+        "optional text field should skip None, got:\n{code}"
+    );
     assert!(
-        !code.contains(r#"map.insert("text".to_string(), serde_json::to_value(_args.text)"#),
-        "optional text should NOT use unconditional insert"
+        code.contains("    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub mediaId: Option<String>,"),
+        "optional mediaId field should skip None"
     );
+
+    // The BTreeMap is the serialized struct, so both have one wire shape
     assert!(
-        !code.contains(r#"map.insert("mediaId".to_string(), serde_json::to_value(_args.mediaId)"#),
-        "optional mediaId should NOT use unconditional insert"
+        code.contains(
+            "    fn from(args: MessagesUpsertArgs) -> Self {\n        match serde_json::to_value(args) {\n            \
+             Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),"
+        ),
+        "From impl should go through Serialize, got:\n{code}"
     );
+    assert!(!code.contains("map.insert("), "fields shouldn't be inserted one by one");
 }
 
 #[test]
@@ -1618,8 +2228,8 @@ fn test_nullable_union_args_skip_none_in_btreemap()
         "union(string, null) should be Option<String>"
     );
     assert!(
-        code.contains(r#"if let Some(val) = _args.description {"#),
-        "nullable union field should use if let Some(val)"
+        code.contains("    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub description: Option<String>,"),
+        "nullable union field should skip None, got:\n{code}"
     );
 }
 
@@ -1790,14 +2400,10 @@ fn test_function_arg_named_type()
         code.contains("pub r#type: String"),
         "function arg named 'type' should be escaped as r#type, got:\n{code}"
     );
-    // The BTreeMap From impl should use r#type for field access but "type" for the key string
-    assert!(
-        code.contains("_args.r#type"),
-        "From impl should access field as _args.r#type, got:\n{code}"
-    );
+    // serde strips the `r#` prefix, so the key is "type" without a rename
     assert!(
-        code.contains("\"type\""),
-        "From impl should use \"type\" as the map key string, got:\n{code}"
+        !code.contains("rename = \"type\""),
+        "r#type shouldn't need a rename, got:\n{code}"
     );
 }
 
@@ -1829,3 +2435,2107 @@ fn test_inline_object_field_keyword()
         "nested object field 'ref' should be escaped as r#ref, got:\n{code}"
     );
 }
+
+// =============================================================================
+// Content equality (ignores system fields)
+// =============================================================================
+
+#[test]
+fn test_table_content_eq()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({
+                winCount: v.number(),
+                meta: v.object({ label: v.string() }),
+                cleared: v.null(),
+                scores: v.record(v.string(), v.number()),
+            }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(
+        code.contains("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct GamesTable"),
+        "table struct should derive PartialEq, got:\n{code}"
+    );
+    assert!(
+        code.contains("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct GamesMeta"),
+        "nested struct should derive PartialEq so content_eq can compare it, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub fn content_eq(&self, other: &Self) -> bool"),
+        "missing content_eq, got:\n{code}"
+    );
+    assert!(
+        code.contains("self.win_count == other.win_count"),
+        "content_eq should compare user fields, got:\n{code}"
+    );
+    assert!(
+        !code.contains("self.id == other.id"),
+        "content_eq should ignore _id, got:\n{code}"
+    );
+    assert!(
+        !code.contains("self.creation_time == other.creation_time"),
+        "content_eq should ignore _creationTime, got:\n{code}"
+    );
+    assert!(
+        !code.contains("self.cleared == other.cleared"),
+        "unit fields are always equal and comparing them trips clippy::unit_cmp, got:\n{code}"
+    );
+    assert!(code.contains("pub fn content_hash(&self) -> u64"), "missing content_hash");
+    assert!(
+        code.contains("        content_digest(&value)\n"),
+        "content_hash should go through the stable digest, got:\n{code}"
+    );
+    assert_eq!(
+        code.matches("fn content_digest(").count(),
+        1,
+        "the digest is shared by every table"
+    );
+    assert!(
+        code.contains("entries.sort_by(|a, b| a.0.cmp(b.0));"),
+        "keys should be sorted before hashing"
+    );
+    assert!(
+        !code.contains("DefaultHasher"),
+        "DefaultHasher isn't stable across Rust releases"
+    );
+    assert!(
+        !code.contains("unwrap_or_default()"),
+        "serialization failures shouldn't share a hash"
+    );
+}
+
+#[test]
+fn test_schema_fingerprint_and_field_counts()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string(), age: v.number() }).index("by_name", ["name"]),
+            logs: defineTable({}),
+        });
+    "#;
+    let fingerprint = |code: &str| {
+        let start = code
+            .find("pub const CONVEX_SCHEMA_FINGERPRINT: &str = \"")
+            .unwrap_or_else(|| panic!("missing CONVEX_SCHEMA_FINGERPRINT, got:\n{code}"));
+        let value = &code[start + "pub const CONVEX_SCHEMA_FINGERPRINT: &str = \"".len()..];
+        value[..value.find('"').unwrap()].to_string()
+    };
+
+    let code = generate_and_read(schema, None);
+    let first = fingerprint(&code);
+    assert_eq!(first.len(), 16, "fingerprint should be 16 hex digits, got {first}");
+    assert!(
+        first.chars().all(|c| c.is_ascii_hexdigit()),
+        "fingerprint should be hex, got {first}"
+    );
+    assert_eq!(
+        fingerprint(&generate_and_read(schema, None)),
+        first,
+        "same schema should give the same fingerprint"
+    );
+    assert_ne!(
+        fingerprint(&generate_and_read(&schema.replace("age: v.number()", "age: v.int64()"), None)),
+        first,
+        "changing a field's validator should change the fingerprint"
+    );
+    assert_ne!(
+        fingerprint(&generate_and_read(&schema.replace("\"by_name\"", "\"by_user_name\""), None)),
+        first,
+        "changing an index should change the fingerprint"
+    );
+
+    assert!(
+        code.contains("pub const FIELD_COUNT: usize = 2;"),
+        "UsersTable should count its two fields, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub const FIELD_COUNT: usize = 0;"),
+        "empty table should count zero fields, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Shared subscriptions (fan-out)
+// =============================================================================
+
+const TYPED_QUERY_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({
+        items: defineTable({ name: v.string() }),
+    });
+"#;
+
+const TYPED_QUERY_FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { query } from "./_generated/server";
+    export const names = query({
+        args: {},
+        returns: v.array(v.string()),
+        handler: async (ctx) => [],
+    });
+"#;
+
+#[test]
+fn test_typed_subscription_exposes_query_identity()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+
+    assert!(
+        code.contains("pub struct QueryToken(String);"),
+        "missing QueryToken, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "        let token = QueryToken::new(\"items:names\", &args);\n        let sub = \
+             self.inner.clone().subscribe(\"items:names\", args).await"
+        ),
+        "subscribe should record the query token, got:\n{code}"
+    );
+    assert!(code.contains("Ok(TypedSubscription::new(sub).with_query_token(token).with_function(\"items:names\"))"));
+    assert!(code.contains("pub fn subscriber_id(&self) -> &convex::SubscriberId"));
+}
+
+#[test]
+fn test_snapshot_subscription_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_snapshot_subscriptions = true,
+    );
+
+    assert!(
+        code.contains("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct Snapshot<T> {"),
+        "snapshots should serialize, got:\n{code}"
+    );
+    assert!(code.contains("pub received_at: std::time::SystemTime,\n}"), "got:\n{code}");
+    assert!(code.contains("pub fn into_snapshots(self) -> SnapshotSubscription<T>"));
+    assert!(code.contains("type Item = Result<Snapshot<T>, ConvexError>;"));
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("Snapshot"),
+        "snapshots should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_buffered_subscription_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_buffered_subscriptions = true,
+    );
+
+    assert!(
+        code.contains("pub fn buffered(self, buffer: SubscriptionBuffer) -> BufferedSubscription<T>"),
+        "missing TypedSubscription::buffered, got:\n{code}"
+    );
+    assert!(code.contains("    KeepLatestOnly,\n"), "missing conflation, got:\n{code}");
+    assert!(
+        code.contains("pub fn dropped(&self) -> u64"),
+        "missing drop counter, got:\n{code}"
+    );
+    // Results are decoded when taken, so dropped ones are never decoded
+    assert!(
+        code.contains("Some(result) => std::task::Poll::Ready(Some(decode_function_result(result, self.function))),"),
+        "buffered results should be decoded on read, got:\n{code}"
+    );
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("BufferedSubscription"),
+        "should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_resilient_subscription_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_resilient_subscriptions = true,
+    );
+
+    assert!(
+        code.contains(
+            "pub fn resilient_subscribe<T, F, Fut>(make_sub: F, backoff: SubscriptionBackoff) -> ResilientSubscription<T, \
+             F>"
+        ),
+        "missing resilient_subscribe, got:\n{code}"
+    );
+    assert!(code.contains("pub async fn next_event(&mut self) -> SubscriptionEvent<T> {"));
+    // Errors the server would repeat don't cause a resubscribe
+    assert!(
+        code.contains("Some(Err(error)) if !error.is_retryable() => return SubscriptionEvent::Error(error),"),
+        "got:\n{code}"
+    );
+    assert!(code.contains("Reconnecting { attempt: u32, retry_in: std::time::Duration, error: Option<ConvexError> },"));
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("resilient_subscribe"),
+        "should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_shared_subscription_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_shared_subscriptions = true,
+    );
+
+    assert!(
+        code.contains("pub struct SharedSubscription<T>"),
+        "missing SharedSubscription, got:\n{code}"
+    );
+    assert!(
+        code.contains("tokio::sync::watch::Receiver"),
+        "SharedSubscription should be backed by a watch channel"
+    );
+    assert!(
+        code.contains("pub fn shared(self) -> SharedSubscription<T>"),
+        "TypedSubscription should expose shared()"
+    );
+}
+
+#[test]
+fn test_shared_subscription_disabled_by_default()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+
+    assert!(code.contains("pub struct TypedSubscription<T>"), "missing TypedSubscription");
+    assert!(
+        !code.contains("SharedSubscription"),
+        "SharedSubscription should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_arc_subscription_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_arc_subscriptions = true,
+    );
+
+    assert!(
+        code.contains("pub struct ArcSubscription<T>"),
+        "missing ArcSubscription, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub fn into_arc(self) -> ArcSubscription<T>"),
+        "TypedSubscription should expose into_arc()"
+    );
+    assert!(
+        code.contains("type Item = Result<std::sync::Arc<T>, ConvexError>;"),
+        "ArcSubscription should yield Arc snapshots"
+    );
+    assert!(
+        code.contains("pub fn map_arc<U, F>(self, f: F) -> MapArc<T, F>"),
+        "missing map_arc"
+    );
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("ArcSubscription"),
+        "ArcSubscription should only be emitted when enabled"
+    );
+}
+
+// =============================================================================
+// Pagination cursors
+// =============================================================================
+
+const PAGINATED_FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { paginationOptsValidator } from "convex/server";
+    import { query } from "./_generated/server";
+    export const list = query({
+        args: { paginationOpts: paginationOptsValidator },
+        handler: async (ctx, args) => ({ page: [], isDone: true, continueCursor: "" }),
+    });
+"#;
+
+#[test]
+fn test_paginated_query_gets_cursor_newtype()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(PAGINATED_FUNCTIONS, "messages.ts")]));
+
+    assert!(
+        code.contains("pub struct MessagesListCursor(String);"),
+        "missing cursor newtype, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl PaginationCursor for MessagesListCursor"),
+        "missing PaginationCursor impl"
+    );
+    assert!(
+        code.contains("const QUERY: &'static str = \"messages:list\";"),
+        "cursor should know its query"
+    );
+    assert!(
+        code.contains("pub fn with_cursor(mut self, cursor: Option<&MessagesListCursor>) -> Self"),
+        "args should take a typed cursor"
+    );
+    assert!(
+        !code.contains("pub trait CursorStore"),
+        "CursorStore should only be emitted when enabled"
+    );
+
+    let store_code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(PAGINATED_FUNCTIONS, "messages.ts")]),
+        |config| config.emit_cursor_store = true,
+    );
+    assert!(store_code.contains("pub trait CursorStore"), "missing CursorStore");
+    assert!(
+        store_code.contains("impl CursorStore for std::collections::HashMap<String, String>"),
+        "missing in-memory CursorStore"
+    );
+}
+
+// =============================================================================
+// Frontend adapters (Leptos / Dioxus)
+// =============================================================================
+
+#[test]
+fn test_frontend_adapters_are_feature_gated()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_frontend_adapters = true,
+    );
+
+    assert!(
+        code.contains("#[cfg(feature = \"leptos\")]"),
+        "Leptos adapter should be gated behind the leptos feature, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub fn into_leptos_signal(mut self) -> leptos::prelude::Signal<Option<T>>"),
+        "missing into_leptos_signal"
+    );
+    assert!(
+        code.contains("#[cfg(feature = \"dioxus\")]"),
+        "Dioxus hook should be gated behind the dioxus feature"
+    );
+    assert!(
+        code.contains("pub fn use_typed_subscription<T, F, Fut>"),
+        "missing use_typed_subscription"
+    );
+    assert!(
+        code.contains("pub async fn next_result(&mut self)"),
+        "adapters rely on TypedSubscription::next_result"
+    );
+}
+
+#[test]
+fn test_frontend_adapters_disabled_by_default()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+
+    assert!(!code.contains("leptos"), "Leptos adapter should only be emitted when enabled");
+    assert!(!code.contains("dioxus"), "Dioxus hook should only be emitted when enabled");
+}
+
+// =============================================================================
+// Tauri command wrappers
+// =============================================================================
+
+#[test]
+fn test_tauri_commands()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({ winCount: v.number() }),
+        });
+        "#,
+        Some(vec![(
+            r#"
+            import { v } from "convex/values";
+            import { query, mutation } from "./_generated/server";
+            export const count = query({
+                args: {},
+                returns: v.number(),
+                handler: async (ctx) => 0,
+            });
+            export const win = mutation({
+                args: { by: v.number() },
+                handler: async (ctx, args) => {},
+            });
+            "#,
+            "games.ts",
+        )]),
+        |config| config.emit_tauri_commands = true,
+    );
+
+    assert!(
+        code.contains("pub mod tauri_commands"),
+        "missing tauri_commands module, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub async fn query_games_count(api: tauri::State<'_, ConvexApiClient>) -> Result<f64, String>"),
+        "typed query command should return the typed value, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "pub async fn games_win(api: tauri::State<'_, ConvexApiClient>, args: GamesWinArgs) -> \
+             Result<serde_json::Value, String>"
+        ),
+        "untyped mutation command should take typed args and return JSON, got:\n{code}"
+    );
+    assert!(
+        code.contains("fn convex_value_to_json"),
+        "untyped commands need the convex_value_to_json helper"
+    );
+}
+
+// =============================================================================
+// Axum router
+// =============================================================================
+
+#[test]
+fn test_axum_router()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({ winCount: v.number() }),
+        });
+        "#,
+        Some(vec![(
+            r#"
+            import { v } from "convex/values";
+            import { query, mutation } from "./_generated/server";
+            export const count = query({
+                args: {},
+                returns: v.number(),
+                handler: async (ctx) => 0,
+            });
+            export const winGame = mutation({
+                args: { by: v.number() },
+                handler: async (ctx, args) => {},
+            });
+            "#,
+            "games.ts",
+        )]),
+        |config| config.emit_axum_router = true,
+    );
+
+    assert!(
+        code.contains("pub fn convex_router(client: ConvexApiClient) -> axum::Router"),
+        "missing convex_router, got:\n{code}"
+    );
+    assert!(
+        code.contains(".route(\"/api/games/count\", axum::routing::post(axum_handlers::query_games_count))"),
+        "query should be routed under /api/{{file}}/{{fn}}, got:\n{code}"
+    );
+    assert!(
+        code.contains(".route(\"/api/games/winGame\", axum::routing::post(axum_handlers::games_win_game))"),
+        "mutation should be routed under /api/{{file}}/{{fn}}, got:\n{code}"
+    );
+    assert!(
+        code.contains("axum::Json(args): axum::Json<GamesWinGameArgs>"),
+        "handler should deserialize the typed args struct, got:\n{code}"
+    );
+    assert!(
+        code.contains("Result<axum::Json<f64>, ConvexHttpError>"),
+        "typed return should be serialized directly, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl axum::response::IntoResponse for ConvexHttpError"),
+        "missing ConvexHttpError response mapping"
+    );
+}
+
+// =============================================================================
+// Timeouts and cancellation
+// =============================================================================
+
+#[test]
+fn test_timeout_variants_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_timeouts = true,
+    );
+
+    assert!(
+        code.contains(
+            "pub async fn query_items_names_with_timeout(&self, timeout: std::time::Duration) -> Result<Vec<String>, \
+             ConvexError>"
+        ),
+        "missing timeout variant, got:\n{code}"
+    );
+    assert!(
+        code.contains("Err(_) => Err(ConvexError::Timeout(timeout)),"),
+        "elapsed deadlines should map to ConvexError::Timeout"
+    );
+    assert!(code.contains("/// # Cancellation"), "ConvexApi should document cancel-safety");
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        default_code.contains("Timeout(std::time::Duration),"),
+        "ConvexError should always have a Timeout variant"
+    );
+    assert!(
+        !default_code.contains("_with_timeout"),
+        "timeout variants should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_function_timeouts_set_method_defaults()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { mutation, query } from "./_generated/server";
+        // typegen:timeout 5s
+        export const names = query({
+            args: {},
+            returns: v.array(v.string()),
+            handler: async (ctx) => [],
+        });
+        export const rename = mutation({
+            args: { name: v.string() },
+            handler: async (ctx, args) => null,
+        });
+    "#;
+    let code = generate_and_read_with_config(TYPED_QUERY_SCHEMA, Some(vec![(functions, "items.ts")]), |config| {
+        config.emit_timeouts = true;
+        config
+            .function_timeouts
+            .insert("items:rename".to_string(), "250ms".to_string());
+    });
+
+    assert!(
+        code.contains("pub const QUERY_ITEMS_NAMES_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(5000);"),
+        "the directive should set the default, got:\n{code}"
+    );
+    assert!(
+        code.contains("        self.query_items_names_with_timeout(Self::QUERY_ITEMS_NAMES_TIMEOUT).await\n"),
+        "the ConvexApi method should run with the default, got:\n{code}"
+    );
+    assert!(
+        code.contains("        self.items_rename_with_timeout(args, Self::ITEMS_RENAME_TIMEOUT).await\n"),
+        "the config should set the default, got:\n{code}"
+    );
+    assert_eq!(
+        code.matches("pub async fn query_items_names_with_timeout(").count(),
+        1,
+        "emit_timeouts shouldn't add a second variant, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub async fn subscribe_items_names_with_timeout("),
+        "subscriptions keep their emit_timeouts variant, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_function_priorities()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { mutation, query } from "./_generated/server";
+        // typegen:priority high
+        export const names = query({
+            args: {},
+            returns: v.array(v.string()),
+            handler: async (ctx) => [],
+        });
+        // typegen:priority high
+        export const rename = mutation({
+            args: { name: v.string() },
+            handler: async (ctx, args) => null,
+        });
+    "#;
+    let code = generate_and_read_with_config(TYPED_QUERY_SCHEMA, Some(vec![(functions, "items.ts")]), |config| {
+        config
+            .function_priorities
+            .insert("items:rename".to_string(), "low".to_string());
+    });
+
+    assert!(
+        code.contains("pub enum CallPriority {\n    Low,\n    #[default]\n    Normal,\n    High,\n}"),
+        "got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub const QUERY_ITEMS_NAMES_PRIORITY: CallPriority = CallPriority::High;\n"),
+        "the directive should set the priority, got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub const ITEMS_RENAME_PRIORITY: CallPriority = CallPriority::Low;\n"),
+        "the config should take precedence over the directive, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "        match path {\n            \"items:names\" => CallPriority::High,\n            \"items:rename\" => \
+             CallPriority::Low,\n            _ => CallPriority::Normal,\n        }"
+        ),
+        "priority_of should cover every function with a priority, got:\n{code}"
+    );
+
+    let code = generate_and_read(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(functions.replace("// typegen:priority high", "").as_str(), "items.ts")]),
+    );
+    assert!(!code.contains("CallPriority"), "nothing should be emitted without priorities");
+}
+
+#[test]
+fn test_queries_have_borrowed_args_variants()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { mutation, query } from "./_generated/server";
+        export const search = query({
+            args: { tags: v.array(v.string()) },
+            returns: v.array(v.string()),
+            handler: async (ctx, args) => [],
+        });
+        export const names = query({
+            args: {},
+            returns: v.array(v.string()),
+            handler: async (ctx) => [],
+        });
+        export const rename = mutation({
+            args: { name: v.string() },
+            handler: async (ctx, args) => null,
+        });
+    "#;
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(functions, "items.ts")]));
+
+    assert!(
+        code.contains("impl From<&ItemsSearchArgs> for std::collections::BTreeMap<String, serde_json::Value> {"),
+        "args should convert by reference, got:\n{code}"
+    );
+    assert!(code.contains(
+        "    async fn query_items_search_ref(&self, args: &ItemsSearchArgs) -> Result<Vec<String>, ConvexError> {"
+    ));
+    assert!(code.contains(
+        "    async fn subscribe_items_search_ref(&self, args: &ItemsSearchArgs) -> Result<TypedSubscription<Vec<String>>, \
+         ConvexError> {"
+    ));
+    assert!(
+        !code.contains("fn query_items_names_ref("),
+        "queries without args need no variant"
+    );
+    assert!(!code.contains("fn items_rename_ref("), "only queries get a variant");
+}
+
+#[test]
+fn test_admin_client_opt_in()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_admin_client = true,
+    );
+
+    assert!(
+        code.contains("pub struct ConvexAdminClient {"),
+        "missing ConvexAdminClient, got:\n{code}"
+    );
+    assert!(code.contains(
+        "pub async fn with_admin_key(deployment_url: &str, admin_key: impl Into<String>) -> Result<Self, ConvexError>"
+    ));
+    assert!(code.contains("client.set_admin_auth(admin_key.into(), None).await;"));
+    assert!(code.contains("pub async fn table_size<T: ConvexDocument>(&self) -> Result<u64, ConvexError>"));
+    assert!(
+        code.contains("        for table in [\"items\"] {\n"),
+        "document_counts should cover the schema's tables, got:\n{code}"
+    );
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("ConvexAdminClient"),
+        "the admin client should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_consistent_session_opt_in()
+{
+    let code = generate_and_read_with_config(
+        GATED_SCHEMA,
+        Some(vec![(ENCODING_FUNCTIONS, "notes.ts"), (TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_consistent_reads = true,
+    );
+
+    assert!(
+        code.contains("pub struct ConsistentSession {"),
+        "missing ConsistentSession, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub async fn query_items_names(&mut self) -> Result<ConsistentQuery<Vec<String>>, ConvexError>"),
+        "queries should get a session method, got:\n{code}"
+    );
+    assert!(code.contains("self.add(\"items:names\", args).await"));
+    assert!(!code.contains("ConsistentQuery<f64>"), "only queries belong in a session");
+    assert!(code.contains("pub fn consistent_session(&self) -> ConsistentSession"));
+
+    let default_code = generate_and_read(GATED_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("ConsistentSession"),
+        "sessions should only be emitted when enabled"
+    );
+}
+
+#[test]
+fn test_offline_queue_opt_in()
+{
+    let code = generate_and_read_with_config(
+        GATED_SCHEMA,
+        Some(vec![(ENCODING_FUNCTIONS, "notes.ts"), (TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_offline_queue = true,
+    );
+
+    assert!(
+        code.contains("pub struct OfflineQueue {"),
+        "missing OfflineQueue, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl QueuedMutation for NotesUploadArgs {\n    const PATH: &'static str = Self::FUNCTION_PATH;\n}"),
+        "mutation args should be queueable, got:\n{code}"
+    );
+    assert!(!code.contains("QueuedMutation for ItemsNames"), "queries can't be queued");
+    let push = &code[code.find("pub fn push<M: QueuedMutation>").expect("missing push")..];
+    let push = &push[..push.find("\n    }\n").expect("unterminated push")];
+    assert!(
+        push.contains("std::io::ErrorKind::InvalidData") && !push.contains("panic!("),
+        "a bad args shape should be an error, not a panic, got:\n{push}"
+    );
+    // Replayed args are encoded like a direct call
+    assert!(
+        code.contains("        \"notes:upload\" => &[(\"size\", ValueShape::Float64), (\"version\", ValueShape::Int64),"),
+        "replay should encode args by their validators, got:\n{code}"
+    );
+
+    let default_code = generate_and_read(GATED_SCHEMA, Some(vec![(ENCODING_FUNCTIONS, "notes.ts")]));
+    assert!(
+        !default_code.contains("OfflineQueue"),
+        "the queue should only be emitted when enabled"
+    );
+}
+
+// =============================================================================
+// Tenant router
+// =============================================================================
+
+#[test]
+fn test_tenant_router_forwards_with_tenant_key()
+{
+    let code = generate_and_read_with_config(
+        TYPED_QUERY_SCHEMA,
+        Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]),
+        |config| config.emit_tenant_router = true,
+    );
+
+    assert!(
+        code.contains("pub struct ConvexApiRouter<K>"),
+        "missing ConvexApiRouter, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub async fn query_items_names(&self, tenant: &K) -> Result<Vec<String>, ConvexError>"),
+        "router should forward queries with a tenant key, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub async fn subscribe_items_names(&self, tenant: &K)"),
+        "router should forward subscriptions"
+    );
+    assert!(
+        code.contains("pub async fn evict(&self, tenant: &K) -> bool"),
+        "missing evict"
+    );
+
+    let default_code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+    assert!(
+        !default_code.contains("ConvexApiRouter"),
+        "ConvexApiRouter should only be emitted when enabled"
+    );
+}
+
+// =============================================================================
+// Field overrides
+// =============================================================================
+
+#[test]
+fn test_field_overrides_emit_serde_with()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            users: defineTable({
+                legacyId: v.string(),
+                balance: v.optional(v.number()),
+                reactions: v.record(v.string(), v.number()),
+                name: v.string(),
+            }),
+        });
+        "#,
+        None,
+        |config| {
+            config.field_overrides.insert(
+                "users.legacyId".to_string(),
+                FieldOverride {
+                    string_or_number: true,
+                    ..Default::default()
+                },
+            );
+            config.field_overrides.insert(
+                "users.balance".to_string(),
+                FieldOverride {
+                    string_or_number: true,
+                    ..Default::default()
+                },
+            );
+            config.field_overrides.insert(
+                "users.reactions".to_string(),
+                FieldOverride {
+                    duplicate_keys: DuplicateKeys::Reject,
+                    default_on_error: true,
+                    ..Default::default()
+                },
+            );
+        },
+    );
+
+    assert!(
+        code.contains(
+            "#[serde_with::serde_as]\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct UsersTable"
+        ),
+        "serde_as must precede the derives, got:\n{code}"
+    );
+    assert!(
+        code.contains("#[serde_as(as = \"StringOrNumber\")]\n    pub legacy_id: String"),
+        "id-like string should accept numbers, got:\n{code}"
+    );
+    assert!(code.contains("pub struct StringOrNumber;"), "missing adapter, got:\n{code}");
+    assert!(
+        code.contains("#[serde_as(as = \"Option<serde_with::PickFirst<(_, serde_with::DisplayFromStr)>>\")]"),
+        "optional number should accept numeric strings, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "#[serde_as(as = \"serde_with::DefaultOnError<serde_with::MapPreventDuplicates<_, _>>\")]\n    \
+             #[serde(default)]\n    pub reactions:"
+        ),
+        "record should reject duplicates and default on error, got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub name: String,"),
+        "fields without overrides stay untouched, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_field_overrides_absent_by_default()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            users: defineTable({ legacyId: v.string() }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(
+        !code.contains("serde_as"),
+        "no overrides should mean no serde_with, got:\n{code}"
+    );
+    assert!(!code.contains("StringOrNumber"), "adapter should only be emitted when used");
+}
+
+#[test]
+fn test_raw_json_field_override()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            docs: defineTable({
+                title: v.string(),
+                body: v.array(v.object({ text: v.string() })),
+                draft: v.optional(v.array(v.string())),
+            }),
+        });
+        "#,
+        None,
+        |config| {
+            for field in ["docs.body", "docs.draft"] {
+                config.field_overrides.insert(
+                    field.to_string(),
+                    FieldOverride {
+                        raw_json: true,
+                        default_on_error: true,
+                        ..Default::default()
+                    },
+                );
+            }
+        },
+    );
+
+    assert!(code.contains("pub body: RawJson<Vec<DocsBody>>,"), "got:\n{code}");
+    assert!(
+        code.contains("pub draft: Option<RawJson<Vec<String>>>,"),
+        "the Option should stay outermost, got:\n{code}"
+    );
+    assert!(code.contains("pub struct RawJson<T> {"), "missing RawJson, got:\n{code}");
+    assert!(
+        code.contains("pub struct DocsBody {"),
+        "the parsed type should still be generated"
+    );
+    assert!(
+        !code.contains("serde_as"),
+        "raw fields ignore the other settings, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_string_format_types_iso_dates()
+{
+    let code = generate_and_read_with_config(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            events: defineTable({
+                // typegen:format date-time
+                startsAt: v.string(),
+                dates: v.optional(v.array(v.string())),
+                title: v.string(),
+                meta: v.object({
+                    // typegen:format date-time
+                    note: v.string(),
+                }),
+            }),
+        });
+        "#,
+        None,
+        |config| {
+            config.field_overrides.insert(
+                "events.dates".to_string(),
+                FieldOverride {
+                    format: Some(StringFormat::Date),
+                    ..Default::default()
+                },
+            );
+        },
+    );
+
+    assert!(
+        code.contains("pub starts_at: chrono::DateTime<chrono::Utc>,"),
+        "the directive should type the field, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub dates: Option<Vec<chrono::NaiveDate>>,"),
+        "the config should type the field, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub title: String,"),
+        "other strings stay untouched, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub note: String,"),
+        "nested fields aren't table fields, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Shared types across functions
+// =============================================================================
+
+#[test]
+fn test_identical_function_shapes_share_one_type()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export const status = v.union(v.literal("open"), v.literal("closed"));
+
+        export default defineSchema({
+            games: defineTable({ name: v.string(), status }),
+        });
+        "#,
+        Some(vec![(
+            r#"
+            import { v } from "convex/values";
+            import { query } from "./_generated/server";
+
+            const summary = v.object({ name: v.string(), score: v.number() });
+
+            export const recent = query({ args: {}, returns: v.array(summary), handler: async () => [] });
+            export const top = query({
+                args: { status: v.union(v.literal("open"), v.literal("closed")) },
+                returns: v.array(summary),
+                handler: async () => [],
+            });
+            "#,
+            "games.ts",
+        )]),
+    );
+
+    assert_eq!(
+        code.matches("pub struct GamesRecentReturn").count(),
+        1,
+        "first occurrence should name the shared struct, got:\n{code}"
+    );
+    assert!(
+        !code.contains("pub struct GamesTopReturn"),
+        "identical return shape should not be regenerated, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub type GamesTopReturn = GamesRecentReturn;"),
+        "per-function name should remain as an alias, got:\n{code}"
+    );
+    assert!(
+        code.contains("Result<Vec<GamesRecentReturn>, ConvexError>"),
+        "both queries should return the shared type, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub type GamesTopStatus = GamesStatus;"),
+        "arg union identical to a schema field should reuse the schema enum, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_shared_type_keeps_nested_per_function_names()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            players: defineTable({ name: v.string() }),
+        });
+        "#,
+        Some(vec![(
+            r#"
+            import { v } from "convex/values";
+            import { query } from "./_generated/server";
+
+            const profile = v.object({
+                bio: v.string(),
+                settings: v.optional(v.object({ theme: v.string() })),
+            });
+
+            export const mine = query({ args: {}, returns: profile, handler: async () => null });
+            export const theirs = query({ args: {}, returns: profile, handler: async () => null });
+            "#,
+            "players.ts",
+        )]),
+    );
+
+    assert!(
+        code.contains("pub type PlayersTheirsReturn = PlayersMineReturn;"),
+        "per-function name should remain as an alias, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub type PlayersTheirsReturnSettings = PlayersMineReturnSettings;"),
+        "nested per-function name should remain as an alias, got:\n{code}"
+    );
+    assert!(
+        !code.contains("pub struct PlayersTheirsReturnSettings"),
+        "nested struct should not be regenerated, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_identical_table_fields_keep_own_types()
+{
+    let code = generate_and_read(
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+
+        export default defineSchema({
+            posts: defineTable({ status: v.union(v.literal("draft"), v.literal("live")) }),
+            pages: defineTable({ status: v.union(v.literal("draft"), v.literal("live")) }),
+        });
+        "#,
+        None,
+    );
+
+    assert!(code.contains("pub enum PostsStatus"), "missing PostsStatus, got:\n{code}");
+    assert!(code.contains("pub enum PagesStatus"), "missing PagesStatus, got:\n{code}");
+}
+
+// =============================================================================
+// Feature gates
+// =============================================================================
+
+const GATED_FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { query, mutation, internalMutation } from "./_generated/server";
+    export const list = query({ args: {}, returns: v.array(v.string()), handler: async () => [] });
+    export const save = mutation({ args: { body: v.string() }, handler: async () => null });
+    export const sweep = internalMutation({ args: {}, handler: async () => null });
+"#;
+
+const GATED_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({ notes: defineTable({ body: v.string() }) });
+"#;
+
+#[test]
+fn test_feature_gates_wrap_each_kind()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]), |config| {
+        config.emit_axum_router = true;
+        config.feature_gates = Some(FeatureGates {
+            mutations: Some("writes".to_string()),
+            ..Default::default()
+        });
+    });
+
+    assert!(
+        code.contains(
+            "#[cfg(feature = \"writes\")]\n#[derive(Debug, Clone, Serialize, Deserialize)]\n#[allow(non_snake_case)]\npub \
+             struct NotesSaveArgs"
+        ),
+        "mutation args should use the configured feature, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "#[cfg(feature = \"convex-internal\")]\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct \
+             NotesSweepArgs"
+        ),
+        "internal args should be gated, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[cfg(feature = \"convex-queries\")]\n    #[must_use]\n    fn query_notes_list(&self)"),
+        "trait method should be gated, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[cfg(feature = \"convex-queries\")]\n    async fn query_notes_list(&self)"),
+        "impl method should be gated, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[cfg(feature = \"writes\")]\n    let router = router.route(\"/api/notes/save\""),
+        "routes should rebind the router under cfg, got:\n{code}"
+    );
+    assert!(
+        code.contains("#[allow(dead_code)]\nfn json_to_convex_value"),
+        "helpers may be unused in some feature combinations, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_feature_gates_none_leaves_kind_ungated()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]), |config| {
+        config.feature_gates = Some(FeatureGates {
+            queries: None,
+            ..Default::default()
+        });
+    });
+
+    assert!(!code.contains("convex-queries"), "queries should not be gated, got:\n{code}");
+    assert!(
+        code.contains("#[cfg(feature = \"convex-mutations\")]"),
+        "other kinds keep their default features, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_no_feature_gates_by_default()
+{
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]));
+
+    assert!(
+        !code.contains("#[cfg(feature"),
+        "nothing should be gated by default, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Doc examples
+// =============================================================================
+
+const DOC_FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { query, mutation } from "./_generated/server";
+    export const list = query({ args: { limit: v.number() }, returns: v.array(v.string()), handler: async () => [] });
+    export const save = mutation({ args: { body: v.string(), pinned: v.optional(v.boolean()) }, handler: async () => null });
+"#;
+
+#[test]
+fn test_doc_examples_use_generated_names()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(DOC_FUNCTIONS, "notes.ts")]), |config| {
+        config.doc_examples_module = Some("my_app::convex_types".to_string());
+    });
+
+    assert!(
+        code.contains("/// ```no_run\n/// # use my_app::convex_types::*;\n"),
+        "examples should import the generated module, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// # async fn example(api: ConvexApiClient, args: NotesListArgs) -> Result<(), ConvexError> {"),
+        "examples should run in a hidden wrapper fn, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// let result = api.query_notes_list(args).await?;"),
+        "missing query example, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// let mut subscription = api.subscribe_notes_list(args).await?;"),
+        "missing subscription example, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// let args = NotesSaveArgs { pinned: None, ..args };\n/// api.notes_save(args).await?;"),
+        "mutation example should show optional args, got:\n{code}"
+    );
+    assert!(
+        code.contains("/// ```\n#[allow(unused)]\npub trait ConvexApi"),
+        "examples should document the trait, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_no_doc_examples_by_default()
+{
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(DOC_FUNCTIONS, "notes.ts")]));
+
+    assert!(!code.contains("```no_run"), "examples should be opt-in, got:\n{code}");
+}
+
+// =============================================================================
+// Schema options
+// =============================================================================
+
+#[test]
+fn test_schema_validation_off_marks_tables_advisory()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({ notes: defineTable({ body: v.string() }) }, { schemaValidation: false });
+    "#;
+
+    let code = generate_and_read(schema, None);
+
+    assert!(
+        code.contains("// defineSchema options: schemaValidation: false, strictTableNameTypes: true"),
+        "options should be recorded, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "stored documents against this shape.\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct \
+             NotesTable"
+        ),
+        "table should be marked advisory, got:\n{code}"
+    );
+    assert!(
+        !generate_and_read(GATED_SCHEMA, None).contains("defineSchema options"),
+        "default options should not be noted"
+    );
+}
+
+// =============================================================================
+// Nested type names and dead code
+// =============================================================================
+
+/// `game.statusLog` and `gameStatus.log` both name their object `GameStatusLog`.
+/// The second one is numbered, along with its nested `entry` type.
+const COLLIDING_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({
+        game: defineTable({ statusLog: v.object({ a: v.string() }) }),
+        gameStatus: defineTable({ log: v.object({ entry: v.object({ b: v.string() }) }) }),
+    });
+"#;
+
+#[test]
+fn test_colliding_nested_names_get_distinct_types()
+{
+    let code = generate_and_read(COLLIDING_SCHEMA, None);
+
+    assert!(code.contains("pub struct GameStatusLog {\n    pub a: String,\n}"), "got:\n{code}");
+    assert!(
+        code.contains("pub struct GameStatusLog2 {\n    pub entry: GameStatusLog2Entry,\n}"),
+        "the second shape should get its own numbered type, got:\n{code}"
+    );
+    assert!(code.contains("pub struct GameStatusLog2Entry {"), "got:\n{code}");
+    assert!(code.contains("pub log: GameStatusLog2,"), "got:\n{code}");
+    assert!(!code.contains("GameStatusLogEntry"), "got:\n{code}");
+}
+
+#[test]
+fn test_allow_dead_code_on_nested_types()
+{
+    let code = generate_and_read_with_config(COLLIDING_SCHEMA, None, |config| config.allow_dead_code = true);
+
+    assert!(
+        code.contains(
+            "#[allow(dead_code)]\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct GameStatusLog {"
+        ),
+        "nested types should allow dead code, got:\n{code}"
+    );
+    assert!(
+        !code.contains(
+            "#[allow(dead_code)]\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct GameTable"
+        ),
+        "table structs are left alone, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Arg defaults
+// =============================================================================
+
+const DEFAULTS_FUNCTIONS: &str = r#"
+    import { query } from "./_generated/server";
+    import { v } from "convex/values";
+    export const search = query({
+        args: {
+            text: v.string(),
+            // typegen:default "en"
+            locale: v.optional(v.string()),
+            // typegen:default 20
+            limit: v.optional(v.number()),
+            cursor: v.optional(v.string()),
+        },
+        handler: async () => null,
+    });
+    export const recent = query({
+        args: {
+            // typegen:default true
+            pinned: v.optional(v.boolean()),
+        },
+        handler: async () => null,
+    });
+    export const list = query({ args: { cursor: v.optional(v.string()) }, handler: async () => null });
+"#;
+
+#[test]
+fn test_arg_default_directives_fill_new()
+{
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(DEFAULTS_FUNCTIONS, "notes.ts")]));
+
+    assert!(
+        code.contains(
+            "    pub fn new(text: String) -> Self {\n        Self {\n            text,\n            locale: \
+             Some(\"en\".to_string()),\n            limit: Some(20.0),\n            cursor: None,\n        }\n    }"
+        ),
+        "new() should take required args and fill defaults, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl Default for NotesRecentArgs {\n    fn default() -> Self {\n        Self::new()\n    }\n}"),
+        "all-optional args should get Default, got:\n{code}"
+    );
+    assert!(
+        !code.contains("impl Default for NotesSearchArgs"),
+        "args with required fields can't be Default, got:\n{code}"
+    );
+    assert!(
+        !code.contains("impl Default for NotesListArgs"),
+        "args without defaults are left alone, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_configured_arg_defaults_take_precedence()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(DEFAULTS_FUNCTIONS, "notes.ts")]), |config| {
+        config
+            .arg_defaults
+            .insert("notes:search.locale".to_string(), serde_json::json!("fr"));
+        config
+            .arg_defaults
+            .insert("notes:list.cursor".to_string(), serde_json::json!("start"));
+    });
+
+    assert!(
+        code.contains("locale: Some(\"fr\".to_string()),"),
+        "config should override the directive, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl Default for NotesListArgs"),
+        "config defaults should work without a directive, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Arg encoding
+// =============================================================================
+
+const ENCODING_FUNCTIONS: &str = r#"
+    import { mutation } from "./_generated/server";
+    import { v } from "convex/values";
+    export const upload = mutation({
+        args: {
+            size: v.number(),
+            version: v.int64(),
+            data: v.bytes(),
+            chunks: v.optional(v.array(v.number())),
+            kind: v.union(v.literal("image"), v.literal("video")),
+        },
+        returns: v.bytes(),
+        handler: async () => new ArrayBuffer(0),
+    });
+"#;
+
+#[test]
+fn test_args_are_encoded_by_validator()
+{
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(ENCODING_FUNCTIONS, "notes.ts")]));
+
+    assert!(
+        code.contains(
+            "let args = json_to_convex_args(json_args, &[(\"size\", ValueShape::Float64), (\"version\", \
+             ValueShape::Int64), (\"data\", ValueShape::Bytes), (\"chunks\", ValueShape::Array(&ValueShape::Float64)), \
+             (\"kind\", ValueShape::Plain)]);"
+        ),
+        "each arg should carry its validator's shape, got:\n{code}"
+    );
+    assert!(
+        code.contains("fn json_to_convex_value_as(v: serde_json::Value, shape: &ValueShape) -> convex::Value"),
+        "the shape-driven converter should be emitted, got:\n{code}"
+    );
+    assert!(
+        code.contains("convex::Value::Bytes(b) => serde_json::json!(b),"),
+        "bytes results should round-trip into Vec<u8>, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_finite_number_args()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(ENCODING_FUNCTIONS, "notes.ts")]), |config| {
+        config.finite_number_args = true;
+    });
+
+    assert!(
+        code.contains("    pub size: Finite,\n"),
+        "number args should be Finite, got:\n{code}"
+    );
+    assert!(code.contains("    pub chunks: Option<Vec<Finite>>,\n"), "got:\n{code}");
+    assert!(code.contains("    pub version: i64,\n"), "int64 args are already exact");
+    assert!(
+        code.contains("pub fn new(value: f64) -> Result<Self, NonFiniteError>"),
+        "missing Finite, got:\n{code}"
+    );
+    // Numbers are still encoded as Float64
+    assert!(code.contains("(\"size\", ValueShape::Float64)"));
+
+    let default_code = generate_and_read(GATED_SCHEMA, Some(vec![(ENCODING_FUNCTIONS, "notes.ts")]));
+    assert!(default_code.contains("    pub size: f64,\n"));
+    assert!(!default_code.contains("Finite"), "Finite should only be emitted when enabled");
+}
+
+// =============================================================================
+// Indexed literal enums
+// =============================================================================
+
+const INDEXED_ENUM_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({
+        games: defineTable({
+            status: v.union(v.literal("active"), v.literal("finished")),
+            mode: v.union(v.literal("solo"), v.literal("duo")),
+        }).index("by_status", ["status"]),
+    });
+"#;
+
+const INDEXED_ENUM_FUNCTIONS: &str = r#"
+    import { query } from "./_generated/server";
+    import { v } from "convex/values";
+    export const listByStatus = query({
+        args: { status: v.union(v.literal("active"), v.literal("finished")), limit: v.optional(v.number()) },
+        handler: async () => [],
+    });
+"#;
+
+#[test]
+fn test_indexed_literal_enum_helpers()
+{
+    let code = generate_and_read(INDEXED_ENUM_SCHEMA, Some(vec![(INDEXED_ENUM_FUNCTIONS, "games.ts")]));
+
+    assert!(
+        code.contains(
+            "impl GamesStatus {\n    /// The string Convex stores for this value, e.g. for an index range bound.\n    pub \
+             fn convex_literal(&self) -> &'static str {\n        match self {\n            Self::Active => \
+             \"active\",\n\x20           Self::Finished => \"finished\",\n        }\n    }\n}"
+        ),
+        "indexed enum should get convex_literal(), got:\n{code}"
+    );
+    assert!(
+        !code.contains("impl GamesMode {"),
+        "enums on unindexed columns are left alone, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "    pub async fn query_games_by_status(&self, status: GamesStatus) -> Result<serde_json::Value, \
+             ConvexError> {\n        self.query_games_list_by_status(GamesListByStatusArgs { status, limit: None \
+             }).await\n    }"
+        ),
+        "the matching query should get an enum-typed helper, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// FunctionResult conversions
+// =============================================================================
+
+#[test]
+fn test_typed_returns_convert_from_function_result()
+{
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const names = query({ args: {}, returns: v.array(v.string()), handler: async () => [] });
+        export const first = query({
+            args: {},
+            returns: v.object({ _id: v.id("items"), _creationTime: v.number(), name: v.string() }),
+            handler: async () => null,
+        });
+        export const untyped = query({ args: {}, handler: async () => null });
+    "#;
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(functions, "items.ts")]));
+
+    assert!(
+        code.contains("pub struct ItemsNamesOutput(pub Vec<String>);"),
+        "foreign return types should get a newtype, got:\n{code}"
+    );
+    assert!(code.contains("impl TryFrom<convex::FunctionResult> for ItemsNamesOutput {"));
+    assert!(code.contains("decode_function_result(result, Some(\"items:names\")).map(Self)"));
+    assert!(
+        code.contains("impl TryFrom<convex::FunctionResult> for ItemsTable {"),
+        "table documents should convert directly, got:\n{code}"
+    );
+    assert!(
+        code.contains("decode_function_result(result, None)\n"),
+        "several functions can return a table"
+    );
+    assert!(
+        code.contains("        decode_function_result(result, Some(\"items:first\"))\n"),
+        "ConvexApi methods should share the conversion, got:\n{code}"
+    );
+    assert!(
+        !code.contains("ItemsUntypedOutput"),
+        "untyped returns have nothing to convert"
+    );
+}
+
+#[test]
+fn test_convex_error_tells_failures_apart()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, Some(vec![(TYPED_QUERY_FUNCTIONS, "items.ts")]));
+
+    assert!(
+        code.contains("    ArgumentValidation { message: String, path: Option<String> },"),
+        "got:\n{code}"
+    );
+    assert!(code.contains("    Deserialization { function: Option<String>, path: String, source: serde_json::Error },"));
+    assert!(
+        code.contains("serde_path_to_error::deserialize(convex_value_to_json(&value))"),
+        "results should be decoded with the failing path, got:\n{code}"
+    );
+    assert!(code.contains("path: err.path().to_string(), source: err.into_inner()"));
+    assert!(
+        code.contains("convex::FunctionResult::ErrorMessage(msg) => Err(ConvexError::from_message(msg)),"),
+        "error messages should be classified, got:\n{code}"
+    );
+    assert!(code.contains("matches!(self, ConvexError::Transport(_) | ConvexError::Timeout(_))"));
+}
+
+// =============================================================================
+// Deprecations
+// =============================================================================
+
+#[test]
+fn test_deprecated_directive_marks_args_and_methods()
+{
+    let functions = r#"
+        import { mutation, query } from "./_generated/server";
+        import { v } from "convex/values";
+        // typegen:deprecated use notes:createV2
+        export const create = mutation({ args: { body: v.string() }, handler: async () => null });
+        export const createV2 = mutation({ args: { body: v.string() }, handler: async () => null });
+        export const list = query({ args: {}, handler: async () => null });
+    "#;
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(functions, "notes.ts")]));
+
+    assert!(
+        code.contains("#[deprecated(note = \"use notes:createV2\")]\n#[derive(Debug, Clone, Serialize, Deserialize)]"),
+        "args struct should be deprecated, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "    #[allow(deprecated)]\n    #[deprecated(note = \"use notes:createV2\")]\n    fn notes_create(&self, args: \
+             NotesCreateArgs)"
+        ),
+        "trait method should be deprecated, got:\n{code}"
+    );
+    assert!(
+        code.contains("#[allow(deprecated)]\nimpl From<NotesCreateArgs> for std::collections::BTreeMap"),
+        "generated uses of the deprecated struct shouldn't warn"
+    );
+    assert_eq!(code.matches("#[deprecated").count(), 2, "only notes:create is deprecated");
+    assert!(
+        code.contains("    #[must_use]\n    fn query_notes_list(&self)"),
+        "query methods should be must_use, got:\n{code}"
+    );
+    assert!(
+        !code.contains("#[must_use]\n    fn notes_create_v2"),
+        "mutations aren't must_use"
+    );
+}
+
+#[test]
+fn test_prefixed_method_naming_keeps_bare_aliases()
+{
+    let functions = r#"
+        import { action, mutation, query } from "./_generated/server";
+        import { v } from "convex/values";
+        export const create = mutation({ args: { body: v.string() }, handler: async () => null });
+        export const publish = action({ args: {}, handler: async () => null });
+        export const list = query({ args: {}, handler: async () => null });
+    "#;
+    let function_files = || Some(vec![(functions, "notes.ts")]);
+
+    let code = generate_and_read_with_config(GATED_SCHEMA, function_files(), |config| {
+        config.method_naming = MethodNaming::Prefixed;
+    });
+    assert!(
+        code.contains("    fn mutation_notes_create(&self, args: NotesCreateArgs)"),
+        "got:\n{code}"
+    );
+    assert!(code.contains("    fn action_notes_publish(&self)"), "got:\n{code}");
+    assert!(
+        code.contains("    fn query_notes_list(&self)"),
+        "queries keep their names, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "    #[deprecated(note = \"renamed to `mutation_notes_create`\")]\n    fn notes_create(&self, args: \
+             NotesCreateArgs)"
+        ),
+        "the bare name should stay as a deprecated alias, got:\n{code}"
+    );
+    assert!(
+        code.contains("        self.action_notes_publish()\n"),
+        "aliases forward, got:\n{code}"
+    );
+
+    let code = generate_and_read_with_config(GATED_SCHEMA, function_files(), |config| {
+        config.method_naming = MethodNaming::PrefixedOnly;
+    });
+    assert!(
+        code.contains("    fn mutation_notes_create(&self, args: NotesCreateArgs)"),
+        "got:\n{code}"
+    );
+    assert!(
+        !code.contains("fn notes_create("),
+        "PrefixedOnly drops the aliases, got:\n{code}"
+    );
+
+    let code = generate_and_read(GATED_SCHEMA, function_files());
+    assert!(
+        code.contains("    fn notes_create(&self, args: NotesCreateArgs)"),
+        "bare names by default"
+    );
+    assert!(!code.contains("mutation_notes_create"), "no prefixed names by default");
+}
+
+#[test]
+fn test_split_api_by_file()
+{
+    let notes = r#"
+        import { mutation } from "./_generated/server";
+        import { v } from "convex/values";
+        export const create = mutation({ args: { body: v.string() }, handler: async () => null });
+    "#;
+    let function_files = || Some(vec![(notes, "notes.ts"), (TYPED_QUERY_FUNCTIONS, "items.ts")]);
+
+    let code = generate_and_read_with_config(GATED_SCHEMA, function_files(), |config| config.split_api_by_file = true);
+    assert!(code.contains("pub trait ConvexApi: NotesApi + ItemsApi {}"), "got:\n{code}");
+    assert!(
+        code.contains("impl<T: NotesApi + ItemsApi + ?Sized> ConvexApi for T {}"),
+        "got:\n{code}"
+    );
+    let notes_trait = &code[code.find("pub trait NotesApi {").expect("missing NotesApi")..];
+    let notes_trait = &notes_trait[..notes_trait.find("\n}\n").unwrap()];
+    assert!(
+        notes_trait.contains("fn notes_create(&self, args: NotesCreateArgs)"),
+        "got:\n{notes_trait}"
+    );
+    assert!(
+        !notes_trait.contains("items_names"),
+        "each trait only has its own file's methods"
+    );
+    assert!(code.contains("impl ItemsApi for ConvexApiClient {"), "got:\n{code}");
+
+    let code = generate_and_read(GATED_SCHEMA, function_files());
+    assert!(code.contains("pub trait ConvexApi {"), "one trait by default");
+    assert!(!code.contains("NotesApi"), "no per-file traits by default");
+}
+
+#[test]
+fn test_internal_args_live_in_internal_module()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]), |config| {
+        config.internal_module = true;
+    });
+
+    let module = &code[code
+        .find("pub mod internal {\n    #[allow(unused_imports)]\n    use super::*;\n")
+        .expect("missing internal module")..];
+    let module = &module[..module.find("\n}\n").unwrap()];
+    assert!(module.contains("    pub struct NotesSweepArgs {"), "got:\n{module}");
+    assert!(
+        module.contains("        pub const FUNCTION_PATH: &'static str = \"notes:sweep\";"),
+        "got:\n{module}"
+    );
+    assert!(!module.contains("NotesSaveArgs"), "public args stay at the top level");
+    assert!(code.contains("\npub struct NotesSaveArgs {"), "got:\n{code}");
+
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]));
+    assert!(!code.contains("pub mod internal"), "the internal module is opt-in");
+    assert!(code.contains("\npub struct NotesSweepArgs {"));
+}
+
+// =============================================================================
+// Redaction
+// =============================================================================
+
+#[test]
+fn test_redacted_args_hide_values_from_debug()
+{
+    let functions = r#"
+        import { mutation } from "./_generated/server";
+        import { v } from "convex/values";
+        export const signIn = mutation({
+            args: {
+                email: v.string(),
+                // typegen:redact
+                password: v.string(),
+                token: v.optional(v.string()),
+            },
+            handler: async () => null,
+        });
+    "#;
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(functions, "auth.ts")]), |config| {
+        config.redacted_args.insert("auth:signIn.token".to_string());
+    });
+
+    assert!(
+        code.contains("#[derive(Clone, Serialize, Deserialize)]\n#[allow(non_snake_case)]\npub struct AuthSignInArgs {"),
+        "the derived Debug should be replaced, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "        f.debug_struct(\"AuthSignInArgs\")\n            .field(\"email\", &self.email)\n            \
+             .field(\"password\", &format_args!(\"***\"))\n            .field(\"token\", &format_args!(\"***\"))\n"
+        ),
+        "the directive and the config entry should both redact, got:\n{code}"
+    );
+}
+
+// =============================================================================
+// Pruning unreachable tables
+// =============================================================================
+
+#[test]
+fn test_prune_unreachable_keeps_tables_the_functions_use()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string() }),
+            posts: defineTable({ author: v.id("users"), body: v.string() }),
+            drafts: defineTable({ text: v.string() }),
+            audit: defineTable({ action: v.string() }),
+            metrics: defineTable({ value: v.number() }),
+        });
+    "#;
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const get = query({ args: { id: v.id("posts") }, handler: async () => null });
+        export const draft = query({
+            args: {},
+            returns: v.object({ _id: v.id("drafts"), _creationTime: v.number(), text: v.string() }),
+            handler: async () => null,
+        });
+    "#;
+
+    let code = generate_and_read_with_config(schema, Some(vec![(functions, "posts.ts")]), |config| {
+        config.prune_unreachable = true;
+        config.keep_tables = ["audit".to_string()].into();
+    });
+    assert!(
+        code.contains("pub struct PostsTable"),
+        "arg ids should keep their table, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct UsersTable"),
+        "ids in kept tables should keep their table, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct DraftsTable"),
+        "returned documents should keep their table, got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct AuditTable"),
+        "keep_tables should be kept, got:\n{code}"
+    );
+    assert!(
+        !code.contains("MetricsTable"),
+        "unused tables should be left out, got:\n{code}"
+    );
+
+    let code = generate_and_read(schema, Some(vec![(functions, "posts.ts")]));
+    assert!(code.contains("pub struct MetricsTable"), "pruning is opt-in");
+}
+
+// =============================================================================
+// Function refs
+// =============================================================================
+
+#[test]
+fn test_function_refs_cover_public_and_internal_functions()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]), |config| {
+        config.emit_function_refs = true;
+        config.feature_gates = Some(FeatureGates {
+            mutations: Some("writes".to_string()),
+            ..Default::default()
+        });
+    });
+
+    assert!(
+        code.contains(
+            "    pub const NOTES_LIST: FunctionRef = FunctionRef {\n        path: \"notes:list\",\n        reference: \
+             \"api.notes.list\",\n        kind: FunctionKind::Query,\n        internal: false,\n    };"
+        ),
+        "query should get a public ref, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "        reference: \"internal.notes.sweep\",\n        kind: FunctionKind::Mutation,\n        internal: true,"
+        ),
+        "internal mutation should get an internal ref, got:\n{code}"
+    );
+    assert!(
+        code.contains("    #[cfg(feature = \"writes\")]\n    pub const NOTES_SAVE: FunctionRef"),
+        "refs should follow the feature gates, got:\n{code}"
+    );
+    assert!(
+        code.contains(
+            "        #[cfg(feature = \"writes\")]\n        Self::NOTES_SAVE,\n        #[cfg(feature = \
+             \"convex-internal\")]\n        Self::NOTES_SWEEP,"
+        ),
+        "ALL should list every ref under its gate, got:\n{code}"
+    );
+    assert!(code.contains("pub fn from_path(path: &str) -> Option<FunctionRef>"));
+    assert!(code.contains("impl<'de> Deserialize<'de> for FunctionRef {"));
+
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]));
+    assert!(!code.contains("FunctionRef"), "function refs are opt-in");
+}
+
+#[test]
+fn test_schedule_requests_cover_mutations_and_actions()
+{
+    let code = generate_and_read_with_config(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]), |config| {
+        config.emit_schedule_requests = true;
+        config.internal_module = true;
+        config.feature_gates = Some(FeatureGates {
+            mutations: Some("writes".to_string()),
+            ..Default::default()
+        });
+    });
+
+    assert!(
+        code.contains("pub const NOTES_SAVE: FunctionRef"),
+        "schedule requests need function refs, got:\n{code}"
+    );
+    assert!(code.contains("pub struct ScheduleRequest<A: Schedulable> {"));
+    assert!(
+        code.contains(
+            "#[cfg(feature = \"writes\")]\nimpl Schedulable for NotesSaveArgs {\n    const FUNCTION: FunctionRef = \
+             FunctionRef::NOTES_SAVE;\n}"
+        ),
+        "public mutation should be schedulable under its gate, got:\n{code}"
+    );
+    assert!(
+        code.contains("impl Schedulable for internal::NotesSweepArgs {"),
+        "internal mutation should be schedulable through the internal module, got:\n{code}"
+    );
+    assert!(
+        !code.contains("impl Schedulable for NotesListArgs"),
+        "queries can't be scheduled"
+    );
+    assert!(code.contains("ScheduleAt::After(delay) => map.serialize_entry(\"delayMs\""));
+
+    let code = generate_and_read(GATED_SCHEMA, Some(vec![(GATED_FUNCTIONS, "notes.ts")]));
+    assert!(!code.contains("ScheduleRequest"), "schedule requests are opt-in");
+}
+
+// =============================================================================
+// Import writers
+// =============================================================================
+
+#[test]
+fn test_import_writers_emit_insert_structs()
+{
+    let code = generate_and_read_with_config(TYPED_QUERY_SCHEMA, None, |config| config.emit_import_writers = true);
+
+    assert!(
+        code.contains("pub struct ItemsInsert {\n    pub name: String,\n}"),
+        "insert struct should drop the system fields, got:\n{code}"
+    );
+    assert!(code.contains("impl convex_typegen::import::ImportDocument for ItemsInsert {"));
+    assert!(code.contains("    const TABLE: &'static str = \"items\";"));
+    assert!(
+        code.contains(r#"\"name\":{\"type\":\"string\"}"#),
+        "validator should describe the table's fields, got:\n{code}"
+    );
+    assert!(code.contains("impl From<ItemsTable> for ItemsInsert {"));
+
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, None);
+    assert!(!code.contains("ItemsInsert"), "insert structs are opt-in");
+}
+
+// =============================================================================
+// Typed ids
+// =============================================================================
+
+#[test]
+fn test_typed_ids_name_their_table()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({ name: v.string() }),
+            moves: defineTable({ game: v.id("games"), upload: v.optional(v.id("_storage")) }),
+        });
+    "#;
+    let code = generate_and_read_with_config(schema, None, |config| config.typed_ids = true);
+
+    assert!(code.contains("pub struct ConvexId<T> {"), "got:\n{code}");
+    assert!(code.contains("    pub id: ConvexId<GamesTable>,"));
+    assert!(
+        code.contains("    pub game: ConvexId<GamesTable>,"),
+        "v.id(\"games\") should name the games table, got:\n{code}"
+    );
+    assert!(
+        code.contains("    pub upload: Option<String>,"),
+        "ids outside the schema stay strings"
+    );
+    assert!(code.contains("impl ConvexDocument for MovesTable {\n    const TABLE: &'static str = \"moves\";"));
+    assert!(code.contains("impl<T: ConvexDocument> TryFrom<String> for ConvexId<T> {"));
+    assert!(
+        code.contains("Self::try_from(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)"),
+        "deserializing should reject malformed ids with an error, got:\n{code}"
+    );
+    assert!(!code.contains("debug_assert!"));
+
+    let code = generate_and_read(schema, None);
+    assert!(!code.contains("ConvexId"), "typed ids are opt-in");
+    assert!(code.contains("    pub game: String,"));
+}
+
+#[test]
+fn test_every_table_implements_convex_document()
+{
+    let code = generate_and_read(TYPED_QUERY_SCHEMA, None);
+    assert!(code.contains("pub trait ConvexDocument {"), "got:\n{code}");
+    assert!(
+        code.contains(
+            "impl ConvexDocument for ItemsTable {\n    const TABLE: &'static str = \"items\";\n\n    fn id(&self) -> &str \
+             {\n        &self.id\n    }\n\n    fn creation_time(&self) -> f64 {\n        self.creation_time\n    }\n}"
+        ),
+        "got:\n{code}"
+    );
+
+    let code = generate_and_read_with_config(TYPED_QUERY_SCHEMA, None, |config| {
+        config.typed_ids = true;
+        config.flatten_system_fields = true;
+    });
+    assert!(code.contains("        self.system.id.as_str()\n"), "got:\n{code}");
+    assert!(code.contains("        self.system.creation_time\n"));
+}
+
+#[test]
+fn test_flattened_system_fields()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({ name: v.optional(v.string()) }),
+        });
+    "#;
+    let code = generate_and_read_with_config(schema, None, |config| {
+        config.flatten_system_fields = true;
+        config.display_fields = [("games".to_string(), "name".to_string())].into();
+    });
+
+    assert!(
+        code.contains("pub struct SystemFields {\n    #[serde(rename = \"_id\")]\n    pub id: String,"),
+        "got:\n{code}"
+    );
+    assert!(
+        code.contains("pub struct GamesTable {\n    #[serde(flatten)]\n    pub system: SystemFields,\n"),
+        "table should flatten the system fields, got:\n{code}"
+    );
+    assert!(code.contains("impl AsRef<SystemFields> for GamesTable {"));
+    assert!(
+        code.contains("std::fmt::Display::fmt(&self.system.id, f)"),
+        "Display falls back to the id"
+    );
+
+    let code = generate_and_read_with_config(schema, None, |config| {
+        config.flatten_system_fields = true;
+        config.typed_ids = true;
+    });
+    assert!(code.contains("pub struct SystemFields<T: ConvexDocument> {"), "got:\n{code}");
+    assert!(code.contains("    pub system: SystemFields<GamesTable>,"));
+
+    let code = generate_and_read(schema, None);
+    assert!(!code.contains("SystemFields"), "flattening is opt-in");
+}
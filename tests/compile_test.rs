@@ -0,0 +1,29 @@
+//! Type-checks the generated code with every opt-in output turned on.
+//!
+//! The codegen tests only look for substrings, and the `basic` example builds
+//! the default output. `examples/all_features` sets every `emit_*` flag, plus
+//! the `leptos` and `dioxus` features the frontend adapters are gated on, and
+//! this runs `cargo check` on it. The first run downloads axum, Tauri, Leptos,
+//! and Dioxus:
+//!   cargo test -p convex-typegen --test compile_test
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_all_opt_in_output_compiles()
+{
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/all_features/Cargo.toml");
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["check", "--all-features", "--manifest-path"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to run cargo check");
+
+    assert!(
+        output.status.success(),
+        "the generated code with every emit_* flag doesn't compile:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,67 @@
+//! Workspace members run their build scripts at the same time. This test
+//! starts many generations in separate processes that share one Bun cache, as
+//! they do there. It runs in its own test binary: the children re-run it.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use convex_typegen::{clean_cache, generate, CacheKind, Configuration, ExtractionBackend};
+
+/// Set in the children to the file their generation writes.
+const CHILD_OUT_FILE: &str = "CONVEX_TYPEGEN_STRESS_OUT_FILE";
+
+const GENERATIONS: usize = 8;
+
+#[test]
+fn test_concurrent_generations_share_the_bun_cache()
+{
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    if let Some(out_file) = std::env::var_os(CHILD_OUT_FILE) {
+        let config = Configuration {
+            schema_path: manifest_dir.join("examples/basic/convex/schema.ts"),
+            out_file: PathBuf::from(out_file),
+            function_paths: vec![
+                manifest_dir.join("examples/basic/convex/games.ts"),
+                manifest_dir.join("examples/basic/convex/players.ts"),
+            ],
+            extraction_backend: ExtractionBackend::Bun,
+            env_overrides: false,
+            ..Default::default()
+        };
+        generate(config).expect("generation failed");
+        return;
+    }
+
+    // Kept between runs so Bun is downloaded once
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("concurrency");
+    let out_dir = std::env::temp_dir().join(format!("convex_typegen_concurrency_{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let children: Vec<_> = (0..GENERATIONS)
+        .map(|i| {
+            Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "test_concurrent_generations_share_the_bun_cache", "--nocapture"])
+                .env(CHILD_OUT_FILE, out_dir.join(format!("types_{i}.rs")))
+                .env("CARGO_TARGET_DIR", &target_dir)
+                // Without Bun on PATH every child goes through the cache
+                .env("PATH", "")
+                .spawn()
+                .expect("Failed to start a generation")
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success(), "a concurrent generation failed");
+    }
+
+    let first = std::fs::read_to_string(out_dir.join("types_0.rs")).unwrap();
+    for i in 1..GENERATIONS {
+        let output = std::fs::read_to_string(out_dir.join(format!("types_{i}.rs"))).unwrap();
+        assert_eq!(output, first, "generation {i} differs");
+    }
+
+    // Every lock was released when its generation finished
+    std::env::set_var("CARGO_TARGET_DIR", &target_dir);
+    let report = clean_cache(CacheKind::Locks).unwrap();
+    assert!(report.held_locks.is_empty(), "held: {:?}", report.held_locks);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
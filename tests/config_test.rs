@@ -1,8 +1,12 @@
 use std::fs;
 use std::path::PathBuf;
 
-use convex_typegen::errors::ConvexTypeGeneratorError;
-use convex_typegen::{generate, Configuration};
+use convex_typegen::errors::{ConvexTypeGeneratorError, ExtractionCauseKind};
+use convex_typegen::manifest::StaleReason;
+use convex_typegen::{
+    check_freshness, generate, generate_dry_run, generate_from_model, Artifact, CargoIntegration, Configuration,
+    EmptyFunctionFile, ExtractionBackend, FieldOverride, MethodNaming,
+};
 use tempfile::TempDir;
 
 fn setup_test_dir() -> TempDir
@@ -17,6 +21,33 @@ fn test_configuration_default()
     assert_eq!(config.schema_path, PathBuf::from("convex/schema.ts"));
     assert_eq!(config.out_file, PathBuf::from("src/convex_types.rs"));
     assert!(config.function_paths.is_empty());
+    assert!(config.base_dir.is_none());
+    assert_eq!(config.on_empty_function_file, EmptyFunctionFile::Warn);
+    assert_eq!(config.method_naming, MethodNaming::Bare);
+    assert!(!config.split_api_by_file);
+    assert!(!config.internal_module);
+    assert!(!config.strict);
+    assert!(!config.fail_fast);
+    assert!(!config.print_summary);
+    assert!(config.report_file.is_none());
+    assert!(!config.quiet);
+    assert!(!config.lint);
+    assert!(config.field_overrides.is_empty());
+    assert!(config.custom_validators.is_empty());
+    assert!(!config.prune_unreachable);
+    assert!(!config.flatten_system_fields);
+    assert!(config.keep_tables.is_empty());
+    assert!(config.arg_defaults.is_empty());
+    assert!(config.enum_labels.is_empty() && !config.emit_enum_labels);
+    assert!(config.feature_gates.is_none());
+    assert!(config.doc_examples_module.is_none());
+    assert!(!config.allow_dead_code);
+    assert!(config.verify_syntax);
+    assert!(config.max_output_lines.is_none());
+    assert!(!config.offline);
+    assert!(config.bun_path.is_none());
+    assert!(config.env_overrides);
+    assert_eq!(config.cargo_integration, CargoIntegration::default());
 }
 
 #[test]
@@ -29,11 +60,435 @@ fn test_missing_schema_file()
     };
 
     match generate(config) {
-        Err(ConvexTypeGeneratorError::MissingSchemaFile) => (),
+        Err(ConvexTypeGeneratorError::MissingSchemaFile { .. }) => (),
         other => panic!("Expected MissingSchemaFile error, got {:?}", other),
     }
 }
 
+#[test]
+fn test_relative_paths_resolve_against_base_dir()
+{
+    let temp_dir = setup_test_dir();
+    fs::create_dir(temp_dir.path().join("convex")).unwrap();
+    fs::write(temp_dir.path().join("convex/schema.ts"), SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path: PathBuf::from("convex/schema.ts"),
+        out_file: PathBuf::from("types.rs"),
+        base_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.out_file, temp_dir.path().join("types.rs"));
+    assert!(temp_dir.path().join("types.rs").exists());
+}
+
+#[test]
+fn test_canonicalized_base_dir_resolves_to_plain_paths()
+{
+    let temp_dir = setup_test_dir();
+    fs::create_dir(temp_dir.path().join("convex")).unwrap();
+    fs::write(temp_dir.path().join("convex/schema.ts"), SIZED_SCHEMA).unwrap();
+    // On Windows this is a `\\?\C:\...` path
+    let base_dir = fs::canonicalize(temp_dir.path()).expect("Failed to canonicalize temp directory");
+    let config = Configuration {
+        schema_path: PathBuf::from("convex/schema.ts"),
+        out_file: base_dir.join("types.rs"),
+        base_dir: Some(base_dir),
+        extraction_backend: ExtractionBackend::Ast,
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    let out_file = report.out_file.display().to_string();
+    assert!(
+        !out_file.starts_with(r"\\?\"),
+        "verbatim prefix should be dropped, got {out_file}"
+    );
+    assert!(report.out_file.ends_with("types.rs"));
+    assert!(report.out_file.exists());
+}
+
+#[cfg(windows)]
+#[test]
+fn test_verbatim_paths_are_simplified_on_windows()
+{
+    let temp_dir = setup_test_dir();
+    fs::create_dir(temp_dir.path().join("convex")).unwrap();
+    fs::write(temp_dir.path().join("convex/schema.ts"), SIZED_SCHEMA).unwrap();
+    let plain = dunce::canonicalize(temp_dir.path()).expect("Failed to canonicalize temp directory");
+    let verbatim = PathBuf::from(format!(r"\\?\{}", plain.display()));
+    let config = Configuration {
+        schema_path: verbatim.join("convex").join("schema.ts"),
+        out_file: verbatim.join("types.rs"),
+        extraction_backend: ExtractionBackend::Ast,
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.out_file, plain.join("types.rs"));
+}
+
+#[test]
+fn test_bun_path_that_doesnt_run_fails()
+{
+    let temp_dir = setup_test_dir();
+    fs::write(temp_dir.path().join("schema.ts"), SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path: temp_dir.path().join("schema.ts"),
+        out_file: temp_dir.path().join("types.rs"),
+        bun_path: Some(temp_dir.path().join("missing-bun")),
+        ..Default::default()
+    };
+
+    match generate(config) {
+        Err(error @ ConvexTypeGeneratorError::ExtractionFailed { .. }) => {
+            assert!(error.to_string().contains("missing-bun"), "got: {error}");
+        }
+        other => panic!("Expected ExtractionFailed error, got {:?}", other),
+    }
+}
+
+/// A `bun` that answers `--version` and prints `output` for any other command,
+/// standing in for the extractor.
+#[cfg(unix)]
+fn fake_bun(dir: &std::path::Path, output: &str) -> PathBuf
+{
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("fake-bun");
+    let script =
+        format!("#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo 1.2.0; exit 0; fi\ncat <<'EOF'\n{output}\nEOF\n");
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extractor_records_are_read_line_by_line()
+{
+    let temp_dir = setup_test_dir();
+    fs::write(temp_dir.path().join("schema.ts"), SIZED_SCHEMA).unwrap();
+    let output = [
+        r#"{"record":"options","options":{"schemaValidation":false,"strictTableNameTypes":true}}"#,
+        r#"{"record":"table","name":"users","columns":[{"name":"name","data_type":{"type":"string"}}],"indexes":[]}"#,
+        r#"{"record":"function","name":"list","type":"query","params":[{"name":"limit","data_type":{"type":"number"}}],"return_type":null,"file_name":"users","module_path":"users"}"#,
+        r#"{"record":"end"}"#,
+    ]
+    .join("\n");
+    let config = Configuration {
+        schema_path: temp_dir.path().join("schema.ts"),
+        out_file: temp_dir.path().join("types.rs"),
+        bun_path: Some(fake_bun(temp_dir.path(), &output)),
+        extraction_backend: ExtractionBackend::Bun,
+        ..Default::default()
+    };
+
+    generate(config).expect("Code generation failed");
+    let code = fs::read_to_string(temp_dir.path().join("types.rs")).unwrap();
+    assert!(code.contains("pub struct UsersTable"), "missing the table, got:\n{code}");
+    assert!(
+        code.contains("pub struct UsersListArgs"),
+        "missing the function, got:\n{code}"
+    );
+    assert!(
+        code.contains("schemaValidation: false"),
+        "missing the schema options, got:\n{code}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bad_extractor_records_fail_with_the_item()
+{
+    let temp_dir = setup_test_dir();
+    fs::write(temp_dir.path().join("schema.ts"), SIZED_SCHEMA).unwrap();
+    let run = |output: &str| {
+        generate(Configuration {
+            schema_path: temp_dir.path().join("schema.ts"),
+            out_file: temp_dir.path().join("types.rs"),
+            bun_path: Some(fake_bun(temp_dir.path(), output)),
+            extraction_backend: ExtractionBackend::Bun,
+            ..Default::default()
+        })
+    };
+
+    let malformed = [
+        r#"{"record":"table","name":"users","columns":[],"indexes":[]}"#,
+        r#"{"record":"table","name":"posts","columns":5}"#,
+        r#"{"record":"end"}"#,
+    ]
+    .join("\n");
+    match run(&malformed) {
+        Err(error @ ConvexTypeGeneratorError::ExtractionFailed { .. }) => {
+            assert!(error.to_string().contains("line 2 (table posts)"), "got: {error}");
+        }
+        other => panic!("Expected ExtractionFailed error, got {:?}", other),
+    }
+
+    match run(r#"{"record":"table","name":"users","columns":[],"indexes":[]}"#) {
+        Err(error @ ConvexTypeGeneratorError::ExtractionFailed { .. }) => {
+            assert!(error.to_string().contains("ended before its end record"), "got: {error}");
+        }
+        other => panic!("Expected ExtractionFailed error, got {:?}", other),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bun_load_failures_are_collected()
+{
+    let temp_dir = setup_test_dir();
+    fs::write(temp_dir.path().join("schema.ts"), SIZED_SCHEMA).unwrap();
+    let payments = temp_dir.path().join("payments.ts");
+    let billing = temp_dir.path().join("billing.ts");
+    fs::write(&payments, "import { stripe } from \"./lib/stripe\";\n").unwrap();
+    fs::write(&billing, "export const plans = null;\n").unwrap();
+    let output = [
+        r#"{"record":"table","name":"users","columns":[],"indexes":[]}"#.to_string(),
+        serde_json::json!({
+            "record": "error",
+            "file": payments,
+            "message": format!("ResolveMessage: Cannot find module \"./lib/stripe\" from \"{}\"", payments.display()),
+        })
+        .to_string(),
+        serde_json::json!({ "record": "error", "file": billing, "message": "TypeError: plans.map is not a function" })
+            .to_string(),
+        r#"{"record":"end"}"#.to_string(),
+    ]
+    .join("\n");
+    let config = Configuration {
+        schema_path: temp_dir.path().join("schema.ts"),
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![payments.clone(), billing.clone()],
+        on_empty_function_file: EmptyFunctionFile::Error,
+        bun_path: Some(fake_bun(temp_dir.path(), &output)),
+        extraction_backend: ExtractionBackend::Bun,
+        ..Default::default()
+    };
+
+    let diagnostics = match generate(config.clone()) {
+        Err(ConvexTypeGeneratorError::Diagnostics { diagnostics }) => diagnostics,
+        other => panic!("Expected Diagnostics, got {:?}", other),
+    };
+    // The failed files aren't also reported as empty
+    assert_eq!(diagnostics.len(), 2, "got: {diagnostics:#?}");
+    assert_eq!(diagnostics[0].file.as_ref(), Some(&payments));
+    assert!(
+        diagnostics[0]
+            .suggestion
+            .as_deref()
+            .is_some_and(|fix| fix.contains("helper_stubs entry for \"./lib/stripe\"")),
+        "got: {}",
+        diagnostics[0]
+    );
+    assert_eq!(diagnostics[1].file.as_ref(), Some(&billing));
+    assert_eq!(diagnostics[1].suggestion, None);
+
+    match generate(Configuration {
+        fail_fast: true,
+        ..config
+    }) {
+        Err(ConvexTypeGeneratorError::ExtractionFailed { details, .. }) => {
+            assert!(details.contains("./lib/stripe"), "got: {details}");
+        }
+        other => panic!("Expected ExtractionFailed error, got {:?}", other),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bun_failures_are_diagnosed()
+{
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = setup_test_dir();
+    fs::write(temp_dir.path().join("schema.ts"), SIZED_SCHEMA).unwrap();
+    let payments = temp_dir.path().join("payments.ts");
+    let stderr = format!(
+        "1 | import {{ stripe }} from \"./lib/stripe\";\n                           ^\nerror: Could not resolve: \
+         \"./lib/stripe\"\n    at {file}:1:24\n\n4 | const key = await loadKey();\n                ^\nerror: \"await\" can \
+         only be used inside an \"async\" function\n    at {file}:4:13\n",
+        file = payments.display()
+    );
+    let bun = temp_dir.path().join("failing-bun");
+    let script = format!(
+        "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo 1.2.0; exit 0; fi\ncat >&2 <<'EOF'\n{stderr}EOF\nexit 1\n"
+    );
+    fs::write(&bun, script).unwrap();
+    fs::set_permissions(&bun, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let config = Configuration {
+        schema_path: temp_dir.path().join("schema.ts"),
+        out_file: temp_dir.path().join("types.rs"),
+        bun_path: Some(bun),
+        extraction_backend: ExtractionBackend::Bun,
+        ..Default::default()
+    };
+
+    let error = generate(config).expect_err("a failing Bun run should fail");
+    let ConvexTypeGeneratorError::ExtractionFailed { details, causes } = &error else {
+        panic!("Expected ExtractionFailed error, got {error:?}");
+    };
+    assert!(!details.contains('^'), "raw output should be left out, got: {details}");
+    assert_eq!(causes.len(), 2, "got: {causes:?}");
+
+    assert_eq!(causes[0].kind, ExtractionCauseKind::ModuleNotFound);
+    assert_eq!(causes[0].file.as_deref(), Some(payments.as_path()));
+    assert_eq!(causes[0].line, Some(1));
+    assert_eq!(
+        causes[0].suggestion,
+        "add a helper_stubs entry for \"./lib/stripe\", or create the file"
+    );
+
+    assert_eq!(causes[1].kind, ExtractionCauseKind::TopLevelAwait);
+    assert_eq!(causes[1].line, Some(4));
+
+    let message = error.to_string();
+    assert!(
+        message.contains(&format!("- {}:1: Could not resolve", payments.display())),
+        "got: {message}"
+    );
+}
+
+#[test]
+fn test_missing_schema_file_names_resolved_path()
+{
+    let temp_dir = setup_test_dir();
+    let config = Configuration {
+        schema_path: PathBuf::from("convex/schema.ts"),
+        base_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    match generate(config) {
+        Err(error @ ConvexTypeGeneratorError::MissingSchemaFile { .. }) => {
+            let expected = temp_dir.path().join("convex/schema.ts");
+            assert!(error.to_string().contains(&expected.display().to_string()), "got: {error}");
+        }
+        other => panic!("Expected MissingSchemaFile error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_config_file()
+{
+    let temp_dir = setup_test_dir();
+    fs::create_dir(temp_dir.path().join("convex")).unwrap();
+    fs::write(temp_dir.path().join("convex/schema.ts"), SIZED_SCHEMA).unwrap();
+    let config_path = temp_dir.path().join("convex-typegen.toml");
+    fs::write(
+        &config_path,
+        r#"
+# Shared with the CLI
+schema_path = "convex/schema.ts"
+out_file = "types.rs"
+extraction_backend = "ast"
+max_output_lines = 10_000
+cargo_integration = { rerun_if_changed = true, directives = ["rustc-cfg=convex_types"] }
+
+[field_overrides."posts.title"]
+string_or_number = true
+"#,
+    )
+    .unwrap();
+
+    let config = Configuration::from_file(&config_path).expect("valid config file");
+    assert_eq!(config.base_dir.as_deref(), Some(temp_dir.path()));
+    assert_eq!(config.extraction_backend, ExtractionBackend::Ast);
+    assert_eq!(config.max_output_lines, Some(10_000));
+    assert!(config.field_overrides["posts.title"].string_or_number);
+    assert_eq!(
+        config.cargo_integration,
+        CargoIntegration {
+            rerun_if_changed: true,
+            rerun_if_env_changed: false,
+            directives: vec!["rustc-cfg=convex_types".to_string()],
+        }
+    );
+    assert!(config.verify_syntax, "keys left out keep their defaults");
+
+    // Fields set in code take precedence over the file
+    let report = generate(Configuration {
+        out_file: PathBuf::from("overridden.rs"),
+        ..config
+    })
+    .expect("Code generation failed");
+    assert_eq!(report.out_file, temp_dir.path().join("overridden.rs"));
+}
+
+#[test]
+fn test_invalid_config_file()
+{
+    let temp_dir = setup_test_dir();
+    let config_path = temp_dir.path().join("convex-typegen.toml");
+    fs::write(&config_path, "lint = true\nverify_syntax = maybe\n").unwrap();
+
+    match Configuration::from_file(&config_path) {
+        Err(error @ ConvexTypeGeneratorError::InvalidConfigFile { .. }) => {
+            assert!(error.to_string().contains("line 2"), "got: {error}");
+        }
+        other => panic!("Expected InvalidConfigFile error, got {:?}", other),
+    }
+
+    fs::write(&config_path, "schema = \"convex/schema.ts\"\n").unwrap();
+    match Configuration::from_file(&config_path) {
+        Err(error @ ConvexTypeGeneratorError::InvalidConfigFile { .. }) => {
+            assert!(error.to_string().contains("unknown field `schema`"), "got: {error}");
+        }
+        other => panic!("Expected InvalidConfigFile error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_config_file_accepts_full_toml()
+{
+    let temp_dir = setup_test_dir();
+    let config_path = temp_dir.path().join("convex-typegen.toml");
+    fs::write(
+        &config_path,
+        r#"
+doc_examples_module = """
+my_app::convex"""
+
+[[artifacts]]
+kind = "model_json"
+path = "target/convex-model.json"
+
+[[artifacts]]
+kind = "type_script_declarations"
+path = "target/convex.d.ts"
+"#,
+    )
+    .unwrap();
+
+    let config = Configuration::from_file(&config_path).expect("valid config file");
+    assert_eq!(config.doc_examples_module.as_deref(), Some("my_app::convex"));
+    assert_eq!(
+        config.artifacts,
+        vec![
+            Artifact::ModelJson {
+                path: PathBuf::from("target/convex-model.json")
+            },
+            Artifact::TypeScriptDeclarations {
+                path: PathBuf::from("target/convex.d.ts")
+            },
+        ]
+    );
+
+    // Unknown keys in nested tables are rejected too
+    fs::write(&config_path, "[field_overrides.\"posts.title\"]\nstring_or_numbr = true\n").unwrap();
+    match Configuration::from_file(&config_path) {
+        Err(error @ ConvexTypeGeneratorError::InvalidConfigFile { .. }) => {
+            let message = error.to_string();
+            assert!(message.contains("unknown field `string_or_numbr`"), "got: {message}");
+            assert!(message.contains("line 2"), "got: {message}");
+        }
+        other => panic!("Expected InvalidConfigFile error, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_empty_schema_file()
 {
@@ -51,3 +506,1157 @@ fn test_empty_schema_file()
     let result = generate(config);
     assert!(result.is_ok(), "Empty schema should succeed, got {:?}", result);
 }
+
+#[test]
+fn test_generation_report()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string(), metadata: v.any() }),
+            posts: defineTable({ title: v.string() }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        print_summary: true,
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.out_file, out_file);
+    assert_eq!(report.tables.len(), 2);
+    assert_eq!(report.tables[0].struct_name, "UsersTable");
+    assert_eq!(report.tables[0].fields, 2);
+    assert!(report.functions.is_empty());
+    assert_eq!(
+        report.warnings.len(),
+        1,
+        "expected one v.any() warning, got {:?}",
+        report.warnings
+    );
+    assert_eq!(report.warnings[0].location, "table users.metadata");
+    assert!(
+        report.summary().starts_with("2 tables, 0 functions, 1 warning in "),
+        "unexpected summary: {}",
+        report.summary()
+    );
+}
+
+#[test]
+fn test_unknown_field_override_warns()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string() }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        ..Default::default()
+    };
+    config
+        .field_overrides
+        .insert("users.name".to_string(), FieldOverride::default());
+    config
+        .field_overrides
+        .insert("users.nmae".to_string(), FieldOverride::default());
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.warnings.len(), 1, "got {:?}", report.warnings);
+    assert_eq!(report.warnings[0].location, "field_overrides[\"users.nmae\"]");
+}
+
+#[test]
+fn test_unusable_display_fields_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string(), profile: v.object({ bio: v.string() }) }),
+            posts: defineTable({ title: v.optional(v.string()) }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        ..Default::default()
+    };
+    for (table, field) in [("users", "profile"), ("posts", "titel"), ("post", "title")] {
+        config.display_fields.insert(table.to_string(), field.to_string());
+    }
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "display_fields[\"post\"]: no table with this name",
+            "display_fields[\"posts\"]: `posts` has no field `titel`",
+            "display_fields[\"users\"]: `profile` is v.object({ bio }), which has no Display; use a string, number, \
+             boolean, id, or literal field",
+        ]
+    );
+    let code = fs::read_to_string(temp_dir.path().join("types.rs")).unwrap();
+    assert!(
+        !code.contains("impl std::fmt::Display for"),
+        "unusable fields get no impl, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_unusable_collection_keys_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            players: defineTable({
+                name: v.string(),
+                items: v.array(v.object({ label: v.optional(v.string()), slug: v.string() })),
+            }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        ..Default::default()
+    };
+    for (column, key) in [("players.items", "label"), ("players.name", "name"), ("player.items", "slug")] {
+        config.collection_keys.insert(column.to_string(), key.to_string());
+    }
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "collection_keys[\"player.items\"]: no table field with this name; expected \"table.field\"",
+            "collection_keys[\"players.items\"]: `label` is v.optional(v.string()), not a required v.string()",
+            "collection_keys[\"players.name\"]: not an array of objects",
+        ]
+    );
+    // An unusable key turns the finder off rather than falling back
+    let code = fs::read_to_string(temp_dir.path().join("types.rs")).unwrap();
+    assert!(code.contains("pub fn iter_items(&self)"), "missing iter_items, got:\n{code}");
+    assert!(!code.contains("fn find_item_by"), "unusable keys get no finder, got:\n{code}");
+}
+
+#[test]
+fn test_unusable_enum_labels_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            games: defineTable({
+                name: v.string(),
+                status: v.union(v.literal("active"), v.literal("done")),
+            }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        ..Default::default()
+    };
+    for key in [
+        "games.status.active",
+        "games.status.paused",
+        "games.name.x",
+        "game.status.done",
+    ] {
+        config.enum_labels.insert(key.to_string(), "Label".to_string());
+    }
+
+    let report = generate(config.clone()).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec!["enum_labels: emit_enum_labels is off, so no labels are generated"]
+    );
+
+    config.emit_enum_labels = true;
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "enum_labels[\"game.status.done\"]: no table field with this name; expected \"table.field.value\"",
+            "enum_labels[\"games.name.x\"]: `name` is v.string(), not a union of string literals",
+            "enum_labels[\"games.status.paused\"]: v.union(v.literal(\"active\"), v.literal(\"done\")) has no literal \
+             \"paused\"",
+        ]
+    );
+}
+
+#[test]
+fn test_unusable_arg_defaults_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, "").unwrap();
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).unwrap();
+    fs::write(generated_dir.join("server.ts"), r#"export { query } from "convex/server";"#).unwrap();
+    let function_path = temp_dir.path().join("users.ts");
+    fs::write(
+        &function_path,
+        r#"
+        import { query } from "./_generated/server";
+        import { v } from "convex/values";
+        export const search = query({
+            args: {
+                // typegen:default "en"
+                text: v.string(),
+                // typegen:default 5
+                locale: v.optional(v.string()),
+            },
+            handler: async () => null,
+        });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![function_path],
+        ..Default::default()
+    };
+    config
+        .arg_defaults
+        .insert("users:serch.locale".to_string(), serde_json::json!("en"));
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "arg_defaults[\"users:search.locale\"]: 5 doesn't match v.string()",
+            "arg_defaults[\"users:search.text\"]: only optional args take defaults",
+            "arg_defaults[\"users:serch.locale\"]: no function arg with this name; expected \"module:function.arg\"",
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_redacted_args_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, "").unwrap();
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).unwrap();
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { mutation } from "convex/server";"#,
+    )
+    .unwrap();
+    let function_path = temp_dir.path().join("auth.ts");
+    fs::write(
+        &function_path,
+        r#"
+        import { mutation } from "./_generated/server";
+        import { v } from "convex/values";
+        // typegen:redact
+        export const signIn = mutation({ args: { password: v.string() }, handler: async () => null });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![function_path.clone()],
+        ..Default::default()
+    };
+    config.redacted_args.insert("auth:signIn.pasword".to_string());
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            format!(
+                "{}:4: `typegen:redact` must be directly above an arg of an exported function",
+                function_path.display()
+            ),
+            "redacted_args[\"auth:signIn.pasword\"]: no function arg with this name; expected \"module:function.arg\""
+                .to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_function_timeouts_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, "").unwrap();
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).unwrap();
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { mutation } from "convex/server";"#,
+    )
+    .unwrap();
+    let function_path = temp_dir.path().join("reports.ts");
+    fs::write(
+        &function_path,
+        r#"
+        import { mutation } from "./_generated/server";
+        // typegen:timeout soon
+        export const generate = mutation({ args: {}, handler: async () => null });
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![function_path.clone()],
+        ..Default::default()
+    };
+    config
+        .function_timeouts
+        .insert("reports:generate".to_string(), "5 s".to_string());
+    config
+        .function_timeouts
+        .insert("reports:generat".to_string(), "5s".to_string());
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            format!(
+                "{}:3: invalid `typegen:timeout` value \"soon\"; expected e.g. `250ms`, `5s`, or `2m`",
+                function_path.display()
+            ),
+            "function_timeouts[\"reports:generat\"]: no function with this name; expected \"module:function\"".to_string(),
+            "function_timeouts[\"reports:generate\"]: invalid timeout \"5 s\"; expected e.g. \"250ms\", \"5s\", or \"2m\""
+                .to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_function_priorities_warn()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, "").unwrap();
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).unwrap();
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { mutation } from "convex/server";"#,
+    )
+    .unwrap();
+    let function_path = temp_dir.path().join("search.ts");
+    fs::write(
+        &function_path,
+        r#"
+        import { mutation } from "./_generated/server";
+        // typegen:priority urgent
+        export const rebuildIndex = mutation({ args: {}, handler: async () => null });
+        // typegen:priority low
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![function_path.clone()],
+        ..Default::default()
+    };
+    config
+        .function_priorities
+        .insert("search:rebuildIndex".to_string(), "Low".to_string());
+    config
+        .function_priorities
+        .insert("search:rebuild".to_string(), "low".to_string());
+
+    let report = generate(config).expect("Code generation failed");
+    let warnings: Vec<String> = report.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            format!(
+                "{}:3: invalid `typegen:priority` value \"urgent\"; expected `high`, `normal`, or `low`",
+                function_path.display()
+            ),
+            format!(
+                "{}:5: `typegen:priority` must be directly above an exported function",
+                function_path.display()
+            ),
+            "function_priorities[\"search:rebuild\"]: no function with this name; expected \"module:function\"".to_string(),
+            "function_priorities[\"search:rebuildIndex\"]: invalid priority \"Low\"; expected \"high\", \"normal\", or \
+             \"low\""
+                .to_string(),
+        ]
+    );
+}
+
+/// A schema plus one function file with a query and one that exports no functions.
+fn setup_empty_function_file(temp_dir: &TempDir) -> Configuration
+{
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, "").unwrap();
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).unwrap();
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { query, mutation, action } from "convex/server";"#,
+    )
+    .unwrap();
+
+    let users = temp_dir.path().join("users.ts");
+    fs::write(
+        &users,
+        r#"
+        import { query } from "./_generated/server";
+        export const list = query({ args: {}, handler: async () => [] });
+        "#,
+    )
+    .unwrap();
+    let helpers = temp_dir.path().join("helpers.ts");
+    fs::write(&helpers, "export const PAGE_SIZE = 20;").unwrap();
+
+    Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![users, helpers],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_empty_function_file_warns()
+{
+    let temp_dir = setup_test_dir();
+    let config = setup_empty_function_file(&temp_dir);
+    let helpers = config.function_paths[1].clone();
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.functions.len(), 1);
+    assert_eq!(report.empty_function_files, std::slice::from_ref(&helpers));
+    assert_eq!(report.warnings.len(), 1, "got {:?}", report.warnings);
+    assert_eq!(report.warnings[0].location, format!("function file {}", helpers.display()));
+}
+
+#[test]
+fn test_empty_function_file_error()
+{
+    let temp_dir = setup_test_dir();
+    let mut config = setup_empty_function_file(&temp_dir);
+    config.on_empty_function_file = EmptyFunctionFile::Error;
+    let helpers = config.function_paths[1].clone();
+    let out_file = config.out_file.clone();
+
+    match generate(config) {
+        Err(ConvexTypeGeneratorError::EmptyFunctionFiles { files }) => assert_eq!(files, [helpers]),
+        other => panic!("Expected EmptyFunctionFiles error, got {:?}", other),
+    }
+    assert!(!out_file.exists(), "nothing should be written");
+}
+
+#[test]
+fn test_invalid_generated_code_fails_with_snippet()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    // Hyphenated field names aren't turned into Rust identifiers
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ "first-name": v.string() }),
+        });
+        "#,
+    )
+    .unwrap();
+
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        ..Default::default()
+    };
+
+    match generate(config.clone()) {
+        Err(ConvexTypeGeneratorError::InvalidGeneratedCode { snippet, .. }) => {
+            assert!(
+                snippet.contains("pub first-name: String"),
+                "snippet should show the bad line, got:\n{snippet}"
+            );
+        }
+        other => panic!("Expected InvalidGeneratedCode error, got {:?}", other),
+    }
+    assert!(!out_file.exists(), "invalid code should not be written");
+
+    let config = Configuration {
+        verify_syntax: false,
+        ..config
+    };
+    generate(config).expect("generation without the syntax check should succeed");
+    assert!(out_file.exists());
+}
+
+/// A schema whose `users` table introduces a nested struct and an enum.
+const SIZED_SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({
+        users: defineTable({
+            profile: v.object({ bio: v.string() }),
+            role: v.union(v.literal("admin"), v.literal("member")),
+        }),
+        posts: defineTable({ title: v.string() }),
+    });
+"#;
+
+#[test]
+fn test_report_output_sizes()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    let written = fs::read_to_string(out_file).unwrap();
+    assert_eq!(report.lines, written.lines().count());
+
+    let users = &report.tables[0];
+    let posts = &report.tables[1];
+    assert_eq!(users.types, 3, "UsersTable, UsersProfile, and UsersRole");
+    assert_eq!(posts.types, 1);
+    assert!(users.lines > posts.lines, "users: {}, posts: {}", users.lines, posts.lines);
+}
+
+#[test]
+fn test_artifacts_share_one_extraction()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        artifacts: vec![
+            Artifact::ModelJson {
+                path: temp_dir.path().join("model.json"),
+            },
+            Artifact::TypeScriptDeclarations {
+                path: temp_dir.path().join("api.d.ts"),
+            },
+        ],
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(
+        report.artifacts,
+        vec![temp_dir.path().join("model.json"), temp_dir.path().join("api.d.ts")]
+    );
+
+    let model = fs::read_to_string(temp_dir.path().join("model.json")).unwrap();
+    let model: serde_json::Value = serde_json::from_str(&model).unwrap();
+    assert_eq!(model["schema"]["tables"][0]["name"], "users");
+    assert!(model["functions"].as_array().unwrap().is_empty());
+
+    let declarations = fs::read_to_string(temp_dir.path().join("api.d.ts")).unwrap();
+    assert!(declarations.contains("export interface UsersTable {"), "{declarations}");
+    assert!(
+        declarations.contains("  /** `UsersRole` */\n  role: \"admin\" | \"member\";"),
+        "{declarations}"
+    );
+}
+
+#[test]
+fn test_generate_from_shared_model()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let model_path = temp_dir.path().join("shared").join("app.json");
+    let producer = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("producer.rs"),
+        artifacts: vec![Artifact::ModelJson {
+            path: model_path.clone(),
+        }],
+        ..Default::default()
+    };
+    generate(producer).expect("Code generation failed");
+
+    // The consumer has no schema file to extract from
+    let consumer_out = temp_dir.path().join("consumer.rs");
+    generate_from_model(&model_path, &consumer_out).expect("Generation from the model failed");
+    // Only the inputs in the header and the manifest differ
+    let code = |path: PathBuf| {
+        let code = fs::read_to_string(path).unwrap();
+        code[code.find("#[allow(unused_imports)]").unwrap()..code.rfind("// convex-typegen manifest").unwrap()].to_string()
+    };
+    assert_eq!(code(consumer_out.clone()), code(temp_dir.path().join("producer.rs")));
+
+    fs::write(&model_path, "{}").unwrap();
+    match generate_from_model(&model_path, &consumer_out) {
+        Err(ConvexTypeGeneratorError::InvalidModel { path, .. }) => assert_eq!(path, model_path),
+        other => panic!("Expected InvalidModel error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_report_file_is_written_as_json()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let previous_model = temp_dir.path().join("previous.json");
+    let report_file = temp_dir.path().join("reports").join("typegen.json");
+    generate(Configuration {
+        schema_path: schema_path.clone(),
+        out_file: temp_dir.path().join("types.rs"),
+        artifacts: vec![Artifact::ModelJson {
+            path: previous_model.clone(),
+        }],
+        ..Default::default()
+    })
+    .expect("Code generation failed");
+
+    fs::write(
+        &schema_path,
+        SIZED_SCHEMA.replace(
+            "posts: defineTable({ title: v.string() })",
+            "tags: defineTable({ meta: v.any() })",
+        ),
+    )
+    .unwrap();
+    let report = generate(Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        previous_model: Some(previous_model),
+        report_file: Some(report_file.clone()),
+        ..Default::default()
+    })
+    .expect("Code generation failed");
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_file).unwrap()).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json, report.to_json());
+    assert_eq!(json["schema_fingerprint"].as_str().map(str::len), Some(16));
+    let code = fs::read_to_string(temp_dir.path().join("types.rs")).unwrap();
+    assert!(code.contains(&format!("CONVEX_SCHEMA_FINGERPRINT: &str = {};", json["schema_fingerprint"])));
+    assert_eq!(json["tables"][0]["struct_name"], "UsersTable");
+    assert_eq!(
+        json["model_changes"],
+        serde_json::json!({ "added_tables": ["tags"], "removed_tables": ["posts"], "changed_tables": [] })
+    );
+    assert!(
+        json["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|warning| warning["location"] == "table tags.meta" && warning["lint"].is_null()),
+        "got: {json}"
+    );
+}
+
+#[test]
+fn test_manifest_tells_stale_output_without_generating()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: temp_dir.path().join("types.rs"),
+        base_dir: Some(temp_dir.path().to_path_buf()),
+        typed_ids: true,
+        ..Default::default()
+    };
+    let out_file = temp_dir.path().join("types.rs");
+
+    assert_eq!(check_freshness(config.clone()).unwrap().stale, [StaleReason::Missing]);
+    generate(config.clone()).expect("Code generation failed");
+    let code = fs::read_to_string(&out_file).unwrap();
+    assert!(code.contains("// Generated by convex-typegen "), "got:\n{code}");
+    assert!(code.contains("//   schema.ts ("), "got:\n{code}");
+    assert!(code.contains("//   typed_ids = true\n"), "got:\n{code}");
+    assert!(code.contains("\n// convex-typegen manifest\n// {"), "got:\n{code}");
+    assert!(check_freshness(config.clone()).unwrap().is_fresh());
+
+    // The schema is only read, never extracted
+    fs::write(&schema_path, format!("{SIZED_SCHEMA}\n// touched\n")).unwrap();
+    let freshness = check_freshness(Configuration {
+        typed_ids: false,
+        ..config.clone()
+    })
+    .unwrap();
+    assert_eq!(
+        freshness.stale,
+        [StaleReason::Settings, StaleReason::InputChanged("schema.ts".to_string())]
+    );
+
+    generate(config.clone()).expect("Code generation failed");
+    fs::write(
+        &out_file,
+        fs::read_to_string(&out_file)
+            .unwrap()
+            .replace("pub struct", "pub(crate) struct"),
+    )
+    .unwrap();
+    assert_eq!(check_freshness(config.clone()).unwrap().stale, [StaleReason::Edited]);
+
+    fs::write(&out_file, "// generated by hand\n").unwrap();
+    assert_eq!(check_freshness(config).unwrap().stale, [StaleReason::NoManifest]);
+}
+
+#[test]
+fn test_migration_scaffold_from_previous_model()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    let previous_model = temp_dir.path().join("previous.json");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ profile: v.object({ bio: v.string() }), nickname: v.string() }),
+            posts: defineTable({ title: v.string() }),
+        });
+        "#,
+    )
+    .unwrap();
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: temp_dir.path().join("types.rs"),
+        artifacts: vec![Artifact::ModelJson {
+            path: previous_model.clone(),
+        }],
+        ..Default::default()
+    };
+    generate(config).expect("Code generation failed");
+
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        artifacts: vec![Artifact::MigrationScaffold {
+            path: temp_dir.path().join("migrations.rs"),
+        }],
+        previous_model: Some(previous_model),
+        emit_previous_tables: true,
+        ..Default::default()
+    };
+    generate(config).expect("Code generation failed");
+
+    let code = fs::read_to_string(temp_dir.path().join("types.rs")).unwrap();
+    assert!(code.contains("pub struct UsersTableV1 {"), "{code}");
+    assert!(
+        code.contains("pub profile: UsersProfileV1,"),
+        "nested types should be suffixed: {code}"
+    );
+    assert!(
+        !code.contains("PostsTableV1"),
+        "unchanged tables need no previous version: {code}"
+    );
+
+    let scaffold = fs::read_to_string(temp_dir.path().join("migrations.rs")).unwrap();
+    assert!(
+        scaffold.contains("pub fn migrate_users_v1_to_v2(old: UsersTableV1) -> UsersTable {"),
+        "{scaffold}"
+    );
+    assert!(scaffold.contains("profile: same_shape(old.profile),"), "{scaffold}");
+    assert!(scaffold.contains("role: todo!(\"fill in `role`\"),"), "{scaffold}");
+    assert!(scaffold.contains("// Removed: `nickname` (`string`)"), "{scaffold}");
+    assert!(!scaffold.contains("migrate_posts"), "{scaffold}");
+}
+
+#[test]
+fn test_smoke_test_calls_queries_with_optional_args()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let functions_path = temp_dir.path().join("users.ts");
+    fs::write(
+        &functions_path,
+        r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        export const list = query({
+            args: { limit: v.optional(v.number()) },
+            returns: v.array(v.string()),
+            handler: async (ctx, args) => [],
+        });
+        export const get = query({
+            args: { id: v.id("users") },
+            handler: async (ctx, args) => null,
+        });
+        "#,
+    )
+    .unwrap();
+    let smoke_path = temp_dir.path().join("convex_smoke.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths: vec![functions_path],
+        artifacts: vec![Artifact::SmokeTest {
+            path: smoke_path.clone(),
+            module: "my_app::convex_types".to_string(),
+        }],
+        ..Default::default()
+    };
+    generate(config).expect("Code generation failed");
+
+    let smoke = fs::read_to_string(smoke_path).unwrap();
+    assert!(smoke.contains("use my_app::convex_types::*;"), "{smoke}");
+    assert!(
+        smoke.contains("report(&mut failures, \"users:list\", api.query_users_list(UsersListArgs { limit: None }).await"),
+        "{smoke}"
+    );
+    assert!(smoke.contains("println!(\"skip  users:get (required args)\");"), "{smoke}");
+    assert!(smoke.contains("{failures} of 1 queries failed"), "{smoke}");
+}
+
+#[test]
+fn test_version_tag_wraps_code_and_suffixes_types()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: temp_dir.path().join("convex_types.rs"),
+        version_tag: Some("V7".to_string()),
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    assert_eq!(report.out_file, temp_dir.path().join("convex_types_v7.rs"));
+    assert!(!temp_dir.path().join("convex_types.rs").exists());
+
+    let code = fs::read_to_string(&report.out_file).unwrap();
+    assert!(code.contains("pub mod v7 {"), "{code}");
+    assert!(code.contains("    pub struct UsersTable {"), "{code}");
+    assert!(code.contains("pub use self::v7::UsersTable as UsersTableV7;"), "{code}");
+    assert!(
+        code.contains("pub use self::v7::UsersProfile as UsersProfileV7;"),
+        "nested types should be re-exported: {code}"
+    );
+    assert!(code.contains("pub use self::v7::UsersRole as UsersRoleV7;"), "{code}");
+    assert!(code.contains("#[serde(rename = \"admin\")]"), "{code}");
+
+    for tag in ["v-7", "7"] {
+        let config = Configuration {
+            schema_path: schema_path.clone(),
+            out_file: temp_dir.path().join("convex_types.rs"),
+            version_tag: Some(tag.to_string()),
+            ..Default::default()
+        };
+        match generate(config) {
+            Err(ConvexTypeGeneratorError::InvalidVersionTag(invalid)) => assert_eq!(invalid, tag),
+            other => panic!("expected InvalidVersionTag, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_version_tags_share_a_module()
+{
+    // examples/basic includes its V6 and V7 code in one module, which compiles
+    // only if the two don't collide
+    assert!(std::any::type_name::<basic::versioned::GamesTableV6>().ends_with("versioned::v6::GamesTable"));
+    assert!(std::any::type_name::<basic::versioned::GamesTableV7>().ends_with("versioned::v7::GamesTable"));
+}
+
+#[test]
+fn test_max_output_lines_budget()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        max_output_lines: Some(10),
+        ..Default::default()
+    };
+
+    match generate(config) {
+        Err(ConvexTypeGeneratorError::OutputTooLarge {
+            max_lines,
+            largest,
+            suggestions,
+            ..
+        }) => {
+            assert_eq!(max_lines, 10);
+            assert!(largest[0].starts_with("table users ("), "got {largest:?}");
+            assert!(!suggestions.is_empty());
+        }
+        other => panic!("Expected OutputTooLarge error, got {:?}", other),
+    }
+    assert!(!out_file.exists(), "oversized code should not be written");
+}
+
+#[test]
+fn test_strict_rejects_untyped_fields()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            users: defineTable({ name: v.string(), settings: v.any(), tags: v.array(v.object({})) }),
+        });
+        "#,
+    )
+    .unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        strict: true,
+        ..Default::default()
+    };
+
+    match generate(config.clone()) {
+        Err(ConvexTypeGeneratorError::UntypedFields { locations }) => assert_eq!(
+            locations,
+            [
+                "table users.settings: v.any() maps to serde_json::Value",
+                "table users.tags[]: empty v.object({}) maps to serde_json::Value",
+            ]
+        ),
+        other => panic!("Expected UntypedFields error, got {:?}", other),
+    }
+    assert!(!out_file.exists(), "strict mode should not write untyped code");
+
+    let report = generate(Configuration { strict: false, ..config }).expect("Code generation failed");
+    assert_eq!(report.warnings.len(), 2, "got {:?}", report.warnings);
+}
+
+#[test]
+fn test_unchanged_output_is_not_rewritten()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: out_file.clone(),
+        ..Default::default()
+    };
+
+    let first = generate(config.clone()).expect("Code generation failed");
+    assert!(!first.unchanged);
+    let modified = fs::metadata(&out_file).unwrap().modified().unwrap();
+
+    let second = generate(config.clone()).expect("Code generation failed");
+    assert!(second.unchanged);
+    assert!(second.summary().ends_with("(unchanged)"), "got: {}", second.summary());
+    assert_eq!(fs::metadata(&out_file).unwrap().modified().unwrap(), modified);
+
+    fs::write(&schema_path, SIZED_SCHEMA.replace("title", "heading")).unwrap();
+    let third = generate(config).expect("Code generation failed");
+    assert!(!third.unchanged, "a schema edit should rewrite the file");
+    assert!(fs::read_to_string(&out_file).unwrap().contains("pub heading: String"));
+}
+
+#[test]
+fn test_split_output_rewrites_only_changed_tables()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let users = temp_dir.path().join("types/users.rs");
+    let posts = temp_dir.path().join("types/posts.rs");
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: out_file.clone(),
+        split_output_by_table: true,
+        ..Default::default()
+    };
+
+    let first = generate(config.clone()).expect("Code generation failed");
+    assert_eq!(first.table_files, vec![users.clone(), posts.clone()]);
+    assert_eq!(first.rewritten_files, vec![out_file.clone(), users.clone(), posts.clone()]);
+    let code = fs::read_to_string(&out_file).unwrap();
+    assert!(code.contains("include!(\"types/users.rs\");\n"), "got:\n{code}");
+    assert!(!code.contains("pub struct UsersTable"), "got:\n{code}");
+    let users_code = fs::read_to_string(&users).unwrap();
+    assert!(users_code.contains("pub struct UsersTable"), "got:\n{users_code}");
+    assert!(
+        users_code.contains("pub struct UsersProfile"),
+        "nested types go with their table"
+    );
+    assert!(users_code.contains("pub enum UsersRole"), "nested types go with their table");
+    let users_modified = fs::metadata(&users).unwrap().modified().unwrap();
+
+    let second = generate(config.clone()).expect("Code generation failed");
+    assert!(second.unchanged && second.rewritten_files.is_empty());
+
+    fs::write(&schema_path, SIZED_SCHEMA.replace("title", "heading")).unwrap();
+    let third = generate(config.clone()).expect("Code generation failed");
+    assert_eq!(third.rewritten_files, vec![out_file.clone(), posts.clone()]);
+    assert_eq!(fs::metadata(&users).unwrap().modified().unwrap(), users_modified);
+    assert!(fs::read_to_string(&posts).unwrap().contains("pub heading: String"));
+
+    let notes = temp_dir.path().join("types/notes.rs");
+    fs::write(
+        &notes,
+        "// The `notes` table, generated by convex-typegen and included by types.rs. Do not\n// modify directly.\n",
+    )
+    .unwrap();
+    let helpers = temp_dir.path().join("types/helpers.rs");
+    fs::write(
+        &helpers,
+        "// Hand-written helpers for the generated types\npub fn noop() {}\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("types/README.md"), "not generated\n").unwrap();
+    let plan = generate_dry_run(config.clone()).expect("Dry run failed");
+    assert_eq!(plan.stale_files, vec![notes.clone()]);
+    assert!(!plan.is_unchanged());
+
+    let fourth = generate(config).expect("Code generation failed");
+    assert_eq!(fourth.removed_files, vec![notes.clone()]);
+    assert!(!fourth.unchanged);
+    assert!(!notes.exists());
+    assert!(helpers.exists(), "hand-written modules are kept");
+    assert!(temp_dir.path().join("types/README.md").exists(), "only .rs files are removed");
+}
+
+#[test]
+fn test_split_output_rejects_tables_differing_only_in_case()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({
+            userProfiles: defineTable({ name: v.string() }),
+            userprofiles: defineTable({ name: v.string() }),
+        });
+        "#,
+    )
+    .unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path,
+        out_file: out_file.clone(),
+        split_output_by_table: true,
+        ..Default::default()
+    };
+
+    let error = generate(config).expect_err("colliding table files should fail");
+    let ConvexTypeGeneratorError::InvalidSchema { context, details } = &error else {
+        panic!("Expected InvalidSchema error, got {error:?}");
+    };
+    assert_eq!(context, "tables `userProfiles` and `userprofiles`");
+    assert!(details.contains("types/userProfiles.rs and types/userprofiles.rs"), "got: {details}");
+    assert!(!out_file.exists(), "nothing should be written");
+}
+
+#[test]
+fn test_dry_run_writes_nothing()
+{
+    let temp_dir = setup_test_dir();
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SIZED_SCHEMA).unwrap();
+    let out_file = temp_dir.path().join("types.rs");
+    let config = Configuration {
+        schema_path: schema_path.clone(),
+        out_file: out_file.clone(),
+        extraction_backend: ExtractionBackend::Ast,
+        ..Default::default()
+    };
+
+    let plan = generate_dry_run(config.clone()).expect("Dry run failed");
+    assert!(!out_file.exists(), "a dry run should not write");
+    assert_eq!(plan.code.path, out_file);
+    assert!(plan.code.existing.is_none());
+    assert!(plan.code.diff.starts_with("--- /dev/null\n"), "got:\n{}", plan.code.diff);
+    assert!(!plan.is_unchanged());
+
+    generate(config.clone()).expect("Code generation failed");
+    let plan = generate_dry_run(config.clone()).expect("Dry run failed");
+    assert!(plan.is_unchanged() && plan.report.unchanged);
+    assert_eq!(plan.code.contents, fs::read_to_string(&out_file).unwrap());
+    assert!(plan.code.diff.is_empty());
+    assert_eq!(plan.changed_files().count(), 0);
+
+    fs::write(&schema_path, SIZED_SCHEMA.replace("title", "heading")).unwrap();
+    let plan = generate_dry_run(config).expect("Dry run failed");
+    assert!(!plan.is_unchanged());
+    let diff = &plan.code.diff;
+    assert!(diff.contains("\n@@ -"), "got:\n{diff}");
+    assert!(diff.contains("\n-    pub title: String,\n"), "got:\n{diff}");
+    assert!(diff.contains("\n+    pub heading: String,\n"), "got:\n{diff}");
+    assert!(fs::read_to_string(&out_file).unwrap().contains("pub title: String"));
+}
@@ -0,0 +1,293 @@
+//! The embedded QuickJS backend, built with `--features embedded`. None of
+//! these tests need Bun.
+#![cfg(feature = "embedded")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::{generate, Configuration, CustomValidatorHandler, ExtractionBackend};
+use serde_json::json;
+use tempfile::TempDir;
+
+const SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    import { nullable } from "convex-helpers/validators";
+
+    export const status = v.union(v.literal("active"), v.literal("banned"));
+
+    const profile = v.object({
+        bio: v.optional(v.string()),
+        age: v.number(),
+    });
+
+    export default defineSchema({
+        users: defineTable({
+            name: v.string(),
+            nickname: v.string().optional(),
+            status,
+            profile,
+            note: nullable(v.string()),
+        }).index("by_name", ["name"]),
+        posts: defineTable(v.object({ author: v.id("users"), body: v.string() }))
+            .searchIndex("search_body", { searchField: "body" }),
+    });
+"#;
+
+/// A wrapper factory, which the AST backend can't evaluate.
+const AUTH_HELPER: &str = r#"
+    import { query } from "../_generated/server";
+    export const authedQuery = <Args,>(config: { args: Args; handler: unknown }) => query(config as never);
+"#;
+
+/// Write the schema, function files, and helper modules into a temp dir and
+/// return a configuration that uses the embedded backend.
+fn setup(schema_content: &str, function_files: &[(&str, &str)], helper_files: &[(&str, &str)]) -> (TempDir, Configuration)
+{
+    let temp_dir = TempDir::with_prefix("convex_embedded_test").expect("Failed to create temp directory");
+    let write = |content: &str, filename: &str| {
+        let path = temp_dir.path().join(filename);
+        fs::create_dir_all(path.parent().expect("file has a parent")).expect("Failed to create dir");
+        fs::write(&path, content).expect("Failed to write file");
+        path
+    };
+    let schema_path = write(schema_content, "schema.ts");
+    write(
+        r#"export { query, mutation, action, internalQuery, internalMutation, internalAction, httpAction } from "convex/server";"#,
+        "_generated/server.ts",
+    );
+    for (content, filename) in helper_files {
+        write(content, filename);
+    }
+    let function_paths: Vec<PathBuf> = function_files
+        .iter()
+        .map(|(content, filename)| write(content, filename))
+        .collect();
+
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        function_paths,
+        extraction_backend: ExtractionBackend::Embedded,
+        ..Default::default()
+    };
+    (temp_dir, config)
+}
+
+/// Generate with `config` and return the output.
+fn generate_code(config: Configuration) -> String
+{
+    let out_file = config.out_file.clone();
+    generate(config).unwrap_or_else(|e| panic!("embedded extraction failed: {e}"));
+    fs::read_to_string(out_file).expect("Failed to read generated code")
+}
+
+#[test]
+fn test_embedded_backend_schema()
+{
+    let (_temp_dir, config) = setup(SCHEMA, &[], &[]);
+    let code = generate_code(config);
+
+    for expected in [
+        "pub struct UsersTable",
+        "pub struct PostsTable",
+        "pub name: String",
+        "pub nickname: Option<String>",
+        "pub note: Option<String>",
+        "pub age: f64",
+        "pub bio: Option<String>",
+        "pub author: String",
+        "Banned",
+    ] {
+        assert!(code.contains(expected), "expected `{expected}`, got:\n{code}");
+    }
+}
+
+#[test]
+fn test_embedded_backend_evaluates_helper_code()
+{
+    // TypeScript syntax, a `.js` import of a `.ts` file, a JSON import, object
+    // validator methods, and a wrapper factory
+    let functions = r#"
+        import { v } from "convex/values";
+        import { mutation } from "./_generated/server";
+        import { authedQuery } from "./lib/auth.js";
+        import limits from "./lib/limits.json";
+
+        type Ids = Array<string>;
+        const base = v.object({ name: v.string(), email: v.string(), age: v.number() });
+        const pageSizes: number[] = [limits.pageSize];
+
+        export const me = authedQuery({
+            args: { limit: v.optional(v.number()) },
+            handler: async (_ctx: unknown, _args: Ids) => pageSizes,
+        });
+
+        export const update = mutation({
+            args: base.pick("name", "age").extend({ id: v.id("users") }).partial(),
+            returns: base.omit("email"),
+            handler: async () => null,
+        });
+    "#;
+
+    let (_temp_dir, config) = setup(
+        SCHEMA,
+        &[(functions, "users.ts")],
+        &[(AUTH_HELPER, "lib/auth.ts"), (r#"{ "pageSize": 20 }"#, "lib/limits.json")],
+    );
+    let code = generate_code(config);
+
+    for expected in [
+        "pub struct UsersMeArgs",
+        "pub limit: Option<f64>",
+        "fn query_users_me",
+        "pub struct UsersUpdateArgs",
+        "pub id: Option<String>",
+        "pub age: Option<f64>",
+        "fn users_update",
+    ] {
+        assert!(code.contains(expected), "expected `{expected}`, got:\n{code}");
+    }
+    assert!(
+        !code.contains("pub email: Option<String>"),
+        "`pick` should drop the email field, got:\n{code}"
+    );
+}
+
+#[test]
+fn test_embedded_backend_helper_stubs_and_custom_validators()
+{
+    let schema = r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        import { vEmail } from "./lib/validators";
+
+        export default defineSchema({
+            users: defineTable({ email: vEmail(), backup: v.optional(vEmail()) }),
+        });
+    "#;
+    // The package doesn't exist; the stub and the custom validator stand in
+    let functions = r#"
+        import { v } from "convex/values";
+        import { query } from "./_generated/server";
+        import { withTenant } from "tenant-kit";
+
+        export const list = query({ args: withTenant({ page: v.number() }), handler: async () => [] });
+    "#;
+    let validators = r#"
+        import { isEmail } from "validator-kit";
+        export const vEmail = () => isEmail();
+    "#;
+    let stub = r#"
+        import { v } from "convex/values";
+        export const withTenant = (fields: Record<string, unknown>) => ({ ...fields, tenant: v.string() });
+    "#;
+
+    let (temp_dir, mut config) = setup(
+        schema,
+        &[(functions, "users.ts")],
+        &[(validators, "lib/validators.ts"), (stub, "stubs/tenant.ts")],
+    );
+    config
+        .helper_stubs
+        .insert("^tenant-kit$".to_string(), temp_dir.path().join("stubs/tenant.ts"));
+    config.custom_validators.insert(
+        "vEmail".to_string(),
+        CustomValidatorHandler {
+            module: "lib/validators".to_string(),
+            descriptor: json!({ "type": "string" }),
+            rust_type: Some("crate::Email".to_string()),
+        },
+    );
+    let code = generate_code(config);
+
+    for expected in [
+        "pub email: crate::Email",
+        "pub backup: Option<crate::Email>",
+        "pub page: f64",
+        "pub tenant: String",
+    ] {
+        assert!(code.contains(expected), "expected `{expected}`, got:\n{code}");
+    }
+}
+
+#[test]
+fn test_embedded_backend_reports_files_that_fail_to_load()
+{
+    let missing_package = r#"
+        import { query } from "./_generated/server";
+        import { helper } from "not-installed";
+        export const get = query({ args: {}, handler: async () => helper });
+    "#;
+    let syntax_error = "import { query } from \"./_generated/server\";\nexport const = query({});\n";
+    let fine = r#"
+        import { query } from "./_generated/server";
+        export const list = query({ args: {}, handler: async () => [] });
+    "#;
+
+    let (_temp_dir, config) = setup(
+        SCHEMA,
+        &[
+            (missing_package, "missing.ts"),
+            (syntax_error, "broken.ts"),
+            (fine, "fine.ts"),
+        ],
+        &[],
+    );
+    match generate(config) {
+        Err(ConvexTypeGeneratorError::Diagnostics { diagnostics }) => {
+            assert_eq!(diagnostics.len(), 2, "got {diagnostics:?}");
+            let missing = &diagnostics[0];
+            assert!(
+                missing.file.as_ref().is_some_and(|file| file.ends_with("missing.ts")),
+                "got {missing:?}"
+            );
+            assert!(
+                missing
+                    .suggestion
+                    .as_deref()
+                    .is_some_and(|suggestion| suggestion == "add a helper_stubs entry for \"not-installed\""),
+                "got {missing:?}"
+            );
+            let broken = &diagnostics[1];
+            assert!(
+                broken.file.as_ref().is_some_and(|file| file.ends_with("broken.ts")),
+                "got {broken:?}"
+            );
+            assert_eq!(broken.line, Some(2), "got {broken:?}");
+        }
+        other => panic!("Expected Diagnostics, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_embedded_backend_hands_use_node_files_to_bun()
+{
+    let node_file = r#"
+        // Runs in Node on Convex
+        "use node";
+        import { action } from "./_generated/server";
+        export const send = action({ args: {}, handler: async () => null });
+    "#;
+    let fine = r#"
+        import { query } from "./_generated/server";
+        export const list = query({ args: {}, handler: async () => [] });
+    "#;
+
+    let (temp_dir, mut config) = setup(SCHEMA, &[(fine, "fine.ts"), (node_file, "node.ts")], &[]);
+    // Only Bun can run the "use node" file, and there is none
+    config.bun_path = Some(temp_dir.path().join("no-bun"));
+    config.offline = true;
+    match generate(config.clone()) {
+        Err(ConvexTypeGeneratorError::ExtractionFailed { details, .. }) => {
+            assert!(details.contains("no-bun"), "error should name the Bun path, got: {details}");
+        }
+        other => panic!("Expected ExtractionFailed, got {:?}", other.map(|_| ())),
+    }
+
+    config.function_paths.pop();
+    let report = generate(config).expect("the other files need no Bun");
+    assert!(report.bun_fallbacks.is_empty(), "got {:?}", report.bun_fallbacks);
+    assert_eq!(report.functions.len(), 1);
+}
@@ -0,0 +1,105 @@
+//! `CONVEX_TYPEGEN_*` overrides. The variables are process-wide, so everything
+//! that sets one lives in this single test binary and runs in one test.
+
+use std::fs;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::report::{ResolveEvent, TRACE_RESOLVE_ENV};
+use convex_typegen::{generate, Configuration, ExtractionBackend, ENV_VARS};
+use tempfile::TempDir;
+
+const SCHEMA: &str = r#"
+import { defineSchema, defineTable } from "convex/server";
+import { v } from "convex/values";
+
+export default defineSchema({
+    notes: defineTable({ body: v.string() }),
+});
+"#;
+
+const TRACED_SCHEMA: &str = r#"
+import { defineSchema, defineTable } from "convex/server";
+import { v } from "convex/values";
+
+export default defineSchema({
+    notes: defineTable({ body: v.string(), meta: v.any() }),
+});
+"#;
+
+#[test]
+fn test_env_overrides()
+{
+    let temp_dir = TempDir::with_prefix("convex_typegen_env_test").expect("Failed to create temp directory");
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, SCHEMA).unwrap();
+    let config = || Configuration {
+        schema_path: schema_path.clone(),
+        out_file: temp_dir.path().join("types.rs"),
+        extraction_backend: ExtractionBackend::Ast,
+        ..Default::default()
+    };
+
+    // Set variables win over the configured value; empty ones are ignored.
+    std::env::set_var("CONVEX_TYPEGEN_OUT_FILE", temp_dir.path().join("from_env.rs"));
+    std::env::set_var("CONVEX_TYPEGEN_MAX_OUTPUT_LINES", "5000");
+    std::env::set_var("CONVEX_TYPEGEN_OFFLINE", "1");
+    std::env::set_var("CONVEX_TYPEGEN_BACKEND", "");
+    let overridden = config().from_env_overrides().expect("valid overrides");
+    assert_eq!(overridden.out_file, temp_dir.path().join("from_env.rs"));
+    assert_eq!(overridden.max_output_lines, Some(5000));
+    assert!(overridden.offline);
+    assert_eq!(overridden.extraction_backend, ExtractionBackend::Ast);
+
+    let report = generate(config()).expect("Code generation failed");
+    assert_eq!(report.out_file, temp_dir.path().join("from_env.rs"));
+
+    // Opting out ignores the environment entirely.
+    let report = generate(Configuration {
+        env_overrides: false,
+        ..config()
+    })
+    .expect("Code generation failed");
+    assert_eq!(report.out_file, temp_dir.path().join("types.rs"));
+
+    std::env::set_var("CONVEX_TYPEGEN_LINT", "maybe");
+    match generate(config()) {
+        Err(error @ ConvexTypeGeneratorError::InvalidEnvOverride { .. }) => {
+            assert!(error.to_string().contains("CONVEX_TYPEGEN_LINT"), "got: {error}");
+        }
+        other => panic!("Expected InvalidEnvOverride error, got {:?}", other),
+    }
+
+    for var in ENV_VARS {
+        std::env::remove_var(var);
+    }
+    assert_eq!(
+        config().from_env_overrides().unwrap().out_file,
+        temp_dir.path().join("types.rs")
+    );
+
+    // The Bun extractor reports what it intercepted and why a validator became `any`.
+    fs::write(&schema_path, TRACED_SCHEMA).unwrap();
+    std::env::set_var(TRACE_RESOLVE_ENV, "1");
+    let report = generate(Configuration {
+        extraction_backend: ExtractionBackend::Bun,
+        ..config()
+    })
+    .expect("Code generation failed");
+    std::env::remove_var(TRACE_RESOLVE_ENV);
+    assert!(
+        report.resolve_trace.iter().any(|event| matches!(
+            event,
+            ResolveEvent::Import { specifier, .. } if specifier == "convex/server"
+        )),
+        "got: {:?}",
+        report.resolve_trace
+    );
+    assert!(
+        report.resolve_trace.contains(&ResolveEvent::Any {
+            location: "table notes.meta".to_string(),
+            reason: "declared with v.any()".to_string(),
+        }),
+        "got: {:?}",
+        report.resolve_trace
+    );
+}
@@ -0,0 +1,128 @@
+use std::fs;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::{explain, Configuration};
+use tempfile::TempDir;
+
+const SCHEMA: &str = r#"
+    import { defineSchema, defineTable } from "convex/server";
+    import { v } from "convex/values";
+    export default defineSchema({
+        users: defineTable({
+            name: v.string(),
+            age: v.optional(v.number()),
+            metadata: v.any(),
+        }),
+    });
+"#;
+
+const FUNCTIONS: &str = r#"
+    import { v } from "convex/values";
+    import { query, mutation } from "./_generated/server";
+    export const byName = query({
+        args: { name: v.string() },
+        returns: v.array(v.string()),
+        handler: async (ctx, args) => [],
+    });
+    export const touch = mutation({
+        args: {},
+        handler: async (ctx) => {},
+    });
+"#;
+
+fn explain_in_temp_dir(schema_content: &str, function_files: &[(&str, &str)]) -> String
+{
+    let temp_dir = TempDir::with_prefix("convex_explain_test").expect("Failed to create temp directory");
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(&schema_path, schema_content).expect("Failed to write test schema");
+
+    let generated_dir = temp_dir.path().join("_generated");
+    fs::create_dir_all(&generated_dir).expect("Failed to create _generated dir");
+    fs::write(
+        generated_dir.join("server.ts"),
+        r#"export { query, mutation, action, internalQuery, internalMutation, internalAction, httpAction } from "convex/server";"#,
+    )
+    .expect("Failed to write _generated/server stub");
+
+    let mut function_paths = Vec::new();
+    for (content, filename) in function_files {
+        let fn_path = temp_dir.path().join(filename);
+        fs::write(&fn_path, content).expect("Failed to write function file");
+        function_paths.push(fn_path);
+    }
+
+    let config = Configuration {
+        schema_path,
+        function_paths,
+        ..Default::default()
+    };
+    explain(config).expect("explain failed")
+}
+
+#[test]
+fn test_explain_lists_tables_and_rust_types()
+{
+    let report = explain_in_temp_dir(SCHEMA, &[]);
+
+    assert!(report.contains("users → UsersTable"), "missing table line, got:\n{report}");
+    assert!(
+        report.contains("name: v.string() → String"),
+        "missing column line, got:\n{report}"
+    );
+    assert!(
+        report.contains("age: v.optional(v.number()) → Option<f64>"),
+        "missing optional column line, got:\n{report}"
+    );
+}
+
+#[test]
+fn test_explain_flags_untyped_fields()
+{
+    let report = explain_in_temp_dir(SCHEMA, &[]);
+
+    assert!(
+        report.contains("metadata: v.any() → serde_json::Value"),
+        "missing any column line, got:\n{report}"
+    );
+    assert!(
+        report.contains("⚠ metadata: v.any() maps to serde_json::Value"),
+        "v.any() should be flagged, got:\n{report}"
+    );
+}
+
+#[test]
+fn test_explain_lists_functions()
+{
+    let report = explain_in_temp_dir(SCHEMA, &[(FUNCTIONS, "users.ts")]);
+
+    assert!(
+        report.contains("query users:byName → subscribe_users_by_name, query_users_by_name"),
+        "missing query line, got:\n{report}"
+    );
+    assert!(
+        report.contains("args → UsersByNameArgs"),
+        "missing args struct, got:\n{report}"
+    );
+    assert!(
+        report.contains("returns: v.array(v.string()) → Vec<String>"),
+        "missing return type, got:\n{report}"
+    );
+    assert!(
+        report.contains("returns: (untyped) → serde_json::Value"),
+        "untyped mutation should be reported, got:\n{report}"
+    );
+}
+
+#[test]
+fn test_explain_missing_schema()
+{
+    let config = Configuration {
+        schema_path: "does/not/exist/schema.ts".into(),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        explain(config),
+        Err(ConvexTypeGeneratorError::MissingSchemaFile { .. })
+    ));
+}
@@ -0,0 +1,146 @@
+use std::fs::File;
+use std::io::Write;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::export::SnapshotExport;
+use convex_typegen::import::{ImportDocument, ImportWriter};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+
+/// The shape the generator emits for `games: defineTable({ name: v.string(), score: v.number() })`.
+#[derive(Debug, Deserialize, PartialEq)]
+struct GamesTable
+{
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_creationTime")]
+    creation_time: f64,
+    name: String,
+    score: f64,
+}
+
+/// The shape the generator emits for `GamesInsert` with `emit_import_writers`.
+#[derive(Debug, Serialize)]
+struct GamesInsert
+{
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+}
+
+impl ImportDocument for GamesInsert
+{
+    const TABLE: &'static str = "games";
+    const VALIDATOR: &'static str =
+        r#"{"type":"object","properties":{"name":{"type":"string"},"score":{"type":"optional","inner":{"type":"number"}}}}"#;
+}
+
+/// Write a snapshot export with the given `(entry, contents)` files.
+fn write_export(temp_dir: &TempDir, entries: &[(&str, &str)]) -> std::path::PathBuf
+{
+    let path = temp_dir.path().join("snapshot.zip");
+    let mut zip = zip::ZipWriter::new(File::create(&path).expect("Failed to create export"));
+    for (name, contents) in entries {
+        zip.start_file(*name, SimpleFileOptions::default())
+            .expect("Failed to start entry");
+        zip.write_all(contents.as_bytes()).expect("Failed to write entry");
+    }
+    zip.finish().expect("Failed to finish export");
+    path
+}
+
+#[test]
+fn test_snapshot_export_yields_typed_documents()
+{
+    let temp_dir = TempDir::with_prefix("convex_export_test").expect("Failed to create temp directory");
+    let path = write_export(
+        &temp_dir,
+        &[
+            ("_tables/documents.jsonl", "{\"name\":\"games\",\"id\":10001}\n"),
+            (
+                "games/documents.jsonl",
+                "{\"_id\":\"g1\",\"_creationTime\":1.0,\"name\":\"chess\",\"score\":3}\n\n{\"_id\":\"g2\",\"_creationTime\"\
+                 :2.0,\"name\":\"go\",\"score\":5}\n",
+            ),
+            ("games/generated_schema.jsonl", "\"{}\"\n"),
+            (
+                "players/documents.jsonl",
+                "{\"_id\":\"p1\",\"_creationTime\":1.0,\"name\":\"ann\"}\n",
+            ),
+            ("_components/auth/sessions/documents.jsonl", ""),
+        ],
+    );
+
+    let mut export = SnapshotExport::open(&path).expect("Failed to open export");
+    assert_eq!(export.tables(), ["_tables", "games", "players"]);
+
+    let games: Vec<GamesTable> = export
+        .documents("games")
+        .expect("games should be in the export")
+        .collect::<Result<_, _>>()
+        .expect("every game should decode");
+    assert_eq!(games.len(), 2, "blank lines are skipped");
+    assert_eq!(
+        games[1],
+        GamesTable {
+            id: "g2".to_string(),
+            creation_time: 2.0,
+            name: "go".to_string(),
+            score: 5.0,
+        }
+    );
+
+    // A document that doesn't match the struct fails on its own line
+    let first_player = export
+        .documents::<GamesTable>("players")
+        .expect("players should be in the export")
+        .next();
+    match first_player {
+        Some(Err(error @ ConvexTypeGeneratorError::InvalidExport { .. })) => {
+            assert!(error.to_string().contains("players/documents.jsonl line 1"), "got: {error}");
+        }
+        other => panic!("Expected InvalidExport error, got {:?}", other),
+    }
+
+    match export.documents::<GamesTable>("missing").err() {
+        Some(ConvexTypeGeneratorError::InvalidExport { details, .. }) => {
+            assert!(details.contains("no table `missing`"), "got: {details}");
+        }
+        other => panic!("Expected InvalidExport error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_import_writer_writes_validated_jsonl()
+{
+    let mut writer = ImportWriter::<GamesInsert, _>::new(Vec::new());
+    writer
+        .write(&GamesInsert {
+            name: "chess".to_string(),
+            score: Some(3.0),
+        })
+        .expect("a valid game should be written");
+    writer
+        .write(&GamesInsert {
+            name: "go".to_string(),
+            score: None,
+        })
+        .expect("optional fields may be left out");
+
+    // NaN serializes as null, which `v.number()` rejects
+    let invalid = GamesInsert {
+        name: "poker".to_string(),
+        score: Some(f64::NAN),
+    };
+    match writer.write(&invalid) {
+        Err(ConvexTypeGeneratorError::InvalidImportDocument { table, line, .. }) => {
+            assert_eq!((table.as_str(), line), ("games", 3));
+        }
+        other => panic!("Expected InvalidImportDocument error, got {:?}", other),
+    }
+    assert_eq!(writer.len(), 2, "a rejected document isn't written");
+
+    let out = String::from_utf8(writer.finish().expect("Failed to flush")).expect("JSONL is UTF-8");
+    assert_eq!(out, "{\"name\":\"chess\",\"score\":3.0}\n{\"name\":\"go\"}\n");
+}
@@ -0,0 +1,48 @@
+use convex_typegen::fixtures::sample_document;
+use serde_json::json;
+
+const VALIDATOR: &str = r#"{"type":"object","properties":{
+    "name":{"type":"string"},
+    "owner":{"type":"id","tableName":"users"},
+    "kind":{"type":"union","variants":[{"type":"literal","value":"a"},{"type":"literal","value":"b"}]},
+    "stats":{"type":"object","properties":{"hp":{"type":"number"},"bonus":{"type":"optional","inner":{"type":"number"}}}},
+    "tags":{"type":"array","elements":{"type":"string"}},
+    "note":{"type":"optional","inner":{"type":"string"}}
+}}"#;
+
+#[test]
+fn test_sample_documents_are_deterministic()
+{
+    let document = sample_document("games", VALIDATOR, 7);
+    assert_eq!(document, sample_document("games", VALIDATOR, 7), "same seed, same document");
+    assert_ne!(document["_id"], sample_document("games", VALIDATOR, 8)["_id"]);
+
+    let id = document["_id"].as_str().expect("_id is a string");
+    assert_eq!(id.len(), 32);
+    assert!(
+        id.bytes().all(|b| b"0123456789abcdefghjkmnpqrstvwxyz".contains(&b)),
+        "ids use the Convex alphabet, got {id}"
+    );
+    assert!(document["_creationTime"].is_f64());
+    assert!(document["name"].as_str().is_some_and(|name| name.starts_with("name-")));
+    assert!(["a", "b"].contains(&document["kind"].as_str().expect("kind is a literal")));
+    assert!(document["stats"]["hp"].is_f64());
+    assert_eq!(document["tags"], json!([]));
+    // Optional fields stay unset
+    assert!(document.get("note").is_none() && document["stats"].get("bonus").is_none());
+}
+
+#[test]
+fn test_sample_values_depend_on_their_field_only()
+{
+    let wider = VALIDATOR.replace(
+        r#""name":{"type":"string"},"#,
+        r#""name":{"type":"string"},"level":{"type":"number"},"#,
+    );
+    let before = sample_document("games", VALIDATOR, 1);
+    let after = sample_document("games", &wider, 1);
+    assert!(after["level"].is_f64());
+    for field in ["_id", "name", "owner", "kind", "stats"] {
+        assert_eq!(before[field], after[field], "adding a field changed `{field}`");
+    }
+}
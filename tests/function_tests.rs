@@ -1,6 +1,6 @@
 use std::fs;
 
-use convex_typegen::{generate, Configuration};
+use convex_typegen::{generate, Artifact, Configuration};
 use tempfile::TempDir;
 
 fn setup_test_dir() -> TempDir
@@ -59,7 +59,7 @@ export const testQuery = query({
         schema_path,
         function_paths: vec![function_path],
         out_file: temp_dir.path().join("types.rs"),
-        helper_stubs: std::collections::HashMap::new(),
+        ..Default::default()
     };
 
     let result = generate(config);
@@ -101,10 +101,121 @@ export default defineSchema({
         schema_path,
         function_paths: vec![function_path],
         out_file: temp_dir.path().join("types.rs"),
-        helper_stubs: std::collections::HashMap::new(),
+        ..Default::default()
     };
 
     // Should succeed — plain exports without __type are silently skipped
     let result = generate(config);
     assert!(result.is_ok(), "Expected Ok result, got {:?}", result);
 }
+
+#[test]
+fn test_component_clients_get_namespaced_traits()
+{
+    let temp_dir = setup_test_dir();
+
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+import { defineSchema, defineTable } from "convex/server";
+import { v } from "convex/values";
+
+export default defineSchema({
+    users: defineTable({ name: v.string() }),
+})
+"#,
+    )
+    .unwrap();
+
+    let config_path = temp_dir.path().join("convex.config.ts");
+    fs::write(
+        &config_path,
+        r#"
+import { defineApp } from "convex/server";
+import aggregate from "@convex-dev/aggregate/convex.config";
+import rateLimiter from "@convex-dev/rate-limiter/convex.config";
+const app = defineApp();
+app.use(aggregate);
+app.use(rateLimiter);
+export default app;
+"#,
+    )
+    .unwrap();
+
+    let function_path = temp_dir.path().join("scores.ts");
+    fs::write(
+        &function_path,
+        r#"
+import { v } from "convex/values";
+import { components } from "./_generated/api";
+import { query } from "./_generated/server";
+import { TableAggregate } from "@convex-dev/aggregate";
+import { MINUTE, RateLimiter } from "@convex-dev/rate-limiter";
+import { WorkflowManager } from "@convex-dev/workflow";
+
+const leaderboard = new TableAggregate(components.aggregate, { sortKey: (doc) => doc.score });
+const rateLimiter = new RateLimiter(components.rateLimiter, {
+    send: { kind: "token bucket", rate: 10, period: MINUTE },
+});
+const workflow = new WorkflowManager(components.workflow);
+
+export const { getRateLimit, getServerTime } = rateLimiter.hookAPI("send");
+export const onboard = workflow.define({ args: { userId: v.id("users") }, handler: async () => null });
+export const rank = query({ args: { score: v.number() }, handler: async () => 0 });
+"#,
+    )
+    .unwrap();
+
+    let out_file = temp_dir.path().join("types.rs");
+    let smoke_path = temp_dir.path().join("convex_smoke.rs");
+    let config = Configuration {
+        schema_path,
+        function_paths: vec![config_path, function_path],
+        out_file: out_file.clone(),
+        artifacts: vec![Artifact::SmokeTest {
+            path: smoke_path.clone(),
+            module: "my_app::convex_types".to_string(),
+        }],
+        ..Default::default()
+    };
+
+    let report = generate(config).expect("Code generation failed");
+    let code = fs::read_to_string(out_file).unwrap();
+
+    let kinds: Vec<(&str, &str)> = report
+        .functions
+        .iter()
+        .map(|function| (function.path.as_str(), function.kind.as_str()))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("scores:getRateLimit", "query"),
+            ("scores:getServerTime", "mutation"),
+            ("scores:onboard", "internalMutation"),
+            ("scores:rank", "query"),
+        ]
+    );
+    assert_eq!(report.empty_function_files.len(), 1, "convex.config.ts has no functions");
+    assert!(code.contains("pub struct ScoresGetRateLimitArgs"), "missing hook API args, got:\n{code}");
+
+    assert!(code.contains("pub mod components {"), "got:\n{code}");
+    assert!(code.contains("    pub trait RateLimiterApi {"), "got:\n{code}");
+    assert!(code.contains("    impl RateLimiterApi for ConvexApiClient {"), "got:\n{code}");
+    assert!(
+        code.contains("async fn scores_get_server_time(&self) -> Result<f64, ConvexError>"),
+        "hook API result should be typed, got:\n{code}"
+    );
+
+    let api = &code[code.find("pub trait ConvexApi {").expect("missing ConvexApi")..];
+    let api = &api[..api.find("\n}\n").expect("unterminated ConvexApi")];
+    assert!(api.contains("fn query_scores_rank("), "got:\n{api}");
+    assert!(!api.contains("get_server_time"), "component functions belong to their trait, got:\n{api}");
+
+    let smoke = fs::read_to_string(smoke_path).unwrap();
+    assert!(
+        smoke.contains("use my_app::convex_types::components::*;"),
+        "the smoke test calls component queries through their trait, got:\n{smoke}"
+    );
+}
@@ -36,7 +36,7 @@ fn test_codegen_pipeline()
             manifest_dir.join("examples/basic/convex/games.ts"),
             manifest_dir.join("examples/basic/convex/players.ts"),
         ],
-        helper_stubs: std::collections::HashMap::new(),
+        ..Default::default()
     };
 
     convex_typegen::generate(config).expect("Codegen failed");
@@ -119,18 +119,19 @@ fn test_codegen_pipeline()
         "Missing PlayersUpdateProfileAction enum"
     );
 
-    // Inline v.object structs for args
+    // Inline v.object args identical to schema fields reuse the schema struct,
+    // keeping the per-function name as an alias
     assert!(
-        output.contains("pub struct PlayersCreateProfile"),
-        "Missing PlayersCreateProfile arg struct"
+        output.contains("pub type PlayersCreateProfile = PlayersProfile;"),
+        "Missing PlayersCreateProfile alias"
     );
     assert!(
-        output.contains("pub struct PlayersCreateProfileSettings"),
-        "Missing PlayersCreateProfileSettings arg struct"
+        output.contains("pub type PlayersAddAchievementAchievement = PlayersAchievements;"),
+        "Missing PlayersAddAchievementAchievement alias"
     );
     assert!(
-        output.contains("pub struct PlayersAddAchievementAchievement"),
-        "Missing PlayersAddAchievementAchievement arg struct"
+        !output.contains("pub struct PlayersCreateProfileSettings"),
+        "Shared struct should not be duplicated"
     );
 
     // FUNCTION_PATH constants
@@ -508,6 +509,52 @@ fn test_optional_args_mixed_in_btreemap()
     assert!(!map.contains_key("score"), "None score should be absent");
 }
 
+#[test]
+fn test_non_finite_number_args_are_sent_as_null()
+{
+    use example_types::GamesUpdateWithNoteArgs;
+
+    // JSON has no NaN or infinities, so the number is lost before the call is
+    // sent and `v.optional(v.number())` sees `null`. `finite_number_args`
+    // catches these when the args are built
+    for score in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let map: std::collections::BTreeMap<String, serde_json::Value> = GamesUpdateWithNoteArgs {
+            gameId: "game123".to_string(),
+            note: None,
+            score: Some(score),
+        }
+        .into();
+        assert_eq!(map["score"], serde_json::Value::Null, "{score} should be sent as null");
+    }
+}
+
+#[test]
+fn test_deserialization_error_names_the_failing_path()
+{
+    use example_types::{ConvexError, PlayersListActiveOutput};
+
+    let player = serde_json::json!({
+        "_id": "p1",
+        "_creationTime": 1700000000000.0,
+        "name": "Alice",
+        "score": 10.0,
+        "isActive": true,
+        "profile": { "settings": { "theme": "sepia", "notifications": true } },
+        "rank": "gold",
+        "achievements": [],
+        "stats": {},
+    });
+    let value = convex::Value::try_from(serde_json::json!([player])).expect("valid Convex value");
+
+    match PlayersListActiveOutput::try_from(convex::FunctionResult::Value(value)) {
+        Err(ConvexError::Deserialization { function, path, .. }) => {
+            assert_eq!(function.as_deref(), Some("players:listActive"));
+            assert_eq!(path, "[0].profile.settings.theme");
+        }
+        other => panic!("expected a deserialization error, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_function_paths()
 {
@@ -626,6 +673,49 @@ fn test_players_table_serde_roundtrip()
     assert_eq!(serialized, expected_json);
 }
 
+#[test]
+fn test_players_table_missing_collections_default_to_empty()
+{
+    use example_types::PlayersTable;
+
+    // A document stored before `achievements` and `stats` were added to the schema
+    let input_json = serde_json::json!({
+        "_id": "player1",
+        "_creationTime": 1700000000000.0,
+        "name": "Alice",
+        "score": 42.0,
+        "isActive": true,
+        "profile": {
+            "settings": { "theme": "dark", "notifications": true },
+        },
+        "rank": "gold",
+    });
+
+    let player: PlayersTable = serde_json::from_value(input_json).expect("Deserialize failed");
+    assert!(player.achievements.is_empty());
+    assert!(player.stats.is_empty());
+    assert_eq!(player.profile.bio, None);
+}
+
+#[test]
+fn test_id_union_roundtrip_and_resolve()
+{
+    use example_types::{GamesId, TestSubject};
+
+    let subject: TestSubject = serde_json::from_value(serde_json::json!("k57abc")).expect("Deserialize failed");
+    assert!(matches!(subject, TestSubject::Unknown(ref id) if id == "k57abc"));
+    assert_eq!(subject.table_hint(), None);
+
+    let subject = subject.resolve("games");
+    assert!(matches!(subject, TestSubject::Games(GamesId(ref id)) if id == "k57abc"));
+    assert_eq!(subject.table_hint(), Some("games"));
+    assert_eq!(subject.clone().resolve("players"), subject, "a known table is kept");
+    assert_eq!(serde_json::to_value(&subject).unwrap(), serde_json::json!("k57abc"));
+
+    let unknown: TestSubject = serde_json::from_value(serde_json::json!("k57abc")).unwrap();
+    assert_eq!(unknown.resolve("messages").table_hint(), None, "tables outside the union stay unknown");
+}
+
 #[test]
 fn test_tagged_union_serde()
 {
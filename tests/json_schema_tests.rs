@@ -0,0 +1,76 @@
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::json_schema::to_convex_validator;
+use serde_json::json;
+
+#[test]
+fn test_schemars_output_becomes_validator_source()
+{
+    // schemars::schema_for! of a struct with a nested enum, a map, and a
+    // renamed field
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Envelope",
+        "type": "object",
+        "properties": {
+            "attempts": { "type": "integer", "format": "uint8", "minimum": 0 },
+            "body": { "$ref": "#/$defs/Body" },
+            "content-type": { "type": "string" },
+            "headers": { "type": "object", "additionalProperties": { "type": "string" } },
+            "seq": { "type": "integer", "format": "int64" },
+            "tags": { "type": ["array", "null"], "items": { "type": "string" } }
+        },
+        "required": ["attempts", "body", "content-type", "headers", "seq"],
+        "$defs": {
+            "Body": {
+                "oneOf": [
+                    { "type": "string", "enum": ["empty", "ping"] },
+                    {
+                        "type": "object",
+                        "properties": { "text": { "type": "object", "properties": { "value": { "type": "string" } }, "required": ["value"] } },
+                        "required": ["text"]
+                    }
+                ]
+            }
+        }
+    });
+
+    let source = to_convex_validator(&schema).expect("schema converts");
+    assert_eq!(
+        source,
+        r#"v.object({
+    attempts: v.number(),
+    body: v.union(v.union(v.literal("empty"), v.literal("ping")), v.object({
+        text: v.object({
+            value: v.string(),
+        }),
+    })),
+    "content-type": v.string(),
+    headers: v.record(v.string(), v.string()),
+    seq: v.int64(),
+    tags: v.optional(v.union(v.array(v.string()), v.null())),
+})"#
+    );
+}
+
+#[test]
+fn test_schemas_without_a_convex_equivalent_are_errors()
+{
+    let recursive = json!({
+        "$ref": "#/$defs/Node",
+        "$defs": { "Node": { "type": "object", "properties": { "next": { "$ref": "#/$defs/Node" } } } }
+    });
+    let tuple =
+        json!({ "type": "object", "properties": { "pair": { "type": "array", "prefixItems": [{ "type": "string" }] } } });
+
+    match to_convex_validator(&recursive) {
+        Err(ConvexTypeGeneratorError::UnsupportedJsonSchema { details, .. }) => {
+            assert!(details.contains("recursive"), "got {details}")
+        }
+        other => panic!("recursive schema should fail, got {other:?}"),
+    }
+    match to_convex_validator(&tuple) {
+        Err(ConvexTypeGeneratorError::UnsupportedJsonSchema { path, .. }) => assert_eq!(path, "#/properties/pair"),
+        other => panic!("tuple should fail, got {other:?}"),
+    }
+    assert_eq!(to_convex_validator(&json!(true)).unwrap(), "v.any()");
+}
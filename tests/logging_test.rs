@@ -0,0 +1,68 @@
+//! Runs in its own test binary: the logger is process-wide.
+
+use std::fs;
+use std::sync::Mutex;
+
+use convex_typegen::{generate, Configuration, ExtractionBackend};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use tempfile::TempDir;
+
+struct Capture(Mutex<Vec<(Level, String, String)>>);
+
+impl Log for Capture
+{
+    fn enabled(&self, _metadata: &Metadata) -> bool
+    {
+        true
+    }
+
+    fn log(&self, record: &Record)
+    {
+        let entry = (record.level(), record.target().to_string(), record.args().to_string());
+        self.0.lock().unwrap().push(entry);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn test_messages_go_through_log_unless_quiet()
+{
+    log::set_logger(&LOGGER).expect("no other logger in this binary");
+    log::set_max_level(LevelFilter::Info);
+
+    let temp_dir = TempDir::with_prefix("convex_logging_test").expect("Failed to create temp directory");
+    let schema_path = temp_dir.path().join("schema.ts");
+    fs::write(
+        &schema_path,
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({ users: defineTable({ name: v.string(), metadata: v.any() }) });
+        "#,
+    )
+    .unwrap();
+    let config = Configuration {
+        schema_path,
+        out_file: temp_dir.path().join("types.rs"),
+        extraction_backend: ExtractionBackend::Ast,
+        print_summary: true,
+        ..Default::default()
+    };
+
+    generate(config.clone()).expect("Code generation failed");
+    let records = std::mem::take(&mut *LOGGER.0.lock().unwrap());
+    assert_eq!(records.len(), 1, "only the summary is logged, got {records:?}");
+    let (level, target, message) = &records[0];
+    assert_eq!((*level, target.as_str()), (Level::Info, "convex_typegen"));
+    assert!(
+        message.starts_with("convex-typegen: 1 table, 0 functions, 1 warning in "),
+        "no colors go to a logger, got: {message}"
+    );
+
+    generate(Configuration { quiet: true, ..config }).expect("Code generation failed");
+    let records = LOGGER.0.lock().unwrap();
+    assert!(records.is_empty(), "quiet should drop the summary, got {records:?}");
+}
@@ -0,0 +1,39 @@
+//! Built with `--features no-download`, a missing Bun can't be downloaded. The
+//! test empties `PATH` and the cache, so it lives in its own test binary.
+#![cfg(feature = "no-download")]
+
+use std::fs;
+
+use convex_typegen::errors::ConvexTypeGeneratorError;
+use convex_typegen::{generate, Configuration, ExtractionBackend};
+use tempfile::TempDir;
+
+#[test]
+fn test_missing_bun_is_an_error_without_downloader()
+{
+    let temp_dir = TempDir::with_prefix("convex_typegen_no_download").expect("Failed to create temp directory");
+    fs::write(
+        temp_dir.path().join("schema.ts"),
+        r#"
+        import { defineSchema, defineTable } from "convex/server";
+        import { v } from "convex/values";
+        export default defineSchema({ notes: defineTable({ body: v.string() }) });
+        "#,
+    )
+    .unwrap();
+    std::env::set_var("PATH", temp_dir.path());
+    std::env::set_var("CARGO_TARGET_DIR", temp_dir.path().join("target"));
+
+    let config = Configuration {
+        schema_path: temp_dir.path().join("schema.ts"),
+        out_file: temp_dir.path().join("types.rs"),
+        extraction_backend: ExtractionBackend::Bun,
+        ..Default::default()
+    };
+    match generate(config) {
+        Err(error @ ConvexTypeGeneratorError::ExtractionFailed { .. }) => {
+            assert!(error.to_string().contains("`no-download` feature"), "got: {error}");
+        }
+        other => panic!("Expected ExtractionFailed error, got {:?}", other),
+    }
+}